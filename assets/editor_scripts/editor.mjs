@@ -15,6 +15,289 @@ export class EditorAPI extends EventHandler {
     return this.#settings;
   }
 
+  /**
+   * Requests the engine to reload all tileset images and custom block models
+   * from disk. Any chunks that use the reloaded assets will be remeshed.
+   */
+  reloadAssets() {
+    COMMAND({
+      command: "reloadAssets",
+    });
+  }
+
+  /**
+   * Sets the render distance of the world, in chunks. Chunks outside of this
+   * distance from the camera are unloaded, and the fog is adjusted to match.
+   * The new value is saved to the project settings.
+   *
+   * @param {number} distance The new render distance, in chunks.
+   */
+  setRenderDistance(distance) {
+    COMMAND({
+      command: "setRenderDistance",
+      distance,
+    });
+  }
+
+  /**
+   * Sets the anti-aliasing mode used to render the world. The new value is
+   * saved to the project settings.
+   *
+   * @param {string} mode One of "off", "msaa2", "msaa4", "msaa8", "fxaa", or
+   * "taa".
+   */
+  setAntiAliasing(mode) {
+    COMMAND({
+      command: "setAntiAliasing",
+      mode,
+    });
+  }
+
+  /**
+   * Sets the background color of the world, such as a light gray to keep dark
+   * blocks visible. The new value is saved to the project settings.
+   *
+   * @param {number[]} editor The sky color used within the editor, as
+   * [r, g, b] from 0 to 255.
+   * @param {number[]} [runtime] The sky color used while the game is being
+   * played. If omitted, the editor sky color is used.
+   */
+  setSkyColor(editor, runtime) {
+    COMMAND({
+      command: "setSkyColor",
+      color: {editor, runtime},
+    });
+  }
+
+  /**
+   * Replaces the biomes that tint flagged block faces, such as the top of
+   * grass blocks, across the world. The new value is saved to the project
+   * settings.
+   *
+   * @param {object[]} biomes The new biomes. If empty, faces are never tinted.
+   * @param {string} biomes[].name The name of the biome.
+   * @param {number[]} biomes[].tint The tint of the biome, as [r, g, b] from 0
+   * to 1. Values above 1 brighten the faces.
+   */
+  setBiomes(biomes) {
+    COMMAND({
+      command: "setBiomes",
+      biomes,
+    });
+  }
+
+  /**
+   * Automatically textures the visible surface of all cube blocks within the
+   * given region. Blocks with an exposed top face are replaced with the top
+   * block, blocks with an exposed side face are replaced with the side block,
+   * and blocks with only an exposed bottom face are replaced with the bottom
+   * block. Fully buried blocks are left untouched.
+   *
+   * @param {number[]} min The minimum corner of the region, as [x, y, z].
+   * @param {number[]} max The maximum corner of the region, as [x, y, z].
+   * @param {string} top The uuid of the block to use for exposed tops.
+   * @param {string} side The uuid of the block to use for exposed sides.
+   * @param {string} bottom The uuid of the block to use for exposed bottoms.
+   */
+  fillVisibleSurface(min, max, top, side, bottom) {
+    COMMAND({
+      command: "fillVisibleSurface",
+      min,
+      max,
+      top,
+      side,
+      bottom,
+    });
+  }
+
+  /**
+   * Imports a grayscale heightmap image as terrain, starting at the world
+   * origin. The brightness of each pixel is mapped to the height of a column
+   * of blocks, which is filled with the base block and topped with the
+   * surface block. Large heightmaps are generated over multiple frames.
+   *
+   * @param {string} path The asset path of the image, such as
+   * `"heightmaps/island.png"`.
+   * @param {number} height The height of the column produced by a white
+   * pixel, in blocks.
+   * @param {string} base The uuid of the block that fills each column.
+   * @param {string} surface The uuid of the top block of each column.
+   * @param {number} [scale=1] The width and depth of the column area produced
+   * by each pixel, in blocks.
+   */
+  importHeightmap(path, height, base, surface, scale = 1) {
+    COMMAND({
+      command: "importHeightmap",
+      path,
+      height,
+      base,
+      surface,
+      scale,
+    });
+  }
+
+  /**
+   * Replaces the build rules, which restrict where and which blocks the
+   * player can place and remove. Calling this without any rules allows all
+   * edits again.
+   *
+   * @param {object} [rules] The new build rules.
+   * @param {number[][][]} [rules.regions] The regions in which blocks can be
+   * edited, as `[[minX, minY, minZ], [maxX, maxY, maxZ]]` pairs. If empty,
+   * blocks can be edited anywhere.
+   * @param {string[]} [rules.banned] The uuids of the blocks that cannot be
+   * placed.
+   */
+  setBuildRule({ regions = [], banned = [] } = {}) {
+    COMMAND({
+      command: "setBuildRule",
+      regions,
+      banned,
+    });
+  }
+
+  /**
+   * Sends a raw command to the engine, and waits until the engine has applied
+   * it. Awaiting a command before sending the next one ensures that the next
+   * command sees all of its effects.
+   *
+   * @param {object} command The command to send, such as
+   * `{command: "setProjectName", name: "My Game"}`.
+   * @returns {Promise<void>} A promise that resolves once the command has
+   * been applied.
+   */
+  async apply(command) {
+    await COMMAND_ACK(command);
+  }
+
+  /**
+   * Replaces a single block of the world, and waits until the block has been
+   * placed. If the chunk that contains the block does not exist yet, it is
+   * created.
+   *
+   * @param {number[]} position The world position of the block, as [x, y, z].
+   * @param {string} block The uuid of the new block.
+   * @returns {Promise<void>} A promise that resolves once the block has been
+   * placed, so that `getBlock` returns the new block.
+   */
+  async setBlock(position, block) {
+    await this.apply({
+      command: "setBlock",
+      position,
+      block,
+    });
+  }
+
+  /**
+   * Gets the block at the given position.
+   *
+   * @param {number[]} position The world position of the block, as [x, y, z].
+   * @returns {Promise<string | null>} The uuid of the block, or null if the
+   * chunk that contains the block is not loaded.
+   */
+  async getBlock(position) {
+    return await GET_BLOCK(position);
+  }
+
+  /**
+   * Gets the block currently targeted by the cursor.
+   *
+   * @returns {Promise<{position: number[], face: string, block: string} | null>}
+   * The world position of the targeted block as [x, y, z], the face being
+   * hovered ("Up", "Down", "North", "South", "East", or "West"), and the uuid
+   * of the block. Returns null if the cursor is not hovering over any block.
+   */
+  async getCursorTarget() {
+    let target = await GET_CURSOR_TARGET();
+    return target === null ? null : JSON.parse(target);
+  }
+
+  /**
+   * Lists all project assets that match the given glob pattern, including
+   * assets provided by overlay folders. Within a path segment, "*" matches any
+   * number of characters and "?" matches a single character, while a "**"
+   * segment matches any number of nested folders.
+   *
+   * @param {string} pattern The pattern to match, such as "models/*.glb".
+   * @returns {Promise<string[]>} The paths of all matching assets, relative to
+   * the project asset folder, such as "models/tree.glb".
+   * @throws {TypeError} If the pattern is absolute or contains "..".
+   */
+  async listAssets(pattern) {
+    return JSON.parse(await LIST_ASSETS(pattern));
+  }
+
+  /**
+   * Gets all blocks with the given tag. Blocks without any tags are listed
+   * under the "Uncategorized" tag.
+   *
+   * @param {string} tag The tag to search for.
+   * @returns {Promise<string[]>} The uuids of all matching blocks.
+   */
+  async getBlocksByTag(tag) {
+    return JSON.parse(await GET_BLOCKS_BY_TAG(tag));
+  }
+
+  /**
+   * Renders the given block to a PNG file from an isometric angle, such as for
+   * item icons and documentation. The file is written to the "icons" folder of
+   * the project within a few frames.
+   *
+   * @param {string} uuid The uuid of the block to render.
+   * @param {number} size The image size of the icon, in pixels.
+   * @param {number[]} [background] A solid background color, as [r, g, b, a]
+   * from 0 to 255. If omitted, the background is transparent.
+   * @returns {Promise<string | null>} The asset path of the icon file, or null
+   * if the block does not exist.
+   */
+  async renderBlockIcon(uuid, size, background) {
+    return await RENDER_BLOCK_ICON(uuid, size, background);
+  }
+
+  /**
+   * Chooses which input is forwarded to this script. Replaces any previous
+   * subscription. Subscribed key presses are emitted as "keyPressed" events
+   * with the key name, and mouse clicks are emitted as "mouseClicked" events
+   * with the button name and the cursor position.
+   *
+   * @param {string[]} keys The names of the keys to listen for, following the
+   * names of `KeyboardEvent.code`, such as "KeyA", "Digit1", "Space", or
+   * "ArrowUp".
+   * @param {boolean} [mouse] Whether to listen for mouse clicks. The button is
+   * one of "left", "right", or "middle", and the position is given as [x, y]
+   * in logical pixels from the top-left corner of the window.
+   */
+  subscribeInput(keys, mouse = false) {
+    COMMAND({
+      command: "subscribeInput",
+      keys,
+      mouse,
+    });
+  }
+
+  /**
+   * Sends a custom command to the engine, which is passed to all systems
+   * registered for the given name. Custom events sent by the engine are
+   * emitted as "custom" events with the event name and payload.
+   *
+   * @param {string} name The name of the command.
+   * @param {any} [payload] The payload of the command. Must be convertible to
+   * JSON.
+   * @throws {TypeError} If the payload cannot be converted to JSON.
+   */
+  sendCustom(name, payload = null) {
+    let json = JSON.stringify(payload);
+    if (json === undefined) {
+      throw new TypeError(`Payload of custom command "${name}" is not valid JSON.`);
+    }
+
+    COMMAND({
+      command: "custom",
+      name,
+      payload: JSON.parse(json),
+    });
+  }
+
   /**
    * This async function is the main event loop for the AwgenScript engine.
    * It receives messages from the native layer and forwards them to the event
@@ -28,6 +311,15 @@ export class EditorAPI extends EventHandler {
         case "engineStarted":
           this.emit("engineStarted");
           break;
+        case "keyPressed":
+          this.emit("keyPressed", message.key);
+          break;
+        case "mouseClicked":
+          this.emit("mouseClicked", message.button, message.pos);
+          break;
+        case "custom":
+          this.emit("custom", message.name, message.payload);
+          break;
       }
     }
   }
@@ -50,6 +50,18 @@ export class ProjectSettings extends EventHandler {
     return this.#tilesets;
   }
 
+  /**
+   * Reads a raw property directly from the project settings, without waiting
+   * for the engine. Writes made through this API are applied by the engine in
+   * order, so a value that was just written may not be visible yet.
+   * @param {string} key The key of the property to read.
+   * @returns {string | null} The value of the property, or null if the
+   * property does not exist.
+   */
+  read(key) {
+    return GET_SETTING(key);
+  }
+
   /**
    * This function sets the name of the project. Calling this method will update
    * the project settings in the engine.
@@ -73,14 +73,37 @@ export class Tileset extends EventHandler {
   }
 
   /**
-   * Sends a command to the engine to destroy the tileset.
+   * Updates the surface properties of the materials used to render the
+   * tileset. The materials are updated in place and the properties are saved
+   * to the project settings.
+   * @param {{roughness?: number, metallic?: number, reflectance?: number,
+   * alphaCutoff?: number, insetUvs?: boolean}} material The new material
+   * properties, each number in the range [0, 1]. Omitted properties are reset
+   * to their defaults: a roughness of 1, a metallic value of 0, a reflectance
+   * of 0.5, an alpha cutoff of 0.5, and no UV inset. Pixels with an alpha value
+   * below the alpha cutoff are discarded; the cutoff does not apply to
+   * transparent blocks. Insetting the UVs by half a texel prevents neighboring
+   * tiles from bleeding in at fractional zoom levels.
+   */
+  setMaterial(material) {
+    COMMAND({
+      command: "setTilesetMaterial",
+      tileset: this.#name,
+      material,
+    });
+  }
+
+  /**
+   * Sends a command to the engine to destroy the tileset. If any blocks still
+   * use the tileset, the tileset is not deleted and a warning listing the
+   * dependent blocks is logged instead.
    */
   async destroy() {
     COMMAND({
-      type: "editTileset",
+      command: "editTileset",
       uuid: this.#uuid,
       action: {
-        action: "destroy",
+        action: "delete",
       }
     });
   }
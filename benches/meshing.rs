@@ -0,0 +1,191 @@
+//! Benchmarks for chunk meshing, occlusion, and voxel raycasting.
+//!
+//! These benchmarks run against a headless [`World`] containing only block
+//! definitions and do not require a GPU or window to run.
+
+use awgen::blocks::Block;
+use awgen::blocks::model::BlockModel;
+use awgen::blocks::occlusion::BlockDataOccludedBy;
+use awgen::blocks::shape::{BlockFace, BlockShape};
+use awgen::blocks::systems::build_cube_mesh;
+use awgen::map::biome::BiomeMap;
+use awgen::map::chunk::{ChunkData, ChunkView};
+use awgen::map::light::LightView;
+use awgen::map::remesh::build_models;
+use awgen::math::{BlockPos, CHUNK_SIZE};
+use awgen::utilities::chunk_iter::ChunkIterator;
+use awgen::utilities::raycast::VoxelIterator;
+use bevy::ecs::system::SystemState;
+use bevy::prelude::*;
+use criterion::{Criterion, black_box, criterion_group, criterion_main};
+
+/// A headless meshing harness that contains a world with an air block and a
+/// solid cube block that can be used to build chunk data.
+struct MeshingHarness {
+    /// The world containing the block definitions.
+    world: World,
+
+    /// The system state used to query block models and shapes.
+    state: SystemState<(
+        Query<'static, 'static, &'static BlockModel>,
+        Query<'static, 'static, &'static BlockShape>,
+    )>,
+
+    /// The air block entity.
+    air: Entity,
+
+    /// The solid cube block entity.
+    solid: Entity,
+}
+
+impl MeshingHarness {
+    /// Creates a new meshing harness.
+    fn new() -> Self {
+        let mut world = World::new();
+
+        let air = world
+            .spawn((Block::default(), BlockModel::None, BlockShape::None))
+            .id();
+
+        let face = BlockFace::default();
+        let mesh = build_cube_mesh(&face, &face, &face, &face, &face, &face);
+        let solid = world
+            .spawn((
+                Block::default(),
+                BlockModel::Primitive {
+                    material: Handle::default(),
+                    bounds: mesh.get_bounds(),
+                    mesh: Box::new(mesh),
+                    variants: Vec::new(),
+                },
+                BlockShape::Cube {
+                    tileset: String::new(),
+                    top: face.clone(),
+                    bottom: face.clone(),
+                    north: face.clone(),
+                    south: face.clone(),
+                    east: face.clone(),
+                    west: face,
+                    transparent: false,
+                    non_occluding: false,
+                    variants: Vec::new(),
+                    randomize_rotation: false,
+                },
+            ))
+            .id();
+
+        let state = SystemState::new(&mut world);
+
+        Self {
+            world,
+            state,
+            air,
+            solid,
+        }
+    }
+
+    /// Creates a chunk that contains only air.
+    fn empty_chunk(&self) -> ChunkData {
+        ChunkData::fill(self.air)
+    }
+
+    /// Creates a chunk that is entirely filled with the solid block.
+    fn solid_chunk(&self) -> ChunkData {
+        let mut data = ChunkData::fill(self.air);
+        for pos in ChunkIterator::default() {
+            data.set(pos, self.solid);
+        }
+        data
+    }
+
+    /// Creates a chunk with alternating air and solid blocks. This is the worst
+    /// case scenario for meshing, as no faces are occluded.
+    fn checkerboard_chunk(&self) -> ChunkData {
+        let mut data = ChunkData::fill(self.air);
+        for pos in ChunkIterator::default() {
+            if (pos.x + pos.y + pos.z) % 2 == 0 {
+                data.set(pos, self.solid);
+            }
+        }
+        data
+    }
+}
+
+/// Benchmarks the chunk model building for several chunk layouts.
+fn bench_build_models(c: &mut Criterion) {
+    let mut harness = MeshingHarness::new();
+    let chunks = [
+        ("empty", harness.empty_chunk()),
+        ("checkerboard", harness.checkerboard_chunk()),
+        ("solid", harness.solid_chunk()),
+    ];
+
+    let biomes = BiomeMap::default();
+    let (models, shapes) = harness.state.get(&harness.world);
+    let mut group = c.benchmark_group("build_models");
+    for (name, data) in chunks.iter() {
+        group.bench_function(*name, |b| {
+            b.iter(|| {
+                build_models(
+                    &ChunkView::single(black_box(data)),
+                    BlockPos::new(0, 0, 0),
+                    &LightView::default(),
+                    &biomes,
+                    &models,
+                    &shapes,
+                )
+            })
+        });
+    }
+    group.finish();
+}
+
+/// Benchmarks the occlusion computation for several chunk layouts.
+fn bench_occlusion(c: &mut Criterion) {
+    let mut harness = MeshingHarness::new();
+    let chunks = [
+        ("empty", harness.empty_chunk()),
+        ("checkerboard", harness.checkerboard_chunk()),
+        ("solid", harness.solid_chunk()),
+    ];
+
+    let (_, shapes) = harness.state.get(&harness.world);
+    let mut group = c.benchmark_group("occlusion");
+    for (name, data) in chunks.iter() {
+        group.bench_function(*name, |b| {
+            b.iter(|| BlockDataOccludedBy::from_block_data(black_box(data), &shapes))
+        });
+    }
+    group.finish();
+}
+
+/// Benchmarks the voxel iterator traversal along straight and diagonal rays.
+fn bench_voxel_iter(c: &mut Criterion) {
+    let distance = CHUNK_SIZE as f32 * 16.0;
+    let rays = [
+        ("axis", Vec3::X),
+        ("diagonal", Vec3::ONE),
+        ("oblique", Vec3::new(0.3, -0.7, 0.2)),
+    ];
+
+    let mut group = c.benchmark_group("voxel_iter");
+    for (name, dir) in rays {
+        let dir = Dir3::new(dir).unwrap();
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                VoxelIterator::new(black_box(Vec3::splat(0.5)), dir)
+                    .with_max_distance(distance)
+                    .fold(BlockPos::new(0, 0, 0), |_, (pos, _)| pos)
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_build_models,
+    bench_occlusion,
+    bench_voxel_iter
+);
+criterion_main!(benches);
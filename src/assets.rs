@@ -0,0 +1,394 @@
+//! This module implements the asset source used to load project assets, which
+//! supports layering additional asset folders, such as mod packs, on top of
+//! the base project asset folder.
+//!
+//! When an asset is requested, the overlay folders are searched first, in
+//! reverse order of declaration, followed by the base project asset folder.
+//! This means that assets in later overlays override assets with the same path
+//! in earlier overlays, and all overlays override the base project. Assets that
+//! only exist in a lower layer are still available, so overlays may also simply
+//! supplement the base project with new assets.
+//!
+//! Meta files are always read from the same layer as the asset they belong to,
+//! and directory listings contain the merged contents of all layers. Assets are
+//! only ever written to, and watched for changes in, the base project folder.
+//!
+//! The base project folder can be replaced at runtime through the
+//! [`ProjectAssetFolders`] resource, which is used when switching projects.
+//! Replacing it also moves the asset writer and file watcher to the new
+//! folder.
+
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+use bevy::asset::AsyncReadExt;
+use bevy::asset::io::{
+    AssetReader,
+    AssetReaderError,
+    AssetSource,
+    AssetSourceBuilder,
+    AssetWatcher,
+    AssetWriter,
+    AssetWriterError,
+    ErasedAssetReader,
+    ErasedAssetWriter,
+    PathStream,
+    Reader,
+    VecReader,
+    Writer,
+};
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use futures_util::StreamExt;
+use itertools::Itertools;
+
+/// The time to wait for further changes to a file before the file watcher of
+/// the base project folder reports it as modified.
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// A function that creates a file watcher for the given folder, which reports
+/// changes to the `project://` asset source.
+type CreateWatcher = Box<dyn FnMut(&str) -> Option<Box<dyn AssetWatcher>> + Send + Sync>;
+
+/// This resource is a shared handle to the asset folders that make up the
+/// `project://` asset source. Replacing the base folder immediately redirects
+/// all future asset reads to the new folder.
+#[derive(Clone, Resource)]
+pub struct ProjectAssetFolders {
+    /// The readers for each layer, ordered from the highest precedence to the
+    /// lowest precedence. The last layer is the base project folder.
+    layers: Arc<RwLock<Vec<Arc<dyn ErasedAssetReader>>>>,
+
+    /// The overlay folders, in order of increasing precedence.
+    overlays: Vec<String>,
+
+    /// The writer for the base project folder, if the platform supports
+    /// writing assets.
+    writer: Arc<RwLock<Option<Arc<dyn ErasedAssetWriter>>>>,
+
+    /// The file watcher for the base project folder.
+    watcher: Arc<Mutex<BaseFolderWatcher>>,
+}
+
+/// The file watcher of the base project folder.
+#[derive(Default)]
+struct BaseFolderWatcher {
+    /// Creates a new file watcher for a folder. This is only available once
+    /// the asset source has started watching for changes.
+    create: Option<CreateWatcher>,
+
+    /// The active file watcher, if any. Dropping it stops watching the folder.
+    active: Option<Box<dyn AssetWatcher>>,
+}
+
+impl ProjectAssetFolders {
+    /// Creates a new set of project asset folders for the given base folder and
+    /// overlay folders. Overlays are listed in order of increasing precedence.
+    pub fn new(base: &str, overlays: &[String]) -> Self {
+        Self {
+            layers: Arc::new(RwLock::new(Self::create_layers(base, overlays))),
+            overlays: overlays.to_vec(),
+            writer: Arc::new(RwLock::new(Self::create_writer(base))),
+            watcher: Arc::new(Mutex::new(BaseFolderWatcher::default())),
+        }
+    }
+
+    /// Replaces the base project asset folder. The overlay folders are kept.
+    /// Assets are written to, and watched for changes in, the new folder from
+    /// now on.
+    pub fn set_base(&self, base: &str) {
+        let layers = Self::create_layers(base, &self.overlays);
+        *self.layers.write().unwrap() = layers;
+        *self.writer.write().unwrap() = Self::create_writer(base);
+
+        let mut watcher = self.watcher.lock().unwrap();
+        let watcher = &mut *watcher;
+        if let Some(create) = &mut watcher.create {
+            watcher.active = None;
+            watcher.active = create(base);
+        }
+    }
+
+    /// Lists the paths of all files within the given folder and its
+    /// subfolders, merged across all layers, as `/` separated asset paths.
+    /// Returns an empty list if the folder does not exist.
+    ///
+    /// Listing large folders may take a while, so this should be awaited on a
+    /// background task rather than blocked on from a system.
+    pub async fn list_files(&self, folder: &Path) -> Vec<String> {
+        let reader = LayeredAssetReader::new(self.clone());
+        let mut files = reader.list_files(folder).await;
+        files.sort();
+        files
+    }
+
+    /// Returns a snapshot of the current layer readers.
+    fn layers(&self) -> Vec<Arc<dyn ErasedAssetReader>> {
+        self.layers.read().unwrap().clone()
+    }
+
+    /// Returns the writer of the current base folder.
+    fn writer(&self) -> Result<Arc<dyn ErasedAssetWriter>, AssetWriterError> {
+        self.writer.read().unwrap().clone().ok_or_else(|| {
+            AssetWriterError::Io(std::io::Error::new(
+                ErrorKind::Unsupported,
+                "Writing project assets is not supported",
+            ))
+        })
+    }
+
+    /// Starts watching the given base folder for changes, using the given
+    /// function to create the file watcher. The function is used again to
+    /// watch the new folder whenever the base folder is replaced. Returns false
+    /// if the folder cannot be watched.
+    fn watch(&self, base: &str, mut create: CreateWatcher) -> bool {
+        let mut watcher = self.watcher.lock().unwrap();
+        watcher.active = create(base);
+        watcher.create = Some(create);
+        watcher.active.is_some()
+    }
+
+    /// Creates the writer for the given base folder.
+    fn create_writer(base: &str) -> Option<Arc<dyn ErasedAssetWriter>> {
+        AssetSource::get_default_writer(base.to_string())(false).map(Arc::from)
+    }
+
+    /// Creates the layer readers for the given base folder and overlay folders.
+    fn create_layers(base: &str, overlays: &[String]) -> Vec<Arc<dyn ErasedAssetReader>> {
+        overlays
+            .iter()
+            .rev()
+            .map(String::as_str)
+            .chain(std::iter::once(base))
+            .map(|path| Arc::from(AssetSource::get_default_reader(path.to_string())()))
+            .collect()
+    }
+}
+
+/// Creates the asset source builder for the project asset folders. Assets are
+/// written to, and watched for changes in, the current base folder.
+pub fn project_asset_source(folders: &ProjectAssetFolders, base: &str) -> AssetSourceBuilder {
+    let reader_folders = folders.clone();
+    let writer_folders = folders.clone();
+    let watcher_folders = folders.clone();
+    let base = base.to_string();
+
+    AssetSourceBuilder::default()
+        .with_reader(move || Box::new(LayeredAssetReader::new(reader_folders.clone())))
+        .with_writer(move |_| {
+            writer_folders.writer().ok()?;
+            Some(Box::new(ProjectAssetWriter::new(writer_folders.clone())))
+        })
+        .with_watcher(move |sender| {
+            let create = move |folder: &str| {
+                AssetSource::get_default_watcher(folder.to_string(), WATCH_DEBOUNCE)(sender.clone())
+            };
+
+            if !watcher_folders.watch(&base, Box::new(create)) {
+                return None;
+            }
+
+            let watcher: Box<dyn AssetWatcher> = Box::new(ProjectAssetWatcher);
+            Some(watcher)
+        })
+        .with_watch_warning(AssetSource::get_default_watch_warning())
+}
+
+/// The file watcher handed to the `project://` asset source. The actual file
+/// watcher is owned by the [`ProjectAssetFolders`], so that it can be replaced
+/// whenever the base folder changes.
+struct ProjectAssetWatcher;
+
+impl AssetWatcher for ProjectAssetWatcher {}
+
+/// An asset writer that writes assets to the current base folder of the
+/// [`ProjectAssetFolders`].
+pub struct ProjectAssetWriter {
+    /// The asset folders to write to.
+    folders: ProjectAssetFolders,
+}
+
+impl ProjectAssetWriter {
+    /// Creates a new project asset writer for the given asset folders.
+    pub fn new(folders: ProjectAssetFolders) -> Self {
+        Self { folders }
+    }
+}
+
+impl AssetWriter for ProjectAssetWriter {
+    async fn write<'a>(&'a self, path: &'a Path) -> Result<Box<Writer>, AssetWriterError> {
+        self.folders.writer()?.write(path).await
+    }
+
+    async fn write_meta<'a>(&'a self, path: &'a Path) -> Result<Box<Writer>, AssetWriterError> {
+        self.folders.writer()?.write_meta(path).await
+    }
+
+    async fn remove<'a>(&'a self, path: &'a Path) -> Result<(), AssetWriterError> {
+        self.folders.writer()?.remove(path).await
+    }
+
+    async fn remove_meta<'a>(&'a self, path: &'a Path) -> Result<(), AssetWriterError> {
+        self.folders.writer()?.remove_meta(path).await
+    }
+
+    async fn rename<'a>(
+        &'a self,
+        old_path: &'a Path,
+        new_path: &'a Path,
+    ) -> Result<(), AssetWriterError> {
+        self.folders.writer()?.rename(old_path, new_path).await
+    }
+
+    async fn rename_meta<'a>(
+        &'a self,
+        old_path: &'a Path,
+        new_path: &'a Path,
+    ) -> Result<(), AssetWriterError> {
+        self.folders.writer()?.rename_meta(old_path, new_path).await
+    }
+
+    async fn remove_directory<'a>(&'a self, path: &'a Path) -> Result<(), AssetWriterError> {
+        self.folders.writer()?.remove_directory(path).await
+    }
+
+    async fn remove_empty_directory<'a>(&'a self, path: &'a Path) -> Result<(), AssetWriterError> {
+        self.folders.writer()?.remove_empty_directory(path).await
+    }
+
+    async fn remove_assets_in_directory<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> Result<(), AssetWriterError> {
+        self.folders
+            .writer()?
+            .remove_assets_in_directory(path)
+            .await
+    }
+}
+
+/// An asset reader that reads assets from a stack of asset folders, returning
+/// the asset from the top-most folder that contains it.
+///
+/// Since the folders may be replaced at any time, assets are read fully into
+/// memory from the layer that contains them.
+pub struct LayeredAssetReader {
+    /// The asset folders to read from.
+    folders: ProjectAssetFolders,
+}
+
+impl LayeredAssetReader {
+    /// Creates a new layered asset reader for the given asset folders.
+    pub fn new(folders: ProjectAssetFolders) -> Self {
+        Self { folders }
+    }
+
+    /// Lists the paths of all files within the given folder and its
+    /// subfolders, as `/` separated asset paths.
+    async fn list_files(&self, folder: &Path) -> Vec<String> {
+        let mut files = Vec::new();
+        let mut folders = vec![folder.to_path_buf()];
+
+        while let Some(folder) = folders.pop() {
+            let Ok(stream) = self.read_directory(&folder).await else {
+                continue;
+            };
+
+            for path in stream.collect::<Vec<_>>().await {
+                match self.is_directory(&path).await {
+                    Ok(true) => folders.push(path),
+                    Ok(false) => files.push(
+                        path.components()
+                            .map(|c| c.as_os_str().to_string_lossy())
+                            .join("/"),
+                    ),
+                    Err(_) => continue,
+                }
+            }
+        }
+
+        files
+    }
+
+    /// Reads the asset, or the meta file of the asset, at the given path from
+    /// the top-most layer that contains the asset.
+    async fn read_layered(&self, path: &Path, meta: bool) -> Result<Vec<u8>, AssetReaderError> {
+        for layer in self.folders.layers() {
+            let mut reader = match layer.read(path).await {
+                Ok(_) if meta => layer.read_meta(path).await?,
+                Ok(reader) => reader,
+                Err(AssetReaderError::NotFound(_)) => continue,
+                Err(err) => return Err(err),
+            };
+
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            return Ok(bytes);
+        }
+
+        Err(AssetReaderError::NotFound(path.to_path_buf()))
+    }
+}
+
+impl AssetReader for LayeredAssetReader {
+    async fn read<'a>(&'a self, path: &'a Path) -> Result<Box<Reader<'a>>, AssetReaderError> {
+        let bytes = self.read_layered(path, false).await?;
+        Ok(Box::new(VecReader::new(bytes)))
+    }
+
+    async fn read_meta<'a>(&'a self, path: &'a Path) -> Result<Box<Reader<'a>>, AssetReaderError> {
+        let bytes = self.read_layered(path, true).await?;
+        Ok(Box::new(VecReader::new(bytes)))
+    }
+
+    async fn read_directory<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> Result<Box<PathStream>, AssetReaderError> {
+        let mut found = false;
+        let mut seen = HashSet::new();
+        let mut paths: Vec<PathBuf> = Vec::new();
+
+        for layer in self.folders.layers() {
+            match layer.read_directory(path).await {
+                Ok(stream) => {
+                    found = true;
+                    for entry in stream.collect::<Vec<_>>().await {
+                        if seen.insert(entry.clone()) {
+                            paths.push(entry);
+                        }
+                    }
+                }
+                Err(AssetReaderError::NotFound(_)) => continue,
+                Err(err) => return Err(err),
+            }
+        }
+
+        if !found {
+            return Err(AssetReaderError::NotFound(path.to_path_buf()));
+        }
+
+        Ok(Box::new(futures_util::stream::iter(paths)))
+    }
+
+    async fn is_directory<'a>(&'a self, path: &'a Path) -> Result<bool, AssetReaderError> {
+        let mut found = false;
+
+        for layer in self.folders.layers() {
+            match layer.is_directory(path).await {
+                Ok(true) => return Ok(true),
+                Ok(false) => found = true,
+                Err(AssetReaderError::NotFound(_)) => continue,
+                Err(err) => return Err(err),
+            }
+        }
+
+        if !found {
+            return Err(AssetReaderError::NotFound(path.to_path_buf()));
+        }
+
+        Ok(false)
+    }
+}
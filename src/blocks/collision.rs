@@ -0,0 +1,70 @@
+//! This module implements the physical collision shapes of blocks. Collision
+//! shapes are defined separately from the render mesh of a block, and are used
+//! for physics and walkable surfaces rather than mouse picking.
+
+use bevy::math::Vec3A;
+use bevy::math::bounding::Aabb3d;
+use bevy::prelude::*;
+
+use super::model::BlockModel;
+use super::shape::BlockShape;
+
+/// The collision bounds of a full cube block.
+pub const FULL_CUBE_BOUNDS: Aabb3d = Aabb3d {
+    min: Vec3A::ZERO,
+    max: Vec3A::ONE,
+};
+
+/// The collision shape of a block.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Component)]
+pub enum BlockCollision {
+    /// The collision shape is derived from the shape of the block. Cubic
+    /// blocks use a full cube collision shape, and custom blocks use the
+    /// bounds of their model.
+    #[default]
+    Auto,
+
+    /// The block has no collision.
+    None,
+
+    /// The block has a full cube collision shape.
+    FullCube,
+
+    /// The block has a custom collision shape.
+    Custom {
+        /// The collision bounds of the block, relative to the block position.
+        bounds: Aabb3d,
+    },
+}
+
+impl BlockCollision {
+    /// Gets the collision bounds of the block, relative to the block position.
+    /// Returns `None` if the block has no collision.
+    ///
+    /// Custom models that have not finished loading are treated as having no
+    /// collision.
+    pub fn get_bounds(&self, shape: &BlockShape, model: &BlockModel) -> Option<Aabb3d> {
+        match self {
+            BlockCollision::None => None,
+            BlockCollision::FullCube => Some(FULL_CUBE_BOUNDS),
+            BlockCollision::Custom { bounds } => Some(*bounds),
+            BlockCollision::Auto => match shape {
+                BlockShape::None | BlockShape::Cross { .. } => None,
+                BlockShape::Cube { .. } | BlockShape::Stairs { .. } => Some(FULL_CUBE_BOUNDS),
+                BlockShape::Custom { .. } => {
+                    model.get_bounds().filter(|bounds| bounds.min != bounds.max)
+                }
+            },
+        }
+    }
+
+    /// Returns the display name of this collision type.
+    pub fn name(&self) -> &'static str {
+        match self {
+            BlockCollision::Auto => "Auto",
+            BlockCollision::None => "None",
+            BlockCollision::FullCube => "Full Cube",
+            BlockCollision::Custom { .. } => "Custom",
+        }
+    }
+}
@@ -0,0 +1,26 @@
+//! This module implements light emission properties for blocks.
+
+use bevy::prelude::*;
+
+/// The maximum light level that can be emitted by a block. Light decreases by
+/// one level for every block it travels, so light never reaches further than
+/// this many blocks from its source.
+pub const MAX_LIGHT_LEVEL: u8 = 15;
+
+/// A component that can be attached to a block entity to make it emit light
+/// into the world, such as a torch or a lamp.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Component)]
+pub struct LightEmitter {
+    /// The light level emitted by this block. Values larger than
+    /// [`MAX_LIGHT_LEVEL`] are clamped.
+    pub level: u8,
+}
+
+impl LightEmitter {
+    /// Gets the light level emitted by this block, clamped to
+    /// [`MAX_LIGHT_LEVEL`].
+    #[inline(always)]
+    pub fn level(&self) -> u8 {
+        self.level.min(MAX_LIGHT_LEVEL)
+    }
+}
@@ -4,10 +4,12 @@ use bevy::math::Vec3A;
 use bevy::math::bounding::Aabb3d;
 use bevy::prelude::*;
 use bevy::render::mesh::VertexAttributeValues;
+use bevy::utils::HashMap;
 use tinyvec::TinyVec;
 
 use super::occlusion::OccludedBy;
 use super::tileset::TilePos;
+use crate::math::FaceDirection;
 use crate::utilities::meshbuf::MeshBuf;
 
 /// The maximum number of vertices to store on the stack in a [`BlockMesh`].
@@ -16,6 +18,10 @@ const MAX_STACK_VERTICES: usize = 8;
 /// The maximum number of indices to store on the stack in a [`BlockMesh`].
 const MAX_STACK_INDICES: usize = 16;
 
+/// The number of steps per block used to match vertex positions when smoothing
+/// normals. Vertices closer together than a single step are smoothed together.
+const SMOOTH_NORMALS_PRECISION: f32 = 10_000.0;
+
 /// The mesh of a primitive block model.
 #[derive(Debug, Default, Clone)]
 pub struct BlockMesh {
@@ -46,6 +52,10 @@ pub struct BlockMesh {
     /// The west face of the block mesh. Visible if there is no block to the
     /// west of the block.
     pub west: Option<BlockMeshPart>,
+
+    /// The pixel size of the tiles of the block mesh, if the tile UVs should be
+    /// inset by half a texel. See [`TilePos::transform_uv`].
+    pub tile_pixels: Option<u32>,
 }
 
 impl BlockMesh {
@@ -94,63 +104,115 @@ impl BlockMesh {
         }
     }
 
+    /// Returns an iterator over the parts of the block mesh that are visible
+    /// based on the provided occlusion data, in the order they are appended.
+    fn visible_parts(&self, occlusion: OccludedBy) -> impl Iterator<Item = &BlockMeshPart> {
+        [
+            (&self.center, !occlusion.is_all()),
+            (&self.top, !occlusion.contains(OccludedBy::Up)),
+            (&self.bottom, !occlusion.contains(OccludedBy::Down)),
+            (&self.north, !occlusion.contains(OccludedBy::North)),
+            (&self.south, !occlusion.contains(OccludedBy::South)),
+            (&self.east, !occlusion.contains(OccludedBy::East)),
+            (&self.west, !occlusion.contains(OccludedBy::West)),
+        ]
+        .into_iter()
+        .filter_map(|(part, visible)| part.as_ref().filter(|_| visible))
+    }
+
     /// Appends this block mesh to the given mesh buffer based on the provided
     /// occlusion data.
     pub fn append_to(&self, occlusion: OccludedBy, mesh: &mut MeshBuf) {
-        if let Some(part) = &self.center {
-            if !occlusion.is_all() {
-                part.append_to(mesh);
-            }
+        for part in self.visible_parts(occlusion) {
+            part.append_to(mesh, self.tile_pixels);
         }
+    }
 
-        if let Some(part) = &self.top {
-            if !occlusion.contains(OccludedBy::Up) {
-                part.append_to(mesh);
-            }
-        }
+    /// Returns true if any part of this block mesh is tinted by the biome. See
+    /// [`BlockMeshPart::biome_tint`].
+    pub fn has_biome_tint(&self) -> bool {
+        self.parts()
+            .into_iter()
+            .flatten()
+            .any(|part| part.biome_tint)
+    }
 
-        if let Some(part) = &self.bottom {
-            if !occlusion.contains(OccludedBy::Down) {
-                part.append_to(mesh);
+    /// Multiplies the vertex colors of all visible biome tinted parts of this
+    /// block mesh by the given tint. The block mesh must have been appended to
+    /// the given mesh buffer with the same occlusion data, starting at the
+    /// vertex index `start`.
+    ///
+    /// Vertices without a color yet are given a white color first, so that the
+    /// mesh buffer has a color for every vertex up to this block mesh.
+    pub fn apply_biome_tint(
+        &self,
+        occlusion: OccludedBy,
+        start: usize,
+        tint: Vec3,
+        mesh: &mut MeshBuf,
+    ) {
+        mesh.colors.resize(mesh.positions.len(), [1.0; 4]);
+
+        let mut offset = start;
+        for part in self.visible_parts(occlusion) {
+            let end = offset + part.vertices.len();
+            if part.biome_tint {
+                for color in mesh.colors[offset .. end].iter_mut() {
+                    color[0] *= tint.x;
+                    color[1] *= tint.y;
+                    color[2] *= tint.z;
+                }
             }
+            offset = end;
         }
+    }
 
-        if let Some(part) = &self.north {
-            if !occlusion.contains(OccludedBy::North) {
-                part.append_to(mesh);
+    /// Selects the tiles of all connected texture parts of this block mesh. See
+    /// [`BlockMeshPart::connected`].
+    ///
+    /// The given function is called with the direction of each neighbor within
+    /// the plane of a connected part, and returns true if the block connects
+    /// to the neighbor in that direction.
+    pub fn connect_textures(&mut self, connects: impl Fn(FaceDirection) -> bool) {
+        for part in self.parts_mut().into_iter().flatten() {
+            if !part.connected {
+                continue;
             }
-        }
 
-        if let Some(part) = &self.south {
-            if !occlusion.contains(OccludedBy::South) {
-                part.append_to(mesh);
-            }
-        }
+            let Some((right, down)) = part.tile_axes() else {
+                continue;
+            };
 
-        if let Some(part) = &self.east {
-            if !occlusion.contains(OccludedBy::East) {
-                part.append_to(mesh);
-            }
-        }
+            let mask = [down.opposite(), right, down, right.opposite()]
+                .into_iter()
+                .enumerate()
+                .filter(|(_, dir)| connects(*dir))
+                .fold(0, |mask, (bit, _)| mask | (1 << bit));
 
-        if let Some(part) = &self.west {
-            if !occlusion.contains(OccludedBy::West) {
-                part.append_to(mesh);
+            for vertex in part.vertices.iter_mut() {
+                if let Some(tile) = &mut vertex.tile {
+                    *tile = tile.shifted(mask & 3, mask >> 2);
+                }
             }
         }
     }
 
-    /// Gets the bounds of the block mesh.
+    /// Gets the bounds of the block mesh. Returns an empty box at the origin if
+    /// the mesh has no vertices.
     pub fn get_bounds(&self) -> Aabb3d {
-        Aabb3d::from_point_cloud(
-            Vec3A::ZERO,
-            Quat::IDENTITY,
-            self.parts().into_iter().flatten().flat_map(|part| {
-                part.vertices
-                    .iter()
-                    .map(|vertex| Vec3A::from(vertex.position))
-            }),
-        )
+        let mut points = self
+            .parts()
+            .into_iter()
+            .flatten()
+            .flat_map(|part| part.vertices.iter())
+            .map(|vertex| Vec3A::from(vertex.position))
+            .peekable();
+
+        if points.peek().is_none() {
+            return Aabb3d::new(Vec3A::ZERO, Vec3A::ZERO);
+        }
+
+        Aabb3d::from_point_cloud(Vec3A::ZERO, Quat::IDENTITY, points)
     }
 }
 
@@ -203,6 +265,19 @@ pub struct BlockMeshPart {
 
     /// The indices of the block.
     pub indices: TinyVec<[u16; MAX_STACK_INDICES]>,
+
+    /// Whether this part uses connected textures. The tile of a connected part
+    /// is the top-left tile of a 4x4 grid of tiles, and the tile used for a
+    /// placed block is picked from this grid by which of its four neighbors
+    /// along the edges of the texture connect to it. Connections to the top,
+    /// right, bottom, and left neighbors add 1, 2, 4, and 8 to the index of the
+    /// tile, which is counted left to right, then top to bottom.
+    pub connected: bool,
+
+    /// Whether the vertex colors of this part are tinted by the biome the
+    /// block is placed in, such as for grass. See
+    /// [`BiomeMap`](crate::map::biome::BiomeMap).
+    pub biome_tint: bool,
 }
 
 impl From<[BlockVertex; 4]> for BlockMeshPart {
@@ -213,14 +288,20 @@ impl From<[BlockVertex; 4]> for BlockMeshPart {
         let mut indices = TinyVec::with_capacity(6);
         indices.extend_from_slice(&[0, 1, 2, 0, 2, 3]);
 
-        Self { vertices, indices }
+        Self {
+            vertices,
+            indices,
+            connected: false,
+            biome_tint: false,
+        }
     }
 }
 
 impl BlockMeshPart {
     /// Appends the vertices and indices of this block mesh part to the given
-    /// mesh buffer.
-    pub fn append_to(&self, mesh: &mut MeshBuf) {
+    /// mesh buffer. Tile UVs are inset by half a texel if the pixel size of the
+    /// tiles is given.
+    pub fn append_to(&self, mesh: &mut MeshBuf, tile_pixels: Option<u32>) {
         let offset = mesh.positions.len() as u32;
 
         for vertex in self.vertices.iter() {
@@ -228,7 +309,7 @@ impl BlockMeshPart {
             mesh.normals.push(vertex.normal.into());
 
             if let Some(tile) = vertex.tile {
-                let uv = tile.transform_uv(vertex.uv);
+                let uv = tile.transform_uv(vertex.uv, tile_pixels);
                 mesh.uvs.push(uv.into());
             } else {
                 mesh.uvs.push(vertex.uv.into());
@@ -281,6 +362,59 @@ impl BlockMeshPart {
         mesh_part
     }
 
+    /// Gets the directions within the block that the right and bottom edges of
+    /// the texture of this part point towards, based on the positions and UV
+    /// coordinates of its first triangle. Returns `None` if the part has no
+    /// triangles or its texture coordinates are degenerate.
+    fn tile_axes(&self) -> Option<(FaceDirection, FaceDirection)> {
+        let [a, b, c] = match self.indices.get(.. 3)? {
+            &[a, b, c] => [a, b, c].map(|index| self.vertices[index as usize]),
+            _ => return None,
+        };
+
+        let (e1, e2) = (b.position - a.position, c.position - a.position);
+        let (d1, d2) = (b.uv - a.uv, c.uv - a.uv);
+        let det = d1.x * d2.y - d2.x * d1.y;
+        if det.abs() < f32::EPSILON {
+            return None;
+        }
+
+        let right = (e1 * d2.y - e2 * d1.y) / det;
+        let down = (e2 * d1.x - e1 * d2.x) / det;
+        Some((
+            FaceDirection::from_normal(right)?,
+            FaceDirection::from_normal(down)?,
+        ))
+    }
+
+    /// Recomputes the normals of this block mesh part by averaging the normals
+    /// of all triangles that share a vertex position, weighted by the angle of
+    /// each triangle at that position. Vertices at the same position are
+    /// smoothed together even if they are split, such as along UV seams.
+    pub fn smooth_normals(&mut self) {
+        let key = |pos: Vec3| (pos * SMOOTH_NORMALS_PRECISION).round().as_ivec3();
+
+        let mut normals: HashMap<IVec3, Vec3> = HashMap::new();
+        for triangle in self.indices.chunks_exact(3) {
+            let [a, b, c] = [triangle[0], triangle[1], triangle[2]]
+                .map(|index| self.vertices[index as usize].position);
+            let Some(normal) = (b - a).cross(c - a).try_normalize() else {
+                continue;
+            };
+
+            for (pos, q, r) in [(a, b, c), (b, c, a), (c, a, b)] {
+                let angle = (q - pos).angle_between(r - pos);
+                *normals.entry(key(pos)).or_default() += normal * angle;
+            }
+        }
+
+        for vertex in self.vertices.iter_mut() {
+            if let Some(normal) = normals.get(&key(vertex.position)) {
+                vertex.normal = normal.try_normalize().unwrap_or(vertex.normal);
+            }
+        }
+    }
+
     /// Extends this block mesh part with the vertices and indices of another
     /// block mesh part.
     pub fn extend(&mut self, other: &BlockMeshPart) {
@@ -312,3 +446,86 @@ pub struct BlockVertex {
     /// coordinates specified in the UV field will not be modified.
     pub tile: Option<TilePos>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocks::shape::BlockFace;
+    use crate::blocks::systems::build_cube_mesh;
+
+    #[test]
+    fn connect_textures_samples_neighbors_in_face_plane() {
+        let face = BlockFace::default();
+        let top = BlockFace {
+            tile: TilePos::new(4, 8),
+            connected: true,
+            ..default()
+        };
+        let mut mesh = build_cube_mesh(&top, &face, &face, &face, &face, &face);
+
+        mesh.connect_textures(|dir| {
+            assert!(!matches!(dir, FaceDirection::Up | FaceDirection::Down));
+            true
+        });
+
+        let top_tile = mesh.top.as_ref().unwrap().vertices[0].tile;
+        let north_tile = mesh.north.as_ref().unwrap().vertices[0].tile;
+        assert_eq!(top_tile, Some(TilePos::new(7, 11)));
+        assert_eq!(north_tile, Some(face.tile));
+    }
+
+    #[test]
+    fn biome_tint_only_colors_flagged_visible_parts() {
+        let face = BlockFace::default();
+        let top = BlockFace {
+            biome_tint: true,
+            ..default()
+        };
+        let mesh = build_cube_mesh(&top, &face, &face, &face, &face, &face);
+        assert!(mesh.has_biome_tint());
+
+        let mut buf = MeshBuf::new();
+        buf.positions.push([0.0; 3]);
+
+        let occlusion = OccludedBy::Down | OccludedBy::North;
+        let start = buf.positions.len();
+        mesh.append_to(occlusion, &mut buf);
+        mesh.apply_biome_tint(occlusion, start, Vec3::new(0.5, 1.0, 0.25), &mut buf);
+
+        assert_eq!(buf.colors.len(), buf.positions.len());
+        assert_eq!(buf.colors[0], [1.0; 4]);
+        assert_eq!(buf.colors[1 .. 5], [[0.5, 1.0, 0.25, 1.0]; 4]);
+        assert!(buf.colors[5 ..].iter().all(|color| *color == [1.0; 4]));
+    }
+
+    #[test]
+    fn smooth_normals_averages_shared_positions() {
+        let vertex = |x: f32, y: f32, z: f32, normal: Vec3| BlockVertex {
+            position: Vec3::new(x, y, z),
+            normal,
+            ..default()
+        };
+
+        // Two faces of a cube that share an edge, with split vertices along it.
+        let mut part = BlockMeshPart::from([
+            vertex(0.0, 1.0, 0.0, Vec3::Y),
+            vertex(0.0, 1.0, 1.0, Vec3::Y),
+            vertex(1.0, 1.0, 1.0, Vec3::Y),
+            vertex(1.0, 1.0, 0.0, Vec3::Y),
+        ]);
+        part.extend(&BlockMeshPart::from([
+            vertex(1.0, 1.0, 0.0, Vec3::X),
+            vertex(1.0, 1.0, 1.0, Vec3::X),
+            vertex(1.0, 0.0, 1.0, Vec3::X),
+            vertex(1.0, 0.0, 0.0, Vec3::X),
+        ]));
+
+        part.smooth_normals();
+
+        let edge = Vec3::new(1.0, 1.0, 0.0).normalize();
+        assert!(part.vertices[0].normal.abs_diff_eq(Vec3::Y, 1e-5));
+        assert!(part.vertices[2].normal.abs_diff_eq(edge, 1e-5));
+        assert!(part.vertices[4].normal.abs_diff_eq(edge, 1e-5));
+        assert!(part.vertices[6].normal.abs_diff_eq(Vec3::X, 1e-5));
+    }
+}
@@ -4,12 +4,24 @@ use bevy::asset::embedded_asset;
 use bevy::prelude::*;
 use uuid::Uuid;
 
+#[cfg(feature = "editor")]
+use crate::gamestate::GameState;
+use crate::project::{LoadProject, UnloadProject};
+#[cfg(feature = "editor")]
+use crate::ui::UiKeyboardFocus;
+#[cfg(feature = "editor")]
+use crate::ui::command_palette::EditorActionsAppExt;
+
+pub mod collision;
+pub mod light;
 pub mod mesh;
 pub mod model;
 pub mod occlusion;
 pub mod params;
 pub mod shape;
+pub mod sounds;
 pub mod systems;
+pub mod tags;
 pub mod tileset;
 
 /// The name of the air block, the default block type for empty space.
@@ -23,17 +35,54 @@ pub const AIR_BLOCK_UUID: Uuid = Uuid::from_u128(0);
 pub struct BlocksPlugin;
 impl Plugin for BlocksPlugin {
     fn build(&self, app_: &mut App) {
-        app_.add_systems(
-            Update,
-            (
-                systems::update_rendered_block_model,
-                systems::forward_model_changes_to_rendered,
-                systems::update_block_model,
-                systems::update_custom_block_model_mesh
-                    .after_ignore_deferred(systems::update_block_model),
-            ),
-        )
-        .add_systems(Startup, (systems::load_blocks, tileset::load_tilesets));
+        app_.init_resource::<model::CustomModelIndex>()
+            .add_event::<ReloadBlockAssets>()
+            .add_event::<DeleteBlock>()
+            .add_systems(
+                Update,
+                (
+                    systems::update_rendered_block_model,
+                    systems::forward_model_changes_to_rendered,
+                    systems::update_block_model,
+                    tileset::update_tileset_uv_inset
+                        .before_ignore_deferred(systems::update_block_model),
+                    systems::index_custom_block_models
+                        .after_ignore_deferred(systems::update_block_model),
+                    systems::update_custom_block_model_mesh
+                        .after_ignore_deferred(systems::index_custom_block_models),
+                    systems::reload_block_assets,
+                    systems::delete_blocks,
+                    tileset::refresh_reloaded_tilesets,
+                    tileset::reload_renamed_tileset_images,
+                    tileset::update_tileset_materials,
+                    tileset::check_tileset_image_limits,
+                    tags::load_block_tags,
+                    sounds::load_block_sounds,
+                ),
+            )
+            .add_systems(Startup, tileset::create_missing_material)
+            .add_systems(LoadProject, (systems::load_blocks, tileset::load_tilesets))
+            .add_systems(
+                UnloadProject,
+                (systems::unload_blocks, tileset::unload_tilesets),
+            );
+
+        #[cfg(feature = "editor")]
+        {
+            app_.add_systems(
+                Update,
+                systems::reload_block_assets_hotkey
+                    .before(systems::reload_block_assets)
+                    .run_if(in_state(GameState::Editor))
+                    .run_if(UiKeyboardFocus::is_free),
+            )
+            .register_editor_action(
+                "Reload Block Assets",
+                |mut reload_events: EventWriter<ReloadBlockAssets>| {
+                    reload_events.send(ReloadBlockAssets);
+                },
+            );
+        }
 
         embedded_asset!(app_, "prototype.png");
     }
@@ -63,3 +112,18 @@ pub struct RenderedBlock {
     /// The block entity to read model data from.
     pub block: Entity,
 }
+
+/// An event that requests all tileset images and custom block models to be
+/// reloaded from disk. Any chunks that use the reloaded assets are remeshed
+/// once the new assets finish loading.
+#[derive(Debug, Default, Clone, Copy, Event)]
+pub struct ReloadBlockAssets;
+
+/// An event that requests a block type to be deleted. All instances of the
+/// block within the world are replaced with air before the block entity is
+/// despawned. The air block cannot be deleted.
+#[derive(Debug, Clone, Copy, Event)]
+pub struct DeleteBlock {
+    /// The block entity to delete.
+    pub block: Entity,
+}
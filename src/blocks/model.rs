@@ -2,8 +2,10 @@
 
 use bevy::math::bounding::Aabb3d;
 use bevy::prelude::*;
+use bevy::utils::{HashMap, HashSet};
 
 use super::mesh::BlockMesh;
+use crate::math::BlockPos;
 
 /// The model definition of a block, as defined by the block's mesh and
 /// material.
@@ -21,6 +23,11 @@ pub enum BlockModel {
         /// The mesh of the block.
         mesh: Box<BlockMesh>,
 
+        /// The meshes of the texture variants of the block. Placed blocks pick
+        /// between the main mesh and these meshes based on their position. See
+        /// [`variant_index`].
+        variants: Vec<BlockMesh>,
+
         /// The bounds of the block mesh. Used for raycasting.
         bounds: Aabb3d,
     },
@@ -51,4 +58,169 @@ impl BlockModel {
             BlockModel::Custom { bounds, .. } => Some(*bounds),
         }
     }
+
+    /// Gets the material and mesh used by a placed block at the given world
+    /// position. Returns `None` if the block has no model.
+    pub fn mesh_at(&self, pos: BlockPos) -> Option<(&Handle<StandardMaterial>, &BlockMesh)> {
+        match self {
+            BlockModel::None => None,
+            BlockModel::Primitive {
+                material,
+                mesh,
+                variants,
+                ..
+            } => {
+                let index = variant_index(pos, variants.len() + 1);
+                let mesh = index.checked_sub(1).map_or(&**mesh, |i| &variants[i]);
+                Some((material, mesh))
+            }
+            BlockModel::Custom { material, mesh, .. } => Some((material, mesh)),
+        }
+    }
+}
+
+/// Picks one of the given number of texture variants for a block at the given
+/// world position. The choice only depends on the position, so a block keeps
+/// its variant across remeshes.
+pub fn variant_index(pos: BlockPos, count: usize) -> usize {
+    if count <= 1 {
+        return 0;
+    }
+
+    let mut hash = (pos.x as u32 as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (pos.y as u32 as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F)
+        ^ (pos.z as u32 as u64).wrapping_mul(0x1656_67B1_9E37_79F9);
+    hash ^= hash >> 29;
+    hash = hash.wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    hash ^= hash >> 32;
+
+    (hash % count as u64) as usize
+}
+
+/// This resource maps each custom model asset to the blocks that use it, so
+/// that a finished asset load only updates the blocks that depend on it,
+/// rather than scanning every block.
+///
+/// Multiple blocks may share the same model asset. All of them are listed under
+/// that asset, and each receives its own copy of the mesh when the asset
+/// finishes loading.
+#[derive(Debug, Default, Resource)]
+pub struct CustomModelIndex {
+    /// The blocks that use each custom model asset.
+    blocks: HashMap<AssetId<Gltf>, HashSet<Entity>>,
+
+    /// The custom model asset used by each block.
+    assets: HashMap<Entity, AssetId<Gltf>>,
+
+    /// The blocks that started using a different custom model asset since the
+    /// last time they were checked. If the asset is already loaded, no new
+    /// asset event will be sent for these blocks.
+    pending: HashSet<Entity>,
+}
+
+impl CustomModelIndex {
+    /// Sets the custom model asset used by the given block, or `None` if the
+    /// block does not use a custom model. This does nothing if the asset is
+    /// unchanged.
+    pub fn set(&mut self, block: Entity, asset: Option<AssetId<Gltf>>) {
+        if self.assets.get(&block).copied() == asset {
+            return;
+        }
+
+        self.remove(block);
+
+        if let Some(asset) = asset {
+            self.assets.insert(block, asset);
+            self.blocks.entry(asset).or_default().insert(block);
+            self.pending.insert(block);
+        }
+    }
+
+    /// Removes the given block from the index.
+    pub fn remove(&mut self, block: Entity) {
+        self.pending.remove(&block);
+
+        let Some(asset) = self.assets.remove(&block) else {
+            return;
+        };
+
+        if let Some(blocks) = self.blocks.get_mut(&asset) {
+            blocks.remove(&block);
+            if blocks.is_empty() {
+                self.blocks.remove(&asset);
+            }
+        }
+    }
+
+    /// Returns an iterator over all blocks that use the given custom model
+    /// asset.
+    pub fn blocks_using(&self, asset: AssetId<Gltf>) -> impl Iterator<Item = Entity> + '_ {
+        self.blocks.get(&asset).into_iter().flatten().copied()
+    }
+
+    /// Takes all blocks that started using a different custom model asset
+    /// since the last call, along with their asset.
+    pub fn take_pending(&mut self) -> Vec<(Entity, AssetId<Gltf>)> {
+        self.pending
+            .drain()
+            .filter_map(|block| Some((block, *self.assets.get(&block)?)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shared_asset_lists_all_blocks() {
+        let asset = AssetId::<Gltf>::default();
+        let a = Entity::from_raw(1);
+        let b = Entity::from_raw(2);
+
+        let mut index = CustomModelIndex::default();
+        index.set(a, Some(asset));
+        index.set(b, Some(asset));
+
+        let mut blocks: Vec<_> = index.blocks_using(asset).collect();
+        blocks.sort();
+        assert_eq!(blocks, vec![a, b]);
+        assert_eq!(index.take_pending().len(), 2);
+        assert!(index.take_pending().is_empty());
+
+        index.set(a, None);
+        assert_eq!(index.blocks_using(asset).collect::<Vec<_>>(), vec![b]);
+
+        index.remove(b);
+        assert_eq!(index.blocks_using(asset).count(), 0);
+    }
+
+    #[test]
+    fn variant_index_is_stable() {
+        let mut seen = [false; 4];
+        for x in -8 .. 8 {
+            for z in -8 .. 8 {
+                let pos = BlockPos::new(x, 3, z);
+                let index = variant_index(pos, 4);
+                assert_eq!(index, variant_index(pos, 4));
+                seen[index] = true;
+            }
+        }
+
+        assert!(seen.iter().all(|seen| *seen));
+        assert_eq!(variant_index(BlockPos::new(1, 2, 3), 1), 0);
+    }
+
+    #[test]
+    fn unchanged_asset_is_not_pending() {
+        let asset = AssetId::<Gltf>::default();
+        let block = Entity::from_raw(1);
+
+        let mut index = CustomModelIndex::default();
+        index.set(block, Some(asset));
+        index.take_pending();
+
+        index.set(block, Some(asset));
+        assert!(index.take_pending().is_empty());
+    }
 }
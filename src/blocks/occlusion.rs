@@ -112,52 +112,28 @@ impl BlockDataOccludedBy {
     /// Creates a new [`BlockDataOccludedBy`] data structure from the given
     /// [`BlockDataOccludes`] data structure. All blocks outside the chunk
     /// bounds are considered to be empty.
+    ///
+    /// A block face is only occluded by an occluding neighbor if the block and
+    /// its neighbor are not both transparent.
     pub fn from_occlusion(occlusion: &BlockDataOccludes) -> Self {
         let mut data = BlockDataOccludedBy::new();
 
         for pos in ChunkIterator::default() {
+            let transparent = occlusion.is_transparent(pos);
             let mut occluded_by = OccludedBy::empty();
 
-            if occlusion
-                .get(pos.shift(FaceDirection::Up, 1))
-                .contains(Occludes::Down)
-            {
-                occluded_by |= OccludedBy::Up;
-            }
-
-            if occlusion
-                .get(pos.shift(FaceDirection::Down, 1))
-                .contains(Occludes::Up)
-            {
-                occluded_by |= OccludedBy::Down;
-            }
+            for dir in FaceDirection::DIRECTIONS {
+                let neighbor = pos.shift(dir, 1);
 
-            if occlusion
-                .get(pos.shift(FaceDirection::North, 1))
-                .contains(Occludes::South)
-            {
-                occluded_by |= OccludedBy::North;
-            }
+                if !occlusion.get(neighbor).contains(dir.opposite().into()) {
+                    continue;
+                }
 
-            if occlusion
-                .get(pos.shift(FaceDirection::South, 1))
-                .contains(Occludes::North)
-            {
-                occluded_by |= OccludedBy::South;
-            }
-
-            if occlusion
-                .get(pos.shift(FaceDirection::East, 1))
-                .contains(Occludes::West)
-            {
-                occluded_by |= OccludedBy::East;
-            }
+                if transparent && occlusion.is_transparent(neighbor) {
+                    continue;
+                }
 
-            if occlusion
-                .get(pos.shift(FaceDirection::West, 1))
-                .contains(Occludes::East)
-            {
-                occluded_by |= OccludedBy::West;
+                occluded_by |= OccludedBy::from(dir);
             }
 
             data.set(pos, occluded_by);
@@ -199,6 +175,9 @@ impl Default for BlockDataOccludedBy {
 pub struct BlockDataOccludes {
     /// The occlusion data for each block in the chunk.
     pub data: Box<[Occludes; TOTAL_BLOCKS]>,
+
+    /// The transparency of each block in the chunk.
+    pub transparent: Box<[bool; TOTAL_BLOCKS]>,
 }
 
 impl BlockDataOccludes {
@@ -207,6 +186,7 @@ impl BlockDataOccludes {
     pub fn new() -> Self {
         Self {
             data: Box::new([Occludes::empty(); TOTAL_BLOCKS]),
+            transparent: Box::new([false; TOTAL_BLOCKS]),
         }
     }
 
@@ -216,10 +196,12 @@ impl BlockDataOccludes {
         let mut data = BlockDataOccludes::new();
         for i in 0 .. TOTAL_BLOCKS {
             let block = blocks.get_index(i);
-            data.data[i] = models
-                .get(block)
-                .map(|model| model.occlusion())
-                .unwrap_or(Occludes::empty());
+            let Ok(model) = models.get(block) else {
+                continue;
+            };
+
+            data.data[i] = model.occlusion();
+            data.transparent[i] = model.is_transparent();
         }
 
         data
@@ -234,6 +216,16 @@ impl BlockDataOccludes {
 
         self.data[index]
     }
+
+    /// Gets whether the block at the given position is transparent. If the
+    /// block is outside the chunk bounds, it is considered to be opaque.
+    pub fn is_transparent(&self, pos: BlockPos) -> bool {
+        let Some(index) = pos.index_no_wrap() else {
+            return false;
+        };
+
+        self.transparent[index]
+    }
 }
 
 impl Default for BlockDataOccludes {
@@ -241,3 +233,172 @@ impl Default for BlockDataOccludes {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::SystemState;
+
+    use super::*;
+    use crate::blocks::shape::StairsFacing;
+    use crate::math::CHUNK_SIZE;
+
+    /// The largest coordinate within a chunk.
+    const MAX: i32 = CHUNK_SIZE as i32 - 1;
+
+    /// Creates a cube block shape with the given occlusion properties.
+    fn cube(transparent: bool, non_occluding: bool) -> BlockShape {
+        BlockShape::Cube {
+            tileset: String::new(),
+            top: default(),
+            bottom: default(),
+            north: default(),
+            south: default(),
+            east: default(),
+            west: default(),
+            transparent,
+            non_occluding,
+            variants: Vec::new(),
+            randomize_rotation: false,
+        }
+    }
+
+    /// Computes the occlusion data of a chunk that is filled with air, except
+    /// for the given blocks.
+    fn occluded_by(
+        blocks: impl IntoIterator<Item = (BlockPos, BlockShape)>,
+    ) -> BlockDataOccludedBy {
+        let mut world = World::new();
+        let air = world.spawn(BlockShape::None).id();
+
+        let mut data = ChunkData::fill(air);
+        for (pos, shape) in blocks {
+            let block = world.spawn(shape).id();
+            data.set(pos, block);
+        }
+
+        let mut state = SystemState::<Query<&BlockShape>>::new(&mut world);
+        let shapes = state.get(&world);
+        BlockDataOccludedBy::from_block_data(&data, &shapes)
+    }
+
+    #[test]
+    fn single_block() {
+        let center = BlockPos::new(5, 5, 5);
+        let occlusion = occluded_by([(center, cube(false, false))]);
+
+        assert_eq!(occlusion.get(center), OccludedBy::empty());
+
+        for dir in FaceDirection::DIRECTIONS {
+            let neighbor = center.shift(dir, 1);
+            assert_eq!(occlusion.get(neighbor), OccludedBy::from(dir.opposite()));
+        }
+
+        assert_eq!(occlusion.get(BlockPos::new(6, 6, 5)), OccludedBy::empty());
+        assert_eq!(occlusion.get(BlockPos::new(5, 7, 5)), OccludedBy::empty());
+    }
+
+    #[test]
+    fn solid_slab() {
+        let slab = ChunkIterator::default()
+            .filter(|pos| pos.y == 0)
+            .map(|pos| (pos, cube(false, false)));
+        let occlusion = occluded_by(slab);
+
+        let sides = OccludedBy::North | OccludedBy::South | OccludedBy::East | OccludedBy::West;
+        assert_eq!(occlusion.get(BlockPos::new(5, 0, 5)), sides);
+        assert_eq!(occlusion.get(BlockPos::new(5, 1, 5)), OccludedBy::Down);
+
+        // Blocks outside of the chunk are treated as empty.
+        assert_eq!(
+            occlusion.get(BlockPos::new(0, 0, 5)),
+            sides - OccludedBy::West
+        );
+        assert_eq!(
+            occlusion.get(BlockPos::new(MAX, 0, 5)),
+            sides - OccludedBy::East
+        );
+        assert_eq!(
+            occlusion.get(BlockPos::new(0, 0, 0)),
+            OccludedBy::East | OccludedBy::South
+        );
+        assert_eq!(
+            occlusion.get(BlockPos::new(MAX, 0, MAX)),
+            OccludedBy::West | OccludedBy::North
+        );
+    }
+
+    #[test]
+    fn checkerboard() {
+        let is_solid = |pos: BlockPos| (pos.x + pos.y + pos.z) % 2 == 0;
+        let checkerboard = ChunkIterator::default()
+            .filter(|pos| is_solid(*pos))
+            .map(|pos| (pos, cube(false, false)));
+        let occlusion = occluded_by(checkerboard);
+
+        for pos in ChunkIterator::default() {
+            if is_solid(pos) {
+                assert_eq!(occlusion.get(pos), OccludedBy::empty(), "at {pos:?}");
+                continue;
+            }
+
+            let mut expected = OccludedBy::all();
+            for dir in FaceDirection::DIRECTIONS {
+                if pos.shift(dir, 1).index_no_wrap().is_none() {
+                    expected -= OccludedBy::from(dir);
+                }
+            }
+
+            assert_eq!(occlusion.get(pos), expected, "at {pos:?}");
+        }
+    }
+
+    #[test]
+    fn transparent_blocks() {
+        let a = BlockPos::new(5, 5, 5);
+        let b = BlockPos::new(6, 5, 5);
+
+        let occlusion = occluded_by([(a, cube(true, false)), (b, cube(true, false))]);
+        assert_eq!(occlusion.get(a), OccludedBy::empty());
+        assert_eq!(occlusion.get(b), OccludedBy::empty());
+
+        let occlusion = occluded_by([(a, cube(true, false)), (b, cube(false, false))]);
+        assert_eq!(occlusion.get(a), OccludedBy::East);
+        assert_eq!(occlusion.get(b), OccludedBy::West);
+    }
+
+    #[test]
+    fn non_occluding_blocks() {
+        let a = BlockPos::new(5, 5, 5);
+        let b = BlockPos::new(6, 5, 5);
+
+        let occlusion = occluded_by([(a, cube(false, true)), (b, cube(false, false))]);
+        assert_eq!(occlusion.get(a), OccludedBy::East);
+        assert_eq!(occlusion.get(b), OccludedBy::empty());
+    }
+
+    #[test]
+    fn stairs() {
+        let center = BlockPos::new(5, 5, 5);
+        let stairs = BlockShape::Stairs {
+            tileset: String::new(),
+            facing: StairsFacing::North,
+            top: default(),
+            bottom: default(),
+            north: default(),
+            south: default(),
+            east: default(),
+            west: default(),
+            transparent: false,
+        };
+        let occlusion = occluded_by([(center, stairs)]);
+
+        for dir in FaceDirection::DIRECTIONS {
+            let neighbor = center.shift(dir, 1);
+            let expected = match dir {
+                FaceDirection::Down | FaceDirection::North => OccludedBy::from(dir.opposite()),
+                _ => OccludedBy::empty(),
+            };
+            assert_eq!(occlusion.get(neighbor), expected, "at {dir:?}");
+        }
+    }
+}
@@ -6,10 +6,10 @@ use serde::{Deserialize, Serialize};
 
 use super::occlusion::Occludes;
 use super::tileset::TilePos;
-use crate::math::FaceRotation;
+use crate::math::{FaceDirection, FaceRotation};
 
 /// The shape constructor of a block.
-#[derive(Debug, Default, Clone, Component, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone, PartialEq, Component, Serialize, Deserialize)]
 pub enum BlockShape {
     /// No model.
     #[default]
@@ -37,12 +37,93 @@ pub enum BlockShape {
 
         /// The texture properties of the west face of the block.
         west: BlockFace,
+
+        /// Whether the block is transparent. Transparent blocks still cull
+        /// the faces of opaque neighbors, but do not cull faces against other
+        /// transparent blocks.
+        #[serde(default)]
+        transparent: bool,
+
+        /// Whether the block never culls the faces of its neighbors, even
+        /// though it is a full cube. This is useful for blocks such as leaves,
+        /// where neighboring faces should remain visible through the gaps in
+        /// the texture.
+        #[serde(default)]
+        non_occluding: bool,
+
+        /// The texture variants of the block. Each placed block uses either the
+        /// textures above or one of these variants, picked by its position.
+        #[serde(default)]
+        variants: Vec<BlockVariant>,
+
+        /// Whether each placed block also rotates the texture of its top face
+        /// by a multiple of 90 degrees, picked by its position. This breaks up
+        /// the repetition of large flat areas, such as grass fields.
+        #[serde(default)]
+        randomize_rotation: bool,
+    },
+
+    /// A staircase block, made of a bottom half slab and a quarter step on top
+    /// of it. The faces are textured by the direction they face after the
+    /// stairs are rotated.
+    Stairs {
+        /// The tileset of the block.
+        tileset: String,
+
+        /// The horizontal direction of the tall back side of the stairs. The
+        /// steps rise toward this direction.
+        facing: StairsFacing,
+
+        /// The texture properties of the upward faces of the block.
+        top: BlockFace,
+
+        /// The texture properties of the bottom face of the block.
+        bottom: BlockFace,
+
+        /// The texture properties of the north faces of the block.
+        north: BlockFace,
+
+        /// The texture properties of the south faces of the block.
+        south: BlockFace,
+
+        /// The texture properties of the east faces of the block.
+        east: BlockFace,
+
+        /// The texture properties of the west faces of the block.
+        west: BlockFace,
+
+        /// Whether the block is transparent.
+        #[serde(default)]
+        transparent: bool,
+    },
+
+    /// A plant block, made of two diagonal quads that cross in the center of
+    /// the block, such as grass or flowers. Cross blocks never occlude their
+    /// neighbors, and are never culled by them.
+    Cross {
+        /// The tileset of the block.
+        tileset: String,
+
+        /// The tile position of the plant texture within the tileset.
+        tile: TilePos,
     },
 
     /// A block with a custom shape.
     Custom {
         /// The model name.
         asset: String,
+
+        /// The offset of the model within the block, in blocks, as
+        /// `[x, y, z]`. Used to nudge decorations so they line up with their
+        /// surroundings.
+        #[serde(default)]
+        offset: [f32; 3],
+
+        /// Whether the normals of the model are recomputed by averaging the
+        /// normals of adjacent faces, so that the model is smooth shaded.
+        /// Faces with different materials are never smoothed together.
+        #[serde(default)]
+        smooth_normals: bool,
     },
 }
 
@@ -53,18 +134,219 @@ impl BlockShape {
     /// should always be considered as never occluding.
     ///
     /// This method also assumes that all custom models as fully transparent.
+    /// Cubes that are marked as non-occluding never occlude their neighbors.
+    /// Stairs only occlude through their full bottom and back faces.
     #[inline(always)]
     pub fn occlusion(&self) -> Occludes {
         match self {
             BlockShape::None => Occludes::empty(),
+            BlockShape::Cube { non_occluding, .. } if *non_occluding => Occludes::empty(),
             BlockShape::Cube { .. } => Occludes::all(),
+            BlockShape::Stairs { facing, .. } => {
+                Occludes::Down | Occludes::from(FaceDirection::from(*facing))
+            }
+            BlockShape::Cross { .. } => Occludes::empty(),
             BlockShape::Custom { .. } => Occludes::empty(),
         }
     }
+
+    /// Gets whether the faces of this block are culled when they are occluded
+    /// by neighboring blocks. Cross blocks are never culled, as their quads do
+    /// not lie on the boundary of the block.
+    #[inline(always)]
+    pub fn is_cullable(&self) -> bool {
+        !matches!(self, BlockShape::Cross { .. })
+    }
+
+    /// Returns true if the given name may be used as the model name of a custom
+    /// block shape. Model names are file names within the project `models`
+    /// folder, without the `.glb` extension, so they may only contain ASCII
+    /// letters, digits, spaces, `_`, `-`, and non-leading `.` characters. Path
+    /// separators and `..` are rejected, so a model can never be loaded from
+    /// outside of the `models` folder.
+    pub fn is_valid_model_name(name: &str) -> bool {
+        !name.is_empty()
+            && !name.starts_with('.')
+            && !name.contains("..")
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, ' ' | '_' | '-' | '.'))
+    }
+
+    /// Gets the name of the tileset used by this block shape, if any.
+    pub fn tileset(&self) -> Option<&str> {
+        match self {
+            BlockShape::Cube { tileset, .. }
+            | BlockShape::Stairs { tileset, .. }
+            | BlockShape::Cross { tileset, .. } => Some(tileset),
+            _ => None,
+        }
+    }
+
+    /// Gets a mutable reference to the name of the tileset used by this block
+    /// shape, if any.
+    pub fn tileset_mut(&mut self) -> Option<&mut String> {
+        match self {
+            BlockShape::Cube { tileset, .. }
+            | BlockShape::Stairs { tileset, .. }
+            | BlockShape::Cross { tileset, .. } => Some(tileset),
+            _ => None,
+        }
+    }
+
+    /// Gets whether this block shape references the tileset with the given
+    /// name.
+    pub fn uses_tileset(&self, name: &str) -> bool {
+        self.tileset() == Some(name)
+    }
+
+    /// Gets the texture properties of the faces of this block shape that face
+    /// the given direction, if the shape is textured by a tileset.
+    pub fn face(&self, dir: FaceDirection) -> Option<&BlockFace> {
+        let (BlockShape::Cube {
+            top,
+            bottom,
+            north,
+            south,
+            east,
+            west,
+            ..
+        }
+        | BlockShape::Stairs {
+            top,
+            bottom,
+            north,
+            south,
+            east,
+            west,
+            ..
+        }) = self
+        else {
+            return None;
+        };
+
+        Some(match dir {
+            FaceDirection::Up => top,
+            FaceDirection::Down => bottom,
+            FaceDirection::North => north,
+            FaceDirection::South => south,
+            FaceDirection::East => east,
+            FaceDirection::West => west,
+        })
+    }
+
+    /// Gets a mutable reference to the texture properties of the faces of this
+    /// block shape that face the given direction, if the shape is textured by a
+    /// tileset.
+    pub fn face_mut(&mut self, dir: FaceDirection) -> Option<&mut BlockFace> {
+        let (BlockShape::Cube {
+            top,
+            bottom,
+            north,
+            south,
+            east,
+            west,
+            ..
+        }
+        | BlockShape::Stairs {
+            top,
+            bottom,
+            north,
+            south,
+            east,
+            west,
+            ..
+        }) = self
+        else {
+            return None;
+        };
+
+        Some(match dir {
+            FaceDirection::Up => top,
+            FaceDirection::Down => bottom,
+            FaceDirection::North => north,
+            FaceDirection::South => south,
+            FaceDirection::East => east,
+            FaceDirection::West => west,
+        })
+    }
+
+    /// Gets whether this block shape is transparent. Two neighboring
+    /// transparent blocks never cull their shared faces.
+    #[inline(always)]
+    pub fn is_transparent(&self) -> bool {
+        match self {
+            BlockShape::Cube { transparent, .. } | BlockShape::Stairs { transparent, .. } => {
+                *transparent
+            }
+            _ => false,
+        }
+    }
+}
+
+/// The horizontal direction that the tall back side of a staircase block faces.
+/// Stairs cannot face up or down.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum StairsFacing {
+    /// The back side faces north.
+    #[default]
+    North,
+
+    /// The back side faces south.
+    South,
+
+    /// The back side faces east.
+    East,
+
+    /// The back side faces west.
+    West,
+}
+
+impl StairsFacing {
+    /// An array of all four stairs facing directions.
+    pub const ALL: [StairsFacing; 4] = [
+        StairsFacing::North,
+        StairsFacing::South,
+        StairsFacing::East,
+        StairsFacing::West,
+    ];
+}
+
+impl From<StairsFacing> for FaceDirection {
+    fn from(facing: StairsFacing) -> Self {
+        match facing {
+            StairsFacing::North => FaceDirection::North,
+            StairsFacing::South => FaceDirection::South,
+            StairsFacing::East => FaceDirection::East,
+            StairsFacing::West => FaceDirection::West,
+        }
+    }
+}
+
+/// A texture variant of a cube block. A variant shifts the tiles of all faces
+/// of the block by the given number of columns and rows within the tileset,
+/// wrapping around at the edges of the tileset.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct BlockVariant {
+    /// The number of columns to shift the tiles by.
+    pub columns: u8,
+
+    /// The number of rows to shift the tiles by.
+    pub rows: u8,
+}
+
+impl BlockVariant {
+    /// Applies this variant to the given block face.
+    pub fn apply(self, face: &BlockFace) -> BlockFace {
+        BlockFace {
+            tile: face.tile.shifted(self.columns, self.rows),
+            ..face.clone()
+        }
+    }
 }
 
 /// The texture properties of a face of a block.
-#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BlockFace {
     /// The tile position of the block face within the tileset.
     pub tile: TilePos,
@@ -77,4 +359,127 @@ pub struct BlockFace {
 
     /// Whether the texture is mirrored along the y-axis. (Before rotation)
     pub mirror_y: bool,
+
+    /// The scale of the texture coordinates within the tile. (After rotation)
+    ///
+    /// A scale below one samples a smaller sub-region of the tile. Texture
+    /// coordinates are clamped to stay within the tile, so a scale above one
+    /// stretches the edge of the tile rather than repeating it.
+    #[serde(default = "default_uv_scale")]
+    pub uv_scale: Vec2,
+
+    /// The offset of the texture coordinates within the tile, in tile-local
+    /// coordinates from 0 to 1. (After scaling)
+    #[serde(default)]
+    pub uv_offset: Vec2,
+
+    /// Whether the face uses connected textures, so that neighboring blocks of
+    /// the same type form a seamless surface. The tile of a connected face is
+    /// the top-left tile of a 4x4 grid of tiles, one for each combination of
+    /// connected neighbors. See [`BlockMeshPart::connected`]. Only cube faces
+    /// support connected textures.
+    ///
+    /// [`BlockMeshPart::connected`]: super::mesh::BlockMeshPart::connected
+    #[serde(default)]
+    pub connected: bool,
+
+    /// Whether the face is tinted by the biome the block is placed in, such as
+    /// the top of grass blocks. Faces without this flag are never tinted.
+    #[serde(default)]
+    pub biome_tint: bool,
+}
+
+impl Default for BlockFace {
+    fn default() -> Self {
+        Self {
+            tile: TilePos::default(),
+            rotation: FaceRotation::default(),
+            mirror_x: false,
+            mirror_y: false,
+            uv_scale: default_uv_scale(),
+            uv_offset: Vec2::ZERO,
+            connected: false,
+            biome_tint: false,
+        }
+    }
+}
+
+/// The default UV scale of a block face, used when no scale is stored.
+fn default_uv_scale() -> Vec2 {
+    Vec2::ONE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocks::mesh::BlockMeshPart;
+    use crate::blocks::systems::build_stairs_mesh;
+
+    /// Returns the total area of the quads in the given mesh part.
+    fn part_area(part: &Option<BlockMeshPart>) -> f32 {
+        let Some(part) = part else {
+            return 0.0;
+        };
+
+        part.vertices
+            .chunks(4)
+            .map(|quad| {
+                let a = quad[1].position - quad[0].position;
+                let b = quad[3].position - quad[0].position;
+                a.cross(b).length()
+            })
+            .sum()
+    }
+
+    #[test]
+    fn stairs_occlusion_matches_mesh() {
+        for facing in StairsFacing::ALL {
+            let shape = BlockShape::Stairs {
+                tileset: String::new(),
+                facing,
+                top: default(),
+                bottom: default(),
+                north: default(),
+                south: default(),
+                east: default(),
+                west: default(),
+                transparent: false,
+            };
+            let mesh = build_stairs_mesh(facing, &shape);
+            let occlusion = shape.occlusion();
+
+            for dir in FaceDirection::DIRECTIONS {
+                let part = match dir {
+                    FaceDirection::Up => &mesh.top,
+                    FaceDirection::Down => &mesh.bottom,
+                    FaceDirection::North => &mesh.north,
+                    FaceDirection::South => &mesh.south,
+                    FaceDirection::East => &mesh.east,
+                    FaceDirection::West => &mesh.west,
+                };
+
+                let full_face = (part_area(part) - 1.0).abs() < 1e-4;
+                assert_eq!(
+                    occlusion.contains(Occludes::from(dir)),
+                    full_face,
+                    "Stairs facing {facing:?} disagree on the {dir} face"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn model_names_cannot_escape_models_folder() {
+        assert!(BlockShape::is_valid_model_name("oak_tree"));
+        assert!(BlockShape::is_valid_model_name("Fence Post-2.v1"));
+
+        assert!(!BlockShape::is_valid_model_name(""));
+        assert!(!BlockShape::is_valid_model_name("../../secret"));
+        assert!(!BlockShape::is_valid_model_name(".."));
+        assert!(!BlockShape::is_valid_model_name(".hidden"));
+        assert!(!BlockShape::is_valid_model_name("trees/oak"));
+        assert!(!BlockShape::is_valid_model_name("trees\\oak"));
+        assert!(!BlockShape::is_valid_model_name("C:secret"));
+        assert!(!BlockShape::is_valid_model_name("embedded://awgen/model"));
+    }
 }
@@ -0,0 +1,86 @@
+//! This module implements the sounds that are played when a block is placed or
+//! removed within the world.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::Block;
+use crate::settings::{ProjectSettings, ProjectSettingsError};
+
+/// The prefix of the project settings key used to store the sounds of a block.
+/// The name of the block is appended to this prefix, so the key must be moved
+/// with [`ProjectSettings::rename`] whenever the block is renamed.
+pub const BLOCK_SOUNDS_KEY_PREFIX: &str = "BLOCK_SOUNDS_";
+
+/// The sounds of a block. Each sound is the asset path of an audio clip, such
+/// as `project://sounds/stone_place.ogg`. Blocks without a sound use the
+/// default editor sound instead.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Component, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct BlockSounds {
+    /// The sound played when the block is placed.
+    pub place: Option<String>,
+
+    /// The sound played when the block is removed.
+    pub remove: Option<String>,
+}
+
+impl BlockSounds {
+    /// Returns the project settings key used to store the sounds of the block
+    /// with the given name.
+    pub fn settings_key(block: &str) -> String {
+        format!("{BLOCK_SOUNDS_KEY_PREFIX}{block}")
+    }
+
+    /// Loads the sounds of the block with the given name from the project
+    /// settings. If no sounds are stored, or they cannot be read, no sounds are
+    /// returned.
+    pub fn load(project_settings: &ProjectSettings, block: &str) -> Self {
+        match project_settings.get_json(&Self::settings_key(block)) {
+            Ok(sounds) => sounds.unwrap_or_default(),
+            Err(err) => {
+                error!("Failed to read sounds for block {block}: {err}");
+                Self::default()
+            }
+        }
+    }
+
+    /// Saves these sounds for the block with the given name to the project
+    /// settings. If the block has no sounds, the stored sounds are removed
+    /// instead.
+    pub fn save(
+        &self,
+        project_settings: &ProjectSettings,
+        block: &str,
+    ) -> Result<(), ProjectSettingsError> {
+        let value = (*self != Self::default()).then_some(self);
+        project_settings.set_json(&Self::settings_key(block), value)
+    }
+
+    /// Creates the sounds of a block from the given asset paths. Empty paths
+    /// are treated as missing sounds.
+    pub fn from_paths(place: &str, remove: &str) -> Self {
+        let path = |path: &str| {
+            let path = path.trim();
+            (!path.is_empty()).then(|| path.to_string())
+        };
+
+        Self {
+            place: path(place),
+            remove: path(remove),
+        }
+    }
+}
+
+/// This system loads the stored sounds of all newly spawned blocks from the
+/// project settings.
+pub fn load_block_sounds(
+    project_settings: Res<ProjectSettings>,
+    blocks: Query<(Entity, &Name), (Added<Block>, Without<BlockSounds>)>,
+    mut commands: Commands,
+) {
+    for (block_id, name) in blocks.iter() {
+        let sounds = BlockSounds::load(&project_settings, name.as_str());
+        commands.entity(block_id).insert(sounds);
+    }
+}
@@ -4,15 +4,20 @@ use bevy::gltf::{GltfMesh, GltfNode};
 use bevy::math::Vec3A;
 use bevy::math::bounding::Aabb3d;
 use bevy::prelude::*;
+use bevy::utils::HashSet;
 use uuid::Uuid;
 
+use super::collision::BlockCollision;
 use super::mesh::{BlockMesh, BlockVertex};
-use super::model::BlockModel;
+use super::model::{BlockModel, CustomModelIndex};
 use super::occlusion::OccludedBy;
-use super::shape::{BlockFace, BlockShape};
-use super::tileset::{TilePos, Tileset};
-use super::{AIR_BLOCK_NAME, AIR_BLOCK_UUID, Block, RenderedBlock};
+use super::params::BlockFinder;
+use super::shape::{BlockFace, BlockShape, BlockVariant, StairsFacing};
+use super::tileset::{MissingMaterial, TilePos, Tileset, TilesetUvInset, TransparentMaterial};
+use super::{AIR_BLOCK_NAME, AIR_BLOCK_UUID, Block, DeleteBlock, ReloadBlockAssets, RenderedBlock};
 use crate::blocks::mesh::BlockMeshPart;
+use crate::map::chunk::ChunkData;
+use crate::map::remesh::NeedsRemesh;
 use crate::math::{FaceDirection, FaceRotation};
 use crate::utilities::meshbuf::MeshBuf;
 
@@ -81,15 +86,47 @@ pub fn forward_model_changes_to_rendered(
 }
 
 /// This system listens for changes in block shapes and updates the block models
-/// accordingly.
+/// accordingly. Cube blocks that reference a missing tileset are rendered with
+/// the [`MissingMaterial`] placeholder.
 pub fn update_block_model(
     asset_server: Res<AssetServer>,
-    chunk_materials: Query<(&Handle<StandardMaterial>, &Name), With<Tileset>>,
+    chunk_materials: Query<
+        (
+            &Handle<StandardMaterial>,
+            &TransparentMaterial,
+            &TilesetUvInset,
+            &Name,
+        ),
+        With<Tileset>,
+    >,
+    missing_material: Res<MissingMaterial>,
     mut models: Query<(&mut BlockModel, &BlockShape, &Name), Changed<BlockShape>>,
 ) {
     for (mut model, shape, name) in models.iter_mut() {
         info!("Updating block model for block: {}", name);
 
+        let find_material = |tileset: &str, transparent: bool| {
+            chunk_materials
+                .iter()
+                .find(|(_, _, _, name)| name.as_str() == tileset)
+                .map(|(material, transparent_material, uv_inset, _)| {
+                    let material = if transparent {
+                        transparent_material.0.clone()
+                    } else {
+                        material.clone()
+                    };
+                    (material, uv_inset.tile_pixels)
+                })
+                .unwrap_or_else(|| {
+                    warn!(
+                        "Tried to update block model for {}, but failed to find material for tileset: {}",
+                        name,
+                        tileset
+                    );
+                    (missing_material.0.clone(), None)
+                })
+        };
+
         match shape {
             BlockShape::None => {
                 *model = BlockModel::None;
@@ -102,85 +139,100 @@ pub fn update_block_model(
                 south,
                 east,
                 west,
+                transparent,
+                variants,
+                randomize_rotation,
+                ..
             } => {
-                let material = chunk_materials
-                    .iter()
-                    .find(|(_, name)| ***name == *tileset)
-                    .map(|(material, _)| material.clone())
-                    .unwrap_or_else(|| {
-                        warn!(
-                            "Tried to update block model for {}, but failed to find material for tileset: {}",
-                            name,
-                            tileset
+                let (material, tile_pixels) = find_material(tileset, *transparent);
+                let rotations: &[FaceRotation] = if *randomize_rotation {
+                    &FaceRotation::ALL
+                } else {
+                    &[FaceRotation::C0]
+                };
+
+                // The base textures are the first variant, and each variant is
+                // repeated for every random rotation of its top face.
+                let mut variants: Vec<BlockMesh> = std::iter::once(BlockVariant::default())
+                    .chain(variants.iter().copied())
+                    .flat_map(|variant| rotations.iter().map(move |rotation| (variant, *rotation)))
+                    .map(|(variant, rotation)| {
+                        let top = variant.apply(top);
+                        let mut mesh = build_cube_mesh(
+                            &BlockFace {
+                                rotation: top.rotation.then(rotation),
+                                ..top
+                            },
+                            &variant.apply(bottom),
+                            &variant.apply(north),
+                            &variant.apply(south),
+                            &variant.apply(east),
+                            &variant.apply(west),
                         );
-                        Default::default()
-                    });
+                        mesh.tile_pixels = tile_pixels;
+                        mesh
+                    })
+                    .collect();
 
-                let mut mesh = BlockMesh::default();
+                let mesh = variants.remove(0);
+                let bounds = mesh.get_bounds();
 
-                let mut top_quad = quad(
-                    FaceDirection::Up.rotation_quat(),
-                    Vec3::new(0.0, 0.5, 0.0) + Vec3::splat(0.5),
-                    Vec3::ONE,
-                    top.tile,
-                );
-                update_uv(&mut top_quad, top);
-                mesh.top = Some(top_quad.into());
-
-                let mut bottom_quad = quad(
-                    FaceDirection::Down.rotation_quat(),
-                    Vec3::new(0.0, -0.5, 0.0) + Vec3::splat(0.5),
-                    Vec3::ONE,
-                    bottom.tile,
-                );
-                update_uv(&mut bottom_quad, bottom);
-                mesh.bottom = Some(bottom_quad.into());
-
-                let mut north_quad = quad(
-                    FaceDirection::North.rotation_quat(),
-                    Vec3::new(0.0, 0.0, -0.5) + Vec3::splat(0.5),
-                    Vec3::ONE,
-                    north.tile,
-                );
-                update_uv(&mut north_quad, north);
-                mesh.north = Some(north_quad.into());
-
-                let mut south_quad = quad(
-                    FaceDirection::South.rotation_quat(),
-                    Vec3::new(0.0, 0.0, 0.5) + Vec3::splat(0.5),
-                    Vec3::ONE,
-                    south.tile,
-                );
-                update_uv(&mut south_quad, south);
-                mesh.south = Some(south_quad.into());
-
-                let mut east_quad = quad(
-                    FaceDirection::East.rotation_quat(),
-                    Vec3::new(0.5, 0.0, 0.0) + Vec3::splat(0.5),
-                    Vec3::ONE,
-                    east.tile,
-                );
-                update_uv(&mut east_quad, east);
-                mesh.east = Some(east_quad.into());
-
-                let mut west_quad = quad(
-                    FaceDirection::West.rotation_quat(),
-                    Vec3::new(-0.5, 0.0, 0.0) + Vec3::splat(0.5),
-                    Vec3::ONE,
-                    west.tile,
-                );
-                update_uv(&mut west_quad, west);
-                mesh.west = Some(west_quad.into());
+                *model = BlockModel::Primitive {
+                    material,
+                    mesh: Box::new(mesh),
+                    variants,
+                    bounds,
+                };
+            }
+            BlockShape::Stairs {
+                tileset,
+                facing,
+                transparent,
+                ..
+            } => {
+                let (material, tile_pixels) = find_material(tileset, *transparent);
+                let mut mesh = build_stairs_mesh(*facing, shape);
+                mesh.tile_pixels = tile_pixels;
+                let bounds = mesh.get_bounds();
 
+                *model = BlockModel::Primitive {
+                    material,
+                    mesh: Box::new(mesh),
+                    variants: Vec::new(),
+                    bounds,
+                };
+            }
+            BlockShape::Cross { tileset, tile } => {
+                let (material, tile_pixels) = find_material(tileset, false);
+                let mut mesh = build_cross_mesh(*tile);
+                mesh.tile_pixels = tile_pixels;
                 let bounds = mesh.get_bounds();
 
                 *model = BlockModel::Primitive {
                     material,
                     mesh: Box::new(mesh),
+                    variants: Vec::new(),
+                    bounds,
+                };
+            }
+            BlockShape::Custom { asset, .. } if !BlockShape::is_valid_model_name(asset) => {
+                warn!(
+                    "Invalid custom model name for block {}: {:?}; Using placeholder.",
+                    name, asset
+                );
+
+                let face = BlockFace::default();
+                let mesh = build_cube_mesh(&face, &face, &face, &face, &face, &face);
+                let bounds = mesh.get_bounds();
+
+                *model = BlockModel::Primitive {
+                    material: missing_material.0.clone(),
+                    mesh: Box::new(mesh),
+                    variants: Vec::new(),
                     bounds,
                 };
             }
-            BlockShape::Custom { asset } => {
+            BlockShape::Custom { asset, .. } => {
                 let model_path = format!("project://models/{asset}.glb");
                 let default_mat = GltfAssetLabel::DefaultMaterial.from_asset(model_path.clone());
 
@@ -197,73 +249,370 @@ pub fn update_block_model(
     }
 }
 
+/// This system keeps the [`CustomModelIndex`] up to date as block models are
+/// changed or removed.
+pub fn index_custom_block_models(
+    mut index: ResMut<CustomModelIndex>,
+    models: Query<(Entity, &BlockModel), Changed<BlockModel>>,
+    mut removed: RemovedComponents<BlockModel>,
+) {
+    for block in removed.read() {
+        index.remove(block);
+    }
+
+    for (block, model) in models.iter() {
+        let asset = match model {
+            BlockModel::Custom { asset, .. } => Some(asset.id()),
+            _ => None,
+        };
+
+        index.set(block, asset);
+    }
+}
+
 /// This system listens for asset events and updates custom block models as the
-/// linked assets finish loading.
+/// linked assets finish loading. Only the blocks that use a loaded asset are
+/// updated, as looked up through the [`CustomModelIndex`]. Custom block models
+/// that have just been created for an asset that is already loaded are updated
+/// immediately, since no new asset event will be sent for them. The model is
+/// moved by the offset of the custom block shape, and smooth shaded if enabled.
 pub fn update_custom_block_model_mesh(
     mut asset_events: EventReader<AssetEvent<Gltf>>,
+    asset_server: Res<AssetServer>,
+    mut index: ResMut<CustomModelIndex>,
     gltf: Res<Assets<Gltf>>,
     gltf_nodes: Res<Assets<GltfNode>>,
     gltf_meshes: Res<Assets<GltfMesh>>,
     meshes: Res<Assets<Mesh>>,
-    mut models: Query<(&mut BlockModel, &Name)>,
+    mut models: Query<(&mut BlockModel, &BlockShape, &Name)>,
 ) {
+    let mut updates = HashSet::new();
     for ev in asset_events.read() {
-        let AssetEvent::LoadedWithDependencies { id } = ev else {
+        let (AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id }) = ev else {
             continue;
         };
 
         info!("Loaded custom mesh asset with ID: {}", id);
+        updates.extend(index.blocks_using(*id).map(|block| (block, *id)));
+    }
 
-        for (mut model, name) in models.iter_mut() {
-            let BlockModel::Custom {
-                asset,
-                mesh,
-                bounds,
-                material,
-                ..
-            } = &mut *model
-            else {
-                continue;
-            };
+    updates.extend(
+        index
+            .take_pending()
+            .into_iter()
+            .filter(|(_, id)| asset_server.is_loaded_with_dependencies(*id)),
+    );
 
-            if asset.id() != *id {
-                continue;
-            }
+    for (block, id) in updates {
+        let Ok((mut model, shape, name)) = models.get_mut(block) else {
+            continue;
+        };
 
-            let Some(gltf_data) = gltf.get(asset) else {
-                error!("Failed to retrieve custom mesh for block: {name}");
-                continue;
-            };
+        let Some(gltf_data) = gltf.get(id) else {
+            error!("Failed to retrieve custom mesh for block: {name}");
+            continue;
+        };
 
-            let mut block_mesh = BlockMeshPart::default();
+        let BlockModel::Custom {
+            mesh,
+            bounds,
+            material,
+            ..
+        } = &mut *model
+        else {
+            continue;
+        };
 
-            for gltf_node_handle in &gltf_data.nodes {
-                let gltf_node = gltf_nodes.get(gltf_node_handle).unwrap();
+        let (offset, smooth_normals) = match shape {
+            BlockShape::Custom {
+                offset,
+                smooth_normals,
+                ..
+            } => (Vec3::from(*offset), *smooth_normals),
+            _ => (Vec3::ZERO, false),
+        };
+
+        let mut block_mesh = BlockMeshPart::default();
 
-                let mut transform = gltf_node.transform;
-                transform.translation += Vec3::new(0.5, 0.0, 0.5);
+        for gltf_node_handle in &gltf_data.nodes {
+            let gltf_node = gltf_nodes.get(gltf_node_handle).unwrap();
 
-                if let Some(mesh_handle) = &gltf_node.mesh {
-                    let gltf_mesh = gltf_meshes.get(mesh_handle).unwrap();
-                    for primitive in &gltf_mesh.primitives {
-                        if let Some(mat) = &primitive.material {
-                            *material = mat.clone();
-                        }
-                        let raw_mesh = meshes.get(&primitive.mesh).unwrap();
-                        block_mesh.extend(&BlockMeshPart::new_from(raw_mesh, transform));
+            let mut transform = gltf_node.transform;
+            transform.translation += Vec3::new(0.5, 0.0, 0.5) + offset;
+
+            if let Some(mesh_handle) = &gltf_node.mesh {
+                let gltf_mesh = gltf_meshes.get(mesh_handle).unwrap();
+                for primitive in &gltf_mesh.primitives {
+                    if let Some(mat) = &primitive.material {
+                        *material = mat.clone();
+                    }
+                    let raw_mesh = meshes.get(&primitive.mesh).unwrap();
+                    let mut part = BlockMeshPart::new_from(raw_mesh, transform);
+                    if smooth_normals {
+                        // Each primitive has a single material, so smoothing
+                        // them separately keeps hard edges between materials.
+                        part.smooth_normals();
                     }
+                    block_mesh.extend(&part);
                 }
             }
+        }
 
-            let block_mesh = BlockMesh {
-                center: Some(block_mesh),
-                ..default()
-            };
-            *bounds = block_mesh.get_bounds();
-            *mesh = Box::new(block_mesh);
+        let block_mesh = BlockMesh {
+            center: Some(block_mesh),
+            ..default()
+        };
+        *bounds = block_mesh.get_bounds();
+        *mesh = Box::new(block_mesh);
+
+        info!("Loaded custom mesh model for block: {name}");
+    }
+}
+
+/// This system listens for [`ReloadBlockAssets`] events and re-issues the asset
+/// loads for all tileset images and custom block models. The reloaded assets
+/// are picked up by [`update_custom_block_model_mesh`] and
+/// [`refresh_reloaded_tilesets`](super::tileset::refresh_reloaded_tilesets)
+/// once they finish loading.
+pub fn reload_block_assets(
+    mut reload_events: EventReader<ReloadBlockAssets>,
+    asset_server: Res<AssetServer>,
+    tilesets: Query<(&Handle<Image>, &Name), With<Tileset>>,
+    models: Query<(&BlockModel, &Name)>,
+) {
+    if reload_events.is_empty() {
+        return;
+    }
+    reload_events.clear();
+
+    for (image, name) in tilesets.iter() {
+        let Some(path) = image.path() else {
+            continue;
+        };
+
+        info!("Reloading tileset {name} from: {path}");
+        asset_server.reload(path.clone());
+    }
+
+    for (model, name) in models.iter() {
+        let BlockModel::Custom { asset, .. } = model else {
+            continue;
+        };
+
+        let Some(path) = asset.path() else {
+            continue;
+        };
+
+        info!("Reloading custom model for block {name} from: {path}");
+        asset_server.reload(path.clone());
+    }
+}
+
+/// This system listens for [`DeleteBlock`] events, replaces all instances of
+/// the deleted blocks within the world with air, and despawns the block
+/// entities.
+pub fn delete_blocks(
+    mut delete_events: EventReader<DeleteBlock>,
+    block_finder: BlockFinder,
+    blocks: Query<(&Block, &Name)>,
+    mut chunks: Query<(Entity, &mut ChunkData)>,
+    mut commands: Commands,
+) {
+    for ev in delete_events.read() {
+        let Ok((block, name)) = blocks.get(ev.block) else {
+            warn!("Tried to delete block {}, but it does not exist.", ev.block);
+            continue;
+        };
+
+        if block.uuid == AIR_BLOCK_UUID {
+            warn!("The air block cannot be deleted.");
+            continue;
+        }
+
+        let air_block = block_finder.find_air();
+        for (chunk_id, mut chunk) in chunks.iter_mut() {
+            if chunk.replace(ev.block, air_block) {
+                chunk.try_convert_to_single();
+                commands.entity(chunk_id).insert(NeedsRemesh);
+            }
+        }
+
+        info!("Deleting block: {}", name);
+        commands.entity(ev.block).despawn_recursive();
+    }
+}
+
+/// This system listens for the reload hotkey and requests that all block
+/// assets be reloaded.
+#[cfg(feature = "editor")]
+pub fn reload_block_assets_hotkey(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut reload_events: EventWriter<ReloadBlockAssets>,
+) {
+    if keyboard_input.just_pressed(KeyCode::F5) {
+        reload_events.send(ReloadBlockAssets);
+        info!("Requested reload of all block assets.");
+    }
+}
+
+/// Builds the mesh of a standard cubic block with the given face texture
+/// properties. The cube spans from `(0, 0, 0)` to `(1, 1, 1)`.
+pub fn build_cube_mesh(
+    top: &BlockFace,
+    bottom: &BlockFace,
+    north: &BlockFace,
+    south: &BlockFace,
+    east: &BlockFace,
+    west: &BlockFace,
+) -> BlockMesh {
+    let mut mesh = BlockMesh::default();
+
+    let top_quad = quad(
+        FaceDirection::Up.rotation_quat(),
+        Vec3::new(0.0, 0.5, 0.0) + Vec3::splat(0.5),
+        Vec3::ONE,
+        top.tile,
+    );
+    mesh.top = Some(face_part(top_quad, top));
+
+    let bottom_quad = quad(
+        FaceDirection::Down.rotation_quat(),
+        Vec3::new(0.0, -0.5, 0.0) + Vec3::splat(0.5),
+        Vec3::ONE,
+        bottom.tile,
+    );
+    mesh.bottom = Some(face_part(bottom_quad, bottom));
+
+    let north_quad = quad(
+        FaceDirection::North.rotation_quat(),
+        Vec3::new(0.0, 0.0, -0.5) + Vec3::splat(0.5),
+        Vec3::ONE,
+        north.tile,
+    );
+    mesh.north = Some(face_part(north_quad, north));
+
+    let south_quad = quad(
+        FaceDirection::South.rotation_quat(),
+        Vec3::new(0.0, 0.0, 0.5) + Vec3::splat(0.5),
+        Vec3::ONE,
+        south.tile,
+    );
+    mesh.south = Some(face_part(south_quad, south));
+
+    let east_quad = quad(
+        FaceDirection::East.rotation_quat(),
+        Vec3::new(0.5, 0.0, 0.0) + Vec3::splat(0.5),
+        Vec3::ONE,
+        east.tile,
+    );
+    mesh.east = Some(face_part(east_quad, east));
+
+    let west_quad = quad(
+        FaceDirection::West.rotation_quat(),
+        Vec3::new(-0.5, 0.0, 0.0) + Vec3::splat(0.5),
+        Vec3::ONE,
+        west.tile,
+    );
+    mesh.west = Some(face_part(west_quad, west));
+
+    mesh
+}
+
+/// A quad of the canonical stairs mesh, which faces north. The quads are
+/// listed as their facing direction, center, size, and whether they lie on the
+/// boundary of the block and can be culled by neighboring blocks.
+const STAIRS_QUADS: [(FaceDirection, [f32; 3], [f32; 2], bool); 10] = [
+    // Bottom
+    (FaceDirection::Down, [0.5, 0.0, 0.5], [1.0, 1.0], true),
+    // Back
+    (FaceDirection::North, [0.5, 0.5, 0.0], [1.0, 1.0], true),
+    // Front of the lower step
+    (FaceDirection::South, [0.5, 0.25, 1.0], [1.0, 0.5], true),
+    // Front of the upper step
+    (FaceDirection::South, [0.5, 0.75, 0.5], [1.0, 0.5], false),
+    // Top of the lower step
+    (FaceDirection::Up, [0.5, 0.5, 0.75], [1.0, 0.5], false),
+    // Top of the upper step
+    (FaceDirection::Up, [0.5, 1.0, 0.25], [1.0, 0.5], true),
+    // Sides of the lower step
+    (FaceDirection::East, [1.0, 0.25, 0.5], [1.0, 0.5], true),
+    (FaceDirection::West, [0.0, 0.25, 0.5], [1.0, 0.5], true),
+    // Sides of the upper step
+    (FaceDirection::East, [1.0, 0.75, 0.25], [0.5, 0.5], true),
+    (FaceDirection::West, [0.0, 0.75, 0.25], [0.5, 0.5], true),
+];
+
+/// Builds the mesh of a staircase block, with the tall back side facing the
+/// given horizontal direction. The faces are textured by the faces of the given
+/// shape, based on the direction they face after rotation. Each face samples
+/// the part of the texture that matches its position on the block, so that
+/// neighboring stairs and cubes line up. The stairs span from `(0, 0, 0)` to
+/// `(1, 1, 1)`.
+pub fn build_stairs_mesh(facing: StairsFacing, shape: &BlockShape) -> BlockMesh {
+    let turn = match facing {
+        StairsFacing::North => Quat::IDENTITY,
+        StairsFacing::South => Quat::from_rotation_y(std::f32::consts::PI),
+        StairsFacing::East => Quat::from_rotation_y(-std::f32::consts::FRAC_PI_2),
+        StairsFacing::West => Quat::from_rotation_y(std::f32::consts::FRAC_PI_2),
+    };
+
+    let center = Vec3::splat(0.5);
+    let mut mesh = BlockMesh::default();
+
+    for (dir, pos, size, boundary) in STAIRS_QUADS {
+        let world_dir = FaceDirection::from_normal(turn * Vec3::from(dir)).unwrap();
+        let face = shape.face(world_dir).cloned().unwrap_or_default();
+
+        let scale = Vec3::new(size[0], size[1], 1.0);
+        let mut vertices = quad(dir.rotation_quat(), Vec3::from(pos), scale, face.tile);
+        let face_center = center + Vec3::from(world_dir) * 0.5;
+        let to_face = world_dir.rotation_quat().inverse();
 
-            info!("Loaded custom mesh model for block: {name}");
+        for vertex in vertices.iter_mut() {
+            vertex.position = turn * (vertex.position - center) + center;
+            vertex.normal = turn * vertex.normal;
+
+            // Project the vertex onto the full block face, so that the face
+            // samples the matching part of the tile.
+            let local = to_face * (vertex.position - face_center);
+            let uv = Vec2::new(local.x + 0.5, 0.5 - local.y);
+            vertex.uv = transform_face_uv(uv, &face);
         }
+
+        let part = match (boundary, world_dir) {
+            (false, _) => &mut mesh.center,
+            (true, FaceDirection::Up) => &mut mesh.top,
+            (true, FaceDirection::Down) => &mut mesh.bottom,
+            (true, FaceDirection::North) => &mut mesh.north,
+            (true, FaceDirection::South) => &mut mesh.south,
+            (true, FaceDirection::East) => &mut mesh.east,
+            (true, FaceDirection::West) => &mut mesh.west,
+        };
+
+        part.get_or_insert_with(BlockMeshPart::default)
+            .extend(&BlockMeshPart::from(vertices));
+    }
+
+    mesh
+}
+
+/// Builds the mesh of a cross block, made of two diagonal quads that cross in
+/// the center of the block. Both quads are double sided, so the block is
+/// visible from every direction. All quads are stored in the center part of
+/// the mesh, as none of them lie on the boundary of the block.
+pub fn build_cross_mesh(tile: TilePos) -> BlockMesh {
+    let scale = Vec3::new(std::f32::consts::SQRT_2, 1.0, 1.0);
+    let mut part = BlockMeshPart::default();
+
+    for angle in [45f32, 135.0, 225.0, 315.0] {
+        let rot = Quat::from_rotation_y(angle.to_radians());
+        let vertices = quad(rot, Vec3::splat(0.5), scale, tile);
+        part.extend(&BlockMeshPart::from(vertices));
+    }
+
+    BlockMesh {
+        center: Some(part),
+        ..default()
     }
 }
 
@@ -298,42 +647,47 @@ fn quad(rot: Quat, translate: Vec3, scale: Vec3, tile: TilePos) -> [BlockVertex;
     vertices
 }
 
-/// Updates the UV coordinates of a quad based on the texture properties of the
-/// face.
-fn update_uv(quad: &mut [BlockVertex; 4], face: &BlockFace) {
-    let uv = [
-        Vec2::new(0.0, 1.0),
-        Vec2::new(1.0, 1.0),
-        Vec2::new(1.0, 0.0),
-        Vec2::new(0.0, 0.0),
-    ];
+/// Creates the mesh part of a full quad, as created by [`quad`], updating its
+/// UV coordinates based on the texture properties of the face.
+fn face_part(mut quad: [BlockVertex; 4], face: &BlockFace) -> BlockMeshPart {
+    for vertex in quad.iter_mut() {
+        vertex.uv = transform_face_uv(vertex.uv, face);
+    }
+
+    BlockMeshPart {
+        connected: face.connected,
+        biome_tint: face.biome_tint,
+        ..quad.into()
+    }
+}
+
+/// Transforms a tile-local UV coordinate based on the texture properties of the
+/// face. The texture is rotated, then mirrored, then scaled and offset.
+fn transform_face_uv(uv: Vec2, face: &BlockFace) -> Vec2 {
+    let uv = match face.rotation {
+        FaceRotation::C0 => uv,
+        FaceRotation::C90 => Vec2::new(1.0 - uv.y, uv.x),
+        FaceRotation::C180 => Vec2::new(1.0 - uv.x, 1.0 - uv.y),
+        FaceRotation::C270 => Vec2::new(uv.y, 1.0 - uv.x),
+    };
 
     let uv = if face.mirror_x {
-        [uv[1], uv[0], uv[3], uv[2]]
+        Vec2::new(1.0 - uv.x, uv.y)
     } else {
         uv
     };
 
     let uv = if face.mirror_y {
-        [uv[3], uv[2], uv[1], uv[0]]
+        Vec2::new(uv.x, 1.0 - uv.y)
     } else {
         uv
     };
 
-    let uv = match face.rotation {
-        FaceRotation::C0 => uv,
-        FaceRotation::C90 => [uv[3], uv[0], uv[1], uv[2]],
-        FaceRotation::C180 => [uv[2], uv[3], uv[0], uv[1]],
-        FaceRotation::C270 => [uv[1], uv[2], uv[3], uv[0]],
-    };
-
-    for (vertex, uv) in quad.iter_mut().zip(uv.iter()) {
-        vertex.uv = *uv;
-    }
+    (face.uv_offset + uv * face.uv_scale).clamp(Vec2::ZERO, Vec2::ONE)
 }
 
-/// This system is called on startup to load all block definitions into the
-/// world.
+/// This system is called when the project is loaded to load all block
+/// definitions into the world.
 pub fn load_blocks(mut commands: Commands) {
     // TODO: Load blocks from a file or database.
 
@@ -343,6 +697,7 @@ pub fn load_blocks(mut commands: Commands) {
         },
         Name::new(AIR_BLOCK_NAME),
         BlockModel::default(),
+        BlockCollision::default(),
         BlockShape::None,
     ));
 
@@ -352,10 +707,12 @@ pub fn load_blocks(mut commands: Commands) {
         },
         Name::new("Grass"),
         BlockModel::default(),
+        BlockCollision::default(),
         BlockShape::Cube {
             tileset: "overworld".to_string(),
             top: BlockFace {
                 tile: TilePos::new(0, 0),
+                biome_tint: true,
                 ..default()
             },
             bottom: BlockFace {
@@ -378,6 +735,10 @@ pub fn load_blocks(mut commands: Commands) {
                 tile: TilePos::new(2, 0),
                 ..default()
             },
+            transparent: false,
+            non_occluding: false,
+            variants: Vec::new(),
+            randomize_rotation: true,
         },
     ));
 
@@ -387,6 +748,7 @@ pub fn load_blocks(mut commands: Commands) {
         },
         Name::new("Dirt"),
         BlockModel::default(),
+        BlockCollision::default(),
         BlockShape::Cube {
             tileset: "overworld".to_string(),
             top: BlockFace {
@@ -413,6 +775,10 @@ pub fn load_blocks(mut commands: Commands) {
                 tile: TilePos::new(1, 0),
                 ..default()
             },
+            transparent: false,
+            non_occluding: false,
+            variants: Vec::new(),
+            randomize_rotation: false,
         },
     ));
 
@@ -422,6 +788,7 @@ pub fn load_blocks(mut commands: Commands) {
         },
         Name::new("Debug"),
         BlockModel::default(),
+        BlockCollision::default(),
         BlockShape::Cube {
             tileset: "overworld".to_string(),
             top: BlockFace {
@@ -448,6 +815,10 @@ pub fn load_blocks(mut commands: Commands) {
                 tile: TilePos::new(5, 1),
                 ..default()
             },
+            transparent: false,
+            non_occluding: false,
+            variants: Vec::new(),
+            randomize_rotation: false,
         },
     ));
 
@@ -457,8 +828,18 @@ pub fn load_blocks(mut commands: Commands) {
         },
         Name::new("Sign 1"),
         BlockModel::default(),
+        BlockCollision::default(),
         BlockShape::Custom {
             asset: "sign1".to_string(),
+            offset: [0.0; 3],
+            smooth_normals: false,
         },
     ));
 }
+
+/// This system despawns all block definitions when the project is unloaded.
+pub fn unload_blocks(blocks: Query<Entity, With<Block>>, mut commands: Commands) {
+    for block_id in blocks.iter() {
+        commands.entity(block_id).despawn_recursive();
+    }
+}
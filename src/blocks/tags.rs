@@ -0,0 +1,98 @@
+//! This module implements tags that can be assigned to blocks, used to organize
+//! blocks into categories.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::Block;
+use crate::settings::{ProjectSettings, ProjectSettingsError};
+
+/// The prefix of the project settings key used to store the tags of a block.
+/// The name of the block is appended to this prefix, since block uuids are not
+/// stable across sessions. The key must be moved with
+/// [`ProjectSettings::rename`] whenever the block is renamed.
+pub const BLOCK_TAGS_KEY_PREFIX: &str = "BLOCK_TAGS_";
+
+/// The category that blocks without any tags are listed under.
+pub const UNCATEGORIZED_TAG: &str = "Uncategorized";
+
+/// The tags assigned to a block. Tags are used to group blocks into categories
+/// within the editor, and to look up blocks from scripts.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Component, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct BlockTags(pub Vec<String>);
+
+impl BlockTags {
+    /// Parses a comma-separated list of tags. Whitespace around each tag is
+    /// trimmed, and empty or duplicate tags are skipped.
+    pub fn parse(text: &str) -> Self {
+        let mut tags: Vec<String> = Vec::new();
+
+        for tag in text.split(',').map(str::trim) {
+            if !tag.is_empty() && !tags.iter().any(|t| t == tag) {
+                tags.push(tag.to_string());
+            }
+        }
+
+        Self(tags)
+    }
+
+    /// Checks whether the block is listed under the given category. Blocks
+    /// without any tags are only listed under [`UNCATEGORIZED_TAG`].
+    pub fn has(&self, tag: &str) -> bool {
+        if self.0.is_empty() {
+            return tag == UNCATEGORIZED_TAG;
+        }
+
+        self.0.iter().any(|t| t == tag)
+    }
+
+    /// Returns the categories that the block is listed under.
+    pub fn categories(&self) -> impl Iterator<Item = &str> {
+        let uncategorized = self.0.is_empty().then_some(UNCATEGORIZED_TAG);
+        self.0.iter().map(String::as_str).chain(uncategorized)
+    }
+
+    /// Returns the project settings key used to store the tags of the block
+    /// with the given name.
+    pub fn settings_key(block: &str) -> String {
+        format!("{BLOCK_TAGS_KEY_PREFIX}{block}")
+    }
+
+    /// Loads the tags of the block with the given name from the project
+    /// settings. If no tags are stored, or they cannot be read, no tags are
+    /// returned.
+    pub fn load(project_settings: &ProjectSettings, block: &str) -> Self {
+        match project_settings.get_json(&Self::settings_key(block)) {
+            Ok(tags) => tags.unwrap_or_default(),
+            Err(err) => {
+                error!("Failed to read tags for block {block}: {err}");
+                Self::default()
+            }
+        }
+    }
+
+    /// Saves these tags for the block with the given name to the project
+    /// settings. If there are no tags, the stored tags are removed instead.
+    pub fn save(
+        &self,
+        project_settings: &ProjectSettings,
+        block: &str,
+    ) -> Result<(), ProjectSettingsError> {
+        let value = (!self.0.is_empty()).then_some(self);
+        project_settings.set_json(&Self::settings_key(block), value)
+    }
+}
+
+/// This system loads the stored tags of all newly spawned blocks from the
+/// project settings.
+pub fn load_block_tags(
+    project_settings: Res<ProjectSettings>,
+    blocks: Query<(Entity, &Name), (Added<Block>, Without<BlockTags>)>,
+    mut commands: Commands,
+) {
+    for (block_id, name) in blocks.iter() {
+        let tags = BlockTags::load(&project_settings, name.as_str());
+        commands.entity(block_id).insert(tags);
+    }
+}
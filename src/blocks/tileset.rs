@@ -1,10 +1,14 @@
 //! This module implements tileset loading and management.
 
 use bevy::prelude::*;
+use bevy::render::renderer::RenderDevice;
 use bevy::render::texture::{ImageLoaderSettings, ImageSampler};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use super::shape::BlockShape;
+use crate::settings::ProjectSettings;
+
 /// The total number of tiles in a tileset across one axis.
 ///
 /// The entire tileset is a square image with `TILESET_LENGTH * TILESET_LENGTH`
@@ -20,6 +24,14 @@ pub const PROTOTYPE_TILESET_UUID: Uuid = Uuid::from_u128(0);
 /// The asset path to the prototype tileset image.
 pub const PROTOTYPE_TILESET_PATH: &str = "embedded://awgen/blocks/prototype.png";
 
+/// The prefix of the project settings key used to store the material properties
+/// of a tileset. The name of the tileset is appended to this prefix.
+pub const TILESET_MATERIAL_KEY_PREFIX: &str = "TILESET_MATERIAL_";
+
+/// The color of the placeholder material used for blocks that reference a
+/// tileset that does not exist.
+pub const MISSING_MATERIAL_COLOR: Color = Color::srgb(1.0, 0.0, 1.0);
+
 /// A marker component that defines an entity as a tileset definition.
 ///
 /// When creating a default tileset, the UUID is generated randomly.
@@ -52,8 +64,145 @@ pub struct TilesetBundle {
     /// remaining in RAM after it has been loaded.
     pub image: Handle<Image>,
 
-    /// The material used to render the tileset.
+    /// The material used to render opaque blocks with the tileset.
     pub material: Handle<StandardMaterial>,
+
+    /// The material used to render transparent blocks with the tileset.
+    pub transparent_material: TransparentMaterial,
+
+    /// The surface properties of the tileset materials.
+    pub material_properties: TilesetMaterial,
+
+    /// The UV inset applied to the tiles of the tileset.
+    pub uv_inset: TilesetUvInset,
+}
+
+/// The surface properties of the materials used to render a tileset. Changing
+/// this component updates the tileset materials in place, without reloading
+/// the tileset image.
+#[derive(Debug, Clone, Copy, PartialEq, Component, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TilesetMaterial {
+    /// The perceptual roughness of the material, in the range `[0, 1]`.
+    pub roughness: f32,
+
+    /// How metallic the material appears, in the range `[0, 1]`.
+    pub metallic: f32,
+
+    /// The specular intensity of non-metallic surfaces, in the range `[0, 1]`.
+    pub reflectance: f32,
+
+    /// The alpha cutoff of the opaque material, in the range `[0, 1]`. Pixels
+    /// with an alpha value below this threshold are discarded. This value is
+    /// ignored by the transparent material, which blends instead.
+    pub alpha_cutoff: f32,
+
+    /// Whether the UVs of each tile are inset by half a texel, so that texture
+    /// filtering at fractional zoom levels never bleeds into neighboring tiles
+    /// in the atlas. This is not needed at integer zoom levels, since tilesets
+    /// are sampled with nearest filtering.
+    pub inset_uvs: bool,
+}
+
+impl Default for TilesetMaterial {
+    fn default() -> Self {
+        Self {
+            roughness: 1.0,
+            metallic: 0.0,
+            reflectance: 0.5,
+            alpha_cutoff: TILESET_ALPHA_CUTOFF,
+            inset_uvs: false,
+        }
+    }
+}
+
+impl TilesetMaterial {
+    /// Returns the project settings key used to store the material properties
+    /// of the tileset with the given name.
+    pub fn settings_key(tileset: &str) -> String {
+        format!("{TILESET_MATERIAL_KEY_PREFIX}{tileset}")
+    }
+
+    /// Loads the material properties of the tileset with the given name from
+    /// the project settings. If no properties are stored, or they cannot be
+    /// read, the default matte properties are returned.
+    pub fn load(project_settings: &ProjectSettings, tileset: &str) -> Self {
+        match project_settings.get_json(&Self::settings_key(tileset)) {
+            Ok(material) => material.unwrap_or_default(),
+            Err(err) => {
+                error!("Failed to read material properties for tileset {tileset}: {err}");
+                Self::default()
+            }
+        }
+    }
+
+    /// Saves these material properties for the tileset with the given name to
+    /// the project settings.
+    pub fn save(&self, project_settings: &ProjectSettings, tileset: &str) {
+        if let Err(err) = project_settings.set_json(&Self::settings_key(tileset), Some(self)) {
+            error!("Failed to save material properties for tileset {tileset}: {err}");
+        }
+    }
+
+    /// Applies these properties to the given material.
+    fn apply(&self, material: &mut StandardMaterial) {
+        material.perceptual_roughness = self.roughness.clamp(0.0, 1.0);
+        material.metallic = self.metallic.clamp(0.0, 1.0);
+        material.reflectance = self.reflectance.clamp(0.0, 1.0);
+
+        if let AlphaMode::Mask(_) = material.alpha_mode {
+            material.alpha_mode = AlphaMode::Mask(self.alpha_cutoff.clamp(0.0, 1.0));
+        }
+    }
+}
+
+/// The UV inset of the tiles of a tileset. This is kept up to date with the
+/// [`TilesetMaterial::inset_uvs`] property and the size of the tileset image.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Component)]
+pub struct TilesetUvInset {
+    /// The pixel size of a single tile, if the tile UVs should be inset by
+    /// half a texel, or `None` if no inset is applied. This is also `None`
+    /// while the tileset image is still loading.
+    pub tile_pixels: Option<u32>,
+}
+
+/// The material used to render transparent blocks with a tileset.
+///
+/// Opaque blocks are rendered with an alpha mask, so tiles with cutouts do not
+/// need to be sorted. Transparent blocks use alpha blending instead, and are
+/// split into their own chunk model parts so that they can be sorted back to
+/// front separately from the opaque geometry.
+#[derive(Debug, Default, Clone, Component)]
+pub struct TransparentMaterial(pub Handle<StandardMaterial>);
+
+/// The default alpha cutoff used for opaque tileset materials. Any pixels with
+/// an alpha value below this threshold are discarded.
+const TILESET_ALPHA_CUTOFF: f32 = 0.5;
+
+/// Creates the opaque and transparent materials for the given tileset image.
+fn create_tileset_materials(
+    materials: &mut Assets<StandardMaterial>,
+    image: Handle<Image>,
+    properties: &TilesetMaterial,
+) -> (Handle<StandardMaterial>, TransparentMaterial) {
+    let mut opaque = StandardMaterial {
+        base_color_texture: Some(image.clone()),
+        alpha_mode: AlphaMode::Mask(TILESET_ALPHA_CUTOFF),
+        ..default()
+    };
+    properties.apply(&mut opaque);
+
+    let mut transparent = StandardMaterial {
+        base_color_texture: Some(image),
+        alpha_mode: AlphaMode::Blend,
+        ..default()
+    };
+    properties.apply(&mut transparent);
+
+    let opaque = materials.add(opaque);
+    let transparent = materials.add(transparent);
+
+    (opaque, TransparentMaterial(transparent))
 }
 
 /// A position of a tile in a tileset.
@@ -82,9 +231,42 @@ impl TilePos {
         Self { x, y }
     }
 
+    /// Gets the x-coordinate, or column, of the tile within the tileset.
+    pub fn x(self) -> u8 {
+        self.x
+    }
+
+    /// Gets the y-coordinate, or row, of the tile within the tileset. Rows are
+    /// counted from the top of the tileset image.
+    pub fn y(self) -> u8 {
+        self.y
+    }
+
+    /// Gets the tile that is the given number of columns and rows away from
+    /// this tile, wrapping around at the edges of the tileset.
+    pub fn shifted(self, columns: u8, rows: u8) -> Self {
+        let length = TILESET_LENGTH as u16;
+        Self {
+            x: ((self.x as u16 + columns as u16) % length) as u8,
+            y: ((self.y as u16 + rows as u16) % length) as u8,
+        }
+    }
+
     /// Transforms a UV coordinate in the range of [0, 1] to the UV coordinate
     /// of the tile in the texture atlas.
-    pub fn transform_uv(self, uv: Vec2) -> Vec2 {
+    ///
+    /// If the pixel size of a tile is given, the UV coordinate is inset by half
+    /// a texel on each edge of the tile, so that texture filtering never
+    /// samples the neighboring tiles in the atlas.
+    pub fn transform_uv(self, uv: Vec2, tile_pixels: Option<u32>) -> Vec2 {
+        let uv = match tile_pixels {
+            Some(pixels) if pixels > 0 => {
+                let inset = 0.5 / pixels as f32;
+                Vec2::splat(inset) + uv * (1.0 - inset * 2.0)
+            }
+            _ => uv,
+        };
+
         let size = 1.0 / TILESET_LENGTH as f32;
         Vec2::new(
             uv.x * size + self.x as f32 * size,
@@ -93,9 +275,31 @@ impl TilePos {
     }
 }
 
-/// This system is called on startup to load all tilesets into the world.
+/// This resource contains the placeholder material used for blocks that
+/// reference a tileset that does not exist. The material is a solid, unlit
+/// magenta, so that missing tilesets are immediately visible in the world.
+#[derive(Debug, Clone, Resource)]
+pub struct MissingMaterial(pub Handle<StandardMaterial>);
+
+/// This system is called on startup to create the [`MissingMaterial`]
+/// placeholder.
+pub fn create_missing_material(
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut commands: Commands,
+) {
+    let material = materials.add(StandardMaterial {
+        base_color: MISSING_MATERIAL_COLOR,
+        unlit: true,
+        ..default()
+    });
+    commands.insert_resource(MissingMaterial(material));
+}
+
+/// This system is called when the project is loaded to load all tilesets into
+/// the world.
 pub fn load_tilesets(
     asset_server: Res<AssetServer>,
+    project_settings: Res<ProjectSettings>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     mut commands: Commands,
 ) {
@@ -106,45 +310,254 @@ pub fn load_tilesets(
             settings.sampler = ImageSampler::nearest();
         },
     );
+    let material_properties = TilesetMaterial::load(&project_settings, PROTOTYPE_TILESET_NAME);
+    let (material, transparent_material) =
+        create_tileset_materials(&mut materials, tileset_image.clone(), &material_properties);
     commands.spawn(TilesetBundle {
         tileset: Tileset {
             uuid: PROTOTYPE_TILESET_UUID,
         },
         name: Name::new(PROTOTYPE_TILESET_NAME),
-        image: tileset_image.clone(),
-        material: materials.add(StandardMaterial {
-            base_color_texture: Some(tileset_image),
-            perceptual_roughness: 1.0,
-            ..default()
-        }),
+        image: tileset_image,
+        material,
+        transparent_material,
+        material_properties,
+        uv_inset: default(),
     });
 
-    load_tileset(&asset_server, &mut materials, &mut commands, "overworld");
+    load_tileset(
+        &asset_server,
+        &project_settings,
+        &mut materials,
+        &mut commands,
+        Uuid::new_v4(),
+        "overworld",
+    );
 }
 
-/// Loads the tileset with the given name.
-fn load_tileset(
-    asset_server: &Res<AssetServer>,
-    materials: &mut ResMut<Assets<StandardMaterial>>,
+/// This system despawns all tilesets when the project is unloaded.
+pub fn unload_tilesets(tilesets: Query<Entity, With<Tileset>>, mut commands: Commands) {
+    for tileset_id in tilesets.iter() {
+        commands.entity(tileset_id).despawn_recursive();
+    }
+}
+
+/// Loads the tileset with the given uuid and name. The tileset image is loaded
+/// from the `tilesets` folder of the project, using the name as the file name.
+pub fn load_tileset(
+    asset_server: &AssetServer,
+    project_settings: &ProjectSettings,
+    materials: &mut Assets<StandardMaterial>,
     commands: &mut Commands,
+    uuid: Uuid,
     name: &str,
 ) {
-    let tileset_image = asset_server.load_with_settings(
+    let tileset_image = load_tileset_image(asset_server, name);
+    let material_properties = TilesetMaterial::load(project_settings, name);
+    let (material, transparent_material) =
+        create_tileset_materials(materials, tileset_image.clone(), &material_properties);
+    commands.spawn(TilesetBundle {
+        tileset: Tileset { uuid },
+        name: Name::new(name.to_string()),
+        image: tileset_image,
+        material,
+        transparent_material,
+        material_properties,
+        uv_inset: default(),
+    });
+}
+
+/// Loads the image of the tileset with the given name from the `tilesets`
+/// folder of the project.
+fn load_tileset_image(asset_server: &AssetServer, name: &str) -> Handle<Image> {
+    asset_server.load_with_settings(
         format!("project://tilesets/{name}.png"),
         |settings: &mut ImageLoaderSettings| {
             settings.sampler = ImageSampler::nearest();
         },
-    );
-    commands.spawn(TilesetBundle {
-        name: Name::new(name.to_string()),
-        image: tileset_image.clone(),
-        material: materials.add(StandardMaterial {
-            base_color_texture: Some(tileset_image),
-            perceptual_roughness: 1.0,
-            ..default()
-        }),
-        ..default()
-    });
+    )
+}
+
+/// This system reloads the image of each tileset that has been renamed, since
+/// tileset images are stored under the name of their tileset. Both materials of
+/// the tileset are pointed at the new image. The prototype tileset is skipped,
+/// as its image is embedded.
+#[allow(clippy::type_complexity)]
+pub fn reload_renamed_tileset_images(
+    asset_server: Res<AssetServer>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut tilesets: Query<
+        (
+            &Tileset,
+            &Name,
+            &mut Handle<Image>,
+            &Handle<StandardMaterial>,
+            &TransparentMaterial,
+        ),
+        Changed<Name>,
+    >,
+) {
+    for (tileset, name, mut image, material, transparent_material) in tilesets.iter_mut() {
+        if tileset.uuid == PROTOTYPE_TILESET_UUID {
+            continue;
+        }
+
+        let new_image = load_tileset_image(&asset_server, name.as_str());
+        if *image == new_image {
+            continue;
+        }
+
+        for handle in [material, &transparent_material.0] {
+            if let Some(material) = materials.get_mut(handle) {
+                material.base_color_texture = Some(new_image.clone());
+            }
+        }
+
+        *image = new_image;
+    }
+}
+
+/// This system listens for changes to the [`TilesetMaterial`] properties of a
+/// tileset and updates the tileset materials in place.
+pub fn update_tileset_materials(
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    tilesets: Query<
+        (
+            &TilesetMaterial,
+            &Handle<StandardMaterial>,
+            &TransparentMaterial,
+        ),
+        Changed<TilesetMaterial>,
+    >,
+) {
+    for (properties, material, transparent_material) in tilesets.iter() {
+        for handle in [material, &transparent_material.0] {
+            if let Some(material) = materials.get_mut(handle) {
+                properties.apply(material);
+            }
+        }
+    }
+}
+
+/// This system keeps the [`TilesetUvInset`] of each tileset up to date with its
+/// material properties and image size. All blocks that use a tileset are
+/// marked as changed when its inset changes, so that they are remeshed.
+pub fn update_tileset_uv_inset(
+    images: Res<Assets<Image>>,
+    mut tilesets: Query<
+        (&Handle<Image>, &TilesetMaterial, &mut TilesetUvInset, &Name),
+        With<Tileset>,
+    >,
+    mut shapes: Query<&mut BlockShape, Without<Tileset>>,
+) {
+    for (image, properties, mut uv_inset, name) in tilesets.iter_mut() {
+        let tile_pixels = if properties.inset_uvs {
+            images
+                .get(image)
+                .map(|image| image.width() / TILESET_LENGTH as u32)
+        } else {
+            None
+        };
+
+        if uv_inset.tile_pixels == tile_pixels {
+            continue;
+        }
+        uv_inset.tile_pixels = tile_pixels;
+
+        for mut shape in shapes.iter_mut() {
+            if shape.uses_tileset(name.as_str()) {
+                shape.set_changed();
+            }
+        }
+    }
+}
+
+/// This system listens for tileset images that have been modified, such as
+/// after an asset reload, and refreshes the tileset material. All blocks that
+/// use the tileset are marked as changed so that any chunks containing them are
+/// remeshed.
+pub fn refresh_reloaded_tilesets(
+    mut image_events: EventReader<AssetEvent<Image>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    tilesets: Query<
+        (
+            &Handle<Image>,
+            &Handle<StandardMaterial>,
+            &TransparentMaterial,
+            &Name,
+        ),
+        With<Tileset>,
+    >,
+    mut shapes: Query<&mut BlockShape, Without<Tileset>>,
+) {
+    for ev in image_events.read() {
+        let AssetEvent::Modified { id } = ev else {
+            continue;
+        };
+
+        let Some((_, material, transparent_material, name)) =
+            tilesets.iter().find(|(image, _, _, _)| image.id() == *id)
+        else {
+            continue;
+        };
+
+        // Touching the materials forces them to be prepared again with the new
+        // image data.
+        materials.get_mut(material);
+        materials.get_mut(&transparent_material.0);
+
+        for mut shape in shapes.iter_mut() {
+            if shape.uses_tileset(name.as_str()) {
+                shape.set_changed();
+            }
+        }
+
+        info!("Reloaded tileset: {name}");
+    }
+}
+
+/// This system checks the size of each tileset image once it has been loaded
+/// against the maximum texture size supported by the render device, and logs
+/// an error for any tileset that is too large to be rendered.
+///
+/// Tilesets are always stored as a single 2D atlas texture, with the tile UVs
+/// offset into the atlas by [`TilePos::transform_uv`], so the number of tiles
+/// is never limited by the texture array layer count of the device. Only the
+/// pixel size of the atlas itself is bounded.
+pub fn check_tileset_image_limits(
+    mut image_events: EventReader<AssetEvent<Image>>,
+    render_device: Option<Res<RenderDevice>>,
+    images: Res<Assets<Image>>,
+    tilesets: Query<(&Handle<Image>, &Name), With<Tileset>>,
+) {
+    let Some(render_device) = render_device else {
+        image_events.clear();
+        return;
+    };
+
+    let max_size = render_device.limits().max_texture_dimension_2d;
+
+    for ev in image_events.read() {
+        let (AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id }) = ev else {
+            continue;
+        };
+
+        let Some((_, name)) = tilesets.iter().find(|(image, _)| image.id() == *id) else {
+            continue;
+        };
+
+        let Some(image) = images.get(*id) else {
+            continue;
+        };
+
+        let size = image.size();
+        if size.x > max_size || size.y > max_size {
+            error!(
+                "Tileset {name} is {}x{}, which exceeds the maximum texture size of {max_size} supported by this device",
+                size.x, size.y
+            );
+        }
+    }
 }
 
 /// A struct that represents a tileset definition.
@@ -156,3 +569,176 @@ pub struct TilesetDefinition {
     /// The name of the tileset.
     pub name: String,
 }
+
+impl TilesetDefinition {
+    /// Returns true if the given name may be used as the name of a tileset.
+    /// Tileset names are file names within the project `tilesets` folder,
+    /// without the `.png` extension, so they may only contain ASCII letters,
+    /// digits, spaces, `_`, `-`, and non-leading `.` characters. Path
+    /// separators and `..` are rejected, so a tileset image can never be
+    /// written or loaded outside of the `tilesets` folder.
+    pub fn is_valid_name(name: &str) -> bool {
+        !name.is_empty()
+            && !name.starts_with('.')
+            && !name.contains("..")
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, ' ' | '_' | '-' | '.'))
+    }
+}
+
+/// The signature that every PNG file starts with.
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+/// Reads the width and height of a PNG image from its header. Returns `None`
+/// if the given bytes do not start with a valid PNG header.
+fn png_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    if bytes.len() < 24 || bytes[0 .. 8] != PNG_SIGNATURE || &bytes[12 .. 16] != b"IHDR" {
+        return None;
+    }
+
+    let width = u32::from_be_bytes(bytes[16 .. 20].try_into().ok()?);
+    let height = u32::from_be_bytes(bytes[20 .. 24].try_into().ok()?);
+    Some((width, height))
+}
+
+/// Checks whether the given PNG image data can be used as a tileset. A tileset
+/// image must be square, with a side length that is a non-zero multiple of
+/// [`TILESET_LENGTH`], so that every tile is the same size.
+pub fn validate_tileset_image(bytes: &[u8]) -> Result<(), TilesetImportError> {
+    let (width, height) = png_dimensions(bytes).ok_or(TilesetImportError::NotPng)?;
+
+    if width != height || width == 0 || width % TILESET_LENGTH as u32 != 0 {
+        return Err(TilesetImportError::InvalidSize { width, height });
+    }
+
+    Ok(())
+}
+
+/// Copies the PNG image at the given path into the tileset folder of the given
+/// project asset folder, so that it can be loaded as a tileset with the given
+/// name. The name and image are validated before the image is copied.
+pub fn import_tileset_image(
+    source: &std::path::Path,
+    asset_folder: &std::path::Path,
+    name: &str,
+) -> Result<(), TilesetImportError> {
+    if !TilesetDefinition::is_valid_name(name) {
+        return Err(TilesetImportError::InvalidName(name.to_string()));
+    }
+
+    let bytes = std::fs::read(source)?;
+    validate_tileset_image(&bytes)?;
+
+    let tileset_folder = asset_folder.join("tilesets");
+    std::fs::create_dir_all(&tileset_folder)?;
+    std::fs::write(tileset_folder.join(format!("{name}.png")), bytes)?;
+
+    Ok(())
+}
+
+/// An error that can occur while importing a tileset image.
+#[derive(Debug, thiserror::Error)]
+pub enum TilesetImportError {
+    /// The image could not be read or written.
+    #[error("The tileset image could not be copied: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The tileset name cannot be used as a file name.
+    #[error("The tileset name {0:?} is not a valid file name")]
+    InvalidName(String),
+
+    /// The image is not a PNG file.
+    #[error("The tileset image is not a PNG file")]
+    NotPng,
+
+    /// The image dimensions cannot be divided into tiles.
+    #[error(
+        "The tileset image is {width}x{height}, but must be square with a size that is a multiple of {TILESET_LENGTH}"
+    )]
+    InvalidSize {
+        /// The width of the image, in pixels.
+        width: u32,
+
+        /// The height of the image, in pixels.
+        height: u32,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds the header of a PNG image with the given dimensions.
+    fn png_header(width: u32, height: u32) -> Vec<u8> {
+        let mut bytes = PNG_SIGNATURE.to_vec();
+        bytes.extend_from_slice(&13u32.to_be_bytes());
+        bytes.extend_from_slice(b"IHDR");
+        bytes.extend_from_slice(&width.to_be_bytes());
+        bytes.extend_from_slice(&height.to_be_bytes());
+        bytes
+    }
+
+    #[test]
+    fn transform_uv_inset() {
+        let tile = TilePos::new(1, 2);
+        let size = 1.0 / TILESET_LENGTH as f32;
+
+        assert_eq!(
+            tile.transform_uv(Vec2::ZERO, None),
+            Vec2::new(size, size * 2.0)
+        );
+        assert_eq!(
+            tile.transform_uv(Vec2::ONE, None),
+            Vec2::new(size * 2.0, size * 3.0)
+        );
+        assert_eq!(
+            tile.transform_uv(Vec2::ZERO, Some(0)),
+            Vec2::new(size, size * 2.0)
+        );
+
+        let half_texel = size * 0.5 / 16.0;
+        let min = tile.transform_uv(Vec2::ZERO, Some(16));
+        let max = tile.transform_uv(Vec2::ONE, Some(16));
+        assert!(min.abs_diff_eq(Vec2::new(size, size * 2.0) + half_texel, 1e-6));
+        assert!(max.abs_diff_eq(Vec2::new(size * 2.0, size * 3.0) - half_texel, 1e-6));
+    }
+
+    #[test]
+    fn validate_tileset_dimensions() {
+        assert!(validate_tileset_image(&png_header(256, 256)).is_ok());
+        assert!(validate_tileset_image(&png_header(16, 16)).is_ok());
+
+        assert!(matches!(
+            validate_tileset_image(&png_header(256, 128)),
+            Err(TilesetImportError::InvalidSize { .. })
+        ));
+        assert!(matches!(
+            validate_tileset_image(&png_header(100, 100)),
+            Err(TilesetImportError::InvalidSize { .. })
+        ));
+        assert!(matches!(
+            validate_tileset_image(b"not a png"),
+            Err(TilesetImportError::NotPng)
+        ));
+    }
+
+    #[test]
+    fn reject_unsafe_tileset_names() {
+        assert!(TilesetDefinition::is_valid_name("overworld"));
+        assert!(TilesetDefinition::is_valid_name("Cave Tiles-2.v1"));
+
+        assert!(!TilesetDefinition::is_valid_name(""));
+        assert!(!TilesetDefinition::is_valid_name("../../foo"));
+        assert!(!TilesetDefinition::is_valid_name(".hidden"));
+        assert!(!TilesetDefinition::is_valid_name("tiles/cave"));
+        assert!(!TilesetDefinition::is_valid_name("tiles\\cave"));
+        assert!(!TilesetDefinition::is_valid_name("C:cave"));
+
+        let folder = std::env::temp_dir();
+        assert!(matches!(
+            import_tileset_image(&folder.join("missing.png"), &folder, "../../foo"),
+            Err(TilesetImportError::InvalidName(_))
+        ));
+    }
+}
@@ -5,8 +5,12 @@ use bevy::prelude::*;
 use bevy::render::camera::ScalingMode;
 
 use crate::gamestate::GameState;
+use crate::map::world::VoxelWorld;
+use crate::math::{BlockPos, CHUNK_SIZE};
+use crate::ui::UiKeyboardFocus;
 
-/// The distance from the camera to the clipping plane. (In both directions)
+/// The minimum distance from the camera to the clipping plane. (In both
+/// directions) See [`clip_distance`].
 pub const CAMERA_CLIP_DIST: f32 = 500.0;
 
 /// The base zoom level of the camera.
@@ -15,9 +19,13 @@ pub const BASE_ZOOM: f32 = 16.0;
 /// The minimum zoom level of the camera.
 pub const MIN_ZOOM: f32 = 4.0 / BASE_ZOOM;
 
-/// The maximum zoom level of the camera.
+/// The default maximum zoom level of the camera.
 pub const MAX_ZOOM: f32 = 256.0 / BASE_ZOOM;
 
+/// The hard ceiling of the zoom level of the camera. The maximum zoom level is
+/// never expanded past this value, to avoid precision issues when rendering.
+pub const ZOOM_CEILING: f32 = 4096.0 / BASE_ZOOM;
+
 /// The minimum pitch of the camera. (In radians)
 pub const MIN_PITCH: f32 = -80.0;
 
@@ -31,9 +39,21 @@ impl Plugin for CameraPlugin {
         app_.add_systems(PreStartup, setup_camera)
             .add_systems(
                 Update,
-                (mouse_pan, mouse_rotate, mouse_zoom, keyboard_rotate)
+                (
+                    mouse_pan,
+                    mouse_rotate,
+                    mouse_zoom,
+                    keyboard_rotate.run_if(UiKeyboardFocus::is_free),
+                    frame_all
+                        .after_ignore_deferred(update_loaded_zoom)
+                        .run_if(UiKeyboardFocus::is_free),
+                )
                     .in_set(CameraSystemSets::Controls),
             )
+            .add_systems(
+                Update,
+                update_loaded_zoom.run_if(resource_changed::<VoxelWorld>),
+            )
             .add_systems(
                 Update,
                 smooth_camera_lerp.in_set(CameraSystemSets::Smoothing),
@@ -126,6 +146,24 @@ pub struct CameraControls {
 
     /// The zoom sensitivity of the camera.
     pub zoom_sensitivity: f32,
+
+    /// The maximum zoom level of the camera.
+    pub max_zoom: f32,
+
+    /// Whether the maximum zoom level is expanded to fit all loaded chunks,
+    /// if they do not fit within [`CameraControls::max_zoom`].
+    pub expand_to_loaded_area: bool,
+
+    /// The zoom level required to fit all loaded chunks on screen. This value
+    /// is updated automatically whenever chunks are loaded or unloaded.
+    pub loaded_zoom: f32,
+
+    /// The center of all loaded chunks, in world space.
+    pub loaded_center: Vec3,
+
+    /// Whether holding Shift while panning locks the pan to a single screen
+    /// axis. The axis is chosen from the initial drag direction.
+    pub pan_axis_lock: bool,
 }
 
 impl Default for CameraControls {
@@ -134,10 +172,76 @@ impl Default for CameraControls {
             pan_sensitivity: 1.0,
             rotate_sensitivity: 0.25,
             zoom_sensitivity: 1.0,
+            max_zoom: MAX_ZOOM,
+            expand_to_loaded_area: true,
+            loaded_zoom: MIN_ZOOM,
+            loaded_center: Vec3::ZERO,
+            pan_axis_lock: true,
         }
     }
 }
 
+impl CameraControls {
+    /// Returns the maximum zoom level that the camera can currently zoom out
+    /// to. This is never larger than [`ZOOM_CEILING`].
+    pub fn zoom_limit(&self) -> f32 {
+        let limit = if self.expand_to_loaded_area {
+            self.max_zoom.max(self.loaded_zoom)
+        } else {
+            self.max_zoom
+        };
+
+        limit.min(ZOOM_CEILING)
+    }
+}
+
+/// A screen axis that camera panning can be locked to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PanAxis {
+    /// The pan is locked to the horizontal screen axis.
+    Horizontal,
+
+    /// The pan is locked to the vertical screen axis.
+    Vertical,
+}
+
+impl PanAxis {
+    /// Gets the dominant axis of the given drag delta. Returns `None` if the
+    /// delta is zero.
+    pub fn dominant(delta: Vec2) -> Option<Self> {
+        if delta == Vec2::ZERO {
+            None
+        } else if delta.x.abs() >= delta.y.abs() {
+            Some(Self::Horizontal)
+        } else {
+            Some(Self::Vertical)
+        }
+    }
+
+    /// Removes the component of the given drag delta that is not along this
+    /// axis.
+    pub fn constrain(self, delta: Vec2) -> Vec2 {
+        match self {
+            Self::Horizontal => Vec2::new(delta.x, 0.0),
+            Self::Vertical => Vec2::new(0.0, delta.y),
+        }
+    }
+}
+
+/// Gets the distance from the camera to the clipping plane (in both directions)
+/// at the given zoom level. The clipping planes are moved outwards as the camera
+/// zooms out, so that everything within the view is rendered even when framing
+/// a large loaded area.
+pub fn clip_distance(zoom: f32) -> f32 {
+    CAMERA_CLIP_DIST.max(zoom * BASE_ZOOM)
+}
+
+/// Moves the given camera target transform so that the camera is centered on
+/// the given world position. The camera smoothly lerps to the new position.
+pub fn focus_camera_on(target: &mut Transform, pos: Vec3) {
+    target.translation = pos;
+}
+
 /// Spawns a camera.
 fn setup_camera(mut commands: Commands) {
     commands.spawn((MainCamera, IsDefaultUiCamera, Camera3dBundle {
@@ -178,24 +282,34 @@ fn smooth_camera_lerp(
 
     if let Projection::Orthographic(proj) = &mut *projection {
         proj.scale = proj.scale * (target_pos.scale.x / proj.scale).powf(delta);
+
+        let clip = clip_distance(proj.scale);
+        proj.near = -clip;
+        proj.far = clip;
     };
 }
 
 /// This system listens for mouse movement inputs and pans the camera
 /// accordingly. The camera panning is only active when the right mouse button
 /// is pressed.
+///
+/// While Shift is held, the pan is locked to the dominant axis of the initial
+/// drag direction, unless disabled with [`CameraControls::pan_axis_lock`].
 fn mouse_pan(
     mut mouse_motion: EventReader<MouseMotion>,
     mouse_button: Res<ButtonInput<MouseButton>>,
     keycode_button: Res<ButtonInput<KeyCode>>,
     main_cam: Query<(&Camera, &Projection), With<MainCamera>>,
     mut cam_target: Query<(&mut Transform, &CameraTarget, &CameraControls)>,
+    mut locked_axis: Local<Option<PanAxis>>,
 ) {
     if !mouse_button.pressed(MouseButton::Middle) {
+        *locked_axis = None;
         return;
     }
 
     if keycode_button.pressed(KeyCode::AltLeft) {
+        *locked_axis = None;
         return;
     }
 
@@ -203,6 +317,20 @@ fn mouse_pan(
     let (mut target_pos, target_props, controls) = cam_target.single_mut();
 
     let mut delta = mouse_motion.read().map(|e| e.delta).sum::<Vec2>();
+
+    let shift = keycode_button.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    if controls.pan_axis_lock && shift {
+        if locked_axis.is_none() {
+            *locked_axis = PanAxis::dominant(delta);
+        }
+
+        if let Some(axis) = *locked_axis {
+            delta = axis.constrain(delta);
+        }
+    } else {
+        *locked_axis = None;
+    }
+
     let vp_size = camera.logical_viewport_size().unwrap_or(Vec2::ONE);
 
     match projection {
@@ -257,7 +385,44 @@ fn mouse_zoom(
     let mut delta = mouse_wheel.read().map(|e| e.y).sum::<f32>();
     delta *= target_props.zoom_sensitivity;
 
-    target_pos.scale.x = (target_pos.scale.x * 1.25f32.powf(-delta)).clamp(MIN_ZOOM, MAX_ZOOM);
+    target_pos.scale.x = (target_pos.scale.x * 1.25f32.powf(-delta))
+        .clamp(MIN_ZOOM, target_props.zoom_limit().max(MIN_ZOOM));
+}
+
+/// This system updates the zoom level and center point needed to fit all
+/// loaded chunks on screen. The zoom level is based on the bounding sphere of
+/// the loaded chunks, so it fits regardless of the camera rotation.
+fn update_loaded_zoom(world: Res<VoxelWorld>, mut cam_target: Query<&mut CameraControls>) {
+    let mut controls = cam_target.single_mut();
+
+    let Some((min, max)) = world.chunk_bounds() else {
+        controls.loaded_zoom = MIN_ZOOM;
+        controls.loaded_center = Vec3::ZERO;
+        return;
+    };
+
+    let min = BlockPos::from(min).as_vec3();
+    let max = BlockPos::from(max).as_vec3() + Vec3::splat(CHUNK_SIZE as f32);
+
+    controls.loaded_zoom = (max - min).length() / BASE_ZOOM;
+    controls.loaded_center = (min + max) * 0.5;
+}
+
+/// This system listens for the Home key and moves the camera so that all
+/// loaded chunks are framed on screen.
+fn frame_all(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut cam_target: Query<(&mut Transform, &CameraControls)>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::Home) {
+        return;
+    }
+
+    let (mut target_pos, controls) = cam_target.single_mut();
+    target_pos.translation = controls.loaded_center;
+    target_pos.scale.x = controls
+        .loaded_zoom
+        .clamp(MIN_ZOOM, controls.zoom_limit().max(MIN_ZOOM));
 }
 
 /// This system listens for keyboard inputs and rotates the camera accordingly.
@@ -34,3 +34,12 @@ impl GameState {
 pub fn to_splash_screen(mut next_state: ResMut<NextState<GameState>>) {
     next_state.set(GameState::Splash);
 }
+
+/// This system runs on startup to skip the splash screen and transition
+/// directly to the playable state. This is used when running without a window.
+pub fn to_playing_state(mut next_state: ResMut<NextState<GameState>>) {
+    #[cfg(feature = "editor")]
+    next_state.set(GameState::Editor);
+    #[cfg(not(feature = "editor"))]
+    next_state.set(GameState::Runtime);
+}
@@ -0,0 +1,192 @@
+//! This module implements the [`AxisGizmo`], a small orientation indicator in
+//! the corner of the editor viewport that shows which way the world axes are
+//! pointing.
+//!
+//! The gizmo is rendered by its own camera on a reserved render layer, on top
+//! of the world. The camera copies the rotation of the [`MainCamera`], so the
+//! arrows always match the current view. The red arrow points east (+X), the
+//! green arrow points up (+Y), and the blue arrow points north (-Z), following
+//! the conventions of [`FaceDirection`](crate::math::FaceDirection).
+
+use bevy::pbr::{NotShadowCaster, NotShadowReceiver};
+use bevy::prelude::*;
+use bevy::render::camera::{ClearColorConfig, ScalingMode, Viewport};
+use bevy::render::view::{Layer, RenderLayers};
+use bevy::window::PrimaryWindow;
+use bevy_mod_picking::prelude::Pickable;
+
+use crate::camera::MainCamera;
+use crate::ui::EditorWindowState;
+
+/// The render layer that is reserved for the axis gizmo.
+pub const AXIS_GIZMO_LAYER: Layer = 3;
+
+/// The size of the axis gizmo viewport, in logical pixels.
+pub const AXIS_GIZMO_SIZE: f32 = 96.0;
+
+/// The distance between the axis gizmo viewport and the corner of the window,
+/// in logical pixels.
+const AXIS_GIZMO_MARGIN: f32 = 8.0;
+
+/// The length of each axis arrow, including its head.
+const ARROW_LENGTH: f32 = 1.0;
+
+/// The radius of the shaft of each axis arrow.
+const ARROW_RADIUS: f32 = 0.05;
+
+/// The length of the head of each axis arrow.
+const ARROW_HEAD_LENGTH: f32 = 0.3;
+
+/// The radius of the head of each axis arrow.
+const ARROW_HEAD_RADIUS: f32 = 0.12;
+
+/// The axis arrows of the gizmo, as a direction and a color.
+const AXIS_ARROWS: [(Vec3, Color); 3] = [
+    (Vec3::X, Color::srgb(0.9, 0.2, 0.2)),
+    (Vec3::Y, Color::srgb(0.2, 0.8, 0.2)),
+    (Vec3::NEG_Z, Color::srgb(0.2, 0.4, 0.9)),
+];
+
+/// A marker component that indicates the entity is part of the axis gizmo.
+#[derive(Debug, Default, Component)]
+pub struct AxisGizmo;
+
+/// A marker component that indicates the entity is the camera that renders the
+/// axis gizmo.
+#[derive(Debug, Default, Component)]
+pub struct AxisGizmoCamera;
+
+/// This system creates the axis gizmo and its camera.
+pub fn build_axis_gizmo(
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut commands: Commands,
+) {
+    let render_layers = RenderLayers::layer(AXIS_GIZMO_LAYER);
+
+    commands.spawn((
+        AxisGizmo,
+        AxisGizmoCamera,
+        render_layers.clone(),
+        Camera3dBundle {
+            camera: Camera {
+                order: 2,
+                clear_color: ClearColorConfig::None,
+                is_active: false,
+                ..default()
+            },
+            projection: OrthographicProjection {
+                near: 0.0,
+                far: 10.0,
+                scaling_mode: ScalingMode::Fixed {
+                    width: ARROW_LENGTH * 2.5,
+                    height: ARROW_LENGTH * 2.5,
+                },
+                ..default()
+            }
+            .into(),
+            ..default()
+        },
+    ));
+
+    let shaft_length = ARROW_LENGTH - ARROW_HEAD_LENGTH;
+    let shaft = meshes.add(Cylinder::new(ARROW_RADIUS, shaft_length));
+    let head = meshes.add(Cone {
+        radius: ARROW_HEAD_RADIUS,
+        height: ARROW_HEAD_LENGTH,
+    });
+
+    commands
+        .spawn((
+            AxisGizmo,
+            Name::new("Axis Gizmo"),
+            render_layers.clone(),
+            SpatialBundle::default(),
+        ))
+        .with_children(|parent| {
+            for (dir, color) in AXIS_ARROWS {
+                let material = materials.add(StandardMaterial {
+                    base_color: color,
+                    unlit: true,
+                    ..default()
+                });
+                let rotation = Quat::from_rotation_arc(Vec3::Y, dir);
+
+                for (mesh, length, offset) in [
+                    (shaft.clone(), shaft_length, 0.0),
+                    (head.clone(), ARROW_HEAD_LENGTH, shaft_length),
+                ] {
+                    parent.spawn((
+                        render_layers.clone(),
+                        NotShadowCaster,
+                        NotShadowReceiver,
+                        Pickable::IGNORE,
+                        PbrBundle {
+                            mesh,
+                            material: material.clone(),
+                            transform: Transform::from_translation(dir * (offset + length * 0.5))
+                                .with_rotation(rotation),
+                            ..default()
+                        },
+                    ));
+                }
+            }
+        });
+}
+
+/// This system removes the axis gizmo and its camera.
+pub fn remove_axis_gizmo(gizmos: Query<Entity, With<AxisGizmo>>, mut commands: Commands) {
+    for entity in gizmos.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// This system rotates the axis gizmo camera to match the main camera, and
+/// keeps its viewport in the bottom-left corner of the window. The gizmo is
+/// only shown in the map editor.
+pub fn update_axis_gizmo_camera(
+    window_state: Res<State<EditorWindowState>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    main_camera: Query<&GlobalTransform, With<MainCamera>>,
+    mut gizmo_camera: Query<(&mut Camera, &mut Transform), With<AxisGizmoCamera>>,
+) {
+    let Ok((mut camera, mut transform)) = gizmo_camera.get_single_mut() else {
+        return;
+    };
+
+    let (Ok(window), Ok(main_camera)) = (windows.get_single(), main_camera.get_single()) else {
+        camera.is_active = false;
+        return;
+    };
+
+    let scale = window.scale_factor();
+    let size = (AXIS_GIZMO_SIZE * scale) as u32;
+    let margin = (AXIS_GIZMO_MARGIN * scale) as u32;
+    let window_size = window.physical_size();
+
+    let fits = window_size.x >= size + margin && window_size.y >= size + margin;
+    let visible = fits && *window_state.get() == EditorWindowState::MapEditor;
+    if camera.is_active != visible {
+        camera.is_active = visible;
+    }
+
+    if !visible {
+        return;
+    }
+
+    let position = UVec2::new(margin, window_size.y - size - margin);
+    let size = UVec2::splat(size);
+    let outdated = camera.viewport.as_ref().map_or(true, |viewport| {
+        viewport.physical_position != position || viewport.physical_size != size
+    });
+    if outdated {
+        camera.viewport = Some(Viewport {
+            physical_position: position,
+            physical_size: size,
+            ..default()
+        });
+    }
+
+    let rotation = main_camera.compute_transform().rotation;
+    *transform = Transform::from_translation(rotation * Vec3::Z * 5.0).with_rotation(rotation);
+}
@@ -4,20 +4,34 @@
 use bevy::math::bounding::RayCast3d;
 use bevy::prelude::*;
 
-use crate::camera::{MainCamera, CAMERA_CLIP_DIST};
+use crate::camera::{clip_distance, MainCamera};
+use crate::settings::ProjectSettings;
 use crate::utilities::raycast::{VoxelRaycast, VoxelRaycastHit};
-
-/// The distance to raycast from the cursor.
-const RAYCAST_DISTANCE: f32 = CAMERA_CLIP_DIST * 2.0;
+use crate::{PICK_DISTANCE_DEFAULT, PICK_DISTANCE_KEY};
 
 /// The resource that stores the current cursor raycast information.
 ///
 /// This resource is updated every frame and stores the object currently under
 /// the mouse cursor.
-#[derive(Debug, Default, Resource)]
+#[derive(Debug, Resource)]
 pub struct CursorRaycast {
     /// The current block that the cursor is hovering over, if any.
     pub block: Option<VoxelRaycastHit>,
+
+    /// The maximum distance behind the camera focus point at which blocks can
+    /// be picked, in blocks. Blocks further away are ignored, so that clicks
+    /// through gaps in the terrain do not edit distant geometry. If `None`,
+    /// blocks can be picked at any distance.
+    pub max_distance: Option<f32>,
+}
+
+impl Default for CursorRaycast {
+    fn default() -> Self {
+        Self {
+            block: None,
+            max_distance: Some(PICK_DISTANCE_DEFAULT),
+        }
+    }
 }
 
 /// This system runs every frame to update the cursor block based on the current
@@ -25,10 +39,10 @@ pub struct CursorRaycast {
 pub fn update_cursor_block(
     mut cursor: ResMut<CursorRaycast>,
     raycast: VoxelRaycast,
-    camera: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    camera: Query<(&Camera, &GlobalTransform, &Projection), With<MainCamera>>,
     window: Query<&Window>,
 ) {
-    let Ok((camera, cam_transform)) = camera.get_single() else {
+    let Ok((camera, cam_transform, projection)) = camera.get_single() else {
         return;
     };
 
@@ -44,5 +58,40 @@ pub fn update_cursor_block(
         return;
     };
 
-    cursor.block = raycast.raycast(RayCast3d::new(ray.origin, ray.direction, RAYCAST_DISTANCE));
+    // The ray starts at the near clipping plane, so it has to travel through
+    // the whole clipping range to reach the far plane.
+    let zoom = match projection {
+        Projection::Orthographic(proj) => proj.scale,
+        Projection::Perspective(_) => 1.0,
+    };
+    let ray_distance = clip_distance(zoom) * 2.0;
+
+    let hit = raycast.raycast(RayCast3d::new(ray.origin, ray.direction, ray_distance));
+    let max_distance = cursor.max_distance;
+
+    cursor.block = hit.filter(|hit| {
+        let distance = (hit.hit_pos - cam_transform.translation()).dot(*ray.direction);
+        !max_distance.is_some_and(|max| distance > max)
+    });
+}
+
+/// This system loads the pick distance from the project settings.
+pub fn load_pick_distance(
+    project_settings: Res<ProjectSettings>,
+    mut cursor: ResMut<CursorRaycast>,
+) {
+    let distance = match project_settings.get_parsed::<f32>(PICK_DISTANCE_KEY) {
+        Ok(Some(distance)) if distance.is_finite() && distance >= 0.0 => distance,
+        Ok(Some(distance)) => {
+            warn!("Invalid pick distance in project settings: {}", distance);
+            PICK_DISTANCE_DEFAULT
+        }
+        Ok(None) => PICK_DISTANCE_DEFAULT,
+        Err(err) => {
+            error!("Failed to read pick distance: {}", err);
+            PICK_DISTANCE_DEFAULT
+        }
+    };
+
+    cursor.max_distance = (distance > 0.0).then_some(distance);
 }
@@ -0,0 +1,112 @@
+//! This module implements the [`BlockGhostGizmo`] component, which shows a
+//! translucent preview of the selected hotbar block at the position where it
+//! would be placed.
+
+use bevy::pbr::{NotShadowCaster, NotShadowReceiver};
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy_mod_picking::prelude::Pickable;
+
+use super::cursor::CursorRaycast;
+use crate::blocks::RenderedBlock;
+use crate::blocks::params::BlockFinder;
+use crate::ui::hotbar::resource::{Hotbar, HotbarSlotData};
+
+/// The opacity of the block ghost preview.
+const GHOST_ALPHA: f32 = 0.4;
+
+/// The scale of the block ghost preview. The ghost is slightly smaller than a
+/// full block to avoid z-fighting with neighboring block faces.
+const GHOST_SCALE: f32 = 0.98;
+
+/// A marker component that indicates the entity is a block ghost gizmo.
+#[derive(Debug, Default, Component)]
+pub struct BlockGhostGizmo;
+
+/// This system creates the block ghost gizmo.
+pub fn build_block_ghost_gizmo(block_finder: BlockFinder, mut commands: Commands) {
+    commands.spawn((
+        BlockGhostGizmo,
+        NotShadowCaster,
+        NotShadowReceiver,
+        Pickable::IGNORE,
+        RenderedBlock {
+            block: block_finder.find_air(),
+        },
+        PbrBundle {
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+    ));
+}
+
+/// This system moves the block ghost gizmo to the block placement target of
+/// the cursor, and updates it to render the currently selected hotbar block.
+/// The ghost is hidden if there is no placement target or no block selected.
+pub fn update_block_ghost_gizmo(
+    cursor: Res<CursorRaycast>,
+    hotbar: Res<Hotbar>,
+    mut ghost: Query<(&mut Transform, &mut Visibility, &mut RenderedBlock), With<BlockGhostGizmo>>,
+) {
+    let Ok((mut transform, mut visibility, mut rendered)) = ghost.get_single_mut() else {
+        return;
+    };
+
+    let (Some(hit), HotbarSlotData::Block(block)) = (&cursor.block, hotbar.get_selected()) else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+
+    if rendered.block != block {
+        rendered.block = block;
+    }
+
+    let target = hit.block.shift(hit.face, 1);
+    let offset = (1.0 - GHOST_SCALE) / 2.0;
+
+    *visibility = Visibility::Inherited;
+    transform.translation = target.as_vec3() + Vec3::splat(offset);
+    transform.scale = Vec3::splat(GHOST_SCALE);
+}
+
+/// This system replaces the material of the block ghost gizmo with a
+/// translucent copy whenever the rendered block model changes. Translucent
+/// copies are cached by the source material, so each material is only copied
+/// once.
+pub fn update_block_ghost_material(
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut ghost_materials: Local<HashMap<AssetId<StandardMaterial>, Handle<StandardMaterial>>>,
+    mut ghost: Query<
+        &mut Handle<StandardMaterial>,
+        (With<BlockGhostGizmo>, Changed<Handle<StandardMaterial>>),
+    >,
+) {
+    let Ok(mut material) = ghost.get_single_mut() else {
+        return;
+    };
+
+    if ghost_materials
+        .values()
+        .any(|ghost| ghost.id() == material.id())
+    {
+        return;
+    }
+
+    let source_id = material.id();
+    if let Some(ghost_handle) = ghost_materials.get(&source_id) {
+        *material = ghost_handle.clone();
+        return;
+    }
+
+    let Some(source) = materials.get(source_id) else {
+        return;
+    };
+
+    let mut ghost_material = source.clone();
+    ghost_material.base_color.set_alpha(GHOST_ALPHA);
+    ghost_material.alpha_mode = AlphaMode::Blend;
+
+    let ghost_handle = materials.add(ghost_material);
+    ghost_materials.insert(source_id, ghost_handle.clone());
+    *material = ghost_handle;
+}
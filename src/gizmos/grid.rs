@@ -0,0 +1,140 @@
+//! This module implements the [`GridFloor`] gizmo, a ground grid at y=0 that
+//! helps with orientation when building in empty space.
+//!
+//! The grid is rendered by a shader on a plane that follows the camera, so it
+//! appears infinite. It is translucent and does not write depth, so it never
+//! hides any blocks, and it cannot be picked.
+
+use bevy::pbr::{NotShadowCaster, NotShadowReceiver};
+use bevy::prelude::*;
+use bevy::render::render_resource::{AsBindGroup, ShaderRef};
+use bevy_mod_picking::prelude::Pickable;
+
+use crate::camera::MainCamera;
+
+/// The asset path to the grid floor shader.
+pub const GRID_FLOOR_SHADER: &str = "embedded://awgen/gizmos/grid.wgsl";
+
+/// The distance from the camera, in blocks, at which the grid has fully faded
+/// out.
+pub const GRID_FADE_DISTANCE: f32 = 64.0;
+
+/// The color of the grid lines.
+const GRID_COLOR: Color = Color::srgba(0.8, 0.8, 0.8, 0.35);
+
+/// This resource controls whether the grid floor is shown in the editor.
+#[derive(Debug, Resource)]
+pub struct GridFloor {
+    /// Whether the grid floor is visible.
+    pub visible: bool,
+}
+
+impl Default for GridFloor {
+    fn default() -> Self {
+        Self { visible: true }
+    }
+}
+
+/// A marker component that indicates the entity is the grid floor gizmo.
+#[derive(Debug, Default, Component)]
+pub struct GridFloorGizmo;
+
+/// The material used to render the grid floor.
+#[derive(Debug, Clone, Asset, TypePath, AsBindGroup)]
+pub struct GridMaterial {
+    /// The color of the grid lines.
+    #[uniform(0)]
+    pub color: LinearRgba,
+
+    /// The distance from the camera at which the grid has fully faded out.
+    #[uniform(0)]
+    pub fade_distance: f32,
+}
+
+impl Material for GridMaterial {
+    fn fragment_shader() -> ShaderRef {
+        GRID_FLOOR_SHADER.into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode {
+        AlphaMode::Blend
+    }
+}
+
+/// This system creates the grid floor gizmo.
+pub fn build_grid_floor_gizmo(
+    grid: Res<GridFloor>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<GridMaterial>>,
+    mut commands: Commands,
+) {
+    let size = GRID_FADE_DISTANCE * 2.0;
+
+    commands.spawn((
+        GridFloorGizmo,
+        Name::new("Grid Floor"),
+        NotShadowCaster,
+        NotShadowReceiver,
+        Pickable::IGNORE,
+        MaterialMeshBundle {
+            mesh: meshes.add(Plane3d::default().mesh().size(size, size)),
+            material: materials.add(GridMaterial {
+                color: GRID_COLOR.into(),
+                fade_distance: GRID_FADE_DISTANCE,
+            }),
+            visibility: if grid.visible {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            },
+            ..default()
+        },
+    ));
+}
+
+/// This system removes the grid floor gizmo.
+pub fn remove_grid_floor_gizmo(
+    gizmos: Query<Entity, With<GridFloorGizmo>>,
+    mut commands: Commands,
+) {
+    for entity in gizmos.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// This system toggles the grid floor when G is pressed.
+pub fn toggle_grid_floor(keyboard_input: Res<ButtonInput<KeyCode>>, mut grid: ResMut<GridFloor>) {
+    if keyboard_input.just_pressed(KeyCode::KeyG) {
+        grid.visible = !grid.visible;
+        info!("Grid floor visible: {}", grid.visible);
+    }
+}
+
+/// This system moves the grid floor gizmo below the camera, so that it appears
+/// infinite, and updates its visibility.
+pub fn update_grid_floor_gizmo(
+    grid: Res<GridFloor>,
+    camera: Query<&GlobalTransform, With<MainCamera>>,
+    mut gizmo: Query<(&mut Transform, &mut Visibility), With<GridFloorGizmo>>,
+) {
+    let Ok((mut transform, mut visibility)) = gizmo.get_single_mut() else {
+        return;
+    };
+
+    let new_visibility = if grid.visible {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+
+    if *visibility != new_visibility {
+        *visibility = new_visibility;
+    }
+
+    let Ok(camera) = camera.get_single() else {
+        return;
+    };
+
+    let pos = camera.translation().floor();
+    transform.translation = Vec3::new(pos.x, 0.0, pos.z);
+}
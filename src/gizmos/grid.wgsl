@@ -0,0 +1,33 @@
+// This shader renders an infinite grid floor at y=0. Grid lines are drawn at
+// every block edge, anti-aliased using screen-space derivatives, and fade out
+// with the distance from the camera to avoid aliasing on the horizon.
+
+#import bevy_pbr::{
+    forward_io::VertexOutput,
+    mesh_view_bindings::view,
+}
+
+struct GridMaterial {
+    color: vec4<f32>,
+    fade_distance: f32,
+};
+
+@group(2) @binding(0) var<uniform> material: GridMaterial;
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    let coord = in.world_position.xz;
+    let width = max(fwidth(coord), vec2(0.0001));
+    let grid = abs(fract(coord - 0.5) - 0.5) / width;
+    let line = 1.0 - min(min(grid.x, grid.y), 1.0);
+
+    let dist = distance(view.world_position.xz, coord);
+    let fade = 1.0 - smoothstep(material.fade_distance * 0.5, material.fade_distance, dist);
+
+    let alpha = material.color.a * line * fade;
+    if alpha <= 0.001 {
+        discard;
+    }
+
+    return vec4(material.color.rgb, alpha);
+}
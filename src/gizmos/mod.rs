@@ -4,24 +4,57 @@
 use bevy::asset::embedded_asset;
 use bevy::prelude::*;
 
+use crate::blocks;
 use crate::gamestate::GameState;
-use crate::ui::EditorWindowState;
+use crate::project::LoadProject;
+#[cfg(feature = "editor")]
+use crate::ui::command_palette::EditorActionsAppExt;
+use crate::ui::{EditorWindowState, UiKeyboardFocus};
 
+pub mod axis;
 pub mod cursor;
 pub mod face;
+pub mod ghost;
+pub mod grid;
 
 /// This plugin implements Gizmos functionality and management systems.
 pub struct GizmosPlugin;
 impl Plugin for GizmosPlugin {
     fn build(&self, app_: &mut App) {
-        app_.init_resource::<cursor::CursorRaycast>()
-            .add_systems(OnEnter(GameState::Editor), face::build_block_face_gizmo)
+        app_.add_plugins(MaterialPlugin::<grid::GridMaterial>::default())
+            .init_resource::<cursor::CursorRaycast>()
+            .init_resource::<grid::GridFloor>()
+            .add_systems(LoadProject, cursor::load_pick_distance)
+            .add_systems(
+                OnEnter(GameState::Editor),
+                (
+                    face::build_block_face_gizmo,
+                    ghost::build_block_ghost_gizmo,
+                    grid::build_grid_floor_gizmo,
+                    axis::build_axis_gizmo,
+                ),
+            )
+            .add_systems(
+                OnExit(GameState::Editor),
+                (grid::remove_grid_floor_gizmo, axis::remove_axis_gizmo),
+            )
             .add_systems(
                 Update,
                 (
                     cursor::update_cursor_block.in_set(GizmoSystemSets::UpdateCursor),
                     face::update_block_face_gizmo.in_set(GizmoSystemSets::BlockFaceGizmo),
                     face::animate_block_face_gizmo.in_set(GizmoSystemSets::BlockFaceGizmo),
+                    ghost::update_block_ghost_gizmo.in_set(GizmoSystemSets::BlockGhostGizmo),
+                    ghost::update_block_ghost_material
+                        .after_ignore_deferred(blocks::systems::update_rendered_block_model),
+                    grid::toggle_grid_floor
+                        .run_if(in_state(GameState::Editor))
+                        .run_if(in_state(EditorWindowState::MapEditor))
+                        .run_if(UiKeyboardFocus::is_free),
+                    grid::update_grid_floor_gizmo
+                        .after_ignore_deferred(grid::toggle_grid_floor)
+                        .run_if(in_state(GameState::Editor)),
+                    axis::update_axis_gizmo_camera.run_if(in_state(GameState::Editor)),
                 ),
             )
             .configure_sets(
@@ -31,10 +64,22 @@ impl Plugin for GizmosPlugin {
                     GizmoSystemSets::BlockFaceGizmo
                         .after_ignore_deferred(GizmoSystemSets::UpdateCursor)
                         .run_if(in_state(EditorWindowState::MapEditor)),
+                    GizmoSystemSets::BlockGhostGizmo
+                        .after_ignore_deferred(GizmoSystemSets::UpdateCursor)
+                        .run_if(in_state(EditorWindowState::MapEditor)),
                 ),
             );
 
+        #[cfg(feature = "editor")]
+        app_.register_editor_action(
+            "Toggle Grid Floor",
+            |mut grid: ResMut<grid::GridFloor>| {
+                grid.visible = !grid.visible;
+            },
+        );
+
         embedded_asset!(app_, "block_face.glb");
+        embedded_asset!(app_, "grid.wgsl");
     }
 }
 
@@ -46,4 +91,7 @@ pub enum GizmoSystemSets {
 
     /// The system set for updating block face gizmos.
     BlockFaceGizmo,
+
+    /// The system set for updating the block ghost gizmo.
+    BlockGhostGizmo,
 }
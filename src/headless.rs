@@ -0,0 +1,25 @@
+//! This module contains utilities for running the engine without a window,
+//! such as for smoke-testing projects in automated pipelines.
+
+use bevy::core::FrameCount;
+use bevy::prelude::*;
+
+/// This resource, when present, causes the app to exit after the given number
+/// of frames have been run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Resource)]
+pub struct ExitAfterFrames(pub u32);
+
+/// This system sends an [`AppExit`] event once the number of frames defined by
+/// the [`ExitAfterFrames`] resource have been run.
+pub fn exit_after_frames(
+    limit: Res<ExitAfterFrames>,
+    frames: Res<FrameCount>,
+    mut exit_events: EventWriter<AppExit>,
+) {
+    if frames.0 < limit.0 {
+        return;
+    }
+
+    info!("Exiting after {} frames.", frames.0);
+    exit_events.send(AppExit::Success);
+}
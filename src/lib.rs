@@ -0,0 +1,92 @@
+#![doc = include_str!("../README.md")]
+#![warn(missing_docs)]
+#![warn(clippy::missing_docs_in_private_items)]
+
+pub mod assets;
+pub mod blocks;
+pub mod camera;
+pub mod gamestate;
+pub mod gizmos;
+pub mod headless;
+pub mod logic;
+pub mod map;
+pub mod math;
+pub mod project;
+pub mod render;
+pub mod settings;
+pub mod tools;
+pub mod ui;
+pub mod utilities;
+
+/// Whether the engine is running in development mode.
+pub const DEV_MODE: bool = cfg!(feature = "editor");
+
+/// The key used to store the project name in the settings file.
+pub const PROJECT_NAME_KEY: &str = "NAME";
+
+/// The default project name if none is provided.
+pub const PROJECT_NAME_DEFAULT: &str = "Untitled";
+
+/// The key used to store the project version in the settings file.
+pub const PROJECT_VERSION_KEY: &str = "VERSION";
+
+/// The default project version if none is provided.
+pub const PROJECT_VERSION_DEFAULT: &str = "0.0.1";
+
+/// The key used to store the render distance in the settings file.
+pub const RENDER_DISTANCE_KEY: &str = "RENDER_DISTANCE";
+
+/// The default render distance, in chunks, if none is provided.
+pub const RENDER_DISTANCE_DEFAULT: u32 = 8;
+
+/// The key used to store the anti-aliasing mode in the settings file.
+pub const ANTI_ALIASING_KEY: &str = "ANTI_ALIASING";
+
+/// The key used to store the sky color in the settings file.
+pub const SKY_COLOR_KEY: &str = "SKY_COLOR";
+
+/// The key used to store the world seed in the settings file.
+pub const WORLD_SEED_KEY: &str = "WORLD_SEED";
+
+/// The key used to store the list of biomes in the settings file.
+pub const BIOMES_KEY: &str = "BIOMES";
+
+/// The key used to store the number of compute threads in the settings file.
+/// If not set, the number of threads is picked automatically based on the
+/// number of CPU cores.
+pub const COMPUTE_THREADS_KEY: &str = "COMPUTE_THREADS";
+
+/// The largest number of compute threads that may be requested.
+pub const COMPUTE_THREADS_MAX: u16 = 256;
+
+/// The key used to store the width of the starter platform in the settings
+/// file. A width of 0 disables the starter platform.
+pub const STARTER_PLATFORM_SIZE_KEY: &str = "STARTER_PLATFORM_SIZE";
+
+/// The default width of the starter platform, in blocks, if none is provided.
+pub const STARTER_PLATFORM_SIZE_DEFAULT: u32 = 16;
+
+/// The largest width of the starter platform, in blocks.
+pub const STARTER_PLATFORM_SIZE_MAX: u32 = 256;
+
+/// The key used to store the name of the block the starter platform is made of
+/// in the settings file.
+pub const STARTER_PLATFORM_BLOCK_KEY: &str = "STARTER_PLATFORM_BLOCK";
+
+/// The default block of the starter platform if none is provided.
+pub const STARTER_PLATFORM_BLOCK_DEFAULT: &str = "Grass";
+
+/// The key used to store the pick distance in the settings file. A pick
+/// distance of 0 disables the limit.
+pub const PICK_DISTANCE_KEY: &str = "PICK_DISTANCE";
+
+/// The default pick distance, in blocks, if none is provided.
+pub const PICK_DISTANCE_DEFAULT: f32 = 128.0;
+
+/// The key used to store the lowest y coordinate at which blocks can be placed
+/// in the settings file. If missing, blocks can be placed at any depth.
+pub const BUILD_HEIGHT_MIN_KEY: &str = "BUILD_HEIGHT_MIN";
+
+/// The key used to store the highest y coordinate at which blocks can be placed
+/// in the settings file. If missing, blocks can be placed at any height.
+pub const BUILD_HEIGHT_MAX_KEY: &str = "BUILD_HEIGHT_MAX";
@@ -1,14 +1,50 @@
 //! This module contains the native API functions that are exposed to the
 //! JavaScript code.
 
+use std::cell::RefCell;
 use std::future::Future;
 use std::time::Duration;
 
 use bevy::log::{debug, info};
-use boa_engine::{Context, JsArgs, JsNativeError, JsResult, JsValue};
+use boa_engine::{Context, JsArgs, JsError, JsNativeError, JsResult, JsValue};
 
-use crate::logic::channels::{AwgenScriptReceiveChannel, AwgenScriptSendChannel};
-use crate::logic::commands::LogicCommands;
+use crate::logic::channels::{
+    AwgenScriptAckChannel,
+    AwgenScriptQueryChannel,
+    AwgenScriptReceiveChannel,
+    AwgenScriptSendChannel,
+};
+use crate::logic::commands::{LogicCommands, ScriptLogLevel};
+use crate::logic::error::retry_settings;
+use crate::logic::queries::LogicQueryResult;
+use crate::map::seed::SCRIPT_RNG_STREAM;
+use crate::settings::ProjectSettingsReader;
+use crate::utilities::glob::Glob;
+use crate::utilities::rng::SeededRng;
+
+thread_local! {
+    /// The seed and random number generator used by the script engine running
+    /// on the current thread.
+    static SCRIPT_RNG: RefCell<(u64, SeededRng)> =
+        RefCell::new((0, SeededRng::new(0, SCRIPT_RNG_STREAM)));
+
+    /// The read-only project settings handle used by the script engine running
+    /// on the current thread, if any.
+    static SETTINGS_READER: RefCell<Option<ProjectSettingsReader>> = const { RefCell::new(None) };
+}
+
+/// Resets the random number generator of the script engine running on the
+/// current thread to the given world seed. Scripts use their own stream, so
+/// they never desync the world generator on the main thread.
+pub fn set_random_seed(seed: u64) {
+    SCRIPT_RNG.with_borrow_mut(|rng| *rng = (seed, SeededRng::new(seed, SCRIPT_RNG_STREAM)));
+}
+
+/// Sets the read-only project settings handle used by the script engine running
+/// on the current thread.
+pub fn set_settings_reader(reader: Option<ProjectSettingsReader>) {
+    SETTINGS_READER.with_borrow_mut(|current| *current = reader);
+}
 
 /// A native async function that listens for the next incoming event from the
 /// main game.
@@ -48,6 +84,244 @@ pub fn command(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsRe
     Ok(JsValue::undefined())
 }
 
+/// A native async function that sends a command to the main game, and resolves
+/// once the main game has applied the command. This allows scripts to sequence
+/// commands deterministically, such as reading back a block after placing it.
+/// If the command could not be applied, the returned promise is rejected with
+/// the reason instead.
+pub fn command_ack(
+    _this: &JsValue,
+    args: &[JsValue],
+    context: &mut Context,
+) -> impl Future<Output = JsResult<JsValue>> {
+    let message = LogicCommands::from_js_value(args.get_or_undefined(0), context);
+
+    async move {
+        let Some(message) = message else {
+            return Err(JsNativeError::error()
+                .with_message("Invalid message.")
+                .into());
+        };
+
+        let id = AwgenScriptAckChannel::next_id();
+        debug!("Sending AwgenScript command {}: {:?}", id, message);
+
+        let message = LogicCommands::Acknowledge {
+            id,
+            command: Box::new(message),
+        };
+
+        if !AwgenScriptSendChannel::send(message) {
+            return Err(JsNativeError::error()
+                .with_message("SEND message channel has been closed.")
+                .into());
+        }
+
+        match AwgenScriptAckChannel::wait(id).await {
+            Some(Ok(())) => Ok(JsValue::undefined()),
+            Some(Err(message)) => Err(JsNativeError::error().with_message(message).into()),
+            None => Err(JsNativeError::error()
+                .with_message("Acknowledgement channel has been closed.")
+                .into()),
+        }
+    }
+}
+
+/// Sends the query built by the given function to the main game, and waits for
+/// its result. The function is given the correlation id of the query.
+async fn query(build: impl FnOnce(u64) -> LogicCommands) -> JsResult<LogicQueryResult> {
+    let id = AwgenScriptQueryChannel::next_id();
+
+    if !AwgenScriptSendChannel::send(build(id)) {
+        return Err(JsNativeError::error()
+            .with_message("SEND message channel has been closed.")
+            .into());
+    }
+
+    AwgenScriptQueryChannel::wait(id).await.ok_or_else(|| {
+        JsNativeError::error()
+            .with_message("Query channel has been closed.")
+            .into()
+    })
+}
+
+/// Creates the error returned when the main game answers a query with a result
+/// of the wrong kind.
+fn unexpected_query_result(result: LogicQueryResult) -> JsError {
+    JsNativeError::error()
+        .with_message(format!("Unexpected query result: {result:?}"))
+        .into()
+}
+
+/// A native async function that requests the block at the given `[x, y, z]`
+/// position from the main game. Returns the uuid of the block, or `null` if the
+/// chunk that contains the block is not loaded.
+pub fn get_block(
+    _this: &JsValue,
+    args: &[JsValue],
+    context: &mut Context,
+) -> impl Future<Output = JsResult<JsValue>> {
+    let position = args.get_or_undefined(0).to_json(context).and_then(|json| {
+        serde_json::from_value(json).map_err(|_| {
+            JsNativeError::typ()
+                .with_message("Position must be an [x, y, z] array.")
+                .into()
+        })
+    });
+
+    async move {
+        let position = position?;
+        let block = match query(|id| LogicCommands::QueryBlock { id, position }).await? {
+            LogicQueryResult::Block { block } => block,
+            result => return Err(unexpected_query_result(result)),
+        };
+
+        match block {
+            Some(block) => Ok(JsValue::String(block.to_string().into())),
+            None => Ok(JsValue::null()),
+        }
+    }
+}
+
+/// A native async function that requests the paths of all project assets that
+/// match the given glob pattern from the main game, such as `models/**/*.glb`.
+/// Returns the paths as a JSON array string, relative to the project asset
+/// folder. Patterns that could match files outside of the project asset folder
+/// are rejected.
+pub fn list_assets(
+    _this: &JsValue,
+    args: &[JsValue],
+    context: &mut Context,
+) -> impl Future<Output = JsResult<JsValue>> {
+    let pattern = args
+        .get_or_undefined(0)
+        .to_string(context)
+        .map(|pattern| pattern.to_std_string_escaped());
+
+    async move {
+        let pattern = pattern?;
+        if Glob::new(&pattern).is_none() {
+            return Err(JsNativeError::typ()
+                .with_message(format!("Invalid asset pattern: {pattern}"))
+                .into());
+        }
+
+        let paths = match query(|id| LogicCommands::QueryAssets { id, pattern }).await? {
+            LogicQueryResult::Assets { paths } => paths,
+            result => return Err(unexpected_query_result(result)),
+        };
+
+        let json = serde_json::to_string(&paths).unwrap();
+        Ok(JsValue::String(json.into()))
+    }
+}
+
+/// A native async function that requests the block currently targeted by the
+/// cursor from the main game. Returns the target as a JSON string, or `null` if
+/// the cursor is not hovering over any block.
+pub fn get_cursor_target(
+    _this: &JsValue,
+    _args: &[JsValue],
+    _context: &mut Context,
+) -> impl Future<Output = JsResult<JsValue>> {
+    async move {
+        let target = match query(|id| LogicCommands::QueryCursorTarget { id }).await? {
+            LogicQueryResult::CursorTarget { target } => target,
+            result => return Err(unexpected_query_result(result)),
+        };
+
+        match target {
+            Some(target) => {
+                let json = serde_json::to_string(&target).unwrap();
+                Ok(JsValue::String(json.into()))
+            }
+            None => Ok(JsValue::null()),
+        }
+    }
+}
+
+/// A native async function that requests the uuids of all blocks with the given
+/// tag from the main game. Returns the uuids as a JSON array string. Blocks
+/// without any tags are found under the "Uncategorized" tag.
+pub fn get_blocks_by_tag(
+    _this: &JsValue,
+    args: &[JsValue],
+    context: &mut Context,
+) -> impl Future<Output = JsResult<JsValue>> {
+    let tag = args
+        .get_or_undefined(0)
+        .to_string(context)
+        .map(|tag| tag.to_std_string_escaped());
+
+    async move {
+        let tag = tag?;
+
+        let blocks = match query(|id| LogicCommands::QueryBlocksByTag { id, tag }).await? {
+            LogicQueryResult::BlocksByTag { blocks } => blocks,
+            result => return Err(unexpected_query_result(result)),
+        };
+
+        let json = serde_json::to_string(&blocks).unwrap();
+        Ok(JsValue::String(json.into()))
+    }
+}
+
+/// A native async function that renders the block with the given uuid to a PNG
+/// file from an isometric angle. The second argument is the image size in
+/// pixels, and the optional third argument is a solid background color as
+/// `[r, g, b, a]`. The background is transparent if no color is given.
+///
+/// Returns the asset path of the file, or `null` if the block does not exist.
+/// The file is written within a few frames after this function returns.
+pub fn render_block_icon(
+    _this: &JsValue,
+    args: &[JsValue],
+    context: &mut Context,
+) -> impl Future<Output = JsResult<JsValue>> {
+    let uuid = args
+        .get_or_undefined(0)
+        .to_string(context)
+        .map(|uuid| uuid.to_std_string_escaped());
+    let size = args.get_or_undefined(1).to_u32(context);
+    let background = match args.get_or_undefined(2) {
+        value if value.is_null_or_undefined() => Ok(None),
+        value => value.to_json(context).and_then(|json| {
+            serde_json::from_value(json).map(Some).map_err(|_| {
+                JsNativeError::typ()
+                    .with_message("Background must be an [r, g, b, a] array.")
+                    .into()
+            })
+        }),
+    };
+
+    async move {
+        let Ok(uuid) = uuid?.parse() else {
+            return Err(JsNativeError::typ()
+                .with_message("Invalid block uuid.")
+                .into());
+        };
+
+        let size = size?;
+        let background = background?;
+        let command = |id| LogicCommands::QueryRenderBlockIcon {
+            id,
+            uuid,
+            size,
+            background,
+        };
+
+        let path = match query(command).await? {
+            LogicQueryResult::BlockIcon { path } => path,
+            result => return Err(unexpected_query_result(result)),
+        };
+
+        match path {
+            Some(path) => Ok(JsValue::String(path.into())),
+            None => Ok(JsValue::null()),
+        }
+    }
+}
+
 /// A native function that sleeps for a given number of milliseconds.
 pub fn sleep(
     _this: &JsValue,
@@ -62,10 +336,18 @@ pub fn sleep(
     }
 }
 
-/// A native function that prints a message to the console.
+/// A native function that prints a message to the console. The message is also
+/// shown in the script console of the editor.
 pub fn print(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
     let message = args.get_or_undefined(0).to_string(context)?;
-    info!("[AWGEN SCRIPT] {}", message.to_std_string_escaped());
+    let message = message.to_std_string_escaped();
+    info!("[AWGEN SCRIPT] {}", message);
+
+    AwgenScriptSendChannel::send(LogicCommands::Log {
+        level: ScriptLogLevel::Info,
+        message,
+    });
+
     Ok(JsValue::undefined())
 }
 
@@ -74,3 +356,50 @@ pub fn uuid(_this: &JsValue, _args: &[JsValue], _context: &mut Context) -> JsRes
     let uuid = uuid::Uuid::new_v4().to_string().into();
     Ok(JsValue::String(uuid))
 }
+
+/// A native function that returns a seeded random number in the range `[0, 1)`.
+pub fn random(_this: &JsValue, _args: &[JsValue], _context: &mut Context) -> JsResult<JsValue> {
+    let value = SCRIPT_RNG.with_borrow_mut(|(_, rng)| rng.next_f64());
+    Ok(JsValue::Rational(value))
+}
+
+/// A native function that reads a property from the project settings directly
+/// on the script thread, without a round-trip through the main thread. Returns
+/// the value as a string, or null if the property does not exist.
+///
+/// Writes are still sent as commands to the main thread, so a value written by
+/// the script may not be visible until that command has been handled.
+pub fn get_setting(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let key = args.get_or_undefined(0).to_string(context)?;
+    let key = key.to_std_string_escaped();
+
+    let value = SETTINGS_READER.with_borrow(|reader| -> JsResult<Option<String>> {
+        let Some(reader) = reader else {
+            return Err(JsNativeError::error()
+                .with_message("Project settings are not available.")
+                .into());
+        };
+
+        retry_settings(|| reader.get(&key)).map_err(|err| {
+            JsNativeError::error()
+                .with_message(format!("Failed to read setting {key}: {err}"))
+                .into()
+        })
+    })?;
+
+    Ok(match value {
+        Some(value) => JsValue::String(value.into()),
+        None => JsValue::null(),
+    })
+}
+
+/// A native function that returns the world seed. The seed is returned as a
+/// string, as it may not be exactly representable as a JavaScript number.
+pub fn random_seed(
+    _this: &JsValue,
+    _args: &[JsValue],
+    _context: &mut Context,
+) -> JsResult<JsValue> {
+    let seed = SCRIPT_RNG.with_borrow(|(seed, _)| seed.to_string());
+    Ok(JsValue::String(seed.into()))
+}
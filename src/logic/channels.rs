@@ -2,10 +2,12 @@
 //! messages between the AwgenScript engine and the main game.
 
 use bevy::log::info;
-use smol::channel::{Receiver, Sender};
+use smol::channel::{Receiver, Sender, TryRecvError};
+use smol::future;
 
 use super::commands::LogicCommands;
 use super::events::LogicEvent;
+use super::queries::LogicQueryResult;
 
 /// The global sender for logic messages.
 static mut SENDER: Option<Sender<LogicCommands>> = None;
@@ -13,6 +15,31 @@ static mut SENDER: Option<Sender<LogicCommands>> = None;
 /// The global receiver for logic messages.
 static mut RECEIVER: Option<Receiver<LogicEvent>> = None;
 
+/// The global receiver for query results, paired with the correlation id of
+/// the query they answer.
+static mut QUERY_RECEIVER: Option<Receiver<(u64, LogicQueryResult)>> = None;
+
+/// The query results that have been received, but not yet claimed by the query
+/// they answer.
+static mut QUERY_RESULTS: Vec<(u64, LogicQueryResult)> = Vec::new();
+
+/// The id of the most recent query.
+static mut LAST_QUERY_ID: u64 = 0;
+
+/// The global receiver for command acknowledgements, paired with the error
+/// message of the command if it could not be applied.
+static mut ACK_RECEIVER: Option<Receiver<(u64, Result<(), String>)>> = None;
+
+/// The id of the most recent command that has been acknowledged.
+static mut LAST_ACK: u64 = 0;
+
+/// The errors of failed commands that have been acknowledged, but not yet
+/// claimed by the command they belong to.
+static mut ACK_ERRORS: Vec<(u64, String)> = Vec::new();
+
+/// The id of the most recent command that requested an acknowledgement.
+static mut LAST_ACK_ID: u64 = 0;
+
 /// A singleton channel for sending messages from the AwgenScript engine to the
 /// main game.
 pub struct AwgenScriptSendChannel;
@@ -36,13 +63,17 @@ impl AwgenScriptSendChannel {
 
     /// Sends a message to the global sender for logic messages. If the channel
     /// does not exist or is already closed, this function will return false.
+    ///
+    /// If the channel is bounded and full, this function blocks until the main
+    /// thread has received enough messages to make room.
     pub fn send(message: LogicCommands) -> bool {
         let Some(sender) = (unsafe { SENDER.as_ref() }) else {
             return false;
         };
 
-        // Sending blocking messages is fine here as the channels are unbounded
-        // and will always return immediately.
+        // If the channel is bounded and full, this blocks the script engine
+        // until the main thread has made room, which applies backpressure to
+        // scripts that send commands faster than they can be handled.
         if sender.send_blocking(message).is_err() {
             Self::close();
             return false;
@@ -90,3 +121,219 @@ impl AwgenScriptReceiveChannel {
         Some(message)
     }
 }
+
+/// A singleton channel for receiving query results from the main game to the
+/// AwgenScript engine. Unlike [`AwgenScriptReceiveChannel`], messages on this
+/// channel are only sent in response to a query command.
+///
+/// Each query is given a correlation id, which is sent back with its result.
+/// Multiple queries may be in flight at the same time, and their results are
+/// matched to the waiting query by id.
+pub struct AwgenScriptQueryChannel;
+impl AwgenScriptQueryChannel {
+    /// Closes the global receiver for query results, if it is open.
+    pub fn close() {
+        if let Some(receiver) = unsafe { QUERY_RECEIVER.as_ref() } {
+            receiver.close();
+            unsafe { QUERY_RECEIVER = None };
+            info!("ScriptEngine query message channel closed.");
+        }
+    }
+
+    /// Sets the global receiver for query results, closing the previous
+    /// receiver if it exists, and replacing it. This also resets the
+    /// correlation ids and drops all unclaimed results.
+    pub fn set(new_receiver: Receiver<(u64, LogicQueryResult)>) {
+        Self::close();
+        unsafe {
+            QUERY_RECEIVER = Some(new_receiver);
+            QUERY_RESULTS.clear();
+            LAST_QUERY_ID = 0;
+        }
+        info!("ScriptEngine query message channel assigned.");
+    }
+
+    /// Returns a new correlation id for a query.
+    pub fn next_id() -> u64 {
+        unsafe {
+            LAST_QUERY_ID += 1;
+            LAST_QUERY_ID
+        }
+    }
+
+    /// Waits for the result of the query with the given correlation id. Returns
+    /// `None` if the channel does not exist or is closed before the result is
+    /// received.
+    ///
+    /// Multiple queries may be awaited at the same time, so the channel is
+    /// polled instead of awaited. Results for other queries are kept until
+    /// their waiter claims them.
+    pub async fn wait(id: u64) -> Option<LogicQueryResult> {
+        loop {
+            let index = unsafe {
+                QUERY_RESULTS
+                    .iter()
+                    .position(|(result_id, _)| *result_id == id)
+            };
+            if let Some(index) = index {
+                let (_, result) = unsafe { QUERY_RESULTS.swap_remove(index) };
+                return Some(result);
+            }
+
+            let receiver = unsafe { QUERY_RECEIVER.as_ref() }?;
+
+            match receiver.try_recv() {
+                Ok(result) => unsafe { QUERY_RESULTS.push(result) },
+                Err(TryRecvError::Empty) => future::yield_now().await,
+                Err(TryRecvError::Closed) => {
+                    Self::close();
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+/// A singleton channel for receiving command acknowledgements from the main
+/// game to the AwgenScript engine.
+///
+/// Each command that requests an acknowledgement is given a correlation id,
+/// which is sent back once the main game has applied the command, along with an
+/// error message if the command could not be applied. Commands are applied in
+/// the order they are sent, so acknowledgements always arrive in increasing id
+/// order.
+pub struct AwgenScriptAckChannel;
+impl AwgenScriptAckChannel {
+    /// Closes the global receiver for command acknowledgements, if it is open.
+    pub fn close() {
+        if let Some(receiver) = unsafe { ACK_RECEIVER.as_ref() } {
+            receiver.close();
+            unsafe { ACK_RECEIVER = None };
+            info!("ScriptEngine acknowledgement channel closed.");
+        }
+    }
+
+    /// Sets the global receiver for command acknowledgements, closing the
+    /// previous receiver if it exists, and replacing it. This also resets the
+    /// correlation ids and drops all unclaimed errors.
+    pub fn set(new_receiver: Receiver<(u64, Result<(), String>)>) {
+        Self::close();
+        unsafe {
+            ACK_RECEIVER = Some(new_receiver);
+            ACK_ERRORS.clear();
+            LAST_ACK = 0;
+            LAST_ACK_ID = 0;
+        }
+        info!("ScriptEngine acknowledgement channel assigned.");
+    }
+
+    /// Returns a new correlation id for a command that requests an
+    /// acknowledgement.
+    pub fn next_id() -> u64 {
+        unsafe {
+            LAST_ACK_ID += 1;
+            LAST_ACK_ID
+        }
+    }
+
+    /// Waits until the command with the given correlation id has been
+    /// acknowledged, and returns whether it was applied, or the error message
+    /// if it was not. Returns `None` if the channel does not exist or is
+    /// closed before the command is acknowledged.
+    ///
+    /// Multiple commands may be awaited at the same time, so the channel is
+    /// polled instead of awaited. This ensures that an acknowledgement received
+    /// by one waiter is never missed by another.
+    pub async fn wait(id: u64) -> Option<Result<(), String>> {
+        loop {
+            let index = unsafe { ACK_ERRORS.iter().position(|(ack, _)| *ack == id) };
+            if let Some(index) = index {
+                let (_, error) = unsafe { ACK_ERRORS.swap_remove(index) };
+                return Some(Err(error));
+            }
+
+            if unsafe { LAST_ACK } >= id {
+                return Some(Ok(()));
+            }
+
+            let receiver = unsafe { ACK_RECEIVER.as_ref() }?;
+
+            match receiver.try_recv() {
+                Ok((ack, result)) => unsafe {
+                    if let Err(error) = result {
+                        ACK_ERRORS.push((ack, error));
+                    }
+                    LAST_ACK = LAST_ACK.max(ack);
+                },
+                Err(TryRecvError::Empty) => future::yield_now().await,
+                Err(TryRecvError::Closed) => {
+                    Self::close();
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::logic::queries::CursorTarget;
+
+    #[test]
+    fn query_results_are_matched_by_id() {
+        let (send, recv) = smol::channel::unbounded();
+        AwgenScriptQueryChannel::set(recv);
+
+        let first = AwgenScriptQueryChannel::next_id();
+        let second = AwgenScriptQueryChannel::next_id();
+        let target = Some(CursorTarget {
+            position: [1, 2, 3],
+            face: "up".to_string(),
+            block: Uuid::nil(),
+        });
+        send.try_send((second, LogicQueryResult::CursorTarget { target }))
+            .unwrap();
+        send.try_send((first, LogicQueryResult::CursorTarget { target: None }))
+            .unwrap();
+
+        assert!(matches!(
+            smol::block_on(AwgenScriptQueryChannel::wait(first)),
+            Some(LogicQueryResult::CursorTarget { target: None })
+        ));
+        assert!(matches!(
+            smol::block_on(AwgenScriptQueryChannel::wait(second)),
+            Some(LogicQueryResult::CursorTarget { target: Some(_) })
+        ));
+
+        drop(send);
+        assert!(smol::block_on(AwgenScriptQueryChannel::wait(first)).is_none());
+    }
+
+    #[test]
+    fn failed_commands_are_acknowledged_with_their_error() {
+        let (send, recv) = smol::channel::unbounded();
+        AwgenScriptAckChannel::set(recv);
+
+        let first = AwgenScriptAckChannel::next_id();
+        let second = AwgenScriptAckChannel::next_id();
+        send.try_send((first, Err("Unknown block".to_string())))
+            .unwrap();
+        send.try_send((second, Ok(()))).unwrap();
+
+        assert_eq!(
+            smol::block_on(AwgenScriptAckChannel::wait(second)),
+            Some(Ok(()))
+        );
+        assert_eq!(
+            smol::block_on(AwgenScriptAckChannel::wait(first)),
+            Some(Err("Unknown block".to_string()))
+        );
+
+        let third = AwgenScriptAckChannel::next_id();
+        drop(send);
+        assert_eq!(smol::block_on(AwgenScriptAckChannel::wait(third)), None);
+    }
+}
@@ -6,6 +6,10 @@ use boa_engine::{Context, JsValue};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::blocks::tileset::TilesetMaterial;
+use crate::map::biome::Biome;
+use crate::render::{AntiAliasing, SkyColor};
+
 /// An enum that represents all possible commands that can be received from the
 /// AwgenScript engine.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,6 +35,234 @@ pub enum LogicCommands {
         /// The action to take on the tileset.
         action: EditTilesetAction,
     },
+
+    /// A command that is used to reload all tileset images and custom block
+    /// models from disk.
+    ReloadAssets,
+
+    /// A command that is used to update the render distance of the world.
+    SetRenderDistance {
+        /// The new render distance, in chunks.
+        distance: u32,
+    },
+
+    /// A command that is used to update the anti-aliasing mode.
+    SetAntiAliasing {
+        /// The new anti-aliasing mode.
+        mode: AntiAliasing,
+    },
+
+    /// A command that is used to update the background color of the world.
+    SetSkyColor {
+        /// The new sky color.
+        color: SkyColor,
+    },
+
+    /// A command that is used to replace the biomes that tint flagged block
+    /// faces. If empty, faces are never tinted.
+    SetBiomes {
+        /// The new list of biomes.
+        biomes: Vec<Biome>,
+    },
+
+    /// A command that is used to automatically texture the visible surface of
+    /// all cube blocks within a region.
+    FillVisibleSurface {
+        /// The minimum corner of the region, inclusive.
+        min: [i32; 3],
+
+        /// The maximum corner of the region, inclusive.
+        max: [i32; 3],
+
+        /// The uuid of the block to use for blocks with an exposed top face.
+        top: Uuid,
+
+        /// The uuid of the block to use for blocks with an exposed side face.
+        side: Uuid,
+
+        /// The uuid of the block to use for blocks with only an exposed bottom
+        /// face.
+        bottom: Uuid,
+    },
+
+    /// A command that is used to update the surface properties of the
+    /// materials used to render a tileset.
+    SetTilesetMaterial {
+        /// The name of the tileset to modify.
+        tileset: String,
+
+        /// The new material properties. Any omitted properties are reset to
+        /// their default values.
+        material: TilesetMaterial,
+    },
+
+    /// A command that is used to replace a single block of the world. If the
+    /// chunk that contains the block does not exist yet, it is created.
+    SetBlock {
+        /// The position of the block, in world coordinates.
+        position: [i32; 3],
+
+        /// The uuid of the new block.
+        block: Uuid,
+    },
+
+    /// A command that is used to import a grayscale heightmap image as
+    /// terrain, starting at the world origin. Brighter pixels produce taller
+    /// columns of blocks.
+    ImportHeightmap {
+        /// The asset path of the image, relative to the project folder.
+        path: String,
+
+        /// The height of the column produced by a white pixel, in blocks.
+        height: u32,
+
+        /// The uuid of the block that fills each column below its top block.
+        base: Uuid,
+
+        /// The uuid of the top block of each column.
+        surface: Uuid,
+
+        /// The width and depth of the column area produced by each pixel, in
+        /// blocks.
+        scale: u32,
+    },
+
+    /// A command that is used to replace the build rules, which restrict where
+    /// and which blocks the player can place and remove.
+    SetBuildRule {
+        /// The regions in which blocks can be edited, as pairs of inclusive
+        /// `[min, max]` corners. If empty, blocks can be edited anywhere.
+        #[serde(default)]
+        regions: Vec<[[i32; 3]; 2]>,
+
+        /// The uuids of the blocks that cannot be placed.
+        #[serde(default)]
+        banned: Vec<Uuid>,
+    },
+
+    /// A command that is used to request the block at the given position. The
+    /// result is sent back through the query channel. This command is only
+    /// sent internally by the native API and cannot be sent by scripts
+    /// directly.
+    #[serde(skip_deserializing)]
+    QueryBlock {
+        /// The correlation id of the query, which is sent back with the
+        /// result.
+        id: u64,
+
+        /// The position of the block, in world coordinates.
+        position: [i32; 3],
+    },
+
+    /// A command that is used to request the paths of all project assets that
+    /// match a glob pattern. The result is sent back through the query channel.
+    /// This command is only sent internally by the native API and cannot be
+    /// sent by scripts directly.
+    #[serde(skip_deserializing)]
+    QueryAssets {
+        /// The correlation id of the query, which is sent back with the
+        /// result.
+        id: u64,
+
+        /// The glob pattern to match, such as `models/**/*.glb`.
+        pattern: String,
+    },
+
+    /// A command that is used to request the block currently targeted by the
+    /// cursor. The result is sent back through the query channel. This command
+    /// is only sent internally by the native API and cannot be sent by scripts
+    /// directly.
+    #[serde(skip_deserializing)]
+    QueryCursorTarget {
+        /// The correlation id of the query, which is sent back with the
+        /// result.
+        id: u64,
+    },
+
+    /// A command that is used to request all blocks with the given tag. The
+    /// result is sent back through the query channel. This command is only
+    /// sent internally by the native API and cannot be sent by scripts
+    /// directly.
+    #[serde(skip_deserializing)]
+    QueryBlocksByTag {
+        /// The correlation id of the query, which is sent back with the
+        /// result.
+        id: u64,
+
+        /// The tag to search for.
+        tag: String,
+    },
+
+    /// A command that is used to render the icon of a block to a PNG file.
+    /// The asset path of the file is sent back through the query channel. This
+    /// command is only sent internally by the native API and cannot be sent by
+    /// scripts directly.
+    #[serde(skip_deserializing)]
+    QueryRenderBlockIcon {
+        /// The correlation id of the query, which is sent back with the
+        /// result.
+        id: u64,
+
+        /// The uuid of the block to render.
+        uuid: Uuid,
+
+        /// The image size of the icon, in pixels.
+        size: u32,
+
+        /// The background color of the icon as `[r, g, b, a]`, or `None` for a
+        /// transparent background.
+        background: Option<[u8; 4]>,
+    },
+
+    /// A command that is used to choose which input is forwarded to the engine.
+    /// Replaces any previous subscription.
+    SubscribeInput {
+        /// The stable names of the keys whose presses are forwarded, such as
+        /// `"KeyA"` or `"Space"`.
+        #[serde(default)]
+        keys: Vec<String>,
+
+        /// Whether mouse clicks are forwarded.
+        #[serde(default)]
+        mouse: bool,
+    },
+
+    /// A command that is used to send a custom command to the systems
+    /// registered for the given name. See [`super::custom`].
+    Custom {
+        /// The name of the command.
+        name: String,
+
+        /// The payload of the command, as arbitrary JSON.
+        #[serde(default)]
+        payload: serde_json::Value,
+    },
+
+    /// A command that wraps another command, and requests that the given
+    /// correlation id is sent back through the acknowledgement channel once
+    /// the wrapped command has been applied. This command is only sent
+    /// internally by the native API and cannot be sent by scripts directly.
+    #[serde(skip_deserializing)]
+    Acknowledge {
+        /// The correlation id of the command.
+        id: u64,
+
+        /// The command to apply.
+        command: Box<LogicCommands>,
+    },
+
+    /// A command that is used to show a message in the script console, such as
+    /// the output of `print` or an uncaught script error. This command is only
+    /// sent internally by the script engine and cannot be sent by scripts
+    /// directly.
+    #[serde(skip_deserializing)]
+    Log {
+        /// The severity of the message.
+        level: ScriptLogLevel,
+
+        /// The message to show.
+        message: String,
+    },
 }
 
 impl LogicCommands {
@@ -67,3 +299,17 @@ pub enum EditTilesetAction {
     /// Deletes the tileset.
     Delete,
 }
+
+/// The severity of a message shown in the script console.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ScriptLogLevel {
+    /// A regular message, such as the output of `print`.
+    Info,
+
+    /// A warning about a potential problem.
+    Warn,
+
+    /// An error, such as an uncaught exception within a script.
+    Error,
+}
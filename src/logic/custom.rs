@@ -0,0 +1,146 @@
+//! This module implements custom events, which allow game-specific scripts and
+//! systems to communicate with each other without modifying the core
+//! [`LogicCommands`](super::commands::LogicCommands) and
+//! [`LogicEvent`](super::events::LogicEvent) enums.
+//!
+//! Scripts send a custom command with a name and an arbitrary JSON payload,
+//! which is forwarded as a [`CustomLogicCommand`] event and passed to all
+//! handlers registered for that name with
+//! [`CustomLogicAppExt::register_custom_command`]. Systems send a
+//! [`CustomLogicEvent`] to forward a custom event to the active script engine.
+
+use bevy::ecs::system::SystemId;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use serde_json::Value;
+
+use super::events::LogicEvent;
+use super::resources::AwgenScriptChannels;
+
+/// This event is sent when a script sends a custom command.
+#[derive(Debug, Clone, Event)]
+pub struct CustomLogicCommand {
+    /// The name of the command.
+    pub name: String,
+
+    /// The payload of the command.
+    pub payload: Value,
+}
+
+/// This event can be sent to forward a custom event to the active script
+/// engine. The event is dropped if no script engine is running.
+#[derive(Debug, Clone, Event)]
+pub struct CustomLogicEvent {
+    /// The name of the event.
+    pub name: String,
+
+    /// The payload of the event.
+    pub payload: Value,
+}
+
+/// This resource is the registry of all systems that handle custom commands,
+/// grouped by command name.
+#[derive(Debug, Default, Resource)]
+pub struct CustomCommandHandlers {
+    /// The handlers of each command name, in the order they were registered.
+    handlers: HashMap<String, Vec<SystemId<Value>>>,
+}
+
+/// An extension trait for registering custom command handlers on the app.
+pub trait CustomLogicAppExt {
+    /// Registers a system that handles the custom command with the given name.
+    /// The system is run once for each received command, with the payload of
+    /// the command as input. Multiple handlers may be registered for the same
+    /// name.
+    fn register_custom_command<M>(
+        &mut self,
+        name: impl Into<String>,
+        system: impl IntoSystem<Value, (), M> + 'static,
+    ) -> &mut Self;
+}
+
+impl CustomLogicAppExt for App {
+    fn register_custom_command<M>(
+        &mut self,
+        name: impl Into<String>,
+        system: impl IntoSystem<Value, (), M> + 'static,
+    ) -> &mut Self {
+        let system = self.world_mut().register_system(system);
+        self.world_mut()
+            .get_resource_or_insert_with(CustomCommandHandlers::default)
+            .handlers
+            .entry(name.into())
+            .or_default()
+            .push(system);
+        self
+    }
+}
+
+/// This system runs the registered handlers of all received custom commands.
+pub fn dispatch_custom_commands(
+    mut custom_commands: EventReader<CustomLogicCommand>,
+    handlers: Res<CustomCommandHandlers>,
+    mut commands: Commands,
+) {
+    for ev in custom_commands.read() {
+        let Some(systems) = handlers.handlers.get(&ev.name) else {
+            debug!("No handler registered for custom command: {}", ev.name);
+            continue;
+        };
+
+        for system in systems.iter() {
+            commands.run_system_with_input(*system, ev.payload.clone());
+        }
+    }
+}
+
+/// This system forwards all custom events to the active script engine.
+pub fn forward_custom_events(
+    mut custom_events: EventReader<CustomLogicEvent>,
+    channels: Res<AwgenScriptChannels>,
+) {
+    for ev in custom_events.read() {
+        channels.send(LogicEvent::Custom {
+            name: ev.name.clone(),
+            payload: ev.payload.clone(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[derive(Debug, Default, Resource)]
+    struct Received(Vec<Value>);
+
+    #[test]
+    fn commands_run_handlers_by_name() {
+        let mut app = App::new();
+        app.add_event::<CustomLogicCommand>()
+            .init_resource::<CustomCommandHandlers>()
+            .init_resource::<Received>()
+            .add_systems(Update, dispatch_custom_commands)
+            .register_custom_command(
+                "openDoor",
+                |In(payload): In<Value>, mut received: ResMut<Received>| {
+                    received.0.push(payload);
+                },
+            );
+
+        app.world_mut().send_event(CustomLogicCommand {
+            name: "openDoor".to_string(),
+            payload: json!({ "door": 3 }),
+        });
+        app.world_mut().send_event(CustomLogicCommand {
+            name: "closeDoor".to_string(),
+            payload: json!({ "door": 4 }),
+        });
+        app.update();
+
+        let received = app.world().resource::<Received>();
+        assert_eq!(received.0, vec![json!({ "door": 3 })]);
+    }
+}
@@ -0,0 +1,120 @@
+//! This module defines the errors that can occur while running an AwgenScript
+//! engine or executing the commands it sends to the main thread.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::settings::ProjectSettingsError;
+#[cfg(test)]
+use crate::settings::SQLITE_BUSY;
+
+/// The number of times an operation on the project settings is retried if the
+/// database is temporarily busy.
+pub const SETTINGS_BUSY_RETRIES: u32 = 3;
+
+/// The delay between two attempts of an operation on the project settings that
+/// failed because the database was busy.
+pub const SETTINGS_BUSY_DELAY: Duration = Duration::from_millis(5);
+
+/// An error that can occur while running an AwgenScript engine or executing its
+/// commands.
+#[derive(Debug, thiserror::Error)]
+pub enum LogicError {
+    /// The project settings could not be read or written.
+    #[error("Failed to access the project settings: {0}")]
+    Settings(#[from] ProjectSettingsError),
+
+    /// A script file could not be read.
+    #[error("Failed to read the script {path}: {source}")]
+    ScriptIo {
+        /// The path to the script file.
+        path: String,
+
+        /// The underlying error.
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// A script file could not be parsed.
+    #[error("Failed to parse the script {path}: {message}")]
+    ScriptParse {
+        /// The path to the script file.
+        path: String,
+
+        /// The message of the parse error.
+        message: String,
+    },
+}
+
+/// Runs the given operation on the project settings, retrying it a few times if
+/// it fails because the database is temporarily busy. Any other error is
+/// returned immediately.
+pub fn retry_settings<T>(
+    mut operation: impl FnMut() -> Result<T, ProjectSettingsError>,
+) -> Result<T, LogicError> {
+    let mut attempt = 0;
+    loop {
+        match operation() {
+            Ok(value) => return Ok(value),
+            Err(err) if err.is_busy() && attempt < SETTINGS_BUSY_RETRIES => {
+                attempt += 1;
+                debug!("Project settings are busy; Retrying ({attempt}/{SETTINGS_BUSY_RETRIES}).");
+                std::thread::sleep(SETTINGS_BUSY_DELAY);
+            }
+            Err(err) => return Err(err.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates an SQL error with the given result code.
+    fn sql_error(code: isize) -> ProjectSettingsError {
+        ProjectSettingsError::Sql(sqlite::Error {
+            code: Some(code),
+            message: None,
+        })
+    }
+
+    #[test]
+    fn retries_busy_errors() {
+        let mut attempts = 0;
+        let result = retry_settings(|| {
+            attempts += 1;
+            if attempts < 3 {
+                Err(sql_error(SQLITE_BUSY))
+            } else {
+                Ok(attempts)
+            }
+        });
+
+        assert_eq!(result.unwrap(), 3);
+    }
+
+    #[test]
+    fn gives_up_on_busy_errors() {
+        let mut attempts = 0;
+        let result: Result<(), _> = retry_settings(|| {
+            attempts += 1;
+            Err(sql_error(SQLITE_BUSY))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(attempts, SETTINGS_BUSY_RETRIES + 1);
+    }
+
+    #[test]
+    fn does_not_retry_other_errors() {
+        let mut attempts = 0;
+        let result: Result<(), _> = retry_settings(|| {
+            attempts += 1;
+            Err(sql_error(1))
+        });
+
+        assert!(matches!(result, Err(LogicError::Settings(_))));
+        assert_eq!(attempts, 1);
+    }
+}
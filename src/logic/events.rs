@@ -22,6 +22,33 @@ pub enum LogicEvent {
         /// A list of all the tilesets in the project.
         tilesets: Vec<TilesetDefinition>,
     },
+
+    /// An event that is triggered when a key that the engine has subscribed to
+    /// is pressed.
+    KeyPressed {
+        /// The stable name of the key, such as `"KeyA"` or `"Space"`.
+        key: String,
+    },
+
+    /// An event that is triggered when a mouse button is clicked, if the engine
+    /// has subscribed to mouse input.
+    MouseClicked {
+        /// The name of the mouse button: `"left"`, `"right"`, or `"middle"`.
+        button: String,
+
+        /// The position of the cursor within the window, in logical pixels,
+        /// measured from the top-left corner.
+        pos: [f32; 2],
+    },
+
+    /// A custom event sent by a game-specific system. See [`super::custom`].
+    Custom {
+        /// The name of the event.
+        name: String,
+
+        /// The payload of the event, as arbitrary JSON.
+        payload: serde_json::Value,
+    },
 }
 
 impl LogicEvent {
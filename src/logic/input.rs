@@ -0,0 +1,234 @@
+//! This module forwards keyboard and mouse input to the AwgenScript engine.
+//!
+//! Input is opt-in: scripts declare which keys they care about, and whether
+//! they want mouse clicks, using the `subscribeInput` command. Only matching
+//! input is forwarded, so the engine is not flooded with every keystroke.
+//!
+//! Keys are identified by stable names that match the names of the
+//! `KeyboardEvent.code` property in web browsers, such as `"KeyA"`, `"Digit1"`,
+//! `"Space"`, or `"ArrowUp"`. Mouse buttons are named `"left"`, `"right"`, and
+//! `"middle"`.
+
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use bevy::window::PrimaryWindow;
+
+use super::events::LogicEvent;
+use super::resources::AwgenScriptChannels;
+
+/// The stable names of all keys that can be forwarded to scripts.
+const KEY_NAMES: &[(KeyCode, &str)] = &[
+    (KeyCode::KeyA, "KeyA"),
+    (KeyCode::KeyB, "KeyB"),
+    (KeyCode::KeyC, "KeyC"),
+    (KeyCode::KeyD, "KeyD"),
+    (KeyCode::KeyE, "KeyE"),
+    (KeyCode::KeyF, "KeyF"),
+    (KeyCode::KeyG, "KeyG"),
+    (KeyCode::KeyH, "KeyH"),
+    (KeyCode::KeyI, "KeyI"),
+    (KeyCode::KeyJ, "KeyJ"),
+    (KeyCode::KeyK, "KeyK"),
+    (KeyCode::KeyL, "KeyL"),
+    (KeyCode::KeyM, "KeyM"),
+    (KeyCode::KeyN, "KeyN"),
+    (KeyCode::KeyO, "KeyO"),
+    (KeyCode::KeyP, "KeyP"),
+    (KeyCode::KeyQ, "KeyQ"),
+    (KeyCode::KeyR, "KeyR"),
+    (KeyCode::KeyS, "KeyS"),
+    (KeyCode::KeyT, "KeyT"),
+    (KeyCode::KeyU, "KeyU"),
+    (KeyCode::KeyV, "KeyV"),
+    (KeyCode::KeyW, "KeyW"),
+    (KeyCode::KeyX, "KeyX"),
+    (KeyCode::KeyY, "KeyY"),
+    (KeyCode::KeyZ, "KeyZ"),
+    (KeyCode::Digit0, "Digit0"),
+    (KeyCode::Digit1, "Digit1"),
+    (KeyCode::Digit2, "Digit2"),
+    (KeyCode::Digit3, "Digit3"),
+    (KeyCode::Digit4, "Digit4"),
+    (KeyCode::Digit5, "Digit5"),
+    (KeyCode::Digit6, "Digit6"),
+    (KeyCode::Digit7, "Digit7"),
+    (KeyCode::Digit8, "Digit8"),
+    (KeyCode::Digit9, "Digit9"),
+    (KeyCode::F1, "F1"),
+    (KeyCode::F2, "F2"),
+    (KeyCode::F3, "F3"),
+    (KeyCode::F4, "F4"),
+    (KeyCode::F5, "F5"),
+    (KeyCode::F6, "F6"),
+    (KeyCode::F7, "F7"),
+    (KeyCode::F8, "F8"),
+    (KeyCode::F9, "F9"),
+    (KeyCode::F10, "F10"),
+    (KeyCode::F11, "F11"),
+    (KeyCode::F12, "F12"),
+    (KeyCode::ArrowUp, "ArrowUp"),
+    (KeyCode::ArrowDown, "ArrowDown"),
+    (KeyCode::ArrowLeft, "ArrowLeft"),
+    (KeyCode::ArrowRight, "ArrowRight"),
+    (KeyCode::Space, "Space"),
+    (KeyCode::Enter, "Enter"),
+    (KeyCode::Escape, "Escape"),
+    (KeyCode::Tab, "Tab"),
+    (KeyCode::Backspace, "Backspace"),
+    (KeyCode::Delete, "Delete"),
+    (KeyCode::Insert, "Insert"),
+    (KeyCode::Home, "Home"),
+    (KeyCode::End, "End"),
+    (KeyCode::PageUp, "PageUp"),
+    (KeyCode::PageDown, "PageDown"),
+    (KeyCode::ShiftLeft, "ShiftLeft"),
+    (KeyCode::ShiftRight, "ShiftRight"),
+    (KeyCode::ControlLeft, "ControlLeft"),
+    (KeyCode::ControlRight, "ControlRight"),
+    (KeyCode::AltLeft, "AltLeft"),
+    (KeyCode::AltRight, "AltRight"),
+    (KeyCode::Minus, "Minus"),
+    (KeyCode::Equal, "Equal"),
+    (KeyCode::BracketLeft, "BracketLeft"),
+    (KeyCode::BracketRight, "BracketRight"),
+    (KeyCode::Backslash, "Backslash"),
+    (KeyCode::Semicolon, "Semicolon"),
+    (KeyCode::Quote, "Quote"),
+    (KeyCode::Backquote, "Backquote"),
+    (KeyCode::Comma, "Comma"),
+    (KeyCode::Period, "Period"),
+    (KeyCode::Slash, "Slash"),
+];
+
+/// Gets the stable name of the given key, or `None` if the key cannot be
+/// forwarded to scripts.
+pub fn key_name(key: KeyCode) -> Option<&'static str> {
+    KEY_NAMES
+        .iter()
+        .find(|(code, _)| *code == key)
+        .map(|(_, name)| *name)
+}
+
+/// Gets the key with the given stable name, or `None` if the name is unknown.
+pub fn key_from_name(name: &str) -> Option<KeyCode> {
+    KEY_NAMES
+        .iter()
+        .find(|(_, key_name)| *key_name == name)
+        .map(|(code, _)| *code)
+}
+
+/// Gets the stable name of the given mouse button, or `None` if the button
+/// cannot be forwarded to scripts.
+pub fn mouse_button_name(button: MouseButton) -> Option<&'static str> {
+    match button {
+        MouseButton::Left => Some("left"),
+        MouseButton::Right => Some("right"),
+        MouseButton::Middle => Some("middle"),
+        _ => None,
+    }
+}
+
+/// This resource stores which input the active AwgenScript engine has
+/// subscribed to. It is cleared whenever the engine is closed.
+#[derive(Debug, Default, Resource)]
+pub struct ScriptInputSubscription {
+    /// The keys whose presses are forwarded to the engine.
+    pub keys: HashSet<KeyCode>,
+
+    /// Whether mouse clicks are forwarded to the engine.
+    pub mouse: bool,
+}
+
+impl ScriptInputSubscription {
+    /// Replaces the subscription with the given key names and mouse flag.
+    /// Unknown key names are skipped with a warning.
+    pub fn set(&mut self, keys: &[String], mouse: bool) {
+        self.keys.clear();
+        self.mouse = mouse;
+
+        for name in keys {
+            match key_from_name(name) {
+                Some(key) => {
+                    self.keys.insert(key);
+                }
+                None => warn!("Cannot subscribe to unknown key: {}", name),
+            }
+        }
+    }
+
+    /// Removes all subscriptions.
+    pub fn clear(&mut self) {
+        self.keys.clear();
+        self.mouse = false;
+    }
+}
+
+/// This system forwards the subscribed key presses and mouse clicks to the
+/// active AwgenScript engine.
+pub fn forward_script_input(
+    subscription: Res<ScriptInputSubscription>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    channels: Res<AwgenScriptChannels>,
+) {
+    for key in keyboard_input.get_just_pressed() {
+        if !subscription.keys.contains(key) {
+            continue;
+        }
+
+        if let Some(name) = key_name(*key) {
+            channels.send(LogicEvent::KeyPressed {
+                key: name.to_string(),
+            });
+        }
+    }
+
+    if !subscription.mouse {
+        return;
+    }
+
+    let Some(pos) = windows.get_single().ok().and_then(|w| w.cursor_position()) else {
+        return;
+    };
+
+    for button in mouse_input.get_just_pressed() {
+        if let Some(name) = mouse_button_name(*button) {
+            channels.send(LogicEvent::MouseClicked {
+                button: name.to_string(),
+                pos: [pos.x, pos.y],
+            });
+        }
+    }
+}
+
+/// This system removes all input subscriptions of the closed AwgenScript
+/// engine.
+pub fn clear_script_input(mut subscription: ResMut<ScriptInputSubscription>) {
+    subscription.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_names_round_trip() {
+        for (key, name) in KEY_NAMES {
+            assert_eq!(key_name(*key), Some(*name));
+            assert_eq!(key_from_name(name), Some(*key));
+        }
+
+        assert_eq!(key_from_name("NotAKey"), None);
+    }
+
+    #[test]
+    fn subscription_skips_unknown_keys() {
+        let mut subscription = ScriptInputSubscription::default();
+        subscription.set(&["KeyW".to_string(), "Bogus".to_string()], true);
+
+        assert!(subscription.keys.contains(&KeyCode::KeyW));
+        assert_eq!(subscription.keys.len(), 1);
+        assert!(subscription.mouse);
+    }
+}
@@ -5,14 +5,20 @@
 use std::path::{Path, PathBuf};
 
 use bevy::prelude::*;
-use resources::AwgenScriptChannels;
+use custom::{CustomCommandHandlers, CustomLogicCommand, CustomLogicEvent};
+use input::ScriptInputSubscription;
+use resources::{AwgenScriptChannels, ScriptCommandMetrics, ScriptConsole};
 
 use crate::gamestate::GameState;
 
 pub mod api;
 pub mod channels;
 pub mod commands;
+pub mod custom;
+pub mod error;
 pub mod events;
+pub mod input;
+pub mod queries;
 pub mod queue;
 pub mod resources;
 pub mod systems;
@@ -24,17 +30,46 @@ impl Plugin for LogicPlugin {
     fn build(&self, app_: &mut App) {
         app_.init_resource::<LogicPluginSettings>()
             .init_resource::<AwgenScriptChannels>()
+            .init_resource::<ScriptCommandMetrics>()
+            .init_resource::<ScriptConsole>()
+            .init_resource::<ScriptInputSubscription>()
+            .init_resource::<CustomCommandHandlers>()
+            .add_event::<CustomLogicCommand>()
+            .add_event::<CustomLogicEvent>()
             .add_systems(OnEnter(GameState::Runtime), systems::begin_runtime_loop)
-            .add_systems(OnExit(GameState::Runtime), systems::close_engine_loop)
+            .add_systems(
+                OnExit(GameState::Runtime),
+                (systems::close_engine_loop, input::clear_script_input),
+            )
+            .add_systems(
+                Last,
+                (
+                    systems::flush_acknowledgements.run_if(resource_exists::<AwgenScriptChannels>),
+                    systems::close_engine_loop.run_if(on_event::<AppExit>()),
+                )
+                    .chain(),
+            )
+            .add_systems(
+                Update,
+                (
+                    systems::handle_logic_outputs.before(crate::map::edit::set_blocks),
+                    input::forward_script_input,
+                    custom::forward_custom_events,
+                )
+                    .run_if(resource_exists::<AwgenScriptChannels>),
+            )
             .add_systems(
                 Update,
-                systems::handle_logic_outputs.run_if(resource_exists::<AwgenScriptChannels>),
+                custom::dispatch_custom_commands.after(systems::handle_logic_outputs),
             );
 
         #[cfg(feature = "editor")]
         {
             app_.add_systems(OnEnter(GameState::Editor), systems::begin_editor_loop)
-                .add_systems(OnExit(GameState::Editor), systems::close_engine_loop);
+                .add_systems(
+                    OnExit(GameState::Editor),
+                    (systems::close_engine_loop, input::clear_script_input),
+                );
         }
     }
 }
@@ -48,6 +83,25 @@ pub struct LogicPluginSettings {
 
     /// The path to the runtime script source folder.
     pub runtime_script_path: PathBuf,
+
+    /// The maximum number of commands that the script engine may queue for the
+    /// main thread, or `None` for an unbounded queue.
+    ///
+    /// Each frame, the main thread only handles the commands that were queued
+    /// when the frame started. Commands sent after that are handled in the
+    /// next frame, so a script can never stall the main thread.
+    ///
+    /// When the queue is full, the script engine thread blocks on the next
+    /// command it sends until the main thread has handled enough commands to
+    /// make room. With a bound, a script that sends commands in a tight loop is
+    /// therefore slowed down to at most this many commands per frame, instead
+    /// of allocating memory without limit. Events and query results sent to
+    /// the script engine are never bounded, so the main thread never blocks.
+    pub command_channel_capacity: Option<usize>,
+
+    /// Whether the number of handled script commands, and the time spent
+    /// handling them, is logged every frame in which commands are received.
+    pub log_command_metrics: bool,
 }
 
 impl Default for LogicPluginSettings {
@@ -56,6 +110,8 @@ impl Default for LogicPluginSettings {
             #[cfg(feature = "editor")]
             editor_script_path: Path::new("./assets/editor_scripts").to_path_buf(),
             runtime_script_path: Path::new("./scripts").to_path_buf(),
+            command_channel_capacity: None,
+            log_command_metrics: false,
         }
     }
 }
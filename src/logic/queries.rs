@@ -0,0 +1,58 @@
+//! This module contains the results of queries that can be requested by the
+//! AwgenScript engine.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// An enum that represents all possible query results that can be sent back to
+/// the AwgenScript engine in response to a query command.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "query", rename_all = "camelCase")]
+pub enum LogicQueryResult {
+    /// The block that is currently targeted by the cursor.
+    CursorTarget {
+        /// The targeted block, or `None` if the cursor is not hovering over any
+        /// block.
+        target: Option<CursorTarget>,
+    },
+
+    /// The block at the requested position.
+    Block {
+        /// The uuid of the block, or `None` if the chunk that contains the
+        /// block is not loaded.
+        block: Option<Uuid>,
+    },
+
+    /// The project assets that match the requested pattern.
+    Assets {
+        /// The asset paths of the matching files, relative to the project
+        /// asset folder.
+        paths: Vec<String>,
+    },
+
+    /// The blocks that have the requested tag.
+    BlocksByTag {
+        /// The uuids of the matching blocks.
+        blocks: Vec<Uuid>,
+    },
+
+    /// The icon of a block that is being rendered.
+    BlockIcon {
+        /// The asset path of the icon file, or `None` if the block does not
+        /// exist.
+        path: Option<String>,
+    },
+}
+
+/// The block that is targeted by the cursor.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CursorTarget {
+    /// The position of the targeted block, in world coordinates.
+    pub position: [i32; 3],
+
+    /// The face of the targeted block that the cursor is hovering over.
+    pub face: String,
+
+    /// The uuid of the targeted block type.
+    pub block: Uuid,
+}
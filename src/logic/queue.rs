@@ -11,6 +11,9 @@ use futures_util::stream::FuturesUnordered;
 use smol::stream::StreamExt;
 use smol::{LocalExecutor, future};
 
+use super::channels::AwgenScriptSendChannel;
+use super::commands::{LogicCommands, ScriptLogLevel};
+
 /// A listener that signals the script engine to shut down. This is simply a
 /// boolean value that is shared between the main thread and the script engine
 /// thread.
@@ -111,6 +114,10 @@ impl JobQueue for ScriptEngineJobQueue {
                     for job in jobs {
                         if let Err(e) = job.call(&mut context.borrow_mut()) {
                             eprintln!("Uncaught {e}");
+                            AwgenScriptSendChannel::send(LogicCommands::Log {
+                                level: ScriptLogLevel::Error,
+                                message: format!("Uncaught {e}"),
+                            });
                         }
                         future::yield_now().await;
                     }
@@ -1,10 +1,16 @@
 //! The resources module contains the resources used by the logic plugin.
 
+use std::collections::VecDeque;
+use std::future::Future;
+use std::time::Duration;
+
 use bevy::prelude::*;
+use bevy::tasks::IoTaskPool;
 use smol::channel::{Receiver, Sender};
 
-use super::commands::LogicCommands;
+use super::commands::{LogicCommands, ScriptLogLevel};
 use super::events::LogicEvent;
+use super::queries::LogicQueryResult;
 use super::queue::ScriptEngineShutdown;
 
 /// The logic data resource contains the channels used to communicate with the
@@ -19,6 +25,18 @@ pub struct AwgenScriptChannels {
     /// be `None` if there is no engine running.
     receive_channel: Option<Receiver<LogicCommands>>,
 
+    /// The channel to send query results to the active AwgenScript engine. May
+    /// be `None` if there is no engine running.
+    query_channel: Option<Sender<(u64, LogicQueryResult)>>,
+
+    /// The channel to send command acknowledgements to the active AwgenScript
+    /// engine. May be `None` if there is no engine running.
+    ack_channel: Option<Sender<(u64, Result<(), String>)>>,
+
+    /// The correlation ids of the commands that have been handled this frame,
+    /// and are acknowledged at the end of the frame.
+    pending_acks: Vec<(u64, Result<(), String>)>,
+
     /// The signal for the active AwgenScript  engine to shut down. May be
     /// `None` if there is no engine running.
     shutdown: Option<ScriptEngineShutdown>,
@@ -31,11 +49,15 @@ impl AwgenScriptChannels {
         &mut self,
         send_channel: Sender<LogicEvent>,
         receive_channel: Receiver<LogicCommands>,
+        query_channel: Sender<(u64, LogicQueryResult)>,
+        ack_channel: Sender<(u64, Result<(), String>)>,
         shutdown: ScriptEngineShutdown,
     ) {
         self.shutdown();
         self.send_channel = Some(send_channel);
         self.receive_channel = Some(receive_channel);
+        self.query_channel = Some(query_channel);
+        self.ack_channel = Some(ack_channel);
         self.shutdown = Some(shutdown);
     }
 
@@ -50,6 +72,72 @@ impl AwgenScriptChannels {
         }
     }
 
+    /// Sends the result of the query with the given correlation id to the
+    /// active AwgenScript engine.
+    ///
+    /// If the channel is closed, this function does nothing.
+    pub fn respond(&self, id: u64, result: LogicQueryResult) {
+        if let Some(channel) = &self.query_channel {
+            if let Err(e) = channel.try_send((id, result)) {
+                error!("Failed to send query result to AwgenScript engine: {}", e);
+            }
+        }
+    }
+
+    /// Computes the result of the query with the given correlation id on a
+    /// background task, and sends it to the active AwgenScript engine once it
+    /// is ready. This is used for queries that are too slow to answer within
+    /// a single frame.
+    ///
+    /// If the channel is closed, this function does nothing.
+    pub fn respond_later<F>(&self, id: u64, result: F)
+    where
+        F: Future<Output = LogicQueryResult> + Send + 'static,
+    {
+        let Some(channel) = self.query_channel.clone() else {
+            return;
+        };
+
+        IoTaskPool::get()
+            .spawn(async move {
+                if let Err(e) = channel.send((id, result.await)).await {
+                    error!("Failed to send query result to AwgenScript engine: {}", e);
+                }
+            })
+            .detach();
+    }
+
+    /// Marks the command with the given correlation id as handled, with the
+    /// error message if the command could not be applied. The command is
+    /// acknowledged to the active AwgenScript engine at the end of the frame,
+    /// once all systems had a chance to apply its effects. See
+    /// [`Self::flush_acknowledgements`].
+    pub fn acknowledge(&mut self, id: u64, result: Result<(), String>) {
+        self.pending_acks.push((id, result));
+    }
+
+    /// Sends all pending command acknowledgements to the active AwgenScript
+    /// engine.
+    ///
+    /// If the channel is closed, the pending acknowledgements are dropped.
+    pub fn flush_acknowledgements(&mut self) {
+        for ack in self.pending_acks.drain(..) {
+            if let Some(channel) = &self.ack_channel {
+                if let Err(e) = channel.try_send(ack) {
+                    error!("Failed to acknowledge AwgenScript command: {}", e);
+                }
+            }
+        }
+    }
+
+    /// Returns the number of messages from the active AwgenScript engine that
+    /// are waiting to be received, or 0 if there is no engine running.
+    pub fn pending(&self) -> usize {
+        self.receive_channel
+            .as_ref()
+            .map_or(0, |channel| channel.len())
+    }
+
     /// Receives a message from the active AwgenScript engine, or returns
     /// `None` if no message is available.
     ///
@@ -83,5 +171,113 @@ impl AwgenScriptChannels {
             let _ = channel.close();
             self.receive_channel = None;
         }
+
+        if let Some(channel) = &self.query_channel {
+            debug!("Closing AwgenScript engine query channel.");
+            let _ = channel.close();
+            self.query_channel = None;
+        }
+
+        if let Some(channel) = &self.ack_channel {
+            debug!("Closing AwgenScript engine acknowledgement channel.");
+            let _ = channel.close();
+            self.ack_channel = None;
+        }
+
+        self.pending_acks.clear();
+    }
+}
+
+/// The number of queued script commands at which a backlog warning is logged.
+/// Every time the backlog doubles beyond this, another warning is logged.
+pub const SCRIPT_BACKLOG_WARNING: usize = 1000;
+
+/// This resource stores metrics about the commands that were received from the
+/// active AwgenScript engine during the last frame, for diagnosing
+/// script-driven performance problems.
+#[derive(Debug, Default, Resource)]
+pub struct ScriptCommandMetrics {
+    /// The number of commands that were handled during the last frame.
+    pub commands: usize,
+
+    /// The time spent handling commands during the last frame.
+    pub duration: Duration,
+
+    /// The number of commands that were waiting in the channel at the start of
+    /// the last frame.
+    pub backlog: usize,
+
+    /// The total number of commands handled since the metrics were created.
+    pub total_commands: u64,
+
+    /// The backlog at which the next backlog warning is logged.
+    next_warning: usize,
+}
+
+impl ScriptCommandMetrics {
+    /// Records the metrics of the current frame. If the backlog keeps growing,
+    /// meaning the script produces commands faster than they are handled, a
+    /// warning is logged each time the backlog doubles.
+    pub fn record(&mut self, backlog: usize, commands: usize, duration: Duration) {
+        self.backlog = backlog;
+        self.commands = commands;
+        self.duration = duration;
+        self.total_commands += commands as u64;
+
+        let threshold = self.next_warning.max(SCRIPT_BACKLOG_WARNING);
+        if backlog >= threshold {
+            warn!(
+                "AwgenScript command backlog is growing: {} commands queued. The script may be sending commands faster than they can be handled.",
+                backlog
+            );
+            self.next_warning = threshold * 2;
+        } else if backlog < SCRIPT_BACKLOG_WARNING {
+            self.next_warning = SCRIPT_BACKLOG_WARNING;
+        }
+    }
+}
+
+/// The maximum number of lines that are retained by the [`ScriptConsole`]. Once
+/// this limit is reached, the oldest lines are discarded.
+pub const SCRIPT_CONSOLE_MAX_LINES: usize = 1000;
+
+/// A single message shown in the script console.
+#[derive(Debug, Clone)]
+pub struct ScriptLogLine {
+    /// The severity of the message.
+    pub level: ScriptLogLevel,
+
+    /// The message text.
+    pub message: String,
+}
+
+/// This resource stores the most recent messages logged by the active
+/// AwgenScript engine, such as the output of `print` and uncaught script
+/// errors.
+#[derive(Debug, Default, Resource)]
+pub struct ScriptConsole {
+    /// The retained lines, from oldest to newest.
+    lines: VecDeque<ScriptLogLine>,
+}
+
+impl ScriptConsole {
+    /// Adds a message to the console, discarding the oldest message if the
+    /// console is full.
+    pub fn push(&mut self, level: ScriptLogLevel, message: String) {
+        if self.lines.len() >= SCRIPT_CONSOLE_MAX_LINES {
+            self.lines.pop_front();
+        }
+
+        self.lines.push_back(ScriptLogLine { level, message });
+    }
+
+    /// Iterates over all retained lines, from oldest to newest.
+    pub fn lines(&self) -> impl Iterator<Item = &ScriptLogLine> {
+        self.lines.iter()
+    }
+
+    /// Removes all lines from the console.
+    pub fn clear(&mut self) {
+        self.lines.clear();
     }
 }
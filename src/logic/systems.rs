@@ -4,65 +4,573 @@ use std::fs::File;
 use std::io::BufReader;
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
+use std::time::Instant;
 
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use boa_engine::builtins::promise::PromiseState;
 use boa_engine::context::ContextBuilder;
 use boa_engine::module::SimpleModuleLoader;
 use boa_engine::{Context, JsError, Module, NativeFunction, Source, js_string};
 
-use super::channels::{AwgenScriptReceiveChannel, AwgenScriptSendChannel};
-use super::commands::LogicCommands;
+use super::channels::{
+    AwgenScriptAckChannel,
+    AwgenScriptQueryChannel,
+    AwgenScriptReceiveChannel,
+    AwgenScriptSendChannel,
+};
+use super::commands::{LogicCommands, ScriptLogLevel};
+use super::custom::CustomLogicCommand;
+use super::error::{LogicError, retry_settings};
 use super::events::LogicEvent;
+use super::input::ScriptInputSubscription;
+use super::queries::{CursorTarget, LogicQueryResult};
 use super::queue::{ScriptEngineJobQueue, ScriptEngineShutdown};
-use super::resources::AwgenScriptChannels;
+use super::resources::{AwgenScriptChannels, ScriptCommandMetrics, ScriptConsole};
 use super::{LogicPluginSettings, api};
-use crate::blocks::tileset::TilesetDefinition;
+use crate::assets::ProjectAssetFolders;
+use crate::blocks::params::BlockFinder;
+use crate::blocks::shape::BlockShape;
+use crate::blocks::tags::BlockTags;
+use crate::blocks::tileset::{Tileset, TilesetDefinition, TilesetMaterial};
+use crate::blocks::{Block, ReloadBlockAssets};
+#[cfg(feature = "editor")]
+use crate::gamestate::GameState;
+use crate::gizmos::cursor::CursorRaycast;
 use crate::logic::commands::EditTilesetAction;
+use crate::map::biome::BiomeMap;
+use crate::map::chunk::ChunkData;
+use crate::map::edit::SetBlock;
+use crate::map::heightmap::ImportHeightmap;
+use crate::map::rules::{BuildRegion, BuildRules};
+use crate::map::seed::WorldSeed;
+use crate::map::streaming::WorldStreaming;
+use crate::map::surface::FillVisibleSurface;
+use crate::map::world::VoxelWorld;
+use crate::math::BlockPos;
+use crate::render::RenderSettings;
 use crate::settings::ProjectSettings;
+#[cfg(feature = "editor")]
+use crate::ui::confirm::ConfirmDialog;
+use crate::ui::gui3d::export::{ExportBlockIcon, block_icon_asset_path, block_icon_file};
+use crate::utilities::glob::Glob;
 use crate::{PROJECT_NAME_DEFAULT, PROJECT_NAME_KEY, PROJECT_VERSION_DEFAULT, PROJECT_VERSION_KEY};
 
+/// The event writers used by [`handle_logic_outputs`] to forward script
+/// commands to other plugins.
+#[derive(SystemParam)]
+pub struct LogicOutputEvents<'w> {
+    /// Requests that block assets are reloaded from disk.
+    reload: EventWriter<'w, ReloadBlockAssets>,
+
+    /// Requests that the visible surface of a region is filled.
+    surface: EventWriter<'w, FillVisibleSurface>,
+
+    /// Requests that a block icon is exported.
+    icons: EventWriter<'w, ExportBlockIcon>,
+
+    /// Forwards custom commands to their registered handlers.
+    custom: EventWriter<'w, CustomLogicCommand>,
+
+    /// Requests that single blocks of the world are replaced.
+    set_block: EventWriter<'w, SetBlock>,
+
+    /// Requests that heightmap images are imported as terrain.
+    heightmap: EventWriter<'w, ImportHeightmap>,
+}
+
+/// The world and render settings that can be changed by scripts through
+/// [`handle_logic_outputs`].
+#[derive(SystemParam)]
+pub struct LogicWorldSettings<'w> {
+    /// The render distance of the world.
+    streaming: ResMut<'w, WorldStreaming>,
+
+    /// The render settings of the project.
+    render: ResMut<'w, RenderSettings>,
+
+    /// The rules that restrict player edits.
+    build_rules: ResMut<'w, BuildRules>,
+
+    /// The biomes that tint flagged block faces.
+    biomes: ResMut<'w, BiomeMap>,
+}
+
+/// The editor state used by [`handle_logic_outputs`] to ask the user for
+/// confirmation before destructive script commands are run.
+#[cfg(feature = "editor")]
+#[derive(SystemParam)]
+pub struct LogicConfirmDialog<'w> {
+    /// The current game state. Confirmation is only asked for in the editor.
+    state: Res<'w, State<GameState>>,
+
+    /// The shared confirmation dialog.
+    dialog: ResMut<'w, ConfirmDialog>,
+}
+
+/// The engine state that can be queried by scripts through
+/// [`handle_logic_outputs`].
+#[derive(SystemParam)]
+pub struct LogicQueryView<'w, 's> {
+    /// The asset folders of the project, if available.
+    assets: Option<Res<'w, ProjectAssetFolders>>,
+
+    /// The block currently targeted by the cursor.
+    cursor: Res<'w, CursorRaycast>,
+
+    /// The chunks of the voxel world.
+    world: Res<'w, VoxelWorld>,
+
+    /// The block data of all chunks.
+    chunks: Query<'w, 's, &'static ChunkData>,
+}
+
+impl<'w, 's> LogicQueryView<'w, 's> {
+    /// Gets the block at the given position, or `None` if the chunk that
+    /// contains the block is not loaded.
+    fn block_at(&self, pos: BlockPos) -> Option<Entity> {
+        self.world.get_block(pos, &self.chunks)
+    }
+}
+
 /// Handles the logic input channels.
+#[allow(clippy::too_many_arguments)]
 pub fn handle_logic_outputs(
+    settings: Res<LogicPluginSettings>,
     project_settings: Res<ProjectSettings>,
     mut channels: ResMut<AwgenScriptChannels>,
+    mut metrics: ResMut<ScriptCommandMetrics>,
+    mut console: ResMut<ScriptConsole>,
+    mut input: ResMut<ScriptInputSubscription>,
+    block_finder: BlockFinder,
+    query: LogicQueryView,
+    blocks: Query<(&Block, Option<&BlockTags>)>,
+    block_shapes: Query<(&Name, &BlockShape), With<Block>>,
+    mut tilesets: Query<(&Name, &mut TilesetMaterial), With<Tileset>>,
+    mut world_settings: LogicWorldSettings,
+    mut events: LogicOutputEvents,
+    #[cfg(feature = "editor")] mut confirm: LogicConfirmDialog,
 ) {
-    while let Some(output) = channels.receive() {
-        match output {
-            LogicCommands::SetProjectName { name } => {
-                info!("Updating project name: {}", name);
-                project_settings.set(PROJECT_NAME_KEY, Some(&name)).unwrap();
-            }
+    let start = Instant::now();
+    let backlog = channels.pending();
+    let mut commands = 0;
 
-            LogicCommands::SetProjectVersion { version } => {
-                info!("Updating project version: {}", version);
-                project_settings
-                    .set(PROJECT_VERSION_KEY, Some(&version))
-                    .unwrap();
-            }
+    // Only the commands that were queued at the start of the frame are handled,
+    // so a script that keeps sending commands cannot stall the main thread.
+    while commands < backlog {
+        let Some(output) = channels.receive() else {
+            break;
+        };
+        commands += 1;
+
+        let (ack, output) = match output {
+            LogicCommands::Acknowledge { id, command } => (Some(id), *command),
+            output => (None, output),
+        };
+
+        let result = 'command: {
+            match output {
+                LogicCommands::SetProjectName { name } => {
+                    info!("Updating project name: {}", name);
+                    if let Err(err) =
+                        retry_settings(|| project_settings.set(PROJECT_NAME_KEY, Some(&name)))
+                    {
+                        break 'command Err(report_error(
+                            &mut console,
+                            "Failed to update project name",
+                            err,
+                        ));
+                    }
+                }
+
+                LogicCommands::SetProjectVersion { version } => {
+                    info!("Updating project version: {}", version);
+                    if let Err(err) =
+                        retry_settings(|| project_settings.set(PROJECT_VERSION_KEY, Some(&version)))
+                    {
+                        break 'command Err(report_error(
+                            &mut console,
+                            "Failed to update project version",
+                            err,
+                        ));
+                    }
+                }
+
+                LogicCommands::EditTileset { uuid, action } => match action {
+                    EditTilesetAction::Create { name } => {
+                        info!("Creating new tileset {}", uuid);
+                        debug!("Name: {}", name);
+
+                        if !TilesetDefinition::is_valid_name(&name) {
+                            break 'command Err(reject(
+                                &mut console,
+                                format!("Cannot create tileset; Invalid tileset name: {}", name),
+                            ));
+                        }
+
+                        let definition = TilesetDefinition { uuid, name };
+                        if let Err(err) =
+                            retry_settings(|| project_settings.update_tileset(&definition))
+                        {
+                            break 'command Err(report_error(
+                                &mut console,
+                                "Failed to create tileset",
+                                err,
+                            ));
+                        }
+                    }
+                    EditTilesetAction::Update { name } => {
+                        info!("Updating tileset {}", uuid);
+                        debug!("New name: {}", name);
+
+                        if !TilesetDefinition::is_valid_name(&name) {
+                            break 'command Err(reject(
+                                &mut console,
+                                format!("Cannot update tileset; Invalid tileset name: {}", name),
+                            ));
+                        }
+
+                        let definition = TilesetDefinition { uuid, name };
+                        if let Err(err) =
+                            retry_settings(|| project_settings.update_tileset(&definition))
+                        {
+                            break 'command Err(report_error(
+                                &mut console,
+                                "Failed to update tileset",
+                                err,
+                            ));
+                        }
+                    }
+                    EditTilesetAction::Delete => {
+                        let tilesets = match retry_settings(|| project_settings.list_tilesets()) {
+                            Ok(tilesets) => tilesets,
+                            Err(err) => {
+                                break 'command Err(report_error(
+                                    &mut console,
+                                    "Failed to delete tileset",
+                                    err,
+                                ));
+                            }
+                        };
+
+                        let name = tilesets
+                            .into_iter()
+                            .find(|tileset| tileset.uuid == uuid)
+                            .map(|tileset| tileset.name);
+
+                        if let Some(name) = name {
+                            let dependents = block_shapes
+                                .iter()
+                                .filter(|(_, shape)| shape.uses_tileset(&name))
+                                .map(|(block_name, _)| block_name.as_str())
+                                .collect::<Vec<_>>();
+
+                            if !dependents.is_empty() {
+                                break 'command Err(reject(
+                                    &mut console,
+                                    format!(
+                                        "Cannot delete tileset {}; It is still used by blocks: {}",
+                                        name,
+                                        dependents.join(", ")
+                                    ),
+                                ));
+                            }
+                        }
+
+                        info!("Deleting tileset {}", uuid);
+                        if let Err(err) = retry_settings(|| project_settings.remove_tileset(&uuid))
+                        {
+                            break 'command Err(report_error(
+                                &mut console,
+                                "Failed to delete tileset",
+                                err,
+                            ));
+                        }
+                    }
+                },
+
+                LogicCommands::ReloadAssets => {
+                    info!("Reloading block assets.");
+                    events.reload.send(ReloadBlockAssets);
+                }
+
+                LogicCommands::SetRenderDistance { distance } => {
+                    info!("Updating render distance: {}", distance);
+                    world_settings.streaming.set_render_distance(distance);
+                }
+
+                LogicCommands::SetAntiAliasing { mode } => {
+                    info!("Updating anti-aliasing mode: {:?}", mode);
+                    world_settings.render.anti_aliasing = mode;
+                }
+
+                LogicCommands::SetSkyColor { color } => {
+                    info!("Updating sky color: {:?}", color);
+                    world_settings.render.sky_color = color;
+                }
+
+                LogicCommands::SetBiomes { biomes } => {
+                    info!("Updating biomes: {} biomes", biomes.len());
+                    world_settings.biomes.biomes = biomes;
+                }
+
+                LogicCommands::FillVisibleSurface {
+                    min,
+                    max,
+                    top,
+                    side,
+                    bottom,
+                } => {
+                    let (Some(top), Some(side), Some(bottom)) = (
+                        block_finder.find_by_uuid(top),
+                        block_finder.find_by_uuid(side),
+                        block_finder.find_by_uuid(bottom),
+                    ) else {
+                        break 'command Err(reject(
+                            &mut console,
+                            "Cannot fill visible surface; Unknown block uuid.".to_string(),
+                        ));
+                    };
+
+                    info!("Filling visible surface.");
+                    events.surface.send(FillVisibleSurface {
+                        min: BlockPos::new(min[0], min[1], min[2]),
+                        max: BlockPos::new(max[0], max[1], max[2]),
+                        top,
+                        side,
+                        bottom,
+                    });
+                }
+
+                LogicCommands::SetTilesetMaterial { tileset, material } => {
+                    let Some((_, mut properties)) = tilesets
+                        .iter_mut()
+                        .find(|(name, _)| name.as_str() == tileset)
+                    else {
+                        break 'command Err(reject(
+                            &mut console,
+                            format!(
+                                "Cannot update tileset material; Unknown tileset: {}",
+                                tileset
+                            ),
+                        ));
+                    };
+
+                    info!("Updating material properties of tileset: {}", tileset);
+                    material.save(&project_settings, &tileset);
+                    *properties = material;
+                }
+
+                LogicCommands::SetBlock { position, block } => {
+                    let Some(block) = block_finder.find_by_uuid(block) else {
+                        break 'command Err(reject(
+                            &mut console,
+                            format!("Cannot set block; Unknown block uuid: {}", block),
+                        ));
+                    };
+
+                    let pos = BlockPos::new(position[0], position[1], position[2]);
+                    debug!("Setting block at {} to {:?}", pos, block);
+                    events.set_block.send(SetBlock { pos, block });
+                }
+
+                LogicCommands::ImportHeightmap {
+                    path,
+                    height,
+                    base,
+                    surface,
+                    scale,
+                } => {
+                    let (Some(base), Some(surface)) = (
+                        block_finder.find_by_uuid(base),
+                        block_finder.find_by_uuid(surface),
+                    ) else {
+                        break 'command Err(reject(
+                            &mut console,
+                            "Cannot import heightmap; Unknown block uuid.".to_string(),
+                        ));
+                    };
 
-            LogicCommands::EditTileset { uuid, action } => match action {
-                EditTilesetAction::Create { name } => {
-                    info!("Creating new tileset {}", uuid);
-                    debug!("Name: {}", name);
+                    let import = ImportHeightmap {
+                        path,
+                        max_height: height,
+                        base,
+                        surface,
+                        scale,
+                    };
 
-                    let definition = TilesetDefinition { uuid, name };
-                    project_settings.update_tileset(&definition).unwrap();
+                    // Imports replace existing blocks, so the user is asked
+                    // first while editing the world.
+                    #[cfg(feature = "editor")]
+                    if *confirm.state.get() == GameState::Editor {
+                        let message = format!(
+                            "Import the heightmap {}? Existing blocks within its area will be \
+                             replaced.",
+                            import.path
+                        );
+                        confirm.dialog.open(message, move |world: &mut World| {
+                            world.send_event(import);
+                        });
+                        break 'command Ok(());
+                    }
+
+                    events.heightmap.send(import);
+                }
+
+                LogicCommands::SetBuildRule { regions, banned } => {
+                    let regions = regions
+                        .into_iter()
+                        .map(|[min, max]| {
+                            BuildRegion::new(
+                                BlockPos::new(min[0], min[1], min[2]),
+                                BlockPos::new(max[0], max[1], max[2]),
+                            )
+                        })
+                        .collect();
+
+                    let banned = banned
+                        .into_iter()
+                        .filter_map(|uuid| {
+                            let block = block_finder.find_by_uuid(uuid);
+                            if block.is_none() {
+                                warn!("Cannot ban block; Unknown block uuid: {}", uuid);
+                            }
+                            block
+                        })
+                        .collect();
+
+                    info!("Updating build rules.");
+                    world_settings.build_rules.regions = regions;
+                    world_settings.build_rules.banned = banned;
+                }
+
+                LogicCommands::QueryBlock { id, position } => {
+                    let pos = BlockPos::new(position[0], position[1], position[2]);
+                    let block = query
+                        .block_at(pos)
+                        .and_then(|block| blocks.get(block).ok())
+                        .map(|(block, _)| block.uuid);
+
+                    channels.respond(id, LogicQueryResult::Block { block });
+                }
+
+                LogicCommands::QueryAssets { id, pattern } => {
+                    let listing = match (Glob::new(&pattern), &query.assets) {
+                        (Some(glob), Some(assets)) => Some((glob, assets.clone())),
+                        (None, _) => {
+                            warn!("Cannot list assets; Invalid pattern: {}", pattern);
+                            None
+                        }
+                        (_, None) => {
+                            warn!("Cannot list assets; No project asset folders.");
+                            None
+                        }
+                    };
+
+                    channels.respond_later(id, async move {
+                        let paths = match listing {
+                            Some((glob, assets)) => assets
+                                .list_files(&glob.base())
+                                .await
+                                .into_iter()
+                                .filter(|path| glob.matches(path))
+                                .collect(),
+                            None => Vec::new(),
+                        };
+
+                        LogicQueryResult::Assets { paths }
+                    });
+                }
+
+                LogicCommands::QueryCursorTarget { id } => {
+                    let target = query.cursor.block.as_ref().and_then(|hit| {
+                        let (block, _) = blocks.get(hit.block_id).ok()?;
+                        Some(CursorTarget {
+                            position: [hit.block.x, hit.block.y, hit.block.z],
+                            face: hit.face.to_string(),
+                            block: block.uuid,
+                        })
+                    });
+
+                    channels.respond(id, LogicQueryResult::CursorTarget { target });
+                }
+
+                LogicCommands::QueryBlocksByTag { id, tag } => {
+                    let blocks = blocks
+                        .iter()
+                        .filter(|(_, tags)| tags.cloned().unwrap_or_default().has(&tag))
+                        .map(|(block, _)| block.uuid)
+                        .collect();
+
+                    channels.respond(id, LogicQueryResult::BlocksByTag { blocks });
+                }
+
+                LogicCommands::QueryRenderBlockIcon {
+                    id,
+                    uuid,
+                    size,
+                    background,
+                } => {
+                    let path = block_finder.find_by_uuid(uuid).map(|block| {
+                        let path = block_icon_file(&project_settings.asset_folder(), uuid);
+                        info!("Rendering icon of block {} to: {}", uuid, path.display());
+
+                        events.icons.send(ExportBlockIcon {
+                            block,
+                            size,
+                            path,
+                            background: background.map(|[r, g, b, a]| Color::srgba_u8(r, g, b, a)),
+                        });
+
+                        block_icon_asset_path(uuid)
+                    });
+
+                    if path.is_none() {
+                        warn!("Cannot render block icon; Unknown block: {}", uuid);
+                    }
+
+                    channels.respond(id, LogicQueryResult::BlockIcon { path });
                 }
-                EditTilesetAction::Update { name } => {
-                    info!("Updating tileset {}", uuid);
-                    debug!("New name: {}", name);
 
-                    let definition = TilesetDefinition { uuid, name };
-                    project_settings.update_tileset(&definition).unwrap();
+                LogicCommands::SubscribeInput { keys, mouse } => {
+                    debug!("Subscribing to input: {:?} (mouse: {})", keys, mouse);
+                    input.set(&keys, mouse);
                 }
-                EditTilesetAction::Delete => {
-                    info!("Deleting tileset {}", uuid);
-                    project_settings.remove_tileset(&uuid).unwrap();
+
+                LogicCommands::Custom { name, payload } => {
+                    debug!("Received custom command: {}", name);
+                    events.custom.send(CustomLogicCommand { name, payload });
+                }
+
+                LogicCommands::Log { level, message } => {
+                    console.push(level, message);
+                }
+
+                LogicCommands::Acknowledge { id, .. } => {
+                    let message = format!("Ignoring nested command acknowledgement: {}", id);
+                    channels.acknowledge(id, Err(reject(&mut console, message)));
                 }
-            },
+            }
+
+            Ok(())
+        };
+
+        if let Some(id) = ack {
+            channels.acknowledge(id, result);
         }
     }
+
+    let duration = start.elapsed();
+    if settings.log_command_metrics && commands > 0 {
+        info!(
+            "Handled {} script commands in {:.3} ms ({} queued).",
+            commands,
+            duration.as_secs_f64() * 1000.0,
+            backlog
+        );
+    }
+
+    metrics.record(backlog, commands, duration);
 }
 
 /// This system creates the AwgenScript editor engine thread and initializes the
@@ -71,11 +579,14 @@ pub fn handle_logic_outputs(
 pub fn begin_editor_loop(
     settings: Res<LogicPluginSettings>,
     project_settings: Res<ProjectSettings>,
+    seed: Res<WorldSeed>,
     mut channels: ResMut<AwgenScriptChannels>,
 ) {
     begin_loop(
         settings.editor_script_path.clone(),
         "ScriptEngine-Editor".to_string(),
+        settings.command_channel_capacity,
+        seed.0,
         &project_settings,
         &mut channels,
     );
@@ -86,11 +597,14 @@ pub fn begin_editor_loop(
 pub fn begin_runtime_loop(
     settings: Res<LogicPluginSettings>,
     project_settings: Res<ProjectSettings>,
+    seed: Res<WorldSeed>,
     mut channels: ResMut<AwgenScriptChannels>,
 ) {
     begin_loop(
         settings.runtime_script_path.clone(),
         "ScriptEngine-Runtime".to_string(),
+        settings.command_channel_capacity,
+        seed.0,
         &project_settings,
         &mut channels,
     );
@@ -99,39 +613,99 @@ pub fn begin_runtime_loop(
 /// This function creates a new thread for the AwgenScript engine and
 /// initializes the channels for communication between the engine and the main
 /// game loop.
+///
+/// If a command capacity is given, the channel for commands sent by the engine
+/// is bounded to that many commands. See
+/// [`LogicPluginSettings::command_channel_capacity`].
 fn begin_loop(
     script_path: PathBuf,
     thread_name: String,
+    command_capacity: Option<usize>,
+    seed: u64,
     project_settings: &Res<ProjectSettings>,
     channels: &mut ResMut<AwgenScriptChannels>,
 ) {
     let (in_send, in_recv) = smol::channel::unbounded();
-    let (out_send, out_recv) = smol::channel::unbounded();
+    let (out_send, out_recv) = match command_capacity {
+        Some(capacity) => smol::channel::bounded(capacity.max(1)),
+        None => smol::channel::unbounded(),
+    };
+    let (query_send, query_recv) = smol::channel::unbounded();
+    let (ack_send, ack_recv) = smol::channel::unbounded();
     let shutdown = ScriptEngineShutdown::new();
-    channels.set_channels(in_send, out_recv, shutdown.clone());
+    channels.set_channels(in_send, out_recv, query_send, ack_send, shutdown.clone());
+
+    let settings_reader = project_settings
+        .reader()
+        .inspect_err(|err| warn!("Scripts cannot read project settings directly: {err}"))
+        .ok();
 
     std::thread::Builder::new()
         .name(thread_name)
         .spawn(move || {
             AwgenScriptReceiveChannel::set(in_recv);
             AwgenScriptSendChannel::set(out_send);
+            AwgenScriptQueryChannel::set(query_recv);
+            AwgenScriptAckChannel::set(ack_recv);
+            api::set_random_seed(seed);
+            api::set_settings_reader(settings_reader);
             exec_engine(script_path, shutdown);
         })
         .unwrap();
 
+    let project_name = retry_settings(|| project_settings.get(PROJECT_NAME_KEY))
+        .unwrap_or_else(|err| {
+            error!("Failed to read project name: {err}");
+            None
+        })
+        .unwrap_or_else(|| PROJECT_NAME_DEFAULT.to_string());
+
+    let project_version = retry_settings(|| project_settings.get(PROJECT_VERSION_KEY))
+        .unwrap_or_else(|err| {
+            error!("Failed to read project version: {err}");
+            None
+        })
+        .unwrap_or_else(|| PROJECT_VERSION_DEFAULT.to_string());
+
+    let tilesets = retry_settings(|| project_settings.list_tilesets()).unwrap_or_else(|err| {
+        error!("Failed to read tilesets: {err}");
+        Vec::new()
+    });
+
     channels.send(LogicEvent::EngineStarted {
-        project_name: project_settings
-            .get(PROJECT_NAME_KEY)
-            .unwrap()
-            .unwrap_or_else(|| PROJECT_NAME_DEFAULT.to_string()),
-        project_version: project_settings
-            .get(PROJECT_VERSION_KEY)
-            .unwrap()
-            .unwrap_or_else(|| PROJECT_VERSION_DEFAULT.to_string()),
-        tilesets: project_settings.list_tilesets().unwrap(),
+        project_name,
+        project_version,
+        tilesets,
     });
 }
 
+/// Logs an error that occurred while executing a script command, and shows it
+/// in the script console. Returns the error message, so that it can be sent
+/// back with the command acknowledgement.
+fn report_error(console: &mut ScriptConsole, context: &str, err: LogicError) -> String {
+    let message = format!("{context}: {err}");
+    error!("{message}");
+    console.push(ScriptLogLevel::Error, message.clone());
+    message
+}
+
+/// Logs a warning for a script command that could not be applied, and shows it
+/// in the script console. Returns the warning, so that it can be sent back with
+/// the command acknowledgement.
+fn reject(console: &mut ScriptConsole, message: String) -> String {
+    warn!("{message}");
+    console.push(ScriptLogLevel::Warn, message.clone());
+    message
+}
+
+/// This system sends the acknowledgements of all commands that were handled
+/// this frame to the active AwgenScript engine. It runs at the end of the
+/// frame, so that all systems had a chance to apply the effects of the
+/// commands, such as placing blocks.
+pub fn flush_acknowledgements(mut channels: ResMut<AwgenScriptChannels>) {
+    channels.flush_acknowledgements();
+}
+
 /// This system closes the active AwgenScript engine thread.
 pub fn close_engine_loop(mut channels: ResMut<AwgenScriptChannels>) {
     channels.shutdown();
@@ -154,15 +728,72 @@ pub fn exec_engine(path: PathBuf, shutdown: ScriptEngineShutdown) {
     register(c, "print", 1, NativeFunction::from_fn_ptr(api::print));
     register(c, "sleep", 1, NativeFunction::from_async_fn(api::sleep));
     register(c, "UUID", 0, NativeFunction::from_fn_ptr(api::uuid));
+    register(c, "RANDOM", 0, NativeFunction::from_fn_ptr(api::random));
+    register(
+        c,
+        "GET_SETTING",
+        1,
+        NativeFunction::from_fn_ptr(api::get_setting),
+    );
+    register(
+        c,
+        "RANDOM_SEED",
+        0,
+        NativeFunction::from_fn_ptr(api::random_seed),
+    );
     register(c, "EVENT", 0, NativeFunction::from_async_fn(api::event));
     register(c, "COMMAND", 1, NativeFunction::from_fn_ptr(api::command));
+    register(
+        c,
+        "COMMAND_ACK",
+        1,
+        NativeFunction::from_async_fn(api::command_ack),
+    );
+    register(
+        c,
+        "GET_BLOCK",
+        1,
+        NativeFunction::from_async_fn(api::get_block),
+    );
+    register(
+        c,
+        "LIST_ASSETS",
+        1,
+        NativeFunction::from_async_fn(api::list_assets),
+    );
+    register(
+        c,
+        "GET_CURSOR_TARGET",
+        0,
+        NativeFunction::from_async_fn(api::get_cursor_target),
+    );
+    register(
+        c,
+        "GET_BLOCKS_BY_TAG",
+        1,
+        NativeFunction::from_async_fn(api::get_blocks_by_tag),
+    );
+    register(
+        c,
+        "RENDER_BLOCK_ICON",
+        3,
+        NativeFunction::from_async_fn(api::render_block_icon),
+    );
 
-    let main_file = path.clone().canonicalize().unwrap().join("main.mjs");
-    let relative_path = Path::new("./main.mjs");
-    let file_reader = BufReader::new(File::open(&main_file).unwrap());
-    let source = Source::from_reader(file_reader, Some(relative_path));
-    let module = Module::parse(source, None, &mut context).unwrap();
-    module_loader.insert(main_file, module.clone());
+    let module = match load_main_module(&path, &mut context) {
+        Ok((main_file, module)) => {
+            module_loader.insert(main_file, module.clone());
+            module
+        }
+        Err(err) => {
+            error!("{err}");
+            AwgenScriptSendChannel::send(LogicCommands::Log {
+                level: ScriptLogLevel::Error,
+                message: err.to_string(),
+            });
+            return;
+        }
+    };
 
     let promise = module.load_link_evaluate(&mut context);
     context.run_jobs();
@@ -171,14 +802,37 @@ pub fn exec_engine(path: PathBuf, shutdown: ScriptEngineShutdown) {
         PromiseState::Pending => error!("Failed to execute all AwgenScript jobs."),
         PromiseState::Fulfilled(_) => {}
         PromiseState::Rejected(err) => {
-            error!(
-                "AwgenScript exited with an error: {:?}",
-                JsError::from_opaque(err).try_native(&mut context).unwrap()
-            );
+            let err = JsError::from_opaque(err).try_native(&mut context).unwrap();
+            error!("AwgenScript exited with an error: {:?}", err);
+            AwgenScriptSendChannel::send(LogicCommands::Log {
+                level: ScriptLogLevel::Error,
+                message: format!("Script exited with an error: {err}"),
+            });
         }
     }
 }
 
+/// Reads and parses the `main.mjs` module within the given script folder.
+/// Returns the absolute path of the module file along with the parsed module.
+fn load_main_module(path: &Path, context: &mut Context) -> Result<(PathBuf, Module), LogicError> {
+    let main_file = path.join("main.mjs");
+    let io_error = |source| LogicError::ScriptIo {
+        path: main_file.display().to_string(),
+        source,
+    };
+
+    let main_file = main_file.canonicalize().map_err(io_error)?;
+    let file_reader = BufReader::new(File::open(&main_file).map_err(io_error)?);
+    let source = Source::from_reader(file_reader, Some(Path::new("./main.mjs")));
+
+    let module = Module::parse(source, None, context).map_err(|err| LogicError::ScriptParse {
+        path: main_file.display().to_string(),
+        message: err.to_string(),
+    })?;
+
+    Ok((main_file, module))
+}
+
 /// Registers a native function with the given name and argument count to the
 /// script engine.
 fn register(context: &mut Context, name: &str, args: usize, func: NativeFunction) {
@@ -1,33 +1,47 @@
-#![doc = include_str!("../README.md")]
+//! The executable entry point for the Awgen game engine and runtime.
 #![warn(missing_docs)]
 #![warn(clippy::missing_docs_in_private_items)]
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use std::path::PathBuf;
 use std::process::Termination;
+use std::time::Duration;
 
+use awgen::headless::{self, ExitAfterFrames};
+use awgen::logic::LogicPluginSettings;
+use awgen::settings::ProjectSettings;
+use awgen::{
+    COMPUTE_THREADS_KEY,
+    COMPUTE_THREADS_MAX,
+    DEV_MODE,
+    PROJECT_NAME_DEFAULT,
+    PROJECT_NAME_KEY,
+    PROJECT_VERSION_DEFAULT,
+    PROJECT_VERSION_KEY,
+    assets,
+    blocks,
+    camera,
+    gamestate,
+    gizmos,
+    logic,
+    map,
+    project,
+    render,
+    ui,
+};
+use bevy::app::ScheduleRunnerPlugin;
 use bevy::asset::io::AssetSourceBuilder;
+use bevy::core::{TaskPoolOptions, TaskPoolPlugin, TaskPoolThreadAssignmentPolicy};
 use bevy::log::LogPlugin;
 use bevy::prelude::*;
-use bevy::window::WindowMode;
+use bevy::render::RenderPlugin;
+use bevy::render::settings::{WgpuFeatures, WgpuSettings};
+use bevy::window::{ExitCondition, WindowMode};
+use bevy::winit::WinitPlugin;
 use bevy_egui::EguiPlugin;
 use bevy_framepace::{FramepacePlugin, FramepaceSettings, Limiter};
 use bevy_mod_picking::DefaultPickingPlugins;
 use clap::Parser;
-use logic::LogicPluginSettings;
-use settings::ProjectSettings;
-
-mod blocks;
-mod camera;
-mod gamestate;
-mod gizmos;
-mod logic;
-mod map;
-mod math;
-mod settings;
-mod tools;
-mod ui;
-mod utilities;
 
 /// The command line arguments definition for the engine.
 #[derive(Debug, Parser)]
@@ -48,25 +62,43 @@ struct Args {
     #[arg(short, long)]
     project: Option<String>,
 
+    /// An additional asset folder, such as a mod pack, to layer on top of the
+    /// project assets. May be given multiple times. Assets in later overlays
+    /// override assets in earlier overlays, and all overlays override the
+    /// project assets.
+    #[arg(long, value_name = "PATH")]
+    overlay: Vec<String>,
+
     /// Launch the engine in fullscreen mode.
     #[arg(short, long)]
     fullscreen: bool,
-}
 
-/// Whether the engine is running in development mode.
-pub const DEV_MODE: bool = cfg!(feature = "editor");
+    /// Skip the splash screen and start the project directly. Projects may
+    /// also disable the splash screen through their settings.
+    #[arg(long)]
+    no_splash: bool,
 
-/// The key used to store the project name in the settings file.
-pub const PROJECT_NAME_KEY: &str = "NAME";
+    /// Run the engine without a window or renderer. The splash screen is
+    /// skipped, and the engine runs at a fixed 60 updates per second.
+    #[arg(long)]
+    headless: bool,
 
-/// The default project name if none is provided.
-pub const PROJECT_NAME_DEFAULT: &str = "Untitled";
+    /// Exit the engine after the given number of frames have been run.
+    #[arg(long, value_name = "FRAMES")]
+    exit_after: Option<u32>,
 
-/// The key used to store the project version in the settings file.
-pub const PROJECT_VERSION_KEY: &str = "VERSION";
+    /// The number of worker threads used for background work, such as chunk
+    /// remeshing, from 1 to 256. Overrides the project setting. If neither is
+    /// given, the number is picked automatically based on the number of CPU
+    /// cores.
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u16).range(1 ..= COMPUTE_THREADS_MAX as i64))]
+    threads: Option<u16>,
 
-/// The default project version if none is provided.
-pub const PROJECT_VERSION_DEFAULT: &str = "0.0.1";
+    /// Log the number of script commands handled, and the time spent handling
+    /// them, every frame in which commands are received.
+    #[arg(long)]
+    log_script_metrics: bool,
+}
 
 /// The main function for the Awgen Engine.
 fn main() -> impl Termination {
@@ -92,7 +124,12 @@ fn main() -> impl Termination {
 
     let asset_folder = format!("{}/assets", project_folder.display());
 
+    let asset_folders = assets::ProjectAssetFolders::new(&asset_folder, &args.overlay);
+
     println!("Opening project at: {}", project_folder.display());
+    for overlay in args.overlay.iter() {
+        println!("Adding asset overlay: {}", overlay);
+    }
 
     let settings = match ProjectSettings::new(project_folder, DEV_MODE) {
         Ok(settings) => settings,
@@ -123,12 +160,9 @@ fn main() -> impl Termination {
     println!("Project name: {}", proj_name);
     println!("Project version: {}", proj_version);
 
-    let title = match (DEV_MODE, args.debug) {
-        (true, true) => format!("Awgen Editor [{} - {}] (debug)", proj_name, proj_version),
-        (true, false) => format!("Awgen Editor [{} - {}]", proj_name, proj_version),
-        (false, true) => format!("{} - {} (debug)", proj_name, proj_version),
-        (false, false) => format!("{} - {}", proj_name, proj_version),
-    };
+    let compute_threads = args.threads.or_else(|| read_compute_threads(&settings));
+
+    let title = project::window_title(&proj_name, &proj_version, args.debug);
 
     println!("Debug enabled: {}", args.debug);
     let log_level = if args.debug {
@@ -151,12 +185,74 @@ fn main() -> impl Termination {
         WindowMode::Windowed
     };
 
-    App::new()
-        .insert_resource(ClearColor(Color::BLACK))
+    let mut default_plugins = DefaultPlugins
+        .set(WindowPlugin {
+            primary_window: Some(Window {
+                title,
+                mode: window_mode,
+                ..default()
+            }),
+            ..default()
+        })
+        .set(LogPlugin {
+            level: log_level,
+            filter: "wgpu=error,naga=warn,calloop=debug,polling=debug".to_string(),
+            ..default()
+        });
+
+    if let Some(threads) = compute_threads {
+        println!("Compute threads: {}", threads);
+        default_plugins = default_plugins.set(TaskPoolPlugin {
+            task_pool_options: TaskPoolOptions {
+                compute: TaskPoolThreadAssignmentPolicy {
+                    min_threads: threads as usize,
+                    max_threads: threads as usize,
+                    percent: 1.0,
+                },
+                ..default()
+            },
+        });
+    }
+
+    if args.headless {
+        println!("Running in headless mode.");
+        default_plugins = default_plugins
+            .set(WindowPlugin {
+                primary_window: None,
+                exit_condition: ExitCondition::DontExit,
+                close_when_requested: false,
+            })
+            .set(RenderPlugin {
+                render_creation: WgpuSettings {
+                    backends: None,
+                    ..default()
+                }
+                .into(),
+                ..default()
+            })
+            .disable::<WinitPlugin>();
+    } else if args.debug {
+        // Required by the chunk wireframe debug view.
+        default_plugins = default_plugins.set(RenderPlugin {
+            render_creation: WgpuSettings {
+                features: WgpuFeatures::POLYGON_MODE_LINE,
+                ..default()
+            }
+            .into(),
+            ..default()
+        });
+    }
+
+    let mut app = App::new();
+    app.insert_resource(ClearColor(Color::BLACK))
         .insert_resource(settings)
+        .insert_resource(project::DebugMode(args.debug))
+        .insert_resource(asset_folders.clone())
         .insert_resource(LogicPluginSettings {
             editor_script_path: "./assets/editor_scripts".into(),
             runtime_script_path: format!("{}/scripts", asset_folder).into(),
+            log_command_metrics: args.log_script_metrics,
+            ..default()
         })
         .register_asset_source(
             "editor",
@@ -164,37 +260,68 @@ fn main() -> impl Termination {
         )
         .register_asset_source(
             "project",
-            AssetSourceBuilder::platform_default(&asset_folder, None),
-        )
-        .add_plugins(
-            DefaultPlugins
-                .set(WindowPlugin {
-                    primary_window: Some(Window {
-                        title,
-                        mode: window_mode,
-                        ..default()
-                    }),
-                    ..default()
-                })
-                .set(LogPlugin {
-                    level: log_level,
-                    filter: "wgpu=error,naga=warn,calloop=debug,polling=debug".to_string(),
-                    ..default()
-                }),
+            assets::project_asset_source(&asset_folders, &asset_folder),
         )
-        .add_plugins((DefaultPickingPlugins, EguiPlugin, FramepacePlugin))
+        .add_plugins(default_plugins)
+        .add_plugins((DefaultPickingPlugins, EguiPlugin))
         .add_plugins((
+            project::ProjectPlugin,
             camera::CameraPlugin,
             ui::AwgenUIPlugin,
             blocks::BlocksPlugin,
             map::VoxelWorldPlugin,
             gizmos::GizmosPlugin,
             logic::LogicPlugin,
+            render::RenderSettingsPlugin,
         ))
-        .init_state::<gamestate::GameState>()
-        .add_systems(Startup, |mut settings: ResMut<FramepaceSettings>| {
-            settings.limiter = Limiter::from_framerate(60.0);
-        })
-        .add_systems(Startup, gamestate::to_splash_screen)
-        .run()
+        .init_state::<gamestate::GameState>();
+
+    if args.headless {
+        app.add_plugins(ScheduleRunnerPlugin::run_loop(Duration::from_secs_f64(
+            1.0 / 60.0,
+        )))
+        .add_systems(Startup, gamestate::to_playing_state);
+    } else {
+        app.add_plugins(FramepacePlugin)
+            .add_systems(Startup, |mut settings: ResMut<FramepaceSettings>| {
+                settings.limiter = Limiter::from_framerate(60.0);
+            })
+            .add_systems(
+                Startup,
+                if args.no_splash {
+                    gamestate::to_playing_state.into_configs()
+                } else {
+                    gamestate::to_splash_screen.into_configs()
+                },
+            );
+    }
+
+    if args.debug && !args.headless {
+        app.add_plugins(map::wireframe::ChunkWireframePlugin);
+    }
+
+    if let Some(frames) = args.exit_after {
+        println!("Exiting after {} frames.", frames);
+        app.insert_resource(ExitAfterFrames(frames))
+            .add_systems(PostUpdate, headless::exit_after_frames);
+    }
+
+    app.run()
+}
+
+/// Reads the compute thread count from the project settings. Returns `None` if
+/// the count is missing or invalid.
+fn read_compute_threads(settings: &ProjectSettings) -> Option<u16> {
+    match settings.get_parsed::<u16>(COMPUTE_THREADS_KEY) {
+        Ok(Some(threads)) if (1 ..= COMPUTE_THREADS_MAX).contains(&threads) => Some(threads),
+        Ok(Some(threads)) => {
+            eprintln!("Invalid compute thread count in project settings: {threads}");
+            None
+        }
+        Ok(None) => None,
+        Err(err) => {
+            eprintln!("Failed to read compute thread count: {err}");
+            None
+        }
+    }
 }
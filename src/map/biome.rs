@@ -0,0 +1,192 @@
+//! This module implements biomes, which vary the tint of flagged block faces,
+//! such as the top of grass blocks, across the world.
+//!
+//! The [`BiomeMap`] divides the world into a grid of square cells, and picks a
+//! biome for each corner of the grid based on the world seed. The tint of a
+//! column is blended between the four surrounding corners, so that colors shift
+//! smoothly across biome boundaries.
+//!
+//! The list of biomes is stored in the project settings, and can be replaced by
+//! scripts.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::chunk::ChunkData;
+use super::remesh::NeedsRemeshLater;
+use super::seed::{BIOME_RNG_STREAM, WorldSeed};
+use crate::BIOMES_KEY;
+use crate::settings::ProjectSettings;
+use crate::utilities::rng::SeededRng;
+
+/// The size of a biome cell, in blocks.
+pub const BIOME_CELL_SIZE: i32 = 64;
+
+/// A biome, which defines the tint of flagged block faces within it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Biome {
+    /// The name of the biome.
+    pub name: String,
+
+    /// The tint that is multiplied with the vertex color of flagged faces, as
+    /// `[r, g, b]` in sRGB space. Values above 1 brighten the faces.
+    pub tint: [f32; 3],
+}
+
+impl Biome {
+    /// Creates a new biome with the given name and tint.
+    pub fn new(name: impl Into<String>, tint: [f32; 3]) -> Self {
+        Self {
+            name: name.into(),
+            tint,
+        }
+    }
+}
+
+/// This resource maps every column of the world to a biome tint. Changing this
+/// resource remeshes all chunks.
+#[derive(Debug, Clone, PartialEq, Resource)]
+pub struct BiomeMap {
+    /// The biomes that are placed in the world. If empty, faces are never
+    /// tinted.
+    pub biomes: Vec<Biome>,
+
+    /// The seed used to pick the biome of each cell corner.
+    pub seed: u64,
+}
+
+impl Default for BiomeMap {
+    fn default() -> Self {
+        Self {
+            biomes: vec![
+                Biome::new("Plains", [1.0, 1.0, 1.0]),
+                Biome::new("Forest", [0.7, 0.9, 0.65]),
+                Biome::new("Savanna", [1.1, 1.0, 0.6]),
+                Biome::new("Tundra", [0.8, 0.95, 1.0]),
+            ],
+            seed: 0,
+        }
+    }
+}
+
+impl BiomeMap {
+    /// Gets the tint of the column at the given block coordinates, as a linear
+    /// RGB multiplier.
+    pub fn tint_at(&self, x: i32, z: i32) -> Vec3 {
+        if self.biomes.is_empty() {
+            return Vec3::ONE;
+        }
+
+        let cell_x = x.div_euclid(BIOME_CELL_SIZE);
+        let cell_z = z.div_euclid(BIOME_CELL_SIZE);
+        let tx = smoothstep(x.rem_euclid(BIOME_CELL_SIZE) as f32 / BIOME_CELL_SIZE as f32);
+        let tz = smoothstep(z.rem_euclid(BIOME_CELL_SIZE) as f32 / BIOME_CELL_SIZE as f32);
+
+        let north = self
+            .corner_tint(cell_x, cell_z)
+            .lerp(self.corner_tint(cell_x + 1, cell_z), tx);
+        let south = self
+            .corner_tint(cell_x, cell_z + 1)
+            .lerp(self.corner_tint(cell_x + 1, cell_z + 1), tx);
+        north.lerp(south, tz)
+    }
+
+    /// Gets the tint of the biome at the given corner of the biome grid.
+    fn corner_tint(&self, x: i32, z: i32) -> Vec3 {
+        let key = ((x as u32 as u64) << 32) | z as u32 as u64;
+        let mut rng = SeededRng::new(
+            self.seed ^ key.wrapping_mul(0x9E37_79B9_7F4A_7C15),
+            BIOME_RNG_STREAM,
+        );
+
+        let biome = &self.biomes[rng.next_u32() as usize % self.biomes.len()];
+        let [r, g, b] = biome.tint;
+        let tint = Color::srgb(r, g, b).to_linear();
+        Vec3::new(tint.red, tint.green, tint.blue)
+    }
+}
+
+/// Eases the given value in the range `0.0 ..= 1.0`, so that blending between
+/// corners has no visible seams along the cell edges.
+fn smoothstep(t: f32) -> f32 {
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// This system updates the seed of the biome map to match the world seed.
+pub(super) fn sync_biome_seed(seed: Res<WorldSeed>, mut biomes: ResMut<BiomeMap>) {
+    if biomes.seed != seed.0 {
+        biomes.seed = seed.0;
+    }
+}
+
+/// This system loads the list of biomes from the project settings. If the
+/// project does not store any biomes, the default biomes are used.
+pub(super) fn load_biomes(project_settings: Res<ProjectSettings>, mut biomes: ResMut<BiomeMap>) {
+    let list = match project_settings.get_json(BIOMES_KEY) {
+        Ok(list) => list.unwrap_or_else(|| BiomeMap::default().biomes),
+        Err(err) => {
+            error!("Failed to read biomes: {}", err);
+            BiomeMap::default().biomes
+        }
+    };
+
+    if biomes.biomes != list {
+        biomes.biomes = list;
+    }
+}
+
+/// This system saves the list of biomes to the project settings whenever the
+/// biome map changes.
+pub(super) fn save_biomes(project_settings: Res<ProjectSettings>, biomes: Res<BiomeMap>) {
+    if let Err(err) = project_settings.set_json(BIOMES_KEY, Some(&biomes.biomes)) {
+        error!("Failed to save biomes: {}", err);
+    }
+}
+
+/// This system marks all chunks for remeshing when the biome map changes.
+pub(super) fn remesh_biome_chunks(chunks: Query<Entity, With<ChunkData>>, mut commands: Commands) {
+    for chunk_id in chunks.iter() {
+        commands
+            .entity(chunk_id)
+            .insert(NeedsRemeshLater::default());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tint_blends_between_corners() {
+        let map = BiomeMap {
+            biomes: vec![Biome::new("Plains", [1.0, 1.0, 1.0])],
+            seed: 7,
+        };
+        assert_eq!(map.tint_at(-100, 35), Vec3::ONE);
+
+        let map = BiomeMap::default();
+        assert_eq!(map.tint_at(12, -40), map.tint_at(12, -40));
+        assert_eq!(map.tint_at(0, 0), map.corner_tint(0, 0));
+
+        let edge = map.tint_at(BIOME_CELL_SIZE - 1, 0);
+        let next = map.corner_tint(1, 0);
+        assert!(edge.distance(next) < 0.01);
+
+        let empty = BiomeMap {
+            biomes: Vec::new(),
+            seed: 0,
+        };
+        assert_eq!(empty.tint_at(3, 4), Vec3::ONE);
+    }
+
+    #[test]
+    fn biomes_parse_from_json() {
+        let json = r#"[{"name": "Swamp", "tint": [0.5, 0.6, 0.4]}]"#;
+        let biomes: Vec<Biome> = serde_json::from_str(json).unwrap();
+        assert_eq!(biomes, vec![Biome::new("Swamp", [0.5, 0.6, 0.4])]);
+
+        let defaults = BiomeMap::default().biomes;
+        let json = serde_json::to_string(&defaults).unwrap();
+        assert_eq!(serde_json::from_str::<Vec<Biome>>(&json).unwrap(), defaults);
+    }
+}
@@ -3,7 +3,9 @@
 use bevy::prelude::*;
 use itertools::Itertools;
 
-use crate::math::{BlockPos, TOTAL_BLOCKS};
+use super::world::VoxelWorld;
+use crate::math::{BlockPos, CHUNK_SIZE, ChunkPos, FaceDirection, TOTAL_BLOCKS};
+use crate::utilities::chunk_iter::ChunkIterator;
 
 /// The data of the blocks within a chunk. This is stored as an enum to allow
 /// for data compression when all blocks in the chunk are the same type.
@@ -57,6 +59,13 @@ impl ChunkData {
     }
 
     /// Returns the block at the given position within the [`ChunkData`].
+    ///
+    /// Only the local coordinates of the position within its chunk are used,
+    /// so both world and local positions of blocks within this chunk may be
+    /// given. Positions outside of this chunk, such as the neighbor of a block
+    /// at the chunk border, wrap around to the other side of this chunk and
+    /// return the wrong block. Use [`VoxelWorld::get_block`] for positions that
+    /// may lie in another chunk.
     pub fn get(&self, pos: BlockPos) -> Entity {
         match self {
             Self::Single { block } => *block,
@@ -81,6 +90,73 @@ impl ChunkData {
         }
     }
 
+    /// Replaces all blocks of the given type within this chunk data with a new
+    /// block type.
+    ///
+    /// Returns true if any block was changed, false otherwise.
+    pub fn replace(&mut self, from: Entity, to: Entity) -> bool {
+        if from == to {
+            return false;
+        }
+
+        match self {
+            Self::Single { block } => {
+                if *block != from {
+                    return false;
+                }
+
+                *block = to;
+                true
+            }
+            Self::Multiple { blocks } => {
+                let mut changed = false;
+                for block in blocks.iter_mut().filter(|block| **block == from) {
+                    *block = to;
+                    changed = true;
+                }
+                changed
+            }
+        }
+    }
+
+    /// Computes the blocks that differ between this chunk data and the given
+    /// chunk data, as a list of block positions and the block type of `other`
+    /// at that position. Applying the returned patch to this chunk data with
+    /// [`ChunkData::apply_patch`] makes it equal to `other`.
+    ///
+    /// Comparing two single block type chunks does not need to visit each
+    /// block, and returns an empty patch if both contain the same block type.
+    pub fn diff(&self, other: &ChunkData) -> Vec<(BlockPos, Entity)> {
+        match (self, other) {
+            (Self::Single { block: from }, Self::Single { block: to }) if from == to => Vec::new(),
+            (_, Self::Single { block }) => ChunkIterator::default()
+                .filter(|pos| self.get(*pos) != *block)
+                .map(|pos| (pos, *block))
+                .collect(),
+            _ => ChunkIterator::default()
+                .filter_map(|pos| {
+                    let block = other.get(pos);
+                    (self.get(pos) != block).then_some((pos, block))
+                })
+                .collect(),
+        }
+    }
+
+    /// Applies a patch created by [`ChunkData::diff`] to this chunk data,
+    /// setting each listed block position to its new block type.
+    ///
+    /// This method does not convert the chunk data back into a single block
+    /// type; call [`ChunkData::try_convert_to_single`] afterwards if needed.
+    ///
+    /// Returns true if any block was changed, false otherwise.
+    pub fn apply_patch(&mut self, patch: &[(BlockPos, Entity)]) -> bool {
+        let mut changed = false;
+        for &(pos, block) in patch {
+            changed |= self.set(pos, block);
+        }
+        changed
+    }
+
     /// Tries to convert this chunk data into a single block type if all blocks
     /// in the chunk are the same type.
     ///
@@ -98,3 +174,143 @@ impl ChunkData {
         false
     }
 }
+
+/// A read-only view of the blocks around a single chunk, including the six
+/// chunks directly neighboring it. This is used while meshing to sample
+/// neighboring blocks across chunk borders.
+#[derive(Debug, Clone, Copy)]
+pub struct ChunkView<'a> {
+    /// The blocks of the chunk itself.
+    center: &'a ChunkData,
+
+    /// The blocks of the neighboring chunks, indexed by
+    /// [`FaceDirection::index`].
+    neighbors: [Option<&'a ChunkData>; 6],
+}
+
+impl<'a> ChunkView<'a> {
+    /// Creates a view of a single chunk without any loaded neighbors.
+    pub fn single(center: &'a ChunkData) -> Self {
+        Self {
+            center,
+            neighbors: [None; 6],
+        }
+    }
+
+    /// Gathers the blocks around the chunk at the given position.
+    pub fn gather(
+        center: &'a ChunkData,
+        chunk_pos: ChunkPos,
+        world: &VoxelWorld,
+        chunks: &'a Query<&ChunkData>,
+    ) -> Self {
+        let origin = BlockPos::from(chunk_pos);
+        let neighbors = FaceDirection::DIRECTIONS.map(|dir| {
+            let neighbor = ChunkPos::from(origin.shift(dir, CHUNK_SIZE as u32));
+            world.get_chunk(neighbor).and_then(|id| chunks.get(id).ok())
+        });
+
+        Self { center, neighbors }
+    }
+
+    /// Gets the blocks of the chunk itself.
+    pub fn center(&self) -> &'a ChunkData {
+        self.center
+    }
+
+    /// Gets the block at the given position, relative to the center chunk.
+    /// Positions up to one chunk outside of the center chunk along a single
+    /// axis are sampled from the neighboring chunks. Returns `None` for all
+    /// other positions, and for positions within neighboring chunks that are
+    /// not loaded.
+    pub fn get(&self, pos: BlockPos) -> Option<Entity> {
+        let size = CHUNK_SIZE as i32;
+        let offset = IVec3::new(
+            pos.x.div_euclid(size),
+            pos.y.div_euclid(size),
+            pos.z.div_euclid(size),
+        );
+
+        let chunk = if offset == IVec3::ZERO {
+            Some(self.center)
+        } else {
+            match FaceDirection::try_from(offset) {
+                Ok(dir) => self.neighbors[dir.index()],
+                Err(_) => None,
+            }
+        };
+
+        chunk.map(|chunk| chunk.get(pos))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::CHUNK_SIZE;
+
+    /// Asserts that both chunk data containers contain the same blocks.
+    fn assert_same_blocks(a: &ChunkData, b: &ChunkData) {
+        for pos in ChunkIterator::default() {
+            assert_eq!(a.get(pos), b.get(pos), "at {pos}");
+        }
+    }
+
+    #[test]
+    fn diff_round_trip() {
+        let stone = Entity::from_raw(1);
+        let dirt = Entity::from_raw(2);
+        let grass = Entity::from_raw(3);
+
+        let mut from = ChunkData::fill(stone);
+        from.set(BlockPos::new(1, 2, 3), dirt);
+        from.set(BlockPos::new(4, 5, 6), grass);
+
+        let mut to = ChunkData::fill(stone);
+        to.set(BlockPos::new(1, 2, 3), grass);
+        to.set(BlockPos::new(7, 7, 7), dirt);
+
+        let patch = from.diff(&to);
+        assert_eq!(patch.len(), 3);
+
+        assert!(from.apply_patch(&patch));
+        assert_same_blocks(&from, &to);
+        assert!(from.diff(&to).is_empty());
+        assert!(!from.apply_patch(&[]));
+    }
+
+    #[test]
+    fn get_wraps_out_of_bounds_positions() {
+        let stone = Entity::from_raw(1);
+        let dirt = Entity::from_raw(2);
+        let size = CHUNK_SIZE as i32;
+
+        let mut chunk = ChunkData::fill(stone);
+        chunk.set(BlockPos::new(0, 0, 0), dirt);
+
+        assert_eq!(chunk.get(BlockPos::new(size, 0, 0)), dirt);
+        assert_eq!(chunk.get(BlockPos::new(0, -size, 0)), dirt);
+        assert_eq!(chunk.get(BlockPos::new(-1, 0, 0)), stone);
+        assert_eq!(chunk.get(BlockPos::new(size - 1, 0, 0)), stone);
+    }
+
+    #[test]
+    fn diff_single_chunks() {
+        let stone = Entity::from_raw(1);
+        let air = Entity::from_raw(2);
+
+        assert!(
+            ChunkData::fill(stone)
+                .diff(&ChunkData::fill(stone))
+                .is_empty()
+        );
+
+        let mut chunk = ChunkData::fill(stone);
+        let patch = chunk.diff(&ChunkData::fill(air));
+        assert_eq!(patch.len(), TOTAL_BLOCKS);
+
+        chunk.apply_patch(&patch);
+        assert!(chunk.try_convert_to_single());
+        assert_same_blocks(&chunk, &ChunkData::fill(air));
+    }
+}
@@ -0,0 +1,59 @@
+//! This module implements the collision data of chunks. This data is built from
+//! the [`BlockCollision`] shapes of the blocks within a chunk, and is separate
+//! from the render mesh and the mouse picking
+//! [`ChunkCollider`](super::ChunkCollider).
+
+use bevy::math::bounding::Aabb3d;
+use bevy::prelude::*;
+
+use super::chunk::ChunkData;
+use super::remesh::NeedsRemesh;
+use crate::blocks::collision::BlockCollision;
+use crate::blocks::model::BlockModel;
+use crate::blocks::shape::BlockShape;
+use crate::utilities::chunk_iter::ChunkIterator;
+
+/// This component stores the physical collision shapes of all blocks within a
+/// chunk. This component is updated internally when a chunk is remeshed.
+#[derive(Debug, Default, Clone, Component)]
+pub struct ChunkCollision {
+    /// The collision bounds of each block in the chunk, relative to the chunk
+    /// origin.
+    pub boxes: Vec<Aabb3d>,
+}
+
+impl ChunkCollision {
+    /// Builds the collision data for the given chunk data.
+    pub fn from_chunk_data(
+        data: &ChunkData,
+        blocks: &Query<(&BlockCollision, &BlockShape, &BlockModel)>,
+    ) -> Self {
+        let mut boxes = Vec::new();
+
+        for pos in ChunkIterator::default() {
+            let Ok((collision, shape, model)) = blocks.get(data.get(pos)) else {
+                continue;
+            };
+
+            let Some(mut bounds) = collision.get_bounds(shape, model) else {
+                continue;
+            };
+
+            bounds.min += pos.as_vec3a();
+            bounds.max += pos.as_vec3a();
+            boxes.push(bounds);
+        }
+
+        Self { boxes }
+    }
+}
+
+/// This system rebuilds the collision data of all chunks that need remeshing.
+pub(crate) fn update_chunk_collision(
+    blocks: Query<(&BlockCollision, &BlockShape, &BlockModel)>,
+    mut chunks: Query<(&mut ChunkCollision, &ChunkData), With<NeedsRemesh>>,
+) {
+    chunks.par_iter_mut().for_each(|(mut collision, data)| {
+        *collision = ChunkCollision::from_chunk_data(data, &blocks);
+    });
+}
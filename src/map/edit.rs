@@ -0,0 +1,252 @@
+//! This module implements editing blocks of the world. All edits, whether they
+//! are made by the player through the map editor tools or by scripts through
+//! events, are applied through the [`WorldEditor`] system parameter, so that
+//! they share the same checks and chunk bookkeeping.
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::utils::{HashMap, HashSet};
+
+use super::chunk::ChunkData;
+use super::remesh::NeedsRemesh;
+use super::rules::BuildRules;
+use super::world::{VoxelWorld, VoxelWorldCommands};
+use crate::blocks::params::BlockFinder;
+use crate::math::{BlockPos, ChunkPos, FaceDirection};
+
+/// An event that requests a single block of the world to be replaced. If the
+/// chunk that contains the block does not exist yet, it is created and filled
+/// with air.
+#[derive(Debug, Clone, Copy, Event)]
+pub struct SetBlock {
+    /// The position of the block.
+    pub pos: BlockPos,
+
+    /// The new block.
+    pub block: Entity,
+}
+
+/// This event is sent when a block is placed within the world by the player.
+#[derive(Debug, Clone, Copy, Event)]
+pub struct BlockPlaced {
+    /// The block that was placed.
+    pub block: Entity,
+
+    /// The position the block was placed at.
+    pub pos: BlockPos,
+}
+
+/// This event is sent when a block is removed from the world by the player.
+#[derive(Debug, Clone, Copy, Event)]
+pub struct BlockRemoved {
+    /// The block that was removed.
+    pub block: Entity,
+
+    /// The position the block was removed from.
+    pub pos: BlockPos,
+}
+
+/// This event is sent when an edit made by the player is skipped, because the
+/// edit is not allowed by the build rules.
+#[derive(Debug, Clone, Copy, Event)]
+pub struct BlockRejected {
+    /// The position of the rejected edit.
+    pub pos: BlockPos,
+}
+
+/// This resource stores whether existing blocks are protected from being
+/// overwritten. While enabled, blocks are only placed into empty cells, and
+/// placements onto occupied cells are skipped. Removing blocks is not affected.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Resource)]
+pub struct BlockProtection {
+    /// Whether block protection is enabled.
+    pub enabled: bool,
+}
+
+/// The origin of an edit that is applied through the [`WorldEditor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditSource {
+    /// The edit was made by the player. Player edits are checked against the
+    /// [`BuildRules`], and send [`BlockPlaced`], [`BlockRemoved`], and
+    /// [`BlockRejected`] events.
+    Player,
+
+    /// The edit was requested by a script. Script edits are not restricted by
+    /// the build rules, since scripts are the ones declaring them, and do not
+    /// send any events.
+    Script,
+}
+
+/// This system parameter applies block edits to the world. Modified chunks are
+/// marked for remeshing, chunks that become empty are despawned, and missing
+/// chunks are created as needed. Edits on the border of a chunk also remesh the
+/// neighboring chunk, since its connected textures may depend on the edited
+/// block. Edits that would overwrite an existing block
+/// are skipped while [`BlockProtection`] is enabled.
+#[derive(SystemParam)]
+pub struct WorldEditor<'w, 's> {
+    /// The voxel world.
+    world: Res<'w, VoxelWorld>,
+
+    /// The block finder, used to look up the air block.
+    block_finder: BlockFinder<'w, 's>,
+
+    /// The build rules for player edits.
+    rules: Res<'w, BuildRules>,
+
+    /// The block protection state.
+    protection: Res<'w, BlockProtection>,
+
+    /// The query for the data of all chunks.
+    chunks: Query<'w, 's, &'static mut ChunkData>,
+
+    /// The event writer for placed blocks.
+    placed_events: EventWriter<'w, BlockPlaced>,
+
+    /// The event writer for removed blocks.
+    removed_events: EventWriter<'w, BlockRemoved>,
+
+    /// The event writer for rejected edits.
+    rejected_events: EventWriter<'w, BlockRejected>,
+
+    /// The commands used to spawn, despawn, and remesh chunks.
+    commands: Commands<'w, 's>,
+}
+
+impl<'w, 's> WorldEditor<'w, 's> {
+    /// Gets the air block entity.
+    pub fn air(&self) -> Entity {
+        self.block_finder.find_air()
+    }
+
+    /// Gets the build rules that player edits are checked against.
+    pub fn rules(&self) -> &BuildRules {
+        &self.rules
+    }
+
+    /// Gets the block at the given position, or `None` if the chunk that
+    /// contains it is not loaded.
+    pub fn get_block(&self, pos: BlockPos) -> Option<Entity> {
+        self.world.get_block(pos, &self.chunks)
+    }
+
+    /// Sends a [`BlockRejected`] event for an edit at the given position that
+    /// was skipped by the caller.
+    pub fn reject(&mut self, pos: BlockPos) {
+        self.rejected_events.send(BlockRejected { pos });
+    }
+
+    /// Applies the given list of edits to the world, replacing the block at
+    /// each position with the paired block. Returns the number of blocks that
+    /// were changed.
+    pub fn edit(
+        &mut self,
+        edits: impl IntoIterator<Item = (BlockPos, Entity)>,
+        source: EditSource,
+    ) -> usize {
+        let air_block = self.air();
+        let mut dirty_chunks: HashMap<Entity, ChunkPos> = HashMap::default();
+        let mut new_chunks: HashMap<ChunkPos, ChunkData> = HashMap::default();
+        let mut border_chunks: HashSet<ChunkPos> = HashSet::default();
+        let mut changed = 0;
+
+        for (pos, block) in edits {
+            if source == EditSource::Player {
+                let allowed = if block == air_block {
+                    self.rules.can_remove(pos)
+                } else {
+                    self.rules.can_place(pos, block)
+                };
+
+                if !allowed {
+                    trace!("Skipping edit at: {}; Not allowed.", pos);
+                    self.rejected_events.send(BlockRejected { pos });
+                    continue;
+                }
+            }
+
+            let chunk_id = self.world.get_chunk(pos.into());
+            let chunk = match chunk_id {
+                Some(chunk_id) => {
+                    let Ok(chunk) = self.chunks.get_mut(chunk_id) else {
+                        error!("Failed to get chunk data for chunk: {}", chunk_id);
+                        continue;
+                    };
+                    chunk.into_inner()
+                }
+                None if block == air_block => continue,
+                None => new_chunks
+                    .entry(pos.into())
+                    .or_insert_with(|| ChunkData::fill(air_block)),
+            };
+
+            let old_block = chunk.get(pos);
+            if self.protection.enabled && block != air_block && old_block != air_block {
+                trace!("Skipping edit at: {}; Cell is occupied.", pos);
+                continue;
+            }
+
+            if !chunk.set(pos, block) {
+                continue;
+            }
+
+            if let Some(chunk_id) = chunk_id {
+                dirty_chunks.insert(chunk_id, pos.into());
+            }
+            changed += 1;
+
+            let chunk_pos = ChunkPos::from(pos);
+            for dir in FaceDirection::DIRECTIONS {
+                let neighbor = ChunkPos::from(pos.shift(dir, 1));
+                if neighbor != chunk_pos {
+                    border_chunks.insert(neighbor);
+                }
+            }
+
+            if source == EditSource::Player {
+                if block == air_block {
+                    self.removed_events.send(BlockRemoved {
+                        block: old_block,
+                        pos,
+                    });
+                } else {
+                    self.placed_events.send(BlockPlaced { block, pos });
+                }
+            }
+        }
+
+        for (chunk_id, chunk_pos) in dirty_chunks {
+            let Ok(mut chunk) = self.chunks.get_mut(chunk_id) else {
+                continue;
+            };
+
+            border_chunks.remove(&chunk_pos);
+            if chunk.try_convert_to_single() && chunk.get_index(0) == air_block {
+                trace!("Despawning empty chunk at: {}", chunk_pos);
+                self.commands.despawn_chunk(chunk_pos);
+            } else {
+                self.commands.entity(chunk_id).insert(NeedsRemesh);
+            }
+        }
+
+        for chunk_pos in border_chunks {
+            if let Some(chunk_id) = self.world.get_chunk(chunk_pos) {
+                self.commands.entity(chunk_id).insert(NeedsRemesh);
+            }
+        }
+
+        for (chunk_pos, chunk_data) in new_chunks {
+            trace!("Creating new chunk at: {}", chunk_pos);
+            self.commands.spawn_chunk(chunk_pos, chunk_data);
+        }
+
+        changed
+    }
+}
+
+/// This system handles [`SetBlock`] events, replacing the requested blocks
+/// through the [`WorldEditor`].
+pub fn set_blocks(mut events: EventReader<SetBlock>, mut editor: WorldEditor) {
+    let edits = events.read().map(|ev| (ev.pos, ev.block));
+    editor.edit(edits, EditSource::Script);
+}
@@ -0,0 +1,240 @@
+//! This module implements the sphere brush tool, which places or removes a
+//! filled sphere of blocks centered at the cursor position.
+
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use bevy_mod_picking::events::{Click, Pointer};
+use bevy_mod_picking::prelude::PointerButton;
+
+use super::confirm_edits;
+use super::symmetry::Symmetry;
+use crate::gizmos::cursor::CursorRaycast;
+use crate::map::ChunkCollider;
+use crate::map::edit::{EditSource, WorldEditor};
+use crate::math::BlockPos;
+use crate::ui::confirm::ConfirmDialog;
+use crate::ui::hotbar::resource::{Hotbar, HotbarSlotData};
+
+/// The minimum radius of the sphere brush, in blocks.
+pub const MIN_BRUSH_RADIUS: u32 = 1;
+
+/// The maximum radius of the sphere brush, in blocks.
+pub const MAX_BRUSH_RADIUS: u32 = 16;
+
+/// The smallest brush radius, in blocks, at which brush strokes ask the user
+/// for confirmation before they are applied.
+pub const CONFIRM_BRUSH_RADIUS: u32 = 8;
+
+/// The color of the sphere brush preview gizmo.
+const BRUSH_PREVIEW_COLOR: Color = Color::srgb(0.2, 0.8, 1.0);
+
+/// This resource stores the current state of the sphere brush tool.
+#[derive(Debug, Resource)]
+pub struct SphereBrush {
+    /// The radius of the brush, in blocks.
+    pub radius: u32,
+}
+
+impl Default for SphereBrush {
+    fn default() -> Self {
+        Self { radius: 3 }
+    }
+}
+
+impl SphereBrush {
+    /// Returns all block positions within the sphere of this brush, centered at
+    /// the given position.
+    pub fn positions(&self, center: BlockPos) -> Vec<BlockPos> {
+        let r = self.radius as i32;
+        let max_dist = (self.radius as f32 + 0.5).powi(2);
+
+        let mut positions = Vec::new();
+        for z in -r ..= r {
+            for y in -r ..= r {
+                for x in -r ..= r {
+                    if (x * x + y * y + z * z) as f32 > max_dist {
+                        continue;
+                    }
+
+                    positions.push(BlockPos::new(center.x + x, center.y + y, center.z + z));
+                }
+            }
+        }
+
+        positions
+    }
+}
+
+/// Returns true if the sphere brush modifier key is currently held. While held,
+/// primary clicks place a sphere of blocks and secondary clicks remove a sphere
+/// of blocks, instead of editing a single block.
+pub fn brush_modifier_pressed(keys: &ButtonInput<KeyCode>) -> bool {
+    keys.pressed(KeyCode::KeyB)
+}
+
+/// This system adjusts the radius of the sphere brush using the bracket keys
+/// while the brush modifier key is held.
+pub fn adjust_brush_radius(keys: Res<ButtonInput<KeyCode>>, mut brush: ResMut<SphereBrush>) {
+    if !brush_modifier_pressed(&keys) {
+        return;
+    }
+
+    let mut radius = brush.radius;
+    if keys.just_pressed(KeyCode::BracketLeft) {
+        radius = radius.saturating_sub(1);
+    }
+
+    if keys.just_pressed(KeyCode::BracketRight) {
+        radius += 1;
+    }
+
+    radius = radius.clamp(MIN_BRUSH_RADIUS, MAX_BRUSH_RADIUS);
+    if radius != brush.radius {
+        debug!("Sphere brush radius: {}", radius);
+        brush.radius = radius;
+    }
+}
+
+/// This system places or removes a sphere of blocks at the cursor position
+/// when the mouse is clicked while the brush modifier key is held.
+///
+/// The primary button fills the sphere with the currently selected hotbar
+/// block, and the secondary button fills the sphere with air. The sphere is
+/// mirrored across all enabled symmetry planes and applied through the
+/// [`WorldEditor`], so positions that are not allowed by the build rules or
+/// protected by block protection are skipped. Each position that is not allowed
+/// sends a [`BlockRejected`](crate::map::edit::BlockRejected) event. Strokes
+/// with a radius of at least [`CONFIRM_BRUSH_RADIUS`] are only applied once the
+/// user confirms them.
+#[allow(clippy::too_many_arguments)]
+pub fn apply_brush(
+    mut click_events: EventReader<Pointer<Click>>,
+    chunk_colliders: Query<Entity, With<ChunkCollider>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    brush: Res<SphereBrush>,
+    symmetry: Res<Symmetry>,
+    hotbar: Res<Hotbar>,
+    cursor: Res<CursorRaycast>,
+    mut confirm_dialog: ResMut<ConfirmDialog>,
+    mut editor: WorldEditor,
+) {
+    for ev in click_events.read() {
+        if !brush_modifier_pressed(&keys) {
+            trace!("Ignoring click event: {}; Modifier not held.", ev);
+            continue;
+        }
+
+        if !chunk_colliders.contains(ev.target) {
+            trace!("Ignoring click event: {}; Not a chunk collider.", ev);
+            continue;
+        }
+
+        let Some(hit) = &cursor.block else {
+            trace!("Ignoring click event: {}; No block hit in raycast.", ev);
+            return;
+        };
+
+        let brush_block = match ev.button {
+            PointerButton::Primary => {
+                let HotbarSlotData::Block(block) = hotbar.get_selected() else {
+                    trace!("Ignoring click event: {}; No block selected.", ev);
+                    return;
+                };
+                block
+            }
+            PointerButton::Secondary => editor.air(),
+            _ => {
+                trace!("Ignoring click event: {}; Wrong button.", ev);
+                continue;
+            }
+        };
+
+        let positions: HashSet<BlockPos> = symmetry
+            .mirror(hit.block)
+            .into_iter()
+            .flat_map(|center| brush.positions(center))
+            .collect();
+
+        let edits = positions.into_iter().map(|pos| (pos, brush_block));
+        if brush.radius >= CONFIRM_BRUSH_RADIUS {
+            let edits: Vec<_> = edits.collect();
+            let message = format!("Apply a brush stroke to {} blocks?", edits.len());
+            confirm_edits(&mut confirm_dialog, message, edits);
+            continue;
+        }
+
+        editor.edit(edits, EditSource::Player);
+
+        debug!(
+            "Applied sphere brush of radius {} at {}",
+            brush.radius, hit.block
+        );
+    }
+}
+
+/// This system draws a preview gizmo of the volume affected by the sphere brush
+/// while the brush modifier key is held.
+pub fn preview_brush(
+    keys: Res<ButtonInput<KeyCode>>,
+    brush: Res<SphereBrush>,
+    cursor: Res<CursorRaycast>,
+    mut gizmos: Gizmos,
+) {
+    if !brush_modifier_pressed(&keys) {
+        return;
+    }
+
+    let Some(hit) = &cursor.block else {
+        return;
+    };
+
+    gizmos.sphere(
+        hit.block.as_vec3() + Vec3::splat(0.5),
+        Quat::IDENTITY,
+        brush.radius as f32 + 0.5,
+        BRUSH_PREVIEW_COLOR,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sphere_radius_boundary() {
+        let brush = SphereBrush { radius: 2 };
+        let positions = brush.positions(BlockPos::new(0, 0, 0));
+
+        assert!(positions.contains(&BlockPos::new(2, 0, 0)));
+        assert!(positions.contains(&BlockPos::new(2, 1, 0)));
+        assert!(positions.contains(&BlockPos::new(2, 1, 1)));
+        assert!(!positions.contains(&BlockPos::new(2, 2, 0)));
+        assert!(!positions.contains(&BlockPos::new(3, 0, 0)));
+    }
+
+    #[test]
+    fn sphere_sizes() {
+        let point = SphereBrush { radius: 0 };
+        assert_eq!(
+            point.positions(BlockPos::new(0, 0, 0)),
+            vec![BlockPos::new(0, 0, 0)]
+        );
+
+        let small = SphereBrush { radius: 1 };
+        assert_eq!(small.positions(BlockPos::new(0, 0, 0)).len(), 19);
+    }
+
+    #[test]
+    fn sphere_is_centered() {
+        let brush = SphereBrush { radius: 2 };
+        let center = BlockPos::new(10, -5, 3);
+
+        let expected: Vec<BlockPos> = brush
+            .positions(BlockPos::new(0, 0, 0))
+            .into_iter()
+            .map(|p| BlockPos::new(p.x + 10, p.y - 5, p.z + 3))
+            .collect();
+
+        assert_eq!(brush.positions(center), expected);
+    }
+}
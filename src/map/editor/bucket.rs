@@ -0,0 +1,144 @@
+//! This module implements the bucket fill tool, which replaces a connected
+//! region of identical blocks with the currently selected block.
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use bevy_mod_picking::events::{Click, Pointer};
+use bevy_mod_picking::prelude::PointerButton;
+
+use super::confirm_edits;
+use super::symmetry::Symmetry;
+use crate::gizmos::cursor::CursorRaycast;
+use crate::map::ChunkCollider;
+use crate::map::edit::WorldEditor;
+use crate::math::FaceDirection;
+use crate::ui::confirm::ConfirmDialog;
+use crate::ui::hotbar::resource::{Hotbar, HotbarSlotData};
+
+/// The maximum number of blocks that can be replaced by a single bucket fill
+/// operation. This prevents runaway fills on huge uniform areas.
+pub const MAX_BUCKET_FILL_BLOCKS: usize = 4096;
+
+/// Returns true if the bucket fill modifier key is currently held. While held,
+/// primary clicks perform a bucket fill instead of placing a single block.
+pub fn bucket_modifier_pressed(keys: &ButtonInput<KeyCode>) -> bool {
+    keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+}
+
+/// This system performs a bucket fill at the cursor position when the left
+/// mouse button is pressed while the bucket fill modifier key is held.
+///
+/// The fill replaces all blocks that are 6-connected to the block under the
+/// cursor and of the same type with the currently selected hotbar block. Fills
+/// do not extend into chunks that have not been spawned or past the regions
+/// allowed by the build rules, and are capped at [`MAX_BUCKET_FILL_BLOCKS`]
+/// blocks. The filled region is mirrored across all enabled symmetry planes,
+/// replacing whichever blocks are found at the mirrored positions.
+///
+/// The user is asked to confirm the fill before it is applied through the
+/// [`WorldEditor`], so blocks protected by block protection are skipped. A
+/// [`BlockRejected`] event is sent if the block under the cursor may not be
+/// replaced.
+///
+/// [`BlockRejected`]: crate::map::edit::BlockRejected
+pub fn bucket_fill(
+    mut click_events: EventReader<Pointer<Click>>,
+    chunk_colliders: Query<Entity, With<ChunkCollider>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    hotbar: Res<Hotbar>,
+    symmetry: Res<Symmetry>,
+    cursor: Res<CursorRaycast>,
+    mut confirm_dialog: ResMut<ConfirmDialog>,
+    mut editor: WorldEditor,
+) {
+    for ev in click_events.read() {
+        if ev.button != PointerButton::Primary {
+            trace!("Ignoring click event: {}; Wrong button.", ev);
+            continue;
+        }
+
+        if !bucket_modifier_pressed(&keys) {
+            trace!("Ignoring click event: {}; Modifier not held.", ev);
+            continue;
+        }
+
+        if !chunk_colliders.contains(ev.target) {
+            trace!("Ignoring click event: {}; Not a chunk collider.", ev);
+            continue;
+        }
+
+        let HotbarSlotData::Block(fill_block) = hotbar.get_selected() else {
+            trace!("Ignoring click event: {}; No block selected.", ev);
+            return;
+        };
+
+        let Some(hit) = &cursor.block else {
+            trace!("Ignoring click event: {}; No block hit in raycast.", ev);
+            return;
+        };
+
+        let Some(target_block) = editor.get_block(hit.block) else {
+            trace!("No chunk found at target position: {}", hit.block);
+            return;
+        };
+
+        if target_block == fill_block {
+            trace!("Ignoring bucket fill; Target is already the fill block.");
+            return;
+        }
+
+        if !editor.rules().can_place(hit.block, fill_block) {
+            trace!("Ignoring bucket fill; Not allowed at: {}", hit.block);
+            editor.reject(hit.block);
+            return;
+        }
+
+        let mut visited = HashSet::new();
+        let mut queue = VecDeque::new();
+        let mut positions = Vec::new();
+        let mut capped = false;
+
+        visited.insert(hit.block);
+        queue.push_back(hit.block);
+
+        while let Some(pos) = queue.pop_front() {
+            if positions.len() >= MAX_BUCKET_FILL_BLOCKS {
+                capped = true;
+                break;
+            }
+
+            positions.push(pos);
+
+            for dir in FaceDirection::DIRECTIONS {
+                let next = pos.shift(dir, 1);
+                if visited.contains(&next)
+                    || editor.get_block(next) != Some(target_block)
+                    || !editor.rules().can_place(next, fill_block)
+                {
+                    continue;
+                }
+
+                visited.insert(next);
+                queue.push_back(next);
+            }
+        }
+
+        if capped {
+            warn!(
+                "Bucket fill at {} stopped after reaching the limit of {} blocks.",
+                hit.block, MAX_BUCKET_FILL_BLOCKS
+            );
+        }
+
+        let edits: Vec<_> = positions
+            .into_iter()
+            .flat_map(|pos| symmetry.mirror(pos))
+            .map(|pos| (pos, fill_block))
+            .collect();
+
+        let message = format!("Bucket fill {} blocks?", edits.len());
+        confirm_edits(&mut confirm_dialog, message, edits);
+    }
+}
@@ -0,0 +1,170 @@
+//! This module implements the audio and visual feedback that is given when
+//! blocks are placed or removed within the map editor.
+
+use std::time::Duration;
+
+use bevy::audio::{Pitch, Volume};
+use bevy::prelude::*;
+
+use crate::blocks::sounds::BlockSounds;
+use crate::map::edit::{BlockPlaced, BlockRejected, BlockRemoved};
+use crate::math::BlockPos;
+
+/// The minimum time between two feedback sounds. Edits that happen faster than
+/// this, such as when dragging a tool, do not play additional sounds.
+pub const FEEDBACK_SOUND_INTERVAL: Duration = Duration::from_millis(60);
+
+/// The frequency of the default click sound, in Hz.
+const CLICK_FREQUENCY: f32 = 1800.0;
+
+/// The duration of the default click sound.
+const CLICK_DURATION: Duration = Duration::from_millis(25);
+
+/// The volume of the feedback sounds.
+const FEEDBACK_VOLUME: f32 = 0.3;
+
+/// The duration of the visual pop that is shown at an edited block.
+const POP_DURATION: f32 = 0.15;
+
+/// The color of the visual pop shown when a block is placed.
+const PLACE_POP_COLOR: Color = Color::srgb(1.0, 1.0, 1.0);
+
+/// The color of the visual pop shown when a block is removed.
+const REMOVE_POP_COLOR: Color = Color::srgb(1.0, 0.4, 0.2);
+
+/// The color of the visual pop shown when an edit is not allowed by the build
+/// rules.
+const REJECT_POP_COLOR: Color = Color::srgba(0.8, 0.1, 0.1, 0.5);
+
+/// This resource stores the state of the edit feedback.
+#[derive(Debug, Default, Resource)]
+pub struct EditFeedback {
+    /// The default click sound, played for blocks without their own sound.
+    click: Handle<Pitch>,
+
+    /// The time at which the last feedback sound was played.
+    last_sound: Option<Duration>,
+}
+
+/// A visual pop that is shown briefly at an edited block.
+#[derive(Debug, Clone, Copy, Component)]
+pub struct EditPop {
+    /// The position of the edited block.
+    pos: BlockPos,
+
+    /// The color of the pop.
+    color: Color,
+
+    /// The time at which the pop was created.
+    start: f32,
+}
+
+/// This system creates the default click sound of the edit feedback.
+pub fn prepare_edit_feedback(
+    mut pitches: ResMut<Assets<Pitch>>,
+    mut feedback: ResMut<EditFeedback>,
+) {
+    feedback.click = pitches.add(Pitch::new(CLICK_FREQUENCY, CLICK_DURATION));
+}
+
+/// This system plays a sound when blocks are placed or removed. The sound of
+/// the edited block is used if it has one, otherwise a default click is
+/// played. Sounds are throttled to [`FEEDBACK_SOUND_INTERVAL`], so that at most
+/// one sound is played per edit.
+pub fn play_edit_sounds(
+    mut place_events: EventReader<BlockPlaced>,
+    mut remove_events: EventReader<BlockRemoved>,
+    time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    sounds: Query<&BlockSounds>,
+    mut feedback: ResMut<EditFeedback>,
+    mut commands: Commands,
+) {
+    let placed = place_events.read().last().map(|ev| (ev.block, true));
+    let removed = remove_events.read().last().map(|ev| (ev.block, false));
+
+    let Some((block, is_place)) = placed.or(removed) else {
+        return;
+    };
+
+    let now = time.elapsed();
+    if feedback
+        .last_sound
+        .is_some_and(|last| now.saturating_sub(last) < FEEDBACK_SOUND_INTERVAL)
+    {
+        return;
+    }
+    feedback.last_sound = Some(now);
+
+    let settings = PlaybackSettings::DESPAWN.with_volume(Volume::new(FEEDBACK_VOLUME));
+    let sound = sounds.get(block).ok().and_then(|sounds| {
+        if is_place {
+            sounds.place.as_deref()
+        } else {
+            sounds.remove.as_deref()
+        }
+    });
+
+    match sound {
+        Some(path) => {
+            commands.spawn(AudioBundle {
+                source: asset_server.load(path.to_string()),
+                settings,
+            });
+        }
+        None => {
+            commands.spawn(PitchBundle {
+                source: feedback.click.clone(),
+                settings,
+            });
+        }
+    }
+}
+
+/// This system spawns a visual pop at every block that is placed, removed, or
+/// rejected.
+pub fn spawn_edit_pops(
+    mut place_events: EventReader<BlockPlaced>,
+    mut remove_events: EventReader<BlockRemoved>,
+    mut reject_events: EventReader<BlockRejected>,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    let start = time.elapsed_seconds();
+
+    let placed = place_events.read().map(|ev| (ev.pos, PLACE_POP_COLOR));
+    let removed = remove_events.read().map(|ev| (ev.pos, REMOVE_POP_COLOR));
+    let rejected = reject_events.read().map(|ev| (ev.pos, REJECT_POP_COLOR));
+
+    for (pos, color) in placed.chain(removed).chain(rejected) {
+        commands.spawn(EditPop { pos, color, start });
+    }
+}
+
+/// This system draws all visual pops, growing and fading them out over
+/// [`POP_DURATION`], and despawns them once they are finished.
+pub fn draw_edit_pops(
+    time: Res<Time>,
+    pops: Query<(Entity, &EditPop)>,
+    mut gizmos: Gizmos,
+    mut commands: Commands,
+) {
+    let now = time.elapsed_seconds();
+
+    for (entity, pop) in pops.iter() {
+        let progress = (now - pop.start) / POP_DURATION;
+        if progress >= 1.0 {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let scale = 1.0 + 0.2 * progress;
+        let color = pop.color.with_alpha(1.0 - progress);
+
+        gizmos.cuboid(
+            Transform::from_translation(pop.pos.as_vec3() + Vec3::splat(0.5))
+                .with_scale(Vec3::splat(scale)),
+            color,
+        );
+    }
+}
@@ -0,0 +1,100 @@
+//! This module implements the "isolate chunk" debug view, which hides all
+//! chunks except a single one so that its mesh can be inspected on its own.
+
+use bevy::prelude::*;
+
+use crate::gizmos::cursor::CursorRaycast;
+use crate::map::chunk::ChunkData;
+use crate::math::{BlockPos, CHUNK_SIZE, ChunkPos, Position};
+
+/// The color of the boundary that is drawn around the isolated chunk.
+const BOUNDARY_COLOR: Color = Color::srgb(1.0, 0.8, 0.2);
+
+/// This resource stores which chunk is currently isolated, if any.
+///
+/// While a chunk is isolated, the regular chunk visibility updates are paused
+/// and all other chunks are hidden. Clearing the target restores the normal
+/// visibility of all chunks.
+#[derive(Debug, Default, Resource)]
+pub struct IsolatedChunk {
+    /// The position of the isolated chunk, or `None` if no chunk is isolated.
+    pub target: Option<ChunkPos>,
+}
+
+impl IsolatedChunk {
+    /// A run condition that returns true if a chunk is currently isolated.
+    pub fn is_active(isolated: Res<IsolatedChunk>) -> bool {
+        isolated.target.is_some()
+    }
+
+    /// Isolates the chunk under the cursor, or stops isolating if a chunk is
+    /// already isolated.
+    pub fn toggle(&mut self, cursor: &CursorRaycast) {
+        if self.target.take().is_some() {
+            info!("Stopped isolating chunk.");
+            return;
+        }
+
+        let Some(hit) = &cursor.block else {
+            info!("No chunk under the cursor to isolate.");
+            return;
+        };
+
+        let chunk = ChunkPos::from(hit.block);
+        info!("Isolating {chunk}.");
+        self.target = Some(chunk);
+    }
+}
+
+/// This system toggles the isolated chunk when I is pressed.
+pub(super) fn toggle_isolated_chunk(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    cursor: Res<CursorRaycast>,
+    mut isolated: ResMut<IsolatedChunk>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyI) {
+        isolated.toggle(&cursor);
+    }
+}
+
+/// This system hides all chunks except the isolated chunk.
+pub(super) fn update_isolated_chunk_visibility(
+    isolated: Res<IsolatedChunk>,
+    mut chunks: Query<(&Position, &mut Visibility), With<ChunkData>>,
+) {
+    let Some(target) = isolated.target else {
+        return;
+    };
+
+    for (pos, mut visibility) in chunks.iter_mut() {
+        let new_visibility = if ChunkPos::from(pos.block) == target {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+
+        if *visibility != new_visibility {
+            *visibility = new_visibility;
+        }
+    }
+}
+
+/// This system draws the boundary of the isolated chunk.
+pub(super) fn draw_isolated_chunk_bounds(isolated: Res<IsolatedChunk>, mut gizmos: Gizmos) {
+    let Some(target) = isolated.target else {
+        return;
+    };
+
+    let size = CHUNK_SIZE as f32;
+    let origin = BlockPos::from(target).as_vec3();
+
+    gizmos.cuboid(
+        Transform::from_translation(origin + Vec3::splat(size * 0.5)).with_scale(Vec3::splat(size)),
+        BOUNDARY_COLOR,
+    );
+}
+
+/// This system stops isolating the chunk when leaving the editor.
+pub(super) fn clear_isolated_chunk(mut isolated: ResMut<IsolatedChunk>) {
+    isolated.target = None;
+}
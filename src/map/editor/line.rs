@@ -0,0 +1,198 @@
+//! This module implements the line tool, which draws a straight line of blocks
+//! between two clicked positions.
+
+use bevy::prelude::*;
+use bevy_mod_picking::events::{Click, Pointer};
+use bevy_mod_picking::prelude::PointerButton;
+
+use super::symmetry::Symmetry;
+use crate::gizmos::cursor::CursorRaycast;
+use crate::map::ChunkCollider;
+use crate::map::edit::{EditSource, WorldEditor};
+use crate::math::BlockPos;
+use crate::ui::UiKeyboardFocus;
+use crate::ui::hotbar::resource::{Hotbar, HotbarSlotData};
+use crate::utilities::raycast::VoxelIterator;
+
+/// The color of the line tool preview gizmo.
+const LINE_PREVIEW_COLOR: Color = Color::srgb(1.0, 0.8, 0.2);
+
+/// This resource stores the current state of the line tool.
+#[derive(Debug, Default, Resource)]
+pub struct LineTool {
+    /// The first endpoint of the line, if it has been selected.
+    pub start: Option<BlockPos>,
+}
+
+/// Returns true if the line tool modifier key is currently held. While held,
+/// primary clicks select line endpoints instead of placing a single block.
+pub fn line_modifier_pressed(keys: &ButtonInput<KeyCode>) -> bool {
+    keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight])
+}
+
+/// Returns all block positions along the straight line between the two given
+/// block positions, inclusive of both endpoints. The line is traced through the
+/// centers of the two blocks using voxel traversal, so each returned position
+/// is face-connected to the previous one.
+pub fn line_positions(start: BlockPos, end: BlockPos) -> Vec<BlockPos> {
+    let center = Vec3::splat(0.5);
+    let Ok(dir) = Dir3::new(end.as_vec3() - start.as_vec3()) else {
+        return vec![start];
+    };
+
+    let min = BlockPos::new(start.x.min(end.x), start.y.min(end.y), start.z.min(end.z));
+    let max = BlockPos::new(start.x.max(end.x), start.y.max(end.y), start.z.max(end.z));
+
+    let mut positions = Vec::new();
+    for (pos, _) in VoxelIterator::new(start.as_vec3() + center, dir).within_region(min, max) {
+        positions.push(pos);
+        if pos == end {
+            return positions;
+        }
+    }
+
+    positions.push(end);
+    positions
+}
+
+/// Returns the position that a block would be placed at for the current cursor
+/// target, if any.
+fn cursor_target(cursor: &CursorRaycast) -> Option<BlockPos> {
+    cursor
+        .block
+        .as_ref()
+        .map(|hit| hit.block.shift(hit.face, 1))
+}
+
+/// This system handles the selection of line endpoints. The first primary
+/// click, while the line tool modifier key is held, selects the start of the
+/// line, and the second click draws the line using the currently selected
+/// hotbar block. Pressing escape cancels the current line, unless a UI text
+/// field has keyboard focus. The line is mirrored across all enabled symmetry
+/// planes and applied through the [`WorldEditor`], so positions that are not
+/// allowed by the build rules or protected by block protection are skipped.
+/// Each position that is not allowed sends a
+/// [`BlockRejected`](crate::map::edit::BlockRejected) event.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_line(
+    mut click_events: EventReader<Pointer<Click>>,
+    chunk_colliders: Query<Entity, With<ChunkCollider>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    hotbar: Res<Hotbar>,
+    symmetry: Res<Symmetry>,
+    cursor: Res<CursorRaycast>,
+    focus: Res<UiKeyboardFocus>,
+    mut line_tool: ResMut<LineTool>,
+    mut editor: WorldEditor,
+) {
+    if keys.just_pressed(KeyCode::Escape) && !focus.has_focus() && line_tool.start.is_some() {
+        trace!("Cancelled line tool selection.");
+        line_tool.start = None;
+    }
+
+    for ev in click_events.read() {
+        if ev.button != PointerButton::Primary {
+            trace!("Ignoring click event: {}; Wrong button.", ev);
+            continue;
+        }
+
+        if !line_modifier_pressed(&keys) {
+            trace!("Ignoring click event: {}; Modifier not held.", ev);
+            continue;
+        }
+
+        if !chunk_colliders.contains(ev.target) {
+            trace!("Ignoring click event: {}; Not a chunk collider.", ev);
+            continue;
+        }
+
+        let HotbarSlotData::Block(line_block) = hotbar.get_selected() else {
+            trace!("Ignoring click event: {}; No block selected.", ev);
+            return;
+        };
+
+        let Some(target_pos) = cursor_target(&cursor) else {
+            trace!("Ignoring click event: {}; No block hit in raycast.", ev);
+            return;
+        };
+
+        let Some(start) = line_tool.start.take() else {
+            trace!("Selected line start: {}", target_pos);
+            line_tool.start = Some(target_pos);
+            return;
+        };
+
+        let edits = line_positions(start, target_pos)
+            .into_iter()
+            .flat_map(|pos| symmetry.mirror(pos))
+            .map(|pos| (pos, line_block));
+        editor.edit(edits, EditSource::Player);
+
+        debug!("Drew line from {} to {}", start, target_pos);
+    }
+}
+
+/// This system draws a preview gizmo of the line that would be drawn while the
+/// second endpoint of the line tool is being selected.
+pub fn preview_line(line_tool: Res<LineTool>, cursor: Res<CursorRaycast>, mut gizmos: Gizmos) {
+    let Some(start) = line_tool.start else {
+        return;
+    };
+
+    let center = Vec3::splat(0.5);
+    gizmos.cuboid(
+        Transform::from_translation(start.as_vec3() + center),
+        LINE_PREVIEW_COLOR,
+    );
+
+    let Some(end) = cursor_target(&cursor) else {
+        return;
+    };
+
+    gizmos.line(
+        start.as_vec3() + center,
+        end.as_vec3() + center,
+        LINE_PREVIEW_COLOR,
+    );
+    gizmos.cuboid(
+        Transform::from_translation(end.as_vec3() + center),
+        LINE_PREVIEW_COLOR,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns true if the two positions share a face.
+    fn face_connected(a: BlockPos, b: BlockPos) -> bool {
+        (a.x - b.x).abs() + (a.y - b.y).abs() + (a.z - b.z).abs() == 1
+    }
+
+    #[test]
+    fn axis_aligned_line() {
+        let line = line_positions(BlockPos::new(0, 2, 0), BlockPos::new(4, 2, 0));
+        let expected: Vec<_> = (0 ..= 4).map(|x| BlockPos::new(x, 2, 0)).collect();
+        assert_eq!(line, expected);
+
+        let line = line_positions(BlockPos::new(1, 5, 1), BlockPos::new(1, 2, 1));
+        let expected: Vec<_> = (2 ..= 5).rev().map(|y| BlockPos::new(1, y, 1)).collect();
+        assert_eq!(line, expected);
+
+        let pos = BlockPos::new(3, 3, 3);
+        assert_eq!(line_positions(pos, pos), vec![pos]);
+    }
+
+    #[test]
+    fn diagonal_line_is_face_connected() {
+        for end in [BlockPos::new(4, 3, 2), BlockPos::new(-4, 3, -2)] {
+            let start = BlockPos::new(0, 0, 0);
+            let line = line_positions(start, end);
+
+            assert_eq!(line.first(), Some(&start));
+            assert_eq!(line.last(), Some(&end));
+            assert_eq!(line.len(), 10);
+            assert!(line.windows(2).all(|step| face_connected(step[0], step[1])));
+        }
+    }
+}
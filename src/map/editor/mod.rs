@@ -1,25 +1,136 @@
 //! This module implements the map editor.
 
+use bevy::ecs::system::RunSystemOnce;
 use bevy::prelude::*;
 
 use crate::gamestate::GameState;
 use crate::gizmos::GizmoSystemSets;
-use crate::ui::EditorWindowState;
+use crate::gizmos::cursor::CursorRaycast;
+use crate::map::VoxelWorldSystemSets;
+use crate::map::edit::{BlockProtection, EditSource, WorldEditor};
+use crate::map::world::VoxelWorldCommands;
+use crate::math::BlockPos;
+use crate::project::UnloadProject;
+use crate::ui::command_palette::EditorActionsAppExt;
+use crate::ui::confirm::ConfirmDialog;
+use crate::ui::hud::EditorHud;
+use crate::ui::{EditorWindowState, UiKeyboardFocus};
 
+pub mod brush;
+pub mod bucket;
+pub mod feedback;
+pub mod isolate;
+pub mod line;
 pub mod placement;
+pub mod recent;
+pub mod reveal;
 pub mod startup;
+pub mod symmetry;
 
 /// The map editor plugin. This plugin allows for the user to directly edit the
 /// world.
 pub struct MapEditorPlugin;
 impl Plugin for MapEditorPlugin {
     fn build(&self, app_: &mut App) {
-        app_.add_systems(OnEnter(GameState::Editor), startup::prepare_map_editor)
+        app_.init_resource::<line::LineTool>()
+            .init_resource::<brush::SphereBrush>()
+            .init_resource::<symmetry::Symmetry>()
+            .init_resource::<reveal::RevealCursor>()
+            .init_resource::<recent::RecentBlocks>()
+            .init_resource::<recent::RecentBlocksPopup>()
+            .init_resource::<feedback::EditFeedback>()
+            .init_resource::<isolate::IsolatedChunk>()
+            .add_event::<reveal::RevealBlock>()
+            .add_systems(Startup, feedback::prepare_edit_feedback)
+            .add_systems(OnEnter(GameState::Editor), startup::prepare_map_editor)
+            .add_systems(UnloadProject, recent::clear_recent_blocks)
+            .add_systems(OnExit(GameState::Editor), isolate::clear_isolated_chunk)
+            .register_editor_action("Clear Chunks", |mut dialog: ResMut<ConfirmDialog>| {
+                dialog.open("Remove all chunks from the world?", |world: &mut World| {
+                    world.run_system_once(|mut commands: Commands| commands.clear_chunks());
+                });
+            })
+            .register_editor_action(
+                "Toggle Block Protection",
+                |mut protection: ResMut<BlockProtection>| {
+                    protection.enabled = !protection.enabled;
+                },
+            )
+            .register_editor_action(
+                "Show Recent Blocks",
+                |mut popup: ResMut<recent::RecentBlocksPopup>| {
+                    popup.visible = true;
+                },
+            )
+            .register_editor_action(
+                "Toggle Chunk Isolation",
+                |cursor: Res<CursorRaycast>, mut isolated: ResMut<isolate::IsolatedChunk>| {
+                    isolated.toggle(&cursor);
+                },
+            )
             .add_systems(
                 Update,
                 (
                     placement::place_block.in_set(MapEditorSystemSets::PlaceBlock),
                     placement::remove_block.in_set(MapEditorSystemSets::RemoveBlock),
+                    placement::toggle_block_protection
+                        .before_ignore_deferred(MapEditorSystemSets::PlaceBlock)
+                        .run_if(in_state(EditorWindowState::MapEditor))
+                        .run_if(UiKeyboardFocus::is_free),
+                    bucket::bucket_fill.in_set(MapEditorSystemSets::BucketFill),
+                    line::draw_line.in_set(MapEditorSystemSets::DrawLine),
+                    line::preview_line
+                        .after_ignore_deferred(MapEditorSystemSets::DrawLine)
+                        .run_if(in_state(EditorWindowState::MapEditor)),
+                    symmetry::toggle_mirror_planes
+                        .after_ignore_deferred(GizmoSystemSets::UpdateCursor)
+                        .before_ignore_deferred(MapEditorSystemSets::RemoveBlock)
+                        .run_if(in_state(EditorWindowState::MapEditor))
+                        .run_if(UiKeyboardFocus::is_free),
+                    symmetry::draw_mirror_planes
+                        .after_ignore_deferred(GizmoSystemSets::UpdateCursor)
+                        .run_if(in_state(EditorWindowState::MapEditor)),
+                    brush::adjust_brush_radius
+                        .in_set(MapEditorSystemSets::SphereBrush)
+                        .run_if(UiKeyboardFocus::is_free),
+                    brush::apply_brush
+                        .in_set(MapEditorSystemSets::SphereBrush)
+                        .after_ignore_deferred(brush::adjust_brush_radius),
+                    brush::preview_brush
+                        .in_set(MapEditorSystemSets::SphereBrush)
+                        .after_ignore_deferred(brush::apply_brush),
+                    reveal::reveal_block,
+                    recent::remember_placed_blocks
+                        .after_ignore_deferred(MapEditorSystemSets::PlaceBlock),
+                    recent::forget_deleted_blocks,
+                    feedback::play_edit_sounds
+                        .after_ignore_deferred(MapEditorSystemSets::PlaceBlock),
+                    feedback::spawn_edit_pops
+                        .after_ignore_deferred(MapEditorSystemSets::PlaceBlock),
+                    feedback::draw_edit_pops.after_ignore_deferred(feedback::spawn_edit_pops),
+                    recent::toggle_recent_blocks_popup
+                        .run_if(in_state(EditorWindowState::MapEditor))
+                        .run_if(UiKeyboardFocus::is_free),
+                    recent::render_recent_blocks_popup
+                        .after_ignore_deferred(recent::toggle_recent_blocks_popup)
+                        .run_if(in_state(EditorWindowState::MapEditor))
+                        .run_if(|popup: Res<recent::RecentBlocksPopup>| popup.visible)
+                        .run_if(EditorHud::is_visible),
+                ),
+            )
+            .add_systems(
+                Update,
+                (
+                    isolate::toggle_isolated_chunk
+                        .after_ignore_deferred(GizmoSystemSets::UpdateCursor)
+                        .run_if(in_state(EditorWindowState::MapEditor))
+                        .run_if(UiKeyboardFocus::is_free),
+                    isolate::update_isolated_chunk_visibility
+                        .after_ignore_deferred(isolate::toggle_isolated_chunk)
+                        .run_if(isolate::IsolatedChunk::is_active),
+                    isolate::draw_isolated_chunk_bounds
+                        .run_if(in_state(EditorWindowState::MapEditor))
+                        .run_if(isolate::IsolatedChunk::is_active),
                 ),
             )
             .configure_sets(
@@ -32,6 +143,20 @@ impl Plugin for MapEditorPlugin {
                         .after_ignore_deferred(GizmoSystemSets::UpdateCursor)
                         .after_ignore_deferred(MapEditorSystemSets::RemoveBlock)
                         .run_if(in_state(EditorWindowState::MapEditor)),
+                    MapEditorSystemSets::BucketFill
+                        .after_ignore_deferred(GizmoSystemSets::UpdateCursor)
+                        .after_ignore_deferred(MapEditorSystemSets::PlaceBlock)
+                        .run_if(in_state(EditorWindowState::MapEditor)),
+                    MapEditorSystemSets::DrawLine
+                        .after_ignore_deferred(GizmoSystemSets::UpdateCursor)
+                        .after_ignore_deferred(MapEditorSystemSets::PlaceBlock)
+                        .run_if(in_state(EditorWindowState::MapEditor)),
+                    MapEditorSystemSets::SphereBrush
+                        .after_ignore_deferred(GizmoSystemSets::UpdateCursor)
+                        .after_ignore_deferred(MapEditorSystemSets::PlaceBlock)
+                        .run_if(in_state(EditorWindowState::MapEditor)),
+                    VoxelWorldSystemSets::UpdateChunkVisibility
+                        .run_if(not(isolate::IsolatedChunk::is_active)),
                 ),
             );
     }
@@ -45,4 +170,28 @@ pub enum MapEditorSystemSets {
 
     /// The system set for removing blocks.
     RemoveBlock,
+
+    /// The system set for bucket filling blocks.
+    BucketFill,
+
+    /// The system set for drawing lines of blocks.
+    DrawLine,
+
+    /// The system set for the sphere brush tool.
+    SphereBrush,
+}
+
+/// Opens the confirmation dialog with the given message. Once confirmed, the
+/// given edits are applied through the [`WorldEditor`] as player edits.
+pub fn confirm_edits(
+    dialog: &mut ConfirmDialog,
+    message: impl Into<String>,
+    edits: Vec<(BlockPos, Entity)>,
+) {
+    dialog.open(message, move |world: &mut World| {
+        world.run_system_once(move |mut editor: WorldEditor| {
+            let changed = editor.edit(edits.iter().copied(), EditSource::Player);
+            debug!("Applied {} confirmed block edits.", changed);
+        });
+    });
 }
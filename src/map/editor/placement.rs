@@ -5,26 +5,43 @@ use bevy::prelude::*;
 use bevy_mod_picking::events::{Click, Pointer};
 use bevy_mod_picking::prelude::PointerButton;
 
-use crate::blocks::params::BlockFinder;
+use super::brush::brush_modifier_pressed;
+use super::bucket::bucket_modifier_pressed;
+use super::line::line_modifier_pressed;
+use super::symmetry::Symmetry;
 use crate::gizmos::cursor::CursorRaycast;
 use crate::map::ChunkCollider;
-use crate::map::chunk::ChunkData;
-use crate::map::remesh::NeedsRemesh;
-use crate::map::world::{VoxelWorld, VoxelWorldCommands};
+use crate::map::edit::{BlockProtection, EditSource, WorldEditor};
 use crate::ui::hotbar::resource::{Hotbar, HotbarSlotData};
 
+/// This system toggles block protection when `P` is pressed.
+pub fn toggle_block_protection(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut protection: ResMut<BlockProtection>,
+) {
+    if keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        return;
+    }
+
+    if keys.just_pressed(KeyCode::KeyP) {
+        protection.enabled = !protection.enabled;
+        info!("Block protection enabled: {}", protection.enabled);
+    }
+}
+
 /// This system places a block at the cursor position when the left mouse button
-/// is pressed.
-#[allow(clippy::too_many_arguments)]
+/// is pressed. The placement is mirrored across all enabled symmetry planes and
+/// applied through the [`WorldEditor`], so cells that already contain a block
+/// are left untouched while [`BlockProtection`] is enabled, and positions that
+/// are not allowed by the build rules are always skipped.
 pub fn place_block(
     mut click_events: EventReader<Pointer<Click>>,
     chunk_colliders: Query<Entity, With<ChunkCollider>>,
-    block_finder: BlockFinder,
+    keys: Res<ButtonInput<KeyCode>>,
     hotbar: Res<Hotbar>,
+    symmetry: Res<Symmetry>,
     cursor: Res<CursorRaycast>,
-    world: Res<VoxelWorld>,
-    mut chunks: Query<&mut ChunkData>,
-    mut commands: Commands,
+    mut editor: WorldEditor,
 ) {
     for ev in click_events.read() {
         if ev.button != PointerButton::Primary {
@@ -32,6 +49,14 @@ pub fn place_block(
             continue;
         }
 
+        if bucket_modifier_pressed(&keys)
+            || line_modifier_pressed(&keys)
+            || brush_modifier_pressed(&keys)
+        {
+            trace!("Ignoring click event: {}; Tool modifier held.", ev);
+            continue;
+        }
+
         if !chunk_colliders.contains(ev.target) {
             trace!("Ignoring click event: {}; Not a chunk collider.", ev);
             continue;
@@ -47,44 +72,29 @@ pub fn place_block(
             return;
         };
 
-        let air_block = block_finder.find_air();
         let target_pos = hit.block.shift(hit.face, 1);
+        let edits = symmetry
+            .mirror(target_pos)
+            .into_iter()
+            .map(|pos| (pos, place_block));
 
-        let Some(chunk_id) = world.get_chunk(target_pos.into()) else {
-            trace!(
-                "No chunk found at target position: {}; Creating new one.",
-                target_pos
-            );
-            let mut new_chunk = ChunkData::fill(air_block);
-            new_chunk.set(target_pos, place_block);
-            commands.spawn_chunk(target_pos.into(), new_chunk);
-            return;
-        };
-
-        let Ok(mut chunk) = chunks.get_mut(chunk_id) else {
-            error!("Failed to get chunk data for chunk: {};", chunk_id);
-            return;
-        };
-
-        chunk.set(target_pos, place_block);
-        commands.entity(chunk_id).insert(NeedsRemesh);
-        trace!(
-            "Placed block: {:?} at position: {:?}",
-            place_block, target_pos
-        );
+        if editor.edit(edits, EditSource::Player) > 0 {
+            trace!("Placed block: {:?} at: {}", place_block, target_pos);
+        }
     }
 }
 
 /// This system removes a block at the cursor position when the right mouse
-/// button
+/// button is pressed. The removal is mirrored across all enabled symmetry
+/// planes and applied through the [`WorldEditor`], so positions that are not
+/// allowed by the build rules are skipped.
 pub fn remove_block(
     mut click_events: EventReader<Pointer<Click>>,
     chunk_colliders: Query<Entity, With<ChunkCollider>>,
-    block_finder: BlockFinder,
+    keys: Res<ButtonInput<KeyCode>>,
+    symmetry: Res<Symmetry>,
     cursor: Res<CursorRaycast>,
-    world: Res<VoxelWorld>,
-    mut chunks: Query<&mut ChunkData>,
-    mut commands: Commands,
+    mut editor: WorldEditor,
 ) {
     for ev in click_events.read() {
         if ev.button != PointerButton::Secondary {
@@ -92,6 +102,11 @@ pub fn remove_block(
             continue;
         }
 
+        if brush_modifier_pressed(&keys) {
+            trace!("Ignoring click event: {}; Tool modifier held.", ev);
+            continue;
+        }
+
         if !chunk_colliders.contains(ev.target) {
             trace!("Ignoring click event: {}; Not a chunk collider.", ev);
             continue;
@@ -102,31 +117,13 @@ pub fn remove_block(
             return;
         };
 
-        let Some(chunk_id) = world.get_chunk(hit.block.into()) else {
-            trace!(
-                "No chunk found at target position: {}; Nothing to remove.",
-                hit.block
-            );
-            return;
-        };
-
-        let Ok(mut chunk) = chunks.get_mut(chunk_id) else {
-            error!("Failed to get chunk data for chunk: {}", chunk_id);
-            return;
-        };
-
-        let air_block = block_finder.find_air();
+        let air_block = editor.air();
+        let edits = symmetry
+            .mirror(hit.block)
+            .into_iter()
+            .map(|pos| (pos, air_block));
 
         trace!("Removing block at position: {}", hit.block);
-        let dirty = chunk.set(hit.block, air_block);
-
-        if dirty {
-            if chunk.try_convert_to_single() {
-                trace!("Despawning empty chunk at: {:?}", hit.block);
-                commands.despawn_chunk(hit.block.into());
-            } else {
-                commands.entity(chunk_id).insert(NeedsRemesh);
-            }
-        }
+        editor.edit(edits, EditSource::Player);
     }
 }
@@ -0,0 +1,183 @@
+//! This module keeps track of the most recently placed blocks, and implements a
+//! quick-assign popup that allows them to be assigned to the hotbar without
+//! searching through the full block list.
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, egui};
+
+use crate::blocks::DeleteBlock;
+use crate::map::edit::BlockPlaced;
+use crate::ui::hotbar::resource::{Hotbar, HotbarSlotData};
+use crate::ui::theme::EditorTheme;
+
+/// The maximum number of blocks that are remembered as recently placed.
+pub const RECENT_BLOCKS_CAPACITY: usize = 10;
+
+/// This resource stores the most recently placed blocks, ordered from the most
+/// recently placed block to the least recently placed block.
+#[derive(Debug, Default, Clone, Resource)]
+pub struct RecentBlocks {
+    /// The recently placed blocks, most recent first.
+    blocks: VecDeque<Entity>,
+}
+
+impl RecentBlocks {
+    /// Marks the given block as the most recently placed block. If the block
+    /// was already in the list, it is moved to the front instead of being
+    /// added again. The least recently placed block is forgotten if the list
+    /// is full.
+    pub fn push(&mut self, block: Entity) {
+        self.remove(block);
+        self.blocks.push_front(block);
+        self.blocks.truncate(RECENT_BLOCKS_CAPACITY);
+    }
+
+    /// Removes the given block from the list, if present.
+    pub fn remove(&mut self, block: Entity) {
+        self.blocks.retain(|b| *b != block);
+    }
+
+    /// Returns an iterator over the recently placed blocks, most recent first.
+    pub fn iter(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.blocks.iter().copied()
+    }
+
+    /// Returns the number of recently placed blocks.
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Checks whether no blocks have been placed yet.
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+}
+
+/// This resource stores the state of the recent blocks popup.
+#[derive(Debug, Default, Resource)]
+pub struct RecentBlocksPopup {
+    /// Whether the popup is currently visible.
+    pub visible: bool,
+}
+
+/// This system toggles the recent blocks popup when R is pressed. The popup is
+/// closed when Escape is pressed.
+pub fn toggle_recent_blocks_popup(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut popup: ResMut<RecentBlocksPopup>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyR) {
+        popup.visible = !popup.visible;
+    } else if keyboard_input.just_pressed(KeyCode::Escape) {
+        popup.visible = false;
+    }
+}
+
+/// This system renders the recent blocks popup above the hotbar. Clicking a
+/// block assigns it to the currently selected hotbar slot and closes the popup.
+pub fn render_recent_blocks_popup(
+    recent: Res<RecentBlocks>,
+    names: Query<&Name>,
+    theme: Res<EditorTheme>,
+    mut popup: ResMut<RecentBlocksPopup>,
+    mut hotbar: ResMut<Hotbar>,
+    mut contexts: EguiContexts,
+) {
+    let selected = hotbar.get_selected();
+    let mut assign = None;
+
+    egui::Area::new(egui::Id::new("recent_blocks_popup"))
+        .anchor(egui::Align2::CENTER_BOTTOM, egui::vec2(0.0, -64.0))
+        .order(egui::Order::Foreground)
+        .show(contexts.ctx_mut(), |ui| {
+            theme.popup_frame().show(ui, |ui| {
+                ui.label("Recent Blocks");
+                ui.separator();
+
+                if recent.is_empty() {
+                    ui.label("No blocks placed yet.");
+                    return;
+                }
+
+                for block in recent.iter() {
+                    let name = names.get(block).map(Name::as_str).unwrap_or("Unnamed");
+                    let active = selected == HotbarSlotData::Block(block);
+
+                    if ui.selectable_label(active, name).clicked() {
+                        assign = Some(block);
+                    }
+                }
+            });
+        });
+
+    if let Some(block) = assign {
+        let index = hotbar.get_selected_index();
+        hotbar.set_slot(index, HotbarSlotData::Block(block));
+        popup.visible = false;
+    }
+}
+
+/// This system remembers every block that is placed by the player, no matter
+/// which tool was used to place it.
+pub fn remember_placed_blocks(
+    mut placed_events: EventReader<BlockPlaced>,
+    mut recent: ResMut<RecentBlocks>,
+) {
+    for ev in placed_events.read() {
+        recent.push(ev.block);
+    }
+}
+
+/// This system listens for deleted blocks and removes them from the list of
+/// recently placed blocks.
+pub fn forget_deleted_blocks(
+    mut delete_events: EventReader<DeleteBlock>,
+    mut recent: ResMut<RecentBlocks>,
+) {
+    for ev in delete_events.read() {
+        recent.remove(ev.block);
+    }
+}
+
+/// This system forgets all recently placed blocks when the project is
+/// unloaded.
+pub fn clear_recent_blocks(mut recent: ResMut<RecentBlocks>) {
+    recent.blocks.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duplicates_move_to_front() {
+        let a = Entity::from_raw(1);
+        let b = Entity::from_raw(2);
+        let c = Entity::from_raw(3);
+
+        let mut recent = RecentBlocks::default();
+        recent.push(a);
+        recent.push(b);
+        recent.push(c);
+        recent.push(a);
+
+        assert_eq!(recent.iter().collect::<Vec<_>>(), vec![a, c, b]);
+    }
+
+    #[test]
+    fn oldest_block_is_forgotten() {
+        let mut recent = RecentBlocks::default();
+        for i in 0 ..= RECENT_BLOCKS_CAPACITY as u32 {
+            recent.push(Entity::from_raw(i));
+        }
+
+        assert_eq!(recent.len(), RECENT_BLOCKS_CAPACITY);
+        assert_eq!(
+            recent.iter().next(),
+            Some(Entity::from_raw(RECENT_BLOCKS_CAPACITY as u32))
+        );
+        assert!(recent.iter().all(|block| block != Entity::from_raw(0)));
+    }
+}
@@ -0,0 +1,96 @@
+//! This module implements the "reveal in world" action, which moves the camera
+//! to the placed instances of a block type.
+
+use bevy::prelude::*;
+use uuid::Uuid;
+
+use crate::blocks::params::BlockFinder;
+use crate::camera::{CameraTarget, focus_camera_on};
+use crate::map::chunk::ChunkData;
+use crate::map::remesh::UniqueBlocks;
+use crate::math::{BlockPos, Position};
+use crate::utilities::chunk_iter::ChunkIterator;
+
+/// An event that requests the camera to be moved to an instance of the given
+/// block type. Sending this event repeatedly for the same block type cycles
+/// through all instances of that block within the world.
+#[derive(Debug, Clone, Copy, Event)]
+pub struct RevealBlock {
+    /// The uuid of the block type to reveal.
+    pub uuid: Uuid,
+}
+
+/// This resource stores the block instance that was revealed most recently,
+/// so that the next reveal of the same block type continues from there.
+#[derive(Debug, Default, Resource)]
+pub struct RevealCursor {
+    /// The block type and position of the last revealed instance.
+    last: Option<(Entity, BlockPos)>,
+}
+
+/// This system listens for [`RevealBlock`] events and focuses the camera on the
+/// next instance of the requested block type.
+///
+/// Instances are visited in a fixed order, sorted by chunk position and then
+/// by their position within the chunk, wrapping around after the last one.
+pub(super) fn reveal_block(
+    mut events: EventReader<RevealBlock>,
+    mut cursor: ResMut<RevealCursor>,
+    block_finder: BlockFinder,
+    chunks: Query<(&Position, &ChunkData, &UniqueBlocks)>,
+    mut camera_target: Query<&mut Transform, With<CameraTarget>>,
+) {
+    for ev in events.read() {
+        let Some(block) = block_finder.find_by_uuid(ev.uuid) else {
+            warn!("Cannot reveal block; Unknown block uuid: {}", ev.uuid);
+            continue;
+        };
+
+        let mut containing = chunks
+            .iter()
+            .filter(|(_, _, unique_blocks)| unique_blocks.blocks.contains(&block))
+            .map(|(pos, data, _)| (pos.block, data))
+            .collect::<Vec<_>>();
+        containing.sort_by_key(|(pos, _)| (pos.x, pos.y, pos.z));
+
+        let instances = containing
+            .iter()
+            .flat_map(|(origin, data)| {
+                ChunkIterator::default()
+                    .filter(move |local| data.get(*local) == block)
+                    .map(move |local| {
+                        BlockPos::new(origin.x + local.x, origin.y + local.y, origin.z + local.z)
+                    })
+            })
+            .collect::<Vec<_>>();
+
+        if instances.is_empty() {
+            info!("No instances of block {} found in the world.", ev.uuid);
+            continue;
+        }
+
+        let next = match cursor.last {
+            Some((last_block, last_pos)) if last_block == block => instances
+                .iter()
+                .position(|pos| *pos == last_pos)
+                .map_or(0, |index| (index + 1) % instances.len()),
+            _ => 0,
+        };
+
+        let pos = instances[next];
+        cursor.last = Some((block, pos));
+
+        let Ok(mut target) = camera_target.get_single_mut() else {
+            continue;
+        };
+
+        focus_camera_on(&mut target, pos.as_vec3() + Vec3::splat(0.5));
+        info!(
+            "Revealing block {} at {} ({} of {}).",
+            ev.uuid,
+            pos,
+            next + 1,
+            instances.len()
+        );
+    }
+}
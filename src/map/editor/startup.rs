@@ -3,18 +3,30 @@
 //! map editor.
 
 use bevy::prelude::*;
+use bevy::utils::HashMap;
 
 use crate::blocks::params::BlockFinder;
 use crate::map::chunk::ChunkData;
-use crate::map::world::VoxelWorldCommands;
-use crate::math::{BlockPos, CHUNK_SIZE, ChunkPos};
+use crate::map::world::{VoxelWorld, VoxelWorldCommands};
+use crate::math::{BlockPos, ChunkPos};
+use crate::settings::ProjectSettings;
 use crate::ui::hotbar::resource::{Hotbar, HotbarSlotData};
+use crate::{
+    STARTER_PLATFORM_BLOCK_DEFAULT,
+    STARTER_PLATFORM_BLOCK_KEY,
+    STARTER_PLATFORM_SIZE_DEFAULT,
+    STARTER_PLATFORM_SIZE_KEY,
+    STARTER_PLATFORM_SIZE_MAX,
+};
 
 /// This system is called when the application enters the map editor. It sets up
-/// the map editor environment.
+/// the map editor environment, and spawns the starter platform if the world is
+/// empty.
 pub fn prepare_map_editor(
     mut hotbar: ResMut<Hotbar>,
     block_finder: BlockFinder,
+    world: Res<VoxelWorld>,
+    project_settings: Res<ProjectSettings>,
     mut ambient_light: ResMut<AmbientLight>,
     mut commands: Commands,
 ) {
@@ -29,23 +41,78 @@ pub fn prepare_map_editor(
     });
     ambient_light.brightness = 1000.0;
 
-    let air = block_finder.find_air();
     let grass = block_finder.find("Grass").unwrap();
     let dirt = block_finder.find("Dirt").unwrap();
     let debug = block_finder.find("Debug").unwrap();
     let sign1 = block_finder.find("Sign 1").unwrap();
 
-    let mut chunk_data = ChunkData::fill(air);
-    for x in 0 .. CHUNK_SIZE {
-        for z in 0 .. CHUNK_SIZE {
-            chunk_data.set(BlockPos::new(x as i32, 0, z as i32), grass);
-        }
+    if world.chunk_count() == 0 {
+        spawn_starter_platform(&project_settings, &block_finder, &mut commands);
     }
 
-    commands.spawn_chunk(ChunkPos::new(0, 0, 0), chunk_data);
-
     hotbar.set_slot(0, HotbarSlotData::Block(grass));
     hotbar.set_slot(1, HotbarSlotData::Block(dirt));
     hotbar.set_slot(2, HotbarSlotData::Block(debug));
     hotbar.set_slot(3, HotbarSlotData::Block(sign1));
 }
+
+/// Spawns the starter platform, a single layer of blocks that gives new
+/// projects a surface to build on. The size and block of the platform are
+/// loaded from the project settings, and fall back to the defaults if missing
+/// or invalid.
+fn spawn_starter_platform(
+    project_settings: &ProjectSettings,
+    block_finder: &BlockFinder,
+    commands: &mut Commands,
+) {
+    let size = match project_settings.get_parsed(STARTER_PLATFORM_SIZE_KEY) {
+        Ok(size) => size.unwrap_or(STARTER_PLATFORM_SIZE_DEFAULT),
+        Err(err) => {
+            error!("Failed to read starter platform size: {}", err);
+            STARTER_PLATFORM_SIZE_DEFAULT
+        }
+    };
+
+    let size = size.min(STARTER_PLATFORM_SIZE_MAX) as i32;
+    if size == 0 {
+        return;
+    }
+
+    let name = match project_settings.get(STARTER_PLATFORM_BLOCK_KEY) {
+        Ok(Some(value)) => value,
+        Ok(None) => STARTER_PLATFORM_BLOCK_DEFAULT.to_string(),
+        Err(err) => {
+            error!("Failed to read starter platform block: {}", err);
+            STARTER_PLATFORM_BLOCK_DEFAULT.to_string()
+        }
+    };
+
+    let block = match block_finder.find(&name) {
+        Some(block) => block,
+        None => {
+            warn!("Unknown starter platform block: {}; Using default.", name);
+            let Some(block) = block_finder.find(STARTER_PLATFORM_BLOCK_DEFAULT) else {
+                error!("Failed to find default starter platform block.");
+                return;
+            };
+            block
+        }
+    };
+
+    let air = block_finder.find_air();
+    let mut chunks: HashMap<ChunkPos, ChunkData> = HashMap::default();
+    for x in 0 .. size {
+        for z in 0 .. size {
+            let pos = BlockPos::new(x, 0, z);
+            chunks
+                .entry(pos.into())
+                .or_insert_with(|| ChunkData::fill(air))
+                .set(pos, block);
+        }
+    }
+
+    debug!("Spawning {size}x{size} starter platform of block: {name}");
+    for (chunk_pos, chunk_data) in chunks {
+        commands.spawn_chunk(chunk_pos, chunk_data);
+    }
+}
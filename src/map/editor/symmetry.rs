@@ -0,0 +1,184 @@
+//! This module implements the symmetry mode for the map editor, which mirrors
+//! all block edits across one or more configurable planes.
+
+use bevy::prelude::*;
+
+use crate::gizmos::cursor::CursorRaycast;
+use crate::math::BlockPos;
+
+/// The color of the mirror plane gizmos.
+const MIRROR_PLANE_COLOR: Color = Color::srgb(1.0, 0.2, 0.8);
+
+/// The size of the mirror plane gizmos, in blocks.
+const MIRROR_PLANE_SIZE: f32 = 32.0;
+
+/// This resource stores the mirror planes of the symmetry mode.
+///
+/// Each mirror plane is aligned to an axis and passes through the center of the
+/// given block coordinate, so a plane at `x = N` maps the block at `N + d` to
+/// the block at `N - d`. Any combination of planes can be enabled at once, in
+/// which case edits are mirrored across every combination of the planes.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Resource)]
+pub struct Symmetry {
+    /// The mirror plane along the x axis, if enabled.
+    pub x: Option<i32>,
+
+    /// The mirror plane along the z axis, if enabled.
+    pub z: Option<i32>,
+}
+
+impl Symmetry {
+    /// Returns true if any mirror plane is enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.x.is_some() || self.z.is_some()
+    }
+
+    /// Returns the given position, along with all of its mirrored positions.
+    /// The original position is always first, and duplicate positions that lie
+    /// on a mirror plane are removed.
+    pub fn mirror(&self, pos: BlockPos) -> Vec<BlockPos> {
+        let mut positions = vec![pos];
+
+        if let Some(x) = self.x {
+            for i in 0 .. positions.len() {
+                let p = positions[i];
+                positions.push(BlockPos::new(2 * x - p.x, p.y, p.z));
+            }
+        }
+
+        if let Some(z) = self.z {
+            for i in 0 .. positions.len() {
+                let p = positions[i];
+                positions.push(BlockPos::new(p.x, p.y, 2 * z - p.z));
+            }
+        }
+
+        let mut unique = Vec::with_capacity(positions.len());
+        for p in positions {
+            if !unique.contains(&p) {
+                unique.push(p);
+            }
+        }
+
+        unique
+    }
+}
+
+/// This system toggles the mirror planes. Pressing `M` toggles the plane along
+/// the x axis, and pressing `N` toggles the plane along the z axis. New planes
+/// are placed through the block under the cursor.
+pub fn toggle_mirror_planes(
+    keys: Res<ButtonInput<KeyCode>>,
+    cursor: Res<CursorRaycast>,
+    mut symmetry: ResMut<Symmetry>,
+) {
+    let toggle_x = keys.just_pressed(KeyCode::KeyM);
+    let toggle_z = keys.just_pressed(KeyCode::KeyN);
+
+    if !toggle_x && !toggle_z {
+        return;
+    }
+
+    let cursor_pos = cursor.block.as_ref().map(|hit| hit.block);
+
+    if toggle_x {
+        symmetry.x = match (symmetry.x, cursor_pos) {
+            (Some(_), _) => None,
+            (None, Some(pos)) => Some(pos.x),
+            (None, None) => None,
+        };
+        info!("Mirror plane x: {:?}", symmetry.x);
+    }
+
+    if toggle_z {
+        symmetry.z = match (symmetry.z, cursor_pos) {
+            (Some(_), _) => None,
+            (None, Some(pos)) => Some(pos.z),
+            (None, None) => None,
+        };
+        info!("Mirror plane z: {:?}", symmetry.z);
+    }
+}
+
+/// This system draws a gizmo for each enabled mirror plane. The planes are
+/// drawn around the block under the cursor, or around the origin if the cursor
+/// is not over any block.
+pub fn draw_mirror_planes(symmetry: Res<Symmetry>, cursor: Res<CursorRaycast>, mut gizmos: Gizmos) {
+    if !symmetry.is_enabled() {
+        return;
+    }
+
+    let center = cursor
+        .block
+        .as_ref()
+        .map(|hit| hit.block.as_vec3() + Vec3::splat(0.5))
+        .unwrap_or_default();
+    let size = Vec2::splat(MIRROR_PLANE_SIZE);
+
+    if let Some(x) = symmetry.x {
+        let position = Vec3::new(x as f32 + 0.5, center.y, center.z);
+        let rotation = Quat::from_rotation_y(std::f32::consts::FRAC_PI_2);
+        gizmos.rect(position, rotation, size, MIRROR_PLANE_COLOR);
+    }
+
+    if let Some(z) = symmetry.z {
+        let position = Vec3::new(center.x, center.y, z as f32 + 0.5);
+        gizmos.rect(position, Quat::IDENTITY, size, MIRROR_PLANE_COLOR);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mirror_across_one_plane() {
+        let symmetry = Symmetry {
+            x: Some(0),
+            z: None,
+        };
+
+        assert_eq!(
+            symmetry.mirror(BlockPos::new(3, 1, 2)),
+            vec![BlockPos::new(3, 1, 2), BlockPos::new(-3, 1, 2)]
+        );
+        assert_eq!(
+            symmetry.mirror(BlockPos::new(0, 1, 2)),
+            vec![BlockPos::new(0, 1, 2)]
+        );
+    }
+
+    #[test]
+    fn mirror_across_two_planes() {
+        let symmetry = Symmetry {
+            x: Some(0),
+            z: Some(0),
+        };
+
+        let positions = symmetry.mirror(BlockPos::new(3, 1, 2));
+        assert_eq!(positions.len(), 4);
+        assert_eq!(positions[0], BlockPos::new(3, 1, 2));
+        assert!(positions.contains(&BlockPos::new(-3, 1, 2)));
+        assert!(positions.contains(&BlockPos::new(3, 1, -2)));
+        assert!(positions.contains(&BlockPos::new(-3, 1, -2)));
+
+        assert_eq!(
+            symmetry.mirror(BlockPos::new(0, 1, 2)),
+            vec![BlockPos::new(0, 1, 2), BlockPos::new(0, 1, -2)]
+        );
+        assert_eq!(
+            symmetry.mirror(BlockPos::new(0, 1, 0)),
+            vec![BlockPos::new(0, 1, 0)]
+        );
+    }
+
+    #[test]
+    fn mirror_disabled() {
+        let symmetry = Symmetry::default();
+        assert!(!symmetry.is_enabled());
+        assert_eq!(
+            symmetry.mirror(BlockPos::new(3, 1, 2)),
+            vec![BlockPos::new(3, 1, 2)]
+        );
+    }
+}
@@ -0,0 +1,401 @@
+//! This module implements importing a grayscale heightmap image as terrain.
+//!
+//! The brightness of each pixel is mapped to the height of a column of blocks,
+//! where black pixels produce empty columns and white pixels produce columns
+//! of the maximum height. Each column is filled with the base block, and
+//! topped with the surface block. The terrain starts at the world origin and
+//! extends along the positive X and Z axes.
+//!
+//! Large heightmaps span many chunks, so the terrain is generated a few chunk
+//! columns per frame rather than all at once.
+
+use std::collections::VecDeque;
+
+use bevy::asset::LoadState;
+use bevy::prelude::*;
+use bevy::render::render_resource::TextureFormat;
+
+use super::chunk::ChunkData;
+use super::world::{VoxelWorld, VoxelWorldCommands};
+use crate::blocks::params::BlockFinder;
+use crate::math::{BlockPos, CHUNK_SIZE, ChunkPos};
+use crate::utilities::asset_path::project_path_segments;
+
+/// The largest height, in blocks, that a heightmap can be imported with.
+pub const HEIGHTMAP_MAX_HEIGHT: u32 = 256;
+
+/// The number of chunk columns that are generated per frame while importing a
+/// heightmap.
+pub const HEIGHTMAP_COLUMNS_PER_FRAME: usize = 4;
+
+/// An event that requests a grayscale heightmap image to be imported as
+/// terrain. Existing blocks within the area of the heightmap are replaced.
+#[derive(Debug, Clone, Event)]
+pub struct ImportHeightmap {
+    /// The asset path of the image, relative to the project folder. An
+    /// optional `project://` prefix is allowed.
+    pub path: String,
+
+    /// The height of the column produced by a white pixel, in blocks.
+    pub max_height: u32,
+
+    /// The block that fills each column below its top block.
+    pub base: Entity,
+
+    /// The top block of each column.
+    pub surface: Entity,
+
+    /// The width and depth of the column area produced by each pixel, in
+    /// blocks.
+    pub scale: u32,
+}
+
+/// A heightmap that has been decoded into column heights.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Heightmap {
+    /// The width of the image, in pixels.
+    width: u32,
+
+    /// The depth of the image, in pixels.
+    depth: u32,
+
+    /// The width and depth of the column area produced by each pixel, in
+    /// blocks.
+    scale: u32,
+
+    /// The height of each pixel, in blocks, in row-major order.
+    heights: Vec<u32>,
+}
+
+impl Heightmap {
+    /// Creates a new heightmap from the brightness of each pixel, in the range
+    /// `0.0 ..= 1.0` and in row-major order.
+    ///
+    /// Returns `None` if the number of pixels does not match the given size.
+    pub fn from_brightness(
+        width: u32,
+        depth: u32,
+        brightness: &[f32],
+        max_height: u32,
+        scale: u32,
+    ) -> Option<Self> {
+        if brightness.len() != width as usize * depth as usize {
+            return None;
+        }
+
+        let max_height = max_height.min(HEIGHTMAP_MAX_HEIGHT);
+        let heights = brightness
+            .iter()
+            .map(|value| (value.clamp(0.0, 1.0) * max_height as f32).round() as u32)
+            .collect();
+
+        Some(Self {
+            width,
+            depth,
+            scale: scale.max(1),
+            heights,
+        })
+    }
+
+    /// Decodes the given grayscale image into a new heightmap. Color images
+    /// are converted to grayscale by averaging their color channels.
+    ///
+    /// Returns `None` if the pixel format of the image is not supported.
+    pub fn from_image(image: &Image, max_height: u32, scale: u32) -> Option<Self> {
+        let data = &image.data;
+        let brightness: Vec<f32> = match image.texture_descriptor.format {
+            TextureFormat::R8Unorm => data.iter().map(|v| *v as f32 / 255.0).collect(),
+            TextureFormat::Rg8Unorm => data
+                .chunks_exact(2)
+                .map(|pixel| pixel[0] as f32 / 255.0)
+                .collect(),
+            TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => data
+                .chunks_exact(4)
+                .map(|pixel| (pixel[0] as f32 + pixel[1] as f32 + pixel[2] as f32) / 765.0)
+                .collect(),
+            // 16-bit images are not stored in the byte order of the file. Bevy
+            // copies the samples from the `u16` buffer of the image crate, and
+            // texture data is always little-endian, like all supported targets.
+            TextureFormat::R16Uint | TextureFormat::R16Unorm => data
+                .chunks_exact(2)
+                .map(|pixel| u16::from_le_bytes([pixel[0], pixel[1]]) as f32 / 65535.0)
+                .collect(),
+            _ => return None,
+        };
+
+        let size = image.size();
+        Self::from_brightness(size.x, size.y, &brightness, max_height, scale)
+    }
+
+    /// Gets the width and depth of the terrain, in blocks.
+    pub fn size(&self) -> UVec2 {
+        UVec2::new(self.width, self.depth) * self.scale
+    }
+
+    /// Gets the height of the column at the given block coordinates, or `None`
+    /// if the column is outside of the heightmap.
+    pub fn height_at(&self, x: i32, z: i32) -> Option<u32> {
+        let size = self.size();
+        if x < 0 || z < 0 || x as u32 >= size.x || z as u32 >= size.y {
+            return None;
+        }
+
+        let px = x as u32 / self.scale;
+        let pz = z as u32 / self.scale;
+        Some(self.heights[(pz * self.width + px) as usize])
+    }
+
+    /// Gets the positions of all chunk columns that overlap the heightmap, as
+    /// chunk X and Z coordinates.
+    pub fn chunk_columns(&self) -> VecDeque<IVec2> {
+        let size = self.size();
+        let chunks_x = size.x.div_ceil(CHUNK_SIZE as u32) as i32;
+        let chunks_z = size.y.div_ceil(CHUNK_SIZE as u32) as i32;
+
+        (0 .. chunks_z)
+            .flat_map(|z| (0 .. chunks_x).map(move |x| IVec2::new(x, z)))
+            .collect()
+    }
+}
+
+/// A heightmap import that is waiting for its image to load, or that is
+/// currently generating terrain.
+#[derive(Debug)]
+enum HeightmapImport {
+    /// The image of the heightmap is still loading.
+    Loading {
+        /// The import request.
+        request: ImportHeightmap,
+
+        /// The handle of the image.
+        image: Handle<Image>,
+    },
+
+    /// The heightmap is being generated.
+    Generating {
+        /// The decoded heightmap.
+        heightmap: Heightmap,
+
+        /// The block that fills each column below its top block.
+        base: Entity,
+
+        /// The top block of each column.
+        surface: Entity,
+
+        /// The chunk columns that have not been generated yet.
+        columns: VecDeque<IVec2>,
+    },
+}
+
+/// This resource stores all heightmap imports that are in progress, in the
+/// order they were requested.
+#[derive(Debug, Default, Resource)]
+pub struct HeightmapImports {
+    /// The imports in progress.
+    imports: VecDeque<HeightmapImport>,
+}
+
+/// This system handles [`ImportHeightmap`] events, starting to load the image
+/// of each requested heightmap.
+pub(super) fn start_heightmap_imports(
+    mut events: EventReader<ImportHeightmap>,
+    asset_server: Res<AssetServer>,
+    mut imports: ResMut<HeightmapImports>,
+) {
+    for ev in events.read() {
+        let Some(segments) = project_path_segments(&ev.path) else {
+            warn!("Cannot import heightmap; Invalid path: {}", ev.path);
+            continue;
+        };
+
+        let path = segments.join("/");
+
+        info!("Importing heightmap: {}", path);
+        imports.imports.push_back(HeightmapImport::Loading {
+            request: ev.clone(),
+            image: asset_server.load(format!("project://{path}")),
+        });
+    }
+}
+
+/// This system advances the oldest heightmap import. Once its image has loaded,
+/// the terrain is generated over multiple frames, with
+/// [`HEIGHTMAP_COLUMNS_PER_FRAME`] chunk columns generated each frame.
+pub(super) fn generate_heightmap_terrain(
+    mut imports: ResMut<HeightmapImports>,
+    images: Res<Assets<Image>>,
+    asset_server: Res<AssetServer>,
+    world: Res<VoxelWorld>,
+    block_finder: BlockFinder,
+    chunks: Query<&ChunkData>,
+    mut commands: Commands,
+) {
+    let Some(import) = imports.imports.front_mut() else {
+        return;
+    };
+
+    match import {
+        HeightmapImport::Loading { request, image } => {
+            if let Some(img) = images.get(image.id()) {
+                let Some(heightmap) = Heightmap::from_image(img, request.max_height, request.scale)
+                else {
+                    error!(
+                        "Cannot import heightmap; Unsupported image format: {:?}",
+                        img.texture_descriptor.format
+                    );
+                    imports.imports.pop_front();
+                    return;
+                };
+
+                *import = HeightmapImport::Generating {
+                    columns: heightmap.chunk_columns(),
+                    heightmap,
+                    base: request.base,
+                    surface: request.surface,
+                };
+            } else if let Some(LoadState::Failed(err)) = asset_server.get_load_state(image.id()) {
+                error!("Failed to load heightmap image: {}", err);
+                imports.imports.pop_front();
+            }
+        }
+
+        HeightmapImport::Generating {
+            heightmap,
+            base,
+            surface,
+            columns,
+        } => {
+            let air = block_finder.find_air();
+            for _ in 0 .. HEIGHTMAP_COLUMNS_PER_FRAME {
+                let Some(column) = columns.pop_front() else {
+                    break;
+                };
+
+                for (pos, data) in
+                    generate_chunk_column(heightmap, column, *base, *surface, air, |pos| {
+                        world
+                            .get_chunk(pos)
+                            .and_then(|chunk| chunks.get(chunk).ok())
+                            .cloned()
+                    })
+                {
+                    commands.spawn_chunk(pos, data);
+                }
+            }
+
+            if columns.is_empty() {
+                info!("Finished importing heightmap.");
+                imports.imports.pop_front();
+            }
+        }
+    }
+}
+
+/// Generates the chunks of the given chunk column of a heightmap. Chunks above
+/// the highest column are not generated. Blocks outside of the heightmap are
+/// copied from the existing chunks, which are provided by `existing`.
+fn generate_chunk_column(
+    heightmap: &Heightmap,
+    column: IVec2,
+    base: Entity,
+    surface: Entity,
+    air: Entity,
+    existing: impl Fn(ChunkPos) -> Option<ChunkData>,
+) -> Vec<(ChunkPos, ChunkData)> {
+    let size = CHUNK_SIZE as i32;
+    let min_x = column.x * size;
+    let min_z = column.y * size;
+
+    let mut heights = Vec::with_capacity(CHUNK_SIZE * CHUNK_SIZE);
+    for z in min_z .. min_z + size {
+        for x in min_x .. min_x + size {
+            if let Some(height) = heightmap.height_at(x, z) {
+                heights.push((x, z, height as i32));
+            }
+        }
+    }
+
+    let top = heights
+        .iter()
+        .map(|(_, _, height)| *height)
+        .max()
+        .unwrap_or(0);
+    let mut result = Vec::new();
+    for chunk_y in 0 .. (top + size - 1) / size {
+        let pos = ChunkPos::new(column.x, chunk_y, column.y);
+        let mut data = existing(pos).unwrap_or_else(|| ChunkData::fill(air));
+
+        let min_y = chunk_y * size;
+        for &(x, z, height) in heights.iter() {
+            for y in min_y .. min_y + size {
+                let block = if y < height - 1 {
+                    base
+                } else if y == height - 1 {
+                    surface
+                } else {
+                    air
+                };
+                data.set(BlockPos::new(x, y, z), block);
+            }
+        }
+
+        result.push((pos, data));
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::render::render_asset::RenderAssetUsages;
+    use bevy::render::render_resource::{Extent3d, TextureDimension};
+
+    use super::*;
+
+    #[test]
+    fn heightmap_maps_brightness_to_scaled_columns() {
+        let heightmap = Heightmap::from_brightness(2, 1, &[0.0, 1.0], 20, 3).unwrap();
+        assert_eq!(heightmap.size(), UVec2::new(6, 3));
+        assert_eq!(heightmap.height_at(2, 2), Some(0));
+        assert_eq!(heightmap.height_at(3, 0), Some(20));
+        assert_eq!(heightmap.height_at(6, 0), None);
+        assert_eq!(heightmap.height_at(-1, 0), None);
+        assert_eq!(heightmap.chunk_columns(), VecDeque::from([IVec2::ZERO]));
+    }
+
+    #[test]
+    fn chunk_column_fills_base_and_surface() {
+        let base = Entity::from_raw(1);
+        let surface = Entity::from_raw(2);
+        let air = Entity::from_raw(3);
+        let heightmap = Heightmap::from_brightness(1, 1, &[1.0], 20, 1).unwrap();
+
+        let chunks = generate_chunk_column(&heightmap, IVec2::ZERO, base, surface, air, |_| None);
+        assert_eq!(chunks.len(), 2);
+
+        let (_, upper) = &chunks[1];
+        assert_eq!(upper.get(BlockPos::new(0, 18, 0)), base);
+        assert_eq!(upper.get(BlockPos::new(0, 19, 0)), surface);
+        assert_eq!(upper.get(BlockPos::new(0, 20, 0)), air);
+        assert_eq!(upper.get(BlockPos::new(1, 19, 0)), air);
+    }
+
+    #[test]
+    fn r16_images_are_little_endian() {
+        let image = Image::new(
+            Extent3d {
+                width: 2,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            vec![0x00, 0xFF, 0xFF, 0xFF],
+            TextureFormat::R16Uint,
+            RenderAssetUsages::default(),
+        );
+
+        let heightmap = Heightmap::from_image(&image, 256, 1).unwrap();
+        assert_eq!(heightmap.height_at(0, 0), Some(255));
+        assert_eq!(heightmap.height_at(1, 0), Some(256));
+    }
+}
@@ -0,0 +1,443 @@
+//! This module implements flood-fill light propagation for the voxel world.
+//!
+//! Light is emitted by blocks with a [`LightEmitter`] component and spreads
+//! outwards through all non-opaque blocks, decreasing by one level for every
+//! block travelled. The resulting light levels are stored per chunk in a
+//! [`ChunkLight`] side-table and baked into the chunk meshes as a vertex light
+//! term.
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use bevy::utils::{HashMap, HashSet};
+
+use super::chunk::ChunkData;
+use super::remesh::{NeedsRemesh, UniqueBlocks};
+use super::world::VoxelWorld;
+use crate::blocks::light::{LightEmitter, MAX_LIGHT_LEVEL};
+use crate::blocks::shape::BlockShape;
+use crate::math::{BlockPos, CHUNK_SIZE, ChunkPos, FaceDirection, Position, TOTAL_BLOCKS};
+use crate::utilities::chunk_iter::ChunkIterator;
+use crate::utilities::meshbuf::MeshBuf;
+
+/// The brightness added to a vertex at the maximum light level, relative to an
+/// unlit vertex.
+pub const LIGHT_INTENSITY: f32 = 1.5;
+
+/// The light levels of every block within a chunk.
+#[derive(Debug, Clone, PartialEq, Eq, Component)]
+pub struct ChunkLight {
+    /// The light level of each block in the chunk.
+    levels: Box<[u8; TOTAL_BLOCKS]>,
+}
+
+impl Default for ChunkLight {
+    fn default() -> Self {
+        Self {
+            levels: Box::new([0; TOTAL_BLOCKS]),
+        }
+    }
+}
+
+impl ChunkLight {
+    /// Gets the light level of the block at the given position.
+    ///
+    /// If the block position is out of the bounds of this chunk, the
+    /// coordinates will be wrapped around to the other side of the chunk.
+    #[inline(always)]
+    pub fn get(&self, pos: BlockPos) -> u8 {
+        self.levels[pos.index()]
+    }
+
+    /// Sets the light level of the block at the given position.
+    ///
+    /// If the block position is out of the bounds of this chunk, the
+    /// coordinates will be wrapped around to the other side of the chunk.
+    ///
+    /// Returns true if the light level was changed, false otherwise.
+    #[inline(always)]
+    pub fn set(&mut self, pos: BlockPos, level: u8) -> bool {
+        let old = std::mem::replace(&mut self.levels[pos.index()], level);
+        old != level
+    }
+
+    /// Checks whether every block in this chunk has a light level of zero.
+    pub fn is_dark(&self) -> bool {
+        self.levels.iter().all(|&level| level == 0)
+    }
+}
+
+/// A read-only view of the light levels around a single chunk, including the
+/// six chunks directly neighboring it. This is used while meshing to sample
+/// light levels across chunk borders.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LightView<'a> {
+    /// The light levels of the chunk itself.
+    center: Option<&'a ChunkLight>,
+
+    /// The light levels of the neighboring chunks, indexed by
+    /// [`FaceDirection::index`].
+    neighbors: [Option<&'a ChunkLight>; 6],
+}
+
+impl<'a> LightView<'a> {
+    /// Gathers the light levels around the chunk at the given position.
+    pub fn gather(
+        chunk_id: Entity,
+        chunk_pos: ChunkPos,
+        world: &VoxelWorld,
+        lights: &'a Query<&ChunkLight>,
+    ) -> Self {
+        let origin = BlockPos::from(chunk_pos);
+        let neighbors = FaceDirection::DIRECTIONS.map(|dir| {
+            let neighbor = ChunkPos::from(origin.shift(dir, CHUNK_SIZE as u32));
+            world.get_chunk(neighbor).and_then(|id| lights.get(id).ok())
+        });
+
+        Self {
+            center: lights.get(chunk_id).ok(),
+            neighbors,
+        }
+    }
+
+    /// Gets the light level at the given position, relative to the center
+    /// chunk. Positions up to one chunk outside of the center chunk along a
+    /// single axis are sampled from the neighboring chunks. All other positions
+    /// have a light level of zero.
+    pub fn get(&self, pos: BlockPos) -> u8 {
+        let size = CHUNK_SIZE as i32;
+        let offset = IVec3::new(
+            pos.x.div_euclid(size),
+            pos.y.div_euclid(size),
+            pos.z.div_euclid(size),
+        );
+
+        let light = if offset == IVec3::ZERO {
+            self.center
+        } else {
+            match FaceDirection::try_from(offset) {
+                Ok(dir) => self.neighbors[dir.index()],
+                Err(_) => None,
+            }
+        };
+
+        light.map_or(0, |light| light.get(pos))
+    }
+
+    /// Checks whether the center chunk and all of its neighbors are completely
+    /// dark.
+    pub fn is_dark(&self) -> bool {
+        std::iter::once(self.center)
+            .chain(self.neighbors)
+            .flatten()
+            .all(ChunkLight::is_dark)
+    }
+
+    /// Appends the vertex light term for all vertices in the given mesh buffer
+    /// that do not have a color yet. Each vertex samples the brighter of the
+    /// block it belongs to and the block its normal is facing towards.
+    pub fn append_colors(&self, pos: BlockPos, mesh: &mut MeshBuf) {
+        let own = self.get(pos);
+
+        for index in mesh.colors.len() .. mesh.positions.len() {
+            let normal = Vec3::from(mesh.normals[index]);
+            let facing =
+                FaceDirection::from_normal(normal).map_or(0, |dir| self.get(pos.shift(dir, 1)));
+
+            mesh.colors.push(vertex_light(own.max(facing)));
+        }
+    }
+}
+
+/// Converts a light level into a vertex color. A light level of zero leaves
+/// the vertex unchanged.
+#[inline(always)]
+pub fn vertex_light(level: u8) -> [f32; 4] {
+    let level = level.min(MAX_LIGHT_LEVEL) as f32 / MAX_LIGHT_LEVEL as f32;
+    let brightness = 1.0 + LIGHT_INTENSITY * level;
+    [brightness, brightness, brightness, 1.0]
+}
+
+/// This system recalculates the light levels around chunks whose block data
+/// has changed, or which contain blocks whose light emission has changed.
+///
+/// Since light can never travel further than [`MAX_LIGHT_LEVEL`] blocks, which
+/// is less than the size of a chunk, only the changed chunks and their direct
+/// neighbors need to be relit. Those chunks are cleared and refilled from all
+/// emitters within reach, which may be located in the next ring of chunks. The
+/// light of those outer emitters is spread through scratch tables that start
+/// out dark, and only the light levels of the relit chunks are kept.
+/// Chunks whose light levels changed are marked for remeshing, along with
+/// their direct neighbors, which sample light across the shared border.
+#[allow(clippy::too_many_arguments)]
+#[allow(clippy::type_complexity)]
+pub(super) fn propagate_light(
+    world: Res<VoxelWorld>,
+    changed_chunks: Query<&Position, (With<ChunkData>, Changed<ChunkData>)>,
+    changed_emitters: Query<Entity, Changed<LightEmitter>>,
+    mut removed_emitters: RemovedComponents<LightEmitter>,
+    chunks: Query<(&Position, &ChunkData, &UniqueBlocks, &ChunkLight)>,
+    emitters: Query<&LightEmitter>,
+    shapes: Query<&BlockShape>,
+    mut commands: Commands,
+) {
+    let mut dirty: HashSet<ChunkPos> = changed_chunks
+        .iter()
+        .map(|pos| ChunkPos::from(pos.block))
+        .collect();
+
+    let emitter_blocks: HashSet<Entity> = changed_emitters
+        .iter()
+        .chain(removed_emitters.read())
+        .collect();
+
+    if !emitter_blocks.is_empty() {
+        for (pos, _, unique_blocks, _) in chunks.iter() {
+            if unique_blocks
+                .blocks
+                .iter()
+                .any(|b| emitter_blocks.contains(b))
+            {
+                dirty.insert(pos.block.into());
+            }
+        }
+    }
+
+    if dirty.is_empty() {
+        return;
+    }
+
+    let get_chunk = |pos: ChunkPos| {
+        world
+            .get_chunk(pos)
+            .and_then(|chunk_id| chunks.get(chunk_id).ok())
+    };
+
+    let affected = expand_chunks(&dirty);
+    let sources = expand_chunks(&affected);
+
+    let mut tables: HashMap<ChunkPos, ChunkLight> = affected
+        .iter()
+        .filter(|pos| get_chunk(**pos).is_some())
+        .map(|pos| (*pos, ChunkLight::default()))
+        .collect();
+
+    let mut queue: VecDeque<(BlockPos, u8)> = VecDeque::new();
+    if !emitters.is_empty() {
+        for chunk_pos in sources.iter() {
+            let Some((_, data, _, _)) = get_chunk(*chunk_pos) else {
+                continue;
+            };
+
+            if !data.iter().any(|block| emitters.contains(block)) {
+                continue;
+            }
+
+            let origin = BlockPos::from(*chunk_pos);
+            for local in ChunkIterator::default() {
+                let Ok(emitter) = emitters.get(data.get(local)) else {
+                    continue;
+                };
+
+                let level = emitter.level();
+                if level > 0 {
+                    let pos =
+                        BlockPos::new(origin.x + local.x, origin.y + local.y, origin.z + local.z);
+                    queue.push_back((pos, level));
+                }
+            }
+        }
+    }
+
+    while let Some((pos, level)) = queue.pop_front() {
+        let chunk_pos = ChunkPos::from(pos);
+        if !tables.contains_key(&chunk_pos) {
+            if !sources.contains(&chunk_pos) || get_chunk(chunk_pos).is_none() {
+                continue;
+            }
+            tables.insert(chunk_pos, ChunkLight::default());
+        }
+
+        let table = tables.get_mut(&chunk_pos).unwrap();
+
+        if table.get(pos) >= level {
+            continue;
+        }
+
+        table.set(pos, level);
+        if level <= 1 {
+            continue;
+        }
+
+        for dir in FaceDirection::DIRECTIONS {
+            let next = pos.shift(dir, 1);
+            let passes_light = get_chunk(next.into()).is_some_and(|(_, data, _, _)| {
+                match shapes.get(data.get(next)) {
+                    Ok(BlockShape::Cube {
+                        transparent,
+                        non_occluding,
+                        ..
+                    }) => *transparent || *non_occluding,
+                    _ => true,
+                }
+            });
+
+            if passes_light {
+                queue.push_back((next, level - 1));
+            }
+        }
+    }
+
+    let mut remesh: HashSet<ChunkPos> = HashSet::new();
+    for (chunk_pos, table) in tables {
+        if !affected.contains(&chunk_pos) {
+            continue;
+        }
+
+        let Some(chunk_id) = world.get_chunk(chunk_pos) else {
+            continue;
+        };
+
+        let Ok((_, _, _, light)) = chunks.get(chunk_id) else {
+            continue;
+        };
+
+        if *light == table {
+            continue;
+        }
+
+        commands.entity(chunk_id).insert(table);
+
+        remesh.insert(chunk_pos);
+        let origin = BlockPos::from(chunk_pos);
+        for dir in FaceDirection::DIRECTIONS {
+            remesh.insert(origin.shift(dir, CHUNK_SIZE as u32).into());
+        }
+    }
+
+    for chunk_pos in remesh {
+        if let Some(chunk_id) = world.get_chunk(chunk_pos) {
+            commands.entity(chunk_id).insert(NeedsRemesh);
+        }
+    }
+}
+
+/// Returns the given set of chunk positions, extended by all chunks that
+/// directly or diagonally border any of them.
+fn expand_chunks(chunks: &HashSet<ChunkPos>) -> HashSet<ChunkPos> {
+    let mut expanded = HashSet::new();
+
+    for pos in chunks.iter() {
+        for x in -1 ..= 1 {
+            for y in -1 ..= 1 {
+                for z in -1 ..= 1 {
+                    expanded.insert(ChunkPos::new(pos.x + x, pos.y + y, pos.z + z));
+                }
+            }
+        }
+    }
+
+    expanded
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::map::world::VoxelWorldCommands;
+
+    /// Creates a world with an air block and a torch block that emits the
+    /// maximum light level.
+    fn light_world() -> (World, Entity, Entity) {
+        let mut world = World::new();
+        world.init_resource::<VoxelWorld>();
+
+        let air = world.spawn(BlockShape::None).id();
+        let torch = world
+            .spawn((
+                BlockShape::None,
+                LightEmitter {
+                    level: MAX_LIGHT_LEVEL,
+                },
+            ))
+            .id();
+
+        (world, air, torch)
+    }
+
+    /// Spawns a chunk with the given block data into the world.
+    fn spawn_chunk(world: &mut World, pos: ChunkPos, data: ChunkData) {
+        world.run_system_once(move |mut commands: Commands| {
+            commands.spawn_chunk(pos, data.clone());
+        });
+    }
+
+    /// Gets the light level at the given world position.
+    fn light_at(world: &World, pos: BlockPos) -> u8 {
+        let chunk_id = world
+            .resource::<VoxelWorld>()
+            .get_chunk(pos.into())
+            .unwrap();
+        world.get::<ChunkLight>(chunk_id).unwrap().get(pos)
+    }
+
+    #[test]
+    fn light_spreads_within_chunk() {
+        let (mut world, air, torch) = light_world();
+        let mut data = ChunkData::fill(air);
+        data.set(BlockPos::new(8, 8, 8), torch);
+        spawn_chunk(&mut world, ChunkPos::new(0, 0, 0), data);
+
+        world.run_system_once(propagate_light);
+
+        assert_eq!(light_at(&world, BlockPos::new(8, 8, 8)), 15);
+        assert_eq!(light_at(&world, BlockPos::new(10, 8, 8)), 13);
+        assert_eq!(light_at(&world, BlockPos::new(8, 8, 0)), 7);
+        assert_eq!(light_at(&world, BlockPos::new(0, 0, 0)), 0);
+    }
+
+    #[test]
+    fn light_spreads_across_chunk_borders() {
+        let (mut world, air, torch) = light_world();
+        let mut data = ChunkData::fill(air);
+        data.set(BlockPos::new(14, 8, 8), torch);
+        spawn_chunk(&mut world, ChunkPos::new(0, 0, 0), data);
+        spawn_chunk(&mut world, ChunkPos::new(1, 0, 0), ChunkData::fill(air));
+
+        world.run_system_once(propagate_light);
+
+        assert_eq!(light_at(&world, BlockPos::new(16, 8, 8)), 13);
+        assert_eq!(light_at(&world, BlockPos::new(17, 8, 8)), 12);
+    }
+
+    #[test]
+    fn editing_next_to_lit_chunk_keeps_light() {
+        let (mut world, air, torch) = light_world();
+        let mut data = ChunkData::fill(air);
+        data.set(BlockPos::new(15, 8, 8), torch);
+        spawn_chunk(&mut world, ChunkPos::new(0, 0, 0), data);
+        spawn_chunk(&mut world, ChunkPos::new(1, 0, 0), ChunkData::fill(air));
+        spawn_chunk(&mut world, ChunkPos::new(2, 0, 0), ChunkData::fill(air));
+
+        let mut system = IntoSystem::into_system(propagate_light);
+        system.initialize(&mut world);
+        system.run((), &mut world);
+        system.apply_deferred(&mut world);
+        assert_eq!(light_at(&world, BlockPos::new(20, 8, 8)), 10);
+
+        let chunk_id = world
+            .resource::<VoxelWorld>()
+            .get_chunk(ChunkPos::new(2, 0, 0))
+            .unwrap();
+        world
+            .get_mut::<ChunkData>(chunk_id)
+            .unwrap()
+            .set(BlockPos::new(40, 0, 0), air);
+
+        system.run((), &mut world);
+        system.apply_deferred(&mut world);
+        assert_eq!(light_at(&world, BlockPos::new(20, 8, 8)), 10);
+        assert_eq!(light_at(&world, BlockPos::new(29, 8, 8)), 1);
+    }
+}
@@ -4,21 +4,81 @@
 use bevy::prelude::*;
 use world::VoxelWorld;
 
+use crate::project::{LoadProject, UnloadProject};
+
+pub mod biome;
 pub mod chunk;
+pub mod collision;
+pub mod edit;
 #[cfg(feature = "editor")]
 pub mod editor;
+pub mod heightmap;
+pub mod light;
 pub mod remesh;
+pub mod rules;
+pub mod seed;
+pub mod streaming;
+pub mod surface;
+pub mod wireframe;
 pub mod world;
 
 /// The plugin responsible for managing the voxel world.
 pub struct VoxelWorldPlugin;
 impl Plugin for VoxelWorldPlugin {
     fn build(&self, app_: &mut App) {
-        app_.init_resource::<VoxelWorld>().add_plugins((
-            remesh::ChunkRemeshPlugin,
-            #[cfg(feature = "editor")]
-            editor::MapEditorPlugin,
-        ));
+        app_.init_resource::<VoxelWorld>()
+            .init_resource::<streaming::WorldStreaming>()
+            .init_resource::<seed::WorldSeed>()
+            .init_resource::<seed::WorldRng>()
+            .init_resource::<heightmap::HeightmapImports>()
+            .init_resource::<rules::BuildRules>()
+            .init_resource::<biome::BiomeMap>()
+            .init_resource::<edit::BlockProtection>()
+            .add_event::<surface::FillVisibleSurface>()
+            .add_event::<edit::SetBlock>()
+            .add_event::<edit::BlockPlaced>()
+            .add_event::<edit::BlockRemoved>()
+            .add_event::<edit::BlockRejected>()
+            .add_event::<heightmap::ImportHeightmap>()
+            .add_plugins((
+                remesh::ChunkRemeshPlugin,
+                #[cfg(feature = "editor")]
+                editor::MapEditorPlugin,
+            ))
+            .add_systems(
+                Update,
+                (
+                    collision::update_chunk_collision,
+                    surface::fill_visible_surface,
+                    edit::set_blocks,
+                    heightmap::start_heightmap_imports,
+                    heightmap::generate_heightmap_terrain
+                        .after(heightmap::start_heightmap_imports),
+                    streaming::update_chunk_visibility
+                        .in_set(VoxelWorldSystemSets::UpdateChunkVisibility),
+                    streaming::save_render_distance
+                        .run_if(resource_changed::<streaming::WorldStreaming>),
+                    streaming::update_fog.run_if(resource_changed::<streaming::WorldStreaming>),
+                    light::propagate_light.before(remesh::remesh),
+                    biome::sync_biome_seed.run_if(resource_changed::<seed::WorldSeed>),
+                    (biome::remesh_biome_chunks, biome::save_biomes)
+                        .after(biome::sync_biome_seed)
+                        .run_if(resource_changed::<biome::BiomeMap>),
+                ),
+            )
+            .add_systems(
+                LoadProject,
+                (
+                    streaming::load_render_distance,
+                    seed::load_world_seed,
+                    rules::load_build_height,
+                    biome::load_biomes,
+                ),
+            )
+            .add_systems(
+                UnloadProject,
+                (world::unload_world, rules::clear_build_rules),
+            );
     }
 }
 
@@ -27,3 +87,11 @@ impl Plugin for VoxelWorldPlugin {
 /// not contain this chunk will not be seen by the mouse picker.
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Component)]
 pub struct ChunkCollider;
+
+/// The system sets for the voxel world plugin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, SystemSet)]
+pub enum VoxelWorldSystemSets {
+    /// The system set for showing and hiding chunks based on their distance to
+    /// the camera.
+    UpdateChunkVisibility,
+}
@@ -2,32 +2,58 @@
 
 use std::cmp::Ordering;
 
+use bevy::diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic};
 use bevy::prelude::*;
+use bevy::render::mesh::Indices;
 use bevy::utils::{HashMap, HashSet, Parallel};
 
 use super::ChunkCollider;
-use super::chunk::ChunkData;
+use super::biome::BiomeMap;
+use super::chunk::{ChunkData, ChunkView};
+use super::light::{ChunkLight, LightView};
+use super::world::VoxelWorld;
 use crate::blocks::Block;
+use crate::blocks::collision::BlockCollision;
 use crate::blocks::model::BlockModel;
-use crate::blocks::occlusion::BlockDataOccludedBy;
+use crate::blocks::occlusion::{BlockDataOccludedBy, OccludedBy};
 use crate::blocks::shape::BlockShape;
+use crate::math::{BlockPos, ChunkPos, Position};
 use crate::utilities::chunk_iter::ChunkIterator;
 use crate::utilities::meshbuf::MeshBuf;
 
+/// The diagnostic that counts the number of live [`Mesh`] assets.
+pub const MESH_ASSET_COUNT: DiagnosticPath = DiagnosticPath::const_new("awgen/mesh_assets");
+
+/// The diagnostic that counts the number of chunk model parts.
+pub const CHUNK_MESH_COUNT: DiagnosticPath = DiagnosticPath::const_new("awgen/chunk_meshes");
+
+/// The diagnostic that measures the approximate memory used by the vertex and
+/// index data of all chunk meshes, in bytes.
+pub const CHUNK_MESH_BYTES: DiagnosticPath = DiagnosticPath::const_new("awgen/chunk_mesh_bytes");
+
+/// The diagnostic that counts the number of triangles in all chunk meshes.
+pub const CHUNK_TRIANGLE_COUNT: DiagnosticPath =
+    DiagnosticPath::const_new("awgen/chunk_triangles");
+
 /// This plugin handles the remeshing of chunks.
 pub struct ChunkRemeshPlugin;
 impl Plugin for ChunkRemeshPlugin {
     fn build(&self, app_: &mut App) {
-        app_.add_systems(
-            Update,
-            (
-                remesh,
-                update_block_handles,
-                on_block_model_updated,
-                check_remesh_later,
-                remesh_queue_starvation,
-            ),
-        );
+        app_.register_diagnostic(Diagnostic::new(MESH_ASSET_COUNT))
+            .register_diagnostic(Diagnostic::new(CHUNK_MESH_COUNT))
+            .register_diagnostic(Diagnostic::new(CHUNK_MESH_BYTES))
+            .register_diagnostic(Diagnostic::new(CHUNK_TRIANGLE_COUNT))
+            .add_systems(
+                Update,
+                (
+                    remesh,
+                    update_block_handles,
+                    on_block_model_updated,
+                    check_remesh_later,
+                    remesh_queue_starvation,
+                    measure_chunk_meshes.after(remesh),
+                ),
+            );
     }
 }
 
@@ -77,11 +103,16 @@ pub struct UniqueBlocks {
 }
 
 /// This system listens for dirty chunks and remeshes them as needed.
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn remesh(
+    world: Res<VoxelWorld>,
     mut meshes: ResMut<Assets<Mesh>>,
     block_models: Query<&BlockModel>,
     block_shapes: Query<&BlockShape>,
-    chunks: Query<(Entity, &ChunkData, Option<&Children>), With<NeedsRemesh>>,
+    lights: Query<&ChunkLight>,
+    biomes: Res<BiomeMap>,
+    chunks: Query<(Entity, &Position, &ChunkData, Option<&Children>), With<NeedsRemesh>>,
+    chunk_blocks: Query<&ChunkData>,
     mut chunk_model_parts: Query<
         (&mut Handle<Mesh>, &mut Handle<StandardMaterial>),
         With<ChunkModelPart>,
@@ -96,8 +127,19 @@ pub(crate) fn remesh(
 
     chunks.par_iter().for_each_init(
         || queue.borrow_local_mut(),
-        |out, (chunk_id, chunk, _)| {
-            let models = build_models(chunk, &block_models, &block_shapes);
+        |out, (chunk_id, pos, chunk, _)| {
+            let chunk_pos = ChunkPos::from(pos.block);
+            let light = LightView::gather(chunk_id, chunk_pos, &world, &lights);
+            let blocks = ChunkView::gather(chunk, chunk_pos, &world, &chunk_blocks);
+            let origin = BlockPos::from(chunk_pos);
+            let models = build_models(
+                &blocks,
+                origin,
+                &light,
+                &biomes,
+                &block_models,
+                &block_shapes,
+            );
             for model in models {
                 out.push((chunk_id, model));
             }
@@ -109,7 +151,7 @@ pub(crate) fn remesh(
         chunk_models.entry(chunk_id).or_default().push(model);
     }
 
-    for (chunk_id, _, children) in chunks.iter() {
+    for (chunk_id, _, _, children) in chunks.iter() {
         commands
             .entity(chunk_id)
             .remove::<NeedsRemesh>()
@@ -155,6 +197,54 @@ pub(crate) fn remesh(
     }
 }
 
+/// This system measures the number of live mesh assets and the memory and
+/// triangles used by all chunk meshes. A mesh asset count that keeps growing
+/// while the number of chunk meshes stays the same indicates that meshes are
+/// leaking.
+pub(crate) fn measure_chunk_meshes(
+    meshes: Res<Assets<Mesh>>,
+    parts: Query<&Handle<Mesh>, With<ChunkModelPart>>,
+    mut diagnostics: Diagnostics,
+) {
+    diagnostics.add_measurement(&MESH_ASSET_COUNT, || meshes.len() as f64);
+    diagnostics.add_measurement(&CHUNK_MESH_COUNT, || parts.iter().len() as f64);
+    diagnostics.add_measurement(&CHUNK_MESH_BYTES, || {
+        parts
+            .iter()
+            .filter_map(|handle| meshes.get(handle))
+            .map(mesh_bytes)
+            .sum::<usize>() as f64
+    });
+    diagnostics.add_measurement(&CHUNK_TRIANGLE_COUNT, || {
+        parts
+            .iter()
+            .filter_map(|handle| meshes.get(handle))
+            .map(mesh_triangles)
+            .sum::<usize>() as f64
+    });
+}
+
+/// Gets the number of triangles in the given triangle list mesh.
+pub fn mesh_triangles(mesh: &Mesh) -> usize {
+    match mesh.indices() {
+        Some(indices) => indices.len() / 3,
+        None => mesh.count_vertices() / 3,
+    }
+}
+
+/// Gets the approximate number of bytes used by the vertex and index data of
+/// the given mesh.
+pub fn mesh_bytes(mesh: &Mesh) -> usize {
+    let vertices = mesh.get_vertex_size() as usize * mesh.count_vertices();
+    let indices = match mesh.indices() {
+        Some(Indices::U16(indices)) => indices.len() * 2,
+        Some(Indices::U32(indices)) => indices.len() * 4,
+        None => 0,
+    };
+
+    vertices + indices
+}
+
 /// This system iterators over all blocks in chunks that need remeshing and
 /// updates the block handles in the [`RemeshWhenBlockLoaded`] components.
 pub(crate) fn update_block_handles(
@@ -165,15 +255,21 @@ pub(crate) fn update_block_handles(
     });
 }
 
-/// This system listens for changes to [`BlockModel`] components and marks all
-/// chunks that contain that block as [`NeedsRemeshLater`] with a priority of 0,
-/// no starvation.
+/// This system listens for changes to [`BlockModel`] and [`BlockCollision`]
+/// components and marks all chunks that contain that block as
+/// [`NeedsRemeshLater`] with a priority of 0, no starvation.
 ///
 /// Chunks that are already marked as [`NeedsRemesh`] or [`NeedsRemeshLater`]
 /// are ignored.
 #[allow(clippy::type_complexity)]
 pub(crate) fn on_block_model_updated(
-    blocks: Query<Entity, (With<Block>, Changed<BlockModel>)>,
+    blocks: Query<
+        Entity,
+        (
+            With<Block>,
+            Or<(Changed<BlockModel>, Changed<BlockCollision>)>,
+        ),
+    >,
     chunks: Query<
         (Entity, &UniqueBlocks),
         (
@@ -236,16 +332,49 @@ pub(crate) fn remesh_queue_starvation(mut chunks: Query<&mut NeedsRemeshLater>)
 /// This function builds the chunk models from the given block data and
 /// materials.
 ///
+/// The output of this function is deterministic. Blocks are always visited in
+/// the fixed order of the [`ChunkIterator`], and the returned models are sorted
+/// by the order in which their material first appears within the chunk. This
+/// ensures that identical chunk data at the same position always produces
+/// identical meshes.
+///
+/// Blocks with texture variants pick their variant based on their world
+/// position, which is the given chunk origin plus their position within the
+/// chunk. See [`BlockModel::mesh_at`].
+///
+/// Transparent blocks use a separate, alpha blended material, so they are
+/// always split into their own model part. This keeps opaque blocks in the
+/// opaque render pass and allows the transparent parts to be depth sorted.
+///
+/// The given light view is baked into the models as a vertex light term. If
+/// the chunk and its neighbors are completely dark, the models are built
+/// without vertex colors.
+///
+/// Faces that are flagged for biome tinting multiply their vertex colors by the
+/// tint of the given biome map at the world column of their block. Once any
+/// face is tinted, the remaining vertices of the model are given a white color,
+/// so that every vertex has a color.
+///
+/// Faces with connected textures pick their tile based on which neighbors of
+/// the block are the same block type. Neighbors across the chunk border are
+/// sampled from the given chunk view. See
+/// [`BlockMesh::connect_textures`](crate::blocks::mesh::BlockMesh::connect_textures).
+///
 /// This function may return an empty list if the chunk contains no visible
 /// blocks.
 pub fn build_models(
-    data: &ChunkData,
+    blocks: &ChunkView,
+    origin: BlockPos,
+    light: &LightView,
+    biomes: &BiomeMap,
     block_models: &Query<&BlockModel>,
     block_shapes: &Query<&BlockShape>,
 ) -> Vec<ChunkModel> {
+    let data = blocks.center();
     let occlusion = BlockDataOccludedBy::from_block_data(data, block_shapes);
-    let mut meshes: HashMap<Handle<StandardMaterial>, MeshBuf> = HashMap::new();
-    let mut models = Vec::new();
+    let lit = !light.is_dark();
+    let mut material_indices: HashMap<Handle<StandardMaterial>, usize> = HashMap::new();
+    let mut meshes: Vec<(Handle<StandardMaterial>, MeshBuf)> = Vec::new();
 
     for pos in ChunkIterator::default() {
         let block = data.get(pos);
@@ -253,31 +382,57 @@ pub fn build_models(
             continue;
         };
 
-        let (material, mesh) = match model {
-            BlockModel::Primitive { material, mesh, .. } => (material, mesh),
-            BlockModel::Custom { material, mesh, .. } => (material, mesh),
-            _ => continue,
+        let world_pos = BlockPos::new(origin.x + pos.x, origin.y + pos.y, origin.z + pos.z);
+        let Some((material, mesh)) = model.mesh_at(world_pos) else {
+            continue;
         };
 
-        let mesh_buf = match meshes.contains_key(material) {
-            true => meshes.get_mut(material).unwrap(),
-            false => meshes.entry(material.clone()).or_insert_with(MeshBuf::new),
+        let index = match material_indices.get(material) {
+            Some(index) => *index,
+            None => {
+                meshes.push((material.clone(), MeshBuf::new()));
+                material_indices.insert(material.clone(), meshes.len() - 1);
+                meshes.len() - 1
+            }
         };
 
-        let mut block_mesh = *mesh.clone();
+        let occluded_by = match block_shapes.get(block) {
+            Ok(shape) if !shape.is_cullable() => OccludedBy::empty(),
+            _ => occlusion.get(pos),
+        };
+
+        let mut block_mesh = mesh.clone();
+        block_mesh.connect_textures(|dir| blocks.get(pos.shift(dir, 1)) == Some(block));
         block_mesh.rotate(Quat::IDENTITY);
         block_mesh.translate(pos.as_vec3());
-        block_mesh.append_to(occlusion.get(pos), mesh_buf);
+
+        let mesh_buf = &mut meshes[index].1;
+        let start = mesh_buf.positions.len();
+        block_mesh.append_to(occluded_by, mesh_buf);
+
+        if lit {
+            light.append_colors(pos, mesh_buf);
+        }
+
+        if block_mesh.has_biome_tint() {
+            let tint = biomes.tint_at(world_pos.x, world_pos.z);
+            block_mesh.apply_biome_tint(occluded_by, start, tint, mesh_buf);
+        }
     }
 
-    for (tileset, mesh) in meshes.into_iter() {
-        models.push(ChunkModel {
-            mesh: mesh.into(),
-            material: tileset,
-        });
+    for (_, mesh) in meshes.iter_mut() {
+        if !mesh.colors.is_empty() {
+            mesh.colors.resize(mesh.positions.len(), [1.0; 4]);
+        }
     }
 
-    models
+    meshes
+        .into_iter()
+        .map(|(material, mesh)| ChunkModel {
+            mesh: mesh.into(),
+            material,
+        })
+        .collect()
 }
 
 /// A model for a chunk.
@@ -293,3 +448,210 @@ pub struct ChunkModel {
 /// component will be reused when remeshing a chunk.
 #[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Component)]
 pub struct ChunkModelPart;
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::blocks::shape::BlockFace;
+    use crate::blocks::systems::build_cube_mesh;
+
+    /// Creates a world with a solid cube block and a single chunk filled with
+    /// that block.
+    fn chunk_world() -> (World, Entity) {
+        let mut world = World::new();
+        world.init_resource::<Assets<Mesh>>();
+        world.init_resource::<VoxelWorld>();
+        world.init_resource::<BiomeMap>();
+
+        let face = BlockFace::default();
+        let mesh = build_cube_mesh(&face, &face, &face, &face, &face, &face);
+        let solid = world
+            .spawn((
+                Block::default(),
+                BlockModel::Primitive {
+                    material: Handle::default(),
+                    bounds: mesh.get_bounds(),
+                    mesh: Box::new(mesh),
+                    variants: Vec::new(),
+                },
+                BlockShape::Cube {
+                    tileset: String::new(),
+                    top: face.clone(),
+                    bottom: face.clone(),
+                    north: face.clone(),
+                    south: face.clone(),
+                    east: face.clone(),
+                    west: face,
+                    transparent: false,
+                    non_occluding: false,
+                    variants: Vec::new(),
+                    randomize_rotation: false,
+                },
+            ))
+            .id();
+
+        let chunk = world
+            .spawn((
+                Position {
+                    block: BlockPos::new(0, 0, 0),
+                },
+                ChunkData::fill(solid),
+                NeedsRemesh,
+            ))
+            .id();
+
+        (world, chunk)
+    }
+
+    #[test]
+    fn repeated_remesh_reuses_meshes() {
+        let (mut world, chunk) = chunk_world();
+        world.run_system_once(remesh);
+
+        let mesh_count = world.resource::<Assets<Mesh>>().len();
+        assert!(mesh_count > 0);
+
+        for _ in 0 .. 10 {
+            world.entity_mut(chunk).insert(NeedsRemesh);
+            world.run_system_once(remesh);
+        }
+
+        assert_eq!(world.resource::<Assets<Mesh>>().len(), mesh_count);
+
+        let parts = world
+            .query_filtered::<(), With<ChunkModelPart>>()
+            .iter(&world)
+            .count();
+        assert_eq!(parts, mesh_count);
+    }
+
+    #[test]
+    fn custom_blocks_are_baked_into_chunk_meshes() {
+        let mut world = World::new();
+        world.init_resource::<Assets<Mesh>>();
+        world.init_resource::<VoxelWorld>();
+        world.init_resource::<BiomeMap>();
+
+        let air = world
+            .spawn((Block::default(), BlockModel::None, BlockShape::None))
+            .id();
+
+        let face = BlockFace::default();
+        let mesh = build_cube_mesh(&face, &face, &face, &face, &face, &face);
+        let material = Handle::weak_from_u128(0x5167_4e);
+        let sign = world
+            .spawn((
+                Block::default(),
+                BlockModel::Custom {
+                    asset: Handle::default(),
+                    material: material.clone(),
+                    bounds: mesh.get_bounds(),
+                    mesh: Box::new(mesh),
+                },
+                BlockShape::Custom {
+                    asset: "sign".to_string(),
+                    offset: [0.0; 3],
+                    smooth_normals: false,
+                },
+            ))
+            .id();
+
+        let mut data = ChunkData::fill(air);
+        data.set(BlockPos::new(3, 1, 4), sign);
+        world.spawn((
+            Position {
+                block: BlockPos::new(0, 0, 0),
+            },
+            data,
+            NeedsRemesh,
+        ));
+
+        world.run_system_once(remesh);
+
+        let materials: Vec<_> = world
+            .query_filtered::<&Handle<StandardMaterial>, With<ChunkModelPart>>()
+            .iter(&world)
+            .cloned()
+            .collect();
+        assert_eq!(materials, vec![material]);
+    }
+
+    #[test]
+    fn build_models_is_deterministic() {
+        let mut world = World::new();
+        let face = BlockFace::default();
+        let shape = BlockShape::Cube {
+            tileset: String::new(),
+            top: face.clone(),
+            bottom: face.clone(),
+            north: face.clone(),
+            south: face.clone(),
+            east: face.clone(),
+            west: face.clone(),
+            transparent: false,
+            non_occluding: false,
+            variants: Vec::new(),
+            randomize_rotation: false,
+        };
+
+        let air = world
+            .spawn((Block::default(), BlockModel::None, BlockShape::None))
+            .id();
+
+        let mut materials = Vec::new();
+        let mut blocks = Vec::new();
+        for index in 0 .. 3 {
+            let mesh = build_cube_mesh(&face, &face, &face, &face, &face, &face);
+            let material = Handle::weak_from_u128(0x5167_4e00 + index);
+            let block = world
+                .spawn((
+                    Block::default(),
+                    BlockModel::Primitive {
+                        material: material.clone(),
+                        bounds: mesh.get_bounds(),
+                        mesh: Box::new(mesh),
+                        variants: Vec::new(),
+                    },
+                    shape.clone(),
+                ))
+                .id();
+
+            materials.push(material);
+            blocks.push(block);
+        }
+
+        let mut data = ChunkData::fill(air);
+        for pos in ChunkIterator::default() {
+            if (pos.x + pos.y * 3 + pos.z * 7) % 5 != 0 {
+                let index = (pos.x + pos.y + pos.z) as usize % blocks.len();
+                data.set(pos, blocks[index]);
+            }
+        }
+
+        let build = move |models: Query<&BlockModel>, shapes: Query<&BlockShape>| {
+            build_models(
+                &ChunkView::single(&data),
+                BlockPos::new(0, 0, 0),
+                &LightView::default(),
+                &BiomeMap::default(),
+                &models,
+                &shapes,
+            )
+            .into_iter()
+            .map(|model| {
+                let indices = model.mesh.get_index_buffer_bytes().unwrap().to_vec();
+                (model.material, model.mesh.get_vertex_buffer_data(), indices)
+            })
+            .collect::<Vec<_>>()
+        };
+
+        let first = world.run_system_once(build.clone());
+        let second = world.run_system_once(build);
+
+        assert_eq!(first.len(), materials.len());
+        assert!(first.iter().all(|(_, vertices, _)| !vertices.is_empty()));
+        assert_eq!(first, second);
+    }
+}
@@ -0,0 +1,172 @@
+//! This module implements build rules, which restrict where and which blocks
+//! can be placed and removed by the player.
+//!
+//! Since scripts run on a separate thread, they cannot be consulted for every
+//! edit. Instead, scripts declare the rules up front, and the rules are checked
+//! locally whenever the player edits the world.
+//!
+//! Projects may also limit the vertical range in which blocks can be placed
+//! through the project settings. Chunks outside of this range are never shown,
+//! although they are still generated and kept in the world.
+
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+
+use crate::math::{BlockPos, CHUNK_SIZE, ChunkPos};
+use crate::settings::ProjectSettings;
+use crate::{BUILD_HEIGHT_MAX_KEY, BUILD_HEIGHT_MIN_KEY};
+
+/// A region of the world in which blocks can be edited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BuildRegion {
+    /// The minimum corner of the region, inclusive.
+    min: BlockPos,
+
+    /// The maximum corner of the region, inclusive.
+    max: BlockPos,
+}
+
+impl BuildRegion {
+    /// Creates a new build region between the two given corners, inclusive.
+    pub fn new(a: BlockPos, b: BlockPos) -> Self {
+        Self {
+            min: BlockPos::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z)),
+            max: BlockPos::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z)),
+        }
+    }
+
+    /// Returns true if the given position is within this region.
+    pub fn contains(&self, pos: BlockPos) -> bool {
+        pos.is_in_bounds(self.min, self.max)
+    }
+}
+
+/// This resource stores the build rules that are enforced when the player
+/// places or removes blocks. By default, all edits are allowed.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Resource)]
+pub struct BuildRules {
+    /// The regions in which blocks can be edited. If empty, blocks can be
+    /// edited anywhere.
+    pub regions: Vec<BuildRegion>,
+
+    /// The blocks that cannot be placed.
+    pub banned: HashSet<Entity>,
+
+    /// The lowest y coordinate at which blocks can be placed, or `None` if
+    /// unbounded. This limit is loaded from the project settings.
+    pub min_height: Option<i32>,
+
+    /// The highest y coordinate at which blocks can be placed, or `None` if
+    /// unbounded. This limit is loaded from the project settings.
+    pub max_height: Option<i32>,
+}
+
+impl BuildRules {
+    /// Returns true if the given block can be placed at the given position.
+    pub fn can_place(&self, pos: BlockPos, block: Entity) -> bool {
+        self.is_in_height(pos.y) && self.can_remove(pos) && !self.banned.contains(&block)
+    }
+
+    /// Returns true if the given y coordinate is within the build height
+    /// limits.
+    pub fn is_in_height(&self, y: i32) -> bool {
+        !self.min_height.is_some_and(|min| y < min) && !self.max_height.is_some_and(|max| y > max)
+    }
+
+    /// Returns true if any block of the chunk at the given position is within
+    /// the build height limits.
+    pub fn is_chunk_in_height(&self, pos: ChunkPos) -> bool {
+        let min_y = BlockPos::from(pos).y;
+        let max_y = min_y + CHUNK_SIZE as i32 - 1;
+        !self.min_height.is_some_and(|min| max_y < min)
+            && !self.max_height.is_some_and(|max| min_y > max)
+    }
+
+    /// Returns true if the block at the given position can be removed.
+    pub fn can_remove(&self, pos: BlockPos) -> bool {
+        self.regions.is_empty() || self.regions.iter().any(|region| region.contains(pos))
+    }
+}
+
+/// This system resets the build rules when a project is unloaded.
+pub(super) fn clear_build_rules(mut rules: ResMut<BuildRules>) {
+    *rules = BuildRules::default();
+}
+
+/// This system loads the build height limits from the project settings.
+pub(super) fn load_build_height(
+    project_settings: Res<ProjectSettings>,
+    mut rules: ResMut<BuildRules>,
+) {
+    rules.min_height = load_height_limit(&project_settings, BUILD_HEIGHT_MIN_KEY);
+    rules.max_height = load_height_limit(&project_settings, BUILD_HEIGHT_MAX_KEY);
+
+    if let (Some(min), Some(max)) = (rules.min_height, rules.max_height) {
+        if min > max {
+            warn!(
+                "Build height minimum {} is above maximum {}; Ignoring limits.",
+                min, max
+            );
+            rules.min_height = None;
+            rules.max_height = None;
+        }
+    }
+}
+
+/// Reads a single build height limit from the project settings. Returns `None`
+/// if the limit is missing or invalid.
+fn load_height_limit(project_settings: &ProjectSettings, key: &str) -> Option<i32> {
+    match project_settings.get_parsed(key) {
+        Ok(limit) => limit,
+        Err(err) => {
+            error!("Failed to read build height limit: {}", err);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rules_limit_regions_and_blocks() {
+        let stone = Entity::from_raw(1);
+        let tnt = Entity::from_raw(2);
+
+        let mut rules = BuildRules::default();
+        assert!(rules.can_place(BlockPos::new(100, -5, 3), tnt));
+
+        rules.regions.push(BuildRegion::new(
+            BlockPos::new(10, 0, 10),
+            BlockPos::new(0, 4, 0),
+        ));
+        rules.banned.insert(tnt);
+
+        assert!(rules.can_place(BlockPos::new(5, 4, 0), stone));
+        assert!(!rules.can_place(BlockPos::new(5, 4, 0), tnt));
+        assert!(!rules.can_place(BlockPos::new(5, 5, 0), stone));
+        assert!(rules.can_remove(BlockPos::new(10, 0, 10)));
+        assert!(!rules.can_remove(BlockPos::new(11, 0, 10)));
+    }
+
+    #[test]
+    fn height_limits_are_inclusive() {
+        let stone = Entity::from_raw(1);
+        let rules = BuildRules {
+            min_height: Some(-16),
+            max_height: Some(31),
+            ..default()
+        };
+
+        assert!(rules.can_place(BlockPos::new(0, -16, 0), stone));
+        assert!(rules.can_place(BlockPos::new(0, 31, 0), stone));
+        assert!(!rules.can_place(BlockPos::new(0, 32, 0), stone));
+        assert!(!rules.can_place(BlockPos::new(0, -17, 0), stone));
+
+        assert!(rules.is_chunk_in_height(ChunkPos::new(0, -1, 0)));
+        assert!(rules.is_chunk_in_height(ChunkPos::new(0, 1, 0)));
+        assert!(!rules.is_chunk_in_height(ChunkPos::new(0, 2, 0)));
+        assert!(!rules.is_chunk_in_height(ChunkPos::new(0, -2, 0)));
+    }
+}
@@ -0,0 +1,75 @@
+//! This module implements the world seed, which drives all random number
+//! generation for world generation and scripts.
+
+use bevy::prelude::*;
+
+use crate::WORLD_SEED_KEY;
+use crate::settings::ProjectSettings;
+use crate::utilities::rng::SeededRng;
+
+/// The random number stream used by world generation on the main thread.
+pub const WORLD_RNG_STREAM: u64 = 0;
+
+/// The random number stream used by the script engine thread.
+pub const SCRIPT_RNG_STREAM: u64 = 1;
+
+/// The random number stream used to pick the biomes of the
+/// [`BiomeMap`](super::biome::BiomeMap).
+pub const BIOME_RNG_STREAM: u64 = 2;
+
+/// This resource stores the seed of the world. The seed is stored in the
+/// project settings, and a new random seed is created and saved if the project
+/// does not have one yet.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Resource)]
+pub struct WorldSeed(pub u64);
+
+/// This resource is the random number generator used for world generation.
+///
+/// Scripts use a separate generator with the same seed, so that randomness
+/// requested by scripts does not change the values drawn by world generation.
+#[derive(Debug, Clone, Resource)]
+pub struct WorldRng(pub SeededRng);
+
+impl Default for WorldRng {
+    fn default() -> Self {
+        Self(SeededRng::new(0, WORLD_RNG_STREAM))
+    }
+}
+
+/// This system loads the world seed from the project settings, and resets the
+/// world random number generator to match it.
+pub(super) fn load_world_seed(
+    project_settings: Res<ProjectSettings>,
+    mut seed: ResMut<WorldSeed>,
+    mut rng: ResMut<WorldRng>,
+) {
+    let stored = match project_settings.get(WORLD_SEED_KEY) {
+        Ok(Some(value)) => value.parse().ok().or_else(|| {
+            warn!("Invalid world seed in project settings: {}", value);
+            None
+        }),
+        Ok(None) => None,
+        Err(err) => {
+            error!("Failed to read world seed: {}", err);
+            None
+        }
+    };
+
+    let value = match stored {
+        Some(value) => value,
+        None => {
+            let value = uuid::Uuid::new_v4().as_u64_pair().0;
+            info!("Created new world seed: {}", value);
+
+            if let Err(err) = project_settings.set(WORLD_SEED_KEY, Some(&value.to_string())) {
+                error!("Failed to save world seed: {}", err);
+            }
+
+            value
+        }
+    };
+
+    seed.0 = value;
+    rng.0 = SeededRng::new(value, WORLD_RNG_STREAM);
+    debug!("Loaded world seed: {}", value);
+}
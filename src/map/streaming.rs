@@ -0,0 +1,160 @@
+//! This module implements the [`WorldStreaming`] resource, which controls how
+//! far around the camera the world is visible.
+
+use bevy::pbr::{FogFalloff, FogSettings};
+use bevy::prelude::*;
+
+use super::chunk::ChunkData;
+use super::rules::BuildRules;
+use crate::camera::{CameraTarget, MainCamera};
+use crate::math::{BlockPos, CHUNK_SIZE, ChunkPos, Position};
+use crate::settings::ProjectSettings;
+use crate::{RENDER_DISTANCE_DEFAULT, RENDER_DISTANCE_KEY};
+
+/// The maximum render distance, in chunks.
+pub const MAX_RENDER_DISTANCE: u32 = 32;
+
+/// This resource controls the streaming of chunks around the camera.
+///
+/// A single render distance value drives both the radius in which chunks are
+/// loaded and unloaded, and the distance of the fog that hides the edge of the
+/// loaded area. Chunks are measured in a cube around the chunk that contains
+/// the camera target, so a render distance of 0 keeps only that chunk.
+///
+/// As chunks are not yet persisted outside of memory, unloaded chunks are
+/// hidden rather than despawned, and are shown again once they come back into
+/// range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Resource)]
+pub struct WorldStreaming {
+    /// The render distance, in chunks.
+    render_distance: u32,
+}
+
+impl Default for WorldStreaming {
+    fn default() -> Self {
+        Self {
+            render_distance: RENDER_DISTANCE_DEFAULT,
+        }
+    }
+}
+
+impl WorldStreaming {
+    /// Gets the render distance, in chunks.
+    pub fn render_distance(&self) -> u32 {
+        self.render_distance
+    }
+
+    /// Sets the render distance, in chunks. The value is clamped to
+    /// [`MAX_RENDER_DISTANCE`].
+    pub fn set_render_distance(&mut self, distance: u32) {
+        self.render_distance = distance.min(MAX_RENDER_DISTANCE);
+    }
+
+    /// Returns true if the chunk at the given position is within the render
+    /// distance of the given center chunk.
+    pub fn is_in_range(&self, center: ChunkPos, pos: ChunkPos) -> bool {
+        let distance = (pos.x - center.x)
+            .abs()
+            .max((pos.y - center.y).abs())
+            .max((pos.z - center.z).abs());
+        distance as u32 <= self.render_distance
+    }
+
+    /// Returns the distance at which the fog starts and ends, in world units.
+    /// The fog is fully opaque at the edge of the furthest loaded chunk.
+    pub fn fog_distance(&self) -> (f32, f32) {
+        let end = (self.render_distance + 1) as f32 * CHUNK_SIZE as f32;
+        let start = end - CHUNK_SIZE as f32;
+        (start, end)
+    }
+}
+
+/// This system loads the render distance from the project settings.
+pub(super) fn load_render_distance(
+    project_settings: Res<ProjectSettings>,
+    mut streaming: ResMut<WorldStreaming>,
+) {
+    let distance = match project_settings.get(RENDER_DISTANCE_KEY) {
+        Ok(Some(value)) => value.parse().unwrap_or_else(|_| {
+            warn!("Invalid render distance in project settings: {}", value);
+            RENDER_DISTANCE_DEFAULT
+        }),
+        Ok(None) => RENDER_DISTANCE_DEFAULT,
+        Err(err) => {
+            error!("Failed to read render distance: {}", err);
+            RENDER_DISTANCE_DEFAULT
+        }
+    };
+
+    streaming.set_render_distance(distance);
+}
+
+/// This system saves the render distance to the project settings whenever it
+/// is changed.
+pub(super) fn save_render_distance(
+    project_settings: Res<ProjectSettings>,
+    streaming: Res<WorldStreaming>,
+) {
+    let distance = streaming.render_distance().to_string();
+    if let Err(err) = project_settings.set(RENDER_DISTANCE_KEY, Some(&distance)) {
+        error!("Failed to save render distance: {}", err);
+    }
+}
+
+/// This system shows and hides chunks based on their distance to the camera
+/// target. Chunks entirely outside of the build height limits are always
+/// hidden. This only affects visibility; such chunks are still generated and
+/// kept in the world, so their blocks remain available to scripts.
+pub(super) fn update_chunk_visibility(
+    streaming: Res<WorldStreaming>,
+    rules: Res<BuildRules>,
+    camera_target: Query<&Transform, With<CameraTarget>>,
+    mut chunks: Query<(&Position, &mut Visibility), With<ChunkData>>,
+) {
+    let Ok(target) = camera_target.get_single() else {
+        return;
+    };
+
+    let center: ChunkPos = BlockPos::from_vec3(target.translation).into();
+
+    for (pos, mut visibility) in chunks.iter_mut() {
+        let chunk_pos = pos.block.into();
+        let new_visibility =
+            if streaming.is_in_range(center, chunk_pos) && rules.is_chunk_in_height(chunk_pos) {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            };
+
+        if *visibility != new_visibility {
+            *visibility = new_visibility;
+        }
+    }
+}
+
+/// This system updates the fog of the main camera to match the render
+/// distance.
+pub(super) fn update_fog(
+    streaming: Res<WorldStreaming>,
+    clear_color: Res<ClearColor>,
+    mut cameras: Query<(Entity, Option<&mut FogSettings>), With<MainCamera>>,
+    mut commands: Commands,
+) {
+    let (start, end) = streaming.fog_distance();
+    let falloff = FogFalloff::Linear { start, end };
+
+    for (camera_id, fog) in cameras.iter_mut() {
+        match fog {
+            Some(mut fog) => fog.falloff = falloff.clone(),
+            None => {
+                commands.entity(camera_id).insert(FogSettings {
+                    color: clear_color.0,
+                    falloff: falloff.clone(),
+                    ..default()
+                });
+            }
+        }
+    }
+
+    debug!("Updated render distance: {}", streaming.render_distance());
+}
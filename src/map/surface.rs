@@ -0,0 +1,266 @@
+//! This module implements the "fill visible surface" operation, which
+//! automatically textures the exposed surface of a region of cube blocks.
+//!
+//! Since face textures are defined per block type, the surface is textured by
+//! replacing each exposed cube block with one of the provided surface block
+//! types, based on which of its faces are visible.
+
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use super::chunk::ChunkData;
+use super::remesh::NeedsRemesh;
+use super::world::VoxelWorld;
+use crate::blocks::occlusion::Occludes;
+use crate::blocks::shape::BlockShape;
+use crate::math::{BlockPos, FaceDirection};
+
+/// The maximum number of blocks within the region of a single
+/// [`FillVisibleSurface`] request. Larger requests are rejected, since the
+/// region is scanned within a single frame.
+pub const MAX_SURFACE_FILL_BLOCKS: i64 = 128 * 128 * 128;
+
+/// An event that requests the visible surface of all cube blocks within a
+/// region to be automatically textured.
+///
+/// For each cube block within the region, the block is replaced with:
+/// - `top`, if the top face of the block is exposed.
+/// - `side`, if any side face of the block is exposed.
+/// - `bottom`, if only the bottom face of the block is exposed.
+///
+/// Blocks that are fully buried are left untouched, as are all non-cube
+/// blocks. The provided surface blocks are expected to be cube blocks so that
+/// the occlusion of the region remains unchanged. Regions containing more than
+/// [`MAX_SURFACE_FILL_BLOCKS`] blocks are rejected.
+#[derive(Debug, Clone, Copy, Event)]
+pub struct FillVisibleSurface {
+    /// The minimum corner of the region, inclusive.
+    pub min: BlockPos,
+
+    /// The maximum corner of the region, inclusive.
+    pub max: BlockPos,
+
+    /// The block to use for blocks with an exposed top face.
+    pub top: Entity,
+
+    /// The block to use for blocks with an exposed side face.
+    pub side: Entity,
+
+    /// The block to use for blocks with only an exposed bottom face.
+    pub bottom: Entity,
+}
+
+/// This system handles [`FillVisibleSurface`] events, replacing the exposed
+/// cube blocks within the requested region and marking all modified chunks for
+/// remeshing.
+pub(super) fn fill_visible_surface(
+    mut events: EventReader<FillVisibleSurface>,
+    world: Res<VoxelWorld>,
+    shapes: Query<&BlockShape>,
+    mut chunks: Query<&mut ChunkData>,
+    mut commands: Commands,
+) {
+    for ev in events.read() {
+        let min = BlockPos::new(
+            ev.min.x.min(ev.max.x),
+            ev.min.y.min(ev.max.y),
+            ev.min.z.min(ev.max.z),
+        );
+        let max = BlockPos::new(
+            ev.min.x.max(ev.max.x),
+            ev.min.y.max(ev.max.y),
+            ev.min.z.max(ev.max.z),
+        );
+
+        let volume = [(min.x, max.x), (min.y, max.y), (min.z, max.z)]
+            .into_iter()
+            .map(|(min, max)| max as i64 - min as i64 + 1)
+            .fold(1i64, i64::saturating_mul);
+        if volume > MAX_SURFACE_FILL_BLOCKS {
+            error!(
+                "Cannot fill visible surface from {} to {}; The region contains {} blocks, but at most {} are allowed.",
+                min, max, volume, MAX_SURFACE_FILL_BLOCKS
+            );
+            continue;
+        }
+
+        let get_block = |pos: BlockPos| world.get_block(pos, &chunks);
+
+        let is_exposed = |pos: BlockPos, face: FaceDirection| -> bool {
+            let occludes = get_block(pos.shift(face, 1))
+                .and_then(|block| shapes.get(block).ok())
+                .map(|shape| shape.occlusion())
+                .unwrap_or(Occludes::empty());
+            !occludes.contains(face.opposite().into())
+        };
+
+        let mut changes: HashMap<Entity, Vec<(BlockPos, Entity)>> = HashMap::default();
+        for z in min.z ..= max.z {
+            for y in min.y ..= max.y {
+                for x in min.x ..= max.x {
+                    let pos = BlockPos::new(x, y, z);
+                    let Some(block) = get_block(pos) else {
+                        continue;
+                    };
+
+                    if !matches!(shapes.get(block), Ok(BlockShape::Cube { .. })) {
+                        continue;
+                    }
+
+                    let replacement = if is_exposed(pos, FaceDirection::Up) {
+                        ev.top
+                    } else if [
+                        FaceDirection::North,
+                        FaceDirection::South,
+                        FaceDirection::East,
+                        FaceDirection::West,
+                    ]
+                    .into_iter()
+                    .any(|face| is_exposed(pos, face))
+                    {
+                        ev.side
+                    } else if is_exposed(pos, FaceDirection::Down) {
+                        ev.bottom
+                    } else {
+                        continue;
+                    };
+
+                    let chunk_id = world.get_chunk(pos.into()).unwrap();
+                    changes
+                        .entry(chunk_id)
+                        .or_default()
+                        .push((pos, replacement));
+                }
+            }
+        }
+
+        let mut total = 0;
+        for (chunk_id, blocks) in changes {
+            let Ok(mut chunk) = chunks.get_mut(chunk_id) else {
+                error!("Failed to get chunk data for chunk: {}", chunk_id);
+                continue;
+            };
+
+            let mut dirty = false;
+            for (pos, block) in blocks {
+                if chunk.set(pos, block) {
+                    dirty = true;
+                    total += 1;
+                }
+            }
+
+            if dirty {
+                chunk.try_convert_to_single();
+                commands.entity(chunk_id).insert(NeedsRemesh);
+            }
+        }
+
+        info!(
+            "Filled visible surface from {} to {}; {} blocks updated.",
+            min, max, total
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::blocks::shape::BlockFace;
+    use crate::map::world::VoxelWorldCommands;
+    use crate::math::ChunkPos;
+
+    /// Spawns a solid cube block into the world.
+    fn spawn_cube(world: &mut World) -> Entity {
+        let face = BlockFace::default();
+        world
+            .spawn(BlockShape::Cube {
+                tileset: String::new(),
+                top: face.clone(),
+                bottom: face.clone(),
+                north: face.clone(),
+                south: face.clone(),
+                east: face.clone(),
+                west: face,
+                transparent: false,
+                non_occluding: false,
+                variants: Vec::new(),
+                randomize_rotation: false,
+            })
+            .id()
+    }
+
+    /// Gets the block at the given position within the world.
+    fn block_at(world: &World, pos: BlockPos) -> Entity {
+        let chunk_id = world
+            .resource::<VoxelWorld>()
+            .get_chunk(pos.into())
+            .unwrap();
+        world.get::<ChunkData>(chunk_id).unwrap().get(pos)
+    }
+
+    #[test]
+    fn hill_surface_is_textured() {
+        let mut world = World::new();
+        world.init_resource::<VoxelWorld>();
+        world.init_resource::<Events<FillVisibleSurface>>();
+
+        let air = world.spawn(BlockShape::None).id();
+        let stone = spawn_cube(&mut world);
+        let grass = spawn_cube(&mut world);
+        let dirt = spawn_cube(&mut world);
+        let rock = spawn_cube(&mut world);
+
+        // A flat ground with a small hill on top, which is two blocks high
+        // with a single block peak.
+        let mut data = ChunkData::fill(air);
+        for x in 0 .. 16 {
+            for z in 0 .. 16 {
+                for y in 0 ..= 3 {
+                    data.set(BlockPos::new(x, y, z), stone);
+                }
+
+                if (6 ..= 8).contains(&x) && (6 ..= 8).contains(&z) {
+                    data.set(BlockPos::new(x, 4, z), stone);
+                    data.set(BlockPos::new(x, 5, z), stone);
+                }
+            }
+        }
+        data.set(BlockPos::new(7, 6, 7), stone);
+
+        world.run_system_once(move |mut commands: Commands| {
+            commands.spawn_chunk(ChunkPos::new(0, 0, 0), data.clone());
+        });
+
+        world.send_event(FillVisibleSurface {
+            min: BlockPos::new(-100_000, -100_000, -100_000),
+            max: BlockPos::new(100_000, 100_000, 100_000),
+            top: grass,
+            side: dirt,
+            bottom: rock,
+        });
+        world.run_system_once(fill_visible_surface);
+        assert_eq!(block_at(&world, BlockPos::new(7, 6, 7)), stone);
+
+        world.send_event(FillVisibleSurface {
+            min: BlockPos::new(10, 0, 10),
+            max: BlockPos::new(4, 6, 4),
+            top: grass,
+            side: dirt,
+            bottom: rock,
+        });
+        world.run_system_once(fill_visible_surface);
+
+        assert_eq!(block_at(&world, BlockPos::new(7, 6, 7)), grass);
+        assert_eq!(block_at(&world, BlockPos::new(6, 5, 7)), grass);
+        assert_eq!(block_at(&world, BlockPos::new(5, 3, 7)), grass);
+        assert_eq!(block_at(&world, BlockPos::new(6, 4, 7)), dirt);
+        assert_eq!(block_at(&world, BlockPos::new(7, 0, 7)), rock);
+
+        assert_eq!(block_at(&world, BlockPos::new(7, 5, 7)), stone);
+        assert_eq!(block_at(&world, BlockPos::new(7, 4, 7)), stone);
+        assert_eq!(block_at(&world, BlockPos::new(7, 2, 7)), stone);
+        assert_eq!(block_at(&world, BlockPos::new(3, 3, 7)), stone);
+    }
+}
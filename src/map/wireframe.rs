@@ -0,0 +1,82 @@
+//! This module implements the chunk wireframe debug view, which renders all
+//! chunk meshes as wireframes so that the result of the mesher, such as merged
+//! quads, can be inspected visually.
+//!
+//! Wireframe rendering requires the `POLYGON_MODE_LINE` GPU feature, so this
+//! plugin is only added in debug mode.
+
+use bevy::pbr::wireframe::{Wireframe, WireframeConfig, WireframePlugin};
+use bevy::prelude::*;
+
+use super::remesh::ChunkModelPart;
+use crate::ui::UiKeyboardFocus;
+#[cfg(feature = "editor")]
+use crate::ui::command_palette::EditorActionsAppExt;
+
+/// The plugin that adds the chunk wireframe debug view to the app.
+pub struct ChunkWireframePlugin;
+impl Plugin for ChunkWireframePlugin {
+    fn build(&self, app_: &mut App) {
+        if !app_.is_plugin_added::<WireframePlugin>() {
+            app_.add_plugins(WireframePlugin);
+        }
+
+        app_.insert_resource(WireframeConfig {
+            global: false,
+            ..default()
+        })
+        .init_resource::<ChunkWireframe>()
+        .add_systems(
+            Update,
+            (
+                toggle_chunk_wireframe.run_if(UiKeyboardFocus::is_free),
+                update_chunk_wireframes.after_ignore_deferred(toggle_chunk_wireframe),
+            ),
+        );
+
+        #[cfg(feature = "editor")]
+        app_.register_editor_action(
+            "Toggle Chunk Wireframe",
+            |mut wireframe: ResMut<ChunkWireframe>| {
+                wireframe.enabled = !wireframe.enabled;
+            },
+        );
+    }
+}
+
+/// This resource controls whether chunks are rendered as wireframes.
+#[derive(Debug, Default, Resource)]
+pub struct ChunkWireframe {
+    /// Whether chunks are rendered as wireframes.
+    pub enabled: bool,
+}
+
+/// This system toggles the chunk wireframe view when F6 is pressed.
+fn toggle_chunk_wireframe(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut wireframe: ResMut<ChunkWireframe>,
+) {
+    if keyboard_input.just_pressed(KeyCode::F6) {
+        wireframe.enabled = !wireframe.enabled;
+        info!("Chunk wireframe enabled: {}", wireframe.enabled);
+    }
+}
+
+/// This system adds or removes the [`Wireframe`] component on all chunk model
+/// parts, including parts that are spawned while the view is enabled.
+fn update_chunk_wireframes(
+    wireframe: Res<ChunkWireframe>,
+    solid_parts: Query<Entity, (With<ChunkModelPart>, Without<Wireframe>)>,
+    wireframe_parts: Query<Entity, (With<ChunkModelPart>, With<Wireframe>)>,
+    mut commands: Commands,
+) {
+    if wireframe.enabled {
+        for entity in solid_parts.iter() {
+            commands.entity(entity).insert(Wireframe);
+        }
+    } else if wireframe.is_changed() {
+        for entity in wireframe_parts.iter() {
+            commands.entity(entity).remove::<Wireframe>();
+        }
+    }
+}
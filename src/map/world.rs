@@ -1,13 +1,16 @@
 //! This module implements the [`VoxelWorld`] component and associated logic.
 
+use bevy::ecs::query::{QueryData, QueryFilter};
 use bevy::prelude::*;
 use bevy::utils::HashMap;
 use bevy_mod_picking::PickableBundle;
 
 use super::chunk::ChunkData;
+use super::collision::ChunkCollision;
+use super::light::ChunkLight;
 use super::remesh::{NeedsRemesh, UniqueBlocks};
 use crate::map::ChunkCollider;
-use crate::math::{ChunkPos, Position, CHUNK_SIZE};
+use crate::math::{BlockPos, ChunkPos, FaceDirection, Position, CHUNK_SIZE};
 
 /// An infinite, 3D grid of voxels, represented by chunks, that make up a world.
 #[derive(Debug, Default, Resource)]
@@ -21,6 +24,43 @@ impl VoxelWorld {
     pub fn get_chunk(&self, pos: ChunkPos) -> Option<Entity> {
         self.chunks.get(&pos).copied()
     }
+
+    /// Gets the block at the given world position from the chunk that contains
+    /// it, or `None` if that chunk is not loaded.
+    pub fn get_block<'c, D, F>(&self, pos: BlockPos, chunks: &Query<D, F>) -> Option<Entity>
+    where
+        D: QueryData<ReadOnly = &'c ChunkData>,
+        F: QueryFilter,
+    {
+        let chunk_id = self.get_chunk(pos.into())?;
+        let chunk = chunks.get(chunk_id).ok()?;
+        Some(chunk.get(pos))
+    }
+
+    /// Iterates over the positions and entities of all chunks that are
+    /// currently loaded in the world, in no particular order.
+    pub fn iter_chunks(&self) -> impl Iterator<Item = (ChunkPos, Entity)> + '_ {
+        self.chunks.iter().map(|(pos, entity)| (*pos, *entity))
+    }
+
+    /// Gets the number of chunks that are currently loaded in the world.
+    pub fn chunk_count(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Gets the minimum and maximum chunk positions of all chunks that are
+    /// currently loaded in the world, or `None` if no chunks are loaded.
+    pub fn chunk_bounds(&self) -> Option<(ChunkPos, ChunkPos)> {
+        let mut positions = self.chunks.keys();
+        let first = *positions.next()?;
+
+        Some(positions.fold((first, first), |(min, max), pos| {
+            (
+                ChunkPos::new(min.x.min(pos.x), min.y.min(pos.y), min.z.min(pos.z)),
+                ChunkPos::new(max.x.max(pos.x), max.y.max(pos.y), max.z.max(pos.z)),
+            )
+        }))
+    }
 }
 
 /// Commands for spawning and despawning chunks within a voxel world.
@@ -30,6 +70,10 @@ pub trait VoxelWorldCommands {
     /// If the chunk already exists, the data within the chunk will be replaced
     /// by the newly provided data, and the provided bundle will be inserted
     /// into the existing chunk entity, overwriting any existing components.
+    ///
+    /// Newly spawned chunks also mark their loaded neighbors for remeshing,
+    /// since blocks along the shared border, such as connected textures, may
+    /// depend on the blocks of the new chunk.
     fn spawn_chunk(&mut self, pos: ChunkPos, data: ChunkData);
 
     /// Despawns the chunk at the given position within the world. This will
@@ -65,7 +109,9 @@ impl<'w, 's> VoxelWorldCommands for Commands<'w, 's> {
                 .spawn((
                     Position { block: pos.into() },
                     data,
+                    ChunkLight::default(),
                     UniqueBlocks::default(),
+                    ChunkCollision::default(),
                     NeedsRemesh,
                     ChunkCollider,
                     PickableBundle::default(),
@@ -83,6 +129,16 @@ impl<'w, 's> VoxelWorldCommands for Commands<'w, 's> {
             let mut world = app.get_resource_mut::<VoxelWorld>().unwrap();
             world.chunks.insert(pos, chunk_id);
 
+            let origin = BlockPos::from(pos);
+            let neighbors = FaceDirection::DIRECTIONS
+                .iter()
+                .filter_map(|dir| world.get_chunk(origin.shift(*dir, CHUNK_SIZE as u32).into()))
+                .collect::<Vec<_>>();
+
+            for neighbor_id in neighbors {
+                app.entity_mut(neighbor_id).insert(NeedsRemesh);
+            }
+
             info!("Spawned new chunk at {pos}");
         });
     }
@@ -117,3 +173,9 @@ impl<'w, 's> VoxelWorldCommands for Commands<'w, 's> {
         });
     }
 }
+
+/// This system despawns all chunks within the world when the project is
+/// unloaded.
+pub(super) fn unload_world(mut commands: Commands) {
+    commands.clear_chunks();
+}
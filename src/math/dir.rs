@@ -3,9 +3,10 @@
 use std::fmt;
 
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
 /// Represents an axis-aligned direction in 3D space.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum FaceDirection {
     /// The up direction.
     /// This direction points in the positive y-axis direction.
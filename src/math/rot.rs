@@ -18,3 +18,19 @@ pub enum FaceRotation {
     /// 270 degrees clockwise rotation.
     C270,
 }
+
+impl FaceRotation {
+    /// All rotations, in clockwise order.
+    pub const ALL: [FaceRotation; 4] = [
+        FaceRotation::C0,
+        FaceRotation::C90,
+        FaceRotation::C180,
+        FaceRotation::C270,
+    ];
+
+    /// Gets the rotation that results from applying the given rotation after
+    /// this rotation.
+    pub fn then(self, rotation: FaceRotation) -> Self {
+        Self::ALL[(self as usize + rotation as usize) % 4]
+    }
+}
@@ -0,0 +1,191 @@
+//! This module implements the project lifecycle, which allows the open project
+//! to be replaced by another project without restarting the engine.
+//!
+//! All state that is read from a project is loaded within the [`LoadProject`]
+//! schedule, and released within the [`UnloadProject`] schedule. Plugins that
+//! keep project state must add their systems to both schedules. When another
+//! project is opened, the following state is reset:
+//!
+//! - The [`ProjectSettings`] resource is replaced by the settings of the new
+//!   project.
+//! - The `project://` asset source is redirected to the asset folder of the new
+//!   project through the [`ProjectAssetFolders`] resource. Overlay folders are
+//!   kept.
+//! - The runtime script path of the [`LogicPluginSettings`] resource is moved
+//!   to the new asset folder.
+//! - All chunks of the [`VoxelWorld`](crate::map::world::VoxelWorld) are
+//!   despawned.
+//! - All block and tileset definitions are despawned and loaded again.
+//! - The world seed, render distance, render settings and editor theme are
+//!   loaded again from the new project settings.
+//! - The asset writer and file watcher of the `project://` asset source are
+//!   moved to the new asset folder.
+//! - The window title is updated to the name and version of the new project.
+//! - The recently placed blocks, as well as the block and tileset that are
+//!   being edited in the editor, are discarded.
+//! - The engine returns to the splash screen. Leaving the playable game state
+//!   shuts down the script engine, and removes the hotbar and editor HUD, which
+//!   are created again once the new project is entered.
+
+use std::path::PathBuf;
+
+use bevy::ecs::schedule::ScheduleLabel;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::assets::ProjectAssetFolders;
+use crate::gamestate::GameState;
+use crate::logic::LogicPluginSettings;
+use crate::settings::ProjectSettings;
+use crate::{
+    DEV_MODE,
+    PROJECT_NAME_DEFAULT,
+    PROJECT_NAME_KEY,
+    PROJECT_VERSION_DEFAULT,
+    PROJECT_VERSION_KEY,
+};
+
+/// The plugin responsible for loading and switching projects.
+pub struct ProjectPlugin;
+impl Plugin for ProjectPlugin {
+    fn build(&self, app_: &mut App) {
+        app_.add_event::<OpenProject>()
+            .init_resource::<DebugMode>()
+            .init_schedule(LoadProject)
+            .init_schedule(UnloadProject)
+            .add_systems(Startup, load_project)
+            .add_systems(LoadProject, update_window_title)
+            .add_systems(
+                Update,
+                (
+                    load_pending_project.run_if(resource_exists::<PendingProjectLoad>),
+                    open_project.after_ignore_deferred(load_pending_project),
+                ),
+            );
+    }
+}
+
+/// The schedule that loads all state of the current project. This schedule is
+/// run on startup, and again whenever another project is opened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ScheduleLabel)]
+pub struct LoadProject;
+
+/// The schedule that releases all state of the current project before another
+/// project is opened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ScheduleLabel)]
+pub struct UnloadProject;
+
+/// This event requests that the project in the given folder is opened,
+/// replacing the current project.
+#[derive(Debug, Clone, Event)]
+pub struct OpenProject {
+    /// The root folder of the project to open.
+    pub path: PathBuf,
+}
+
+/// This resource stores whether the engine was started in debug mode, which is
+/// shown in the window title.
+#[derive(Debug, Default, Clone, Copy, Resource)]
+pub struct DebugMode(pub bool);
+
+/// Formats the window title for the project with the given name and version.
+pub fn window_title(name: &str, version: &str, debug: bool) -> String {
+    match (DEV_MODE, debug) {
+        (true, true) => format!("Awgen Editor [{} - {}] (debug)", name, version),
+        (true, false) => format!("Awgen Editor [{} - {}]", name, version),
+        (false, true) => format!("{} - {} (debug)", name, version),
+        (false, false) => format!("{} - {}", name, version),
+    }
+}
+
+/// A marker resource indicating that a project has been opened, but not loaded
+/// yet. Loading is delayed by one frame, so that assets released by the
+/// previous project are unloaded before the new project loads assets with the
+/// same paths.
+#[derive(Debug, Default, Resource)]
+struct PendingProjectLoad;
+
+/// This system loads the project on startup.
+fn load_project(world: &mut World) {
+    world.run_schedule(LoadProject);
+}
+
+/// This system loads the project that was opened during the previous frame.
+fn load_pending_project(world: &mut World) {
+    world.remove_resource::<PendingProjectLoad>();
+    world.run_schedule(LoadProject);
+    info!("Loaded project.");
+}
+
+/// This system listens for [`OpenProject`] events and replaces the current
+/// project with the requested one. If the project settings of the new project
+/// cannot be opened, the current project is kept.
+fn open_project(world: &mut World) {
+    let Some(path) = world
+        .resource_mut::<Events<OpenProject>>()
+        .drain()
+        .last()
+        .map(|ev| ev.path)
+    else {
+        return;
+    };
+
+    let settings = match ProjectSettings::new(path.clone(), DEV_MODE) {
+        Ok(settings) => settings,
+        Err(err) => {
+            error!("Failed to open project at {}: {}", path.display(), err);
+            return;
+        }
+    };
+
+    info!("Opening project at: {}", path.display());
+    world.run_schedule(UnloadProject);
+
+    let asset_folder = settings.asset_folder();
+
+    match world.get_resource::<ProjectAssetFolders>() {
+        Some(folders) => folders.set_base(&asset_folder.to_string_lossy()),
+        None => warn!("Project asset folders are not available; Assets will not be switched."),
+    }
+
+    world
+        .resource_mut::<LogicPluginSettings>()
+        .runtime_script_path = asset_folder.join("scripts");
+
+    world.insert_resource(settings);
+    world.insert_resource(PendingProjectLoad);
+    world
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::Splash);
+
+    #[cfg(feature = "editor")]
+    world
+        .resource_mut::<NextState<crate::ui::EditorWindowState>>()
+        .set(crate::ui::EditorWindowState::MapEditor);
+}
+
+/// This system updates the title of the primary window to the name and version
+/// of the current project.
+fn update_window_title(
+    settings: Res<ProjectSettings>,
+    debug: Res<DebugMode>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    let read = |key: &str, default: &str| match settings.get(key) {
+        Ok(value) => value.unwrap_or_else(|| default.to_string()),
+        Err(err) => {
+            error!("Failed to read project settings: {}", err);
+            default.to_string()
+        }
+    };
+
+    let name = read(PROJECT_NAME_KEY, PROJECT_NAME_DEFAULT);
+    let version = read(PROJECT_VERSION_KEY, PROJECT_VERSION_DEFAULT);
+    let title = window_title(&name, &version, debug.0);
+
+    for mut window in windows.iter_mut() {
+        if window.title != title {
+            window.title = title.clone();
+        }
+    }
+}
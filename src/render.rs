@@ -0,0 +1,208 @@
+//! This module implements the render settings, such as anti-aliasing and the
+//! sky color, that are stored within the project settings.
+
+use bevy::core_pipeline::experimental::taa::{
+    TemporalAntiAliasBundle,
+    TemporalAntiAliasPlugin,
+    TemporalAntiAliasSettings,
+};
+use bevy::core_pipeline::fxaa::Fxaa;
+use bevy::prelude::*;
+use bevy::render::camera::TemporalJitter;
+use serde::{Deserialize, Serialize};
+
+use crate::camera::MainCamera;
+use crate::gamestate::GameState;
+use crate::project::LoadProject;
+use crate::settings::ProjectSettings;
+use crate::{ANTI_ALIASING_KEY, SKY_COLOR_KEY};
+
+/// The plugin responsible for applying the render settings.
+pub struct RenderSettingsPlugin;
+impl Plugin for RenderSettingsPlugin {
+    fn build(&self, app_: &mut App) {
+        app_.add_plugins(TemporalAntiAliasPlugin)
+            .init_resource::<RenderSettings>()
+            .add_systems(LoadProject, load_render_settings)
+            .add_systems(
+                Update,
+                (
+                    (save_render_settings, apply_anti_aliasing)
+                        .run_if(resource_changed::<RenderSettings>),
+                    apply_sky_color.run_if(
+                        resource_changed::<RenderSettings>.or_else(state_changed::<GameState>),
+                    ),
+                ),
+            );
+    }
+}
+
+/// The anti-aliasing method used to render the main camera.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AntiAliasing {
+    /// No anti-aliasing. This is the default, as it keeps nearest-sampled
+    /// pixel-art tilesets crisp.
+    #[default]
+    Off,
+
+    /// Multi-sample anti-aliasing with 2 samples.
+    Msaa2,
+
+    /// Multi-sample anti-aliasing with 4 samples.
+    Msaa4,
+
+    /// Multi-sample anti-aliasing with 8 samples.
+    Msaa8,
+
+    /// Fast approximate anti-aliasing.
+    Fxaa,
+
+    /// Temporal anti-aliasing.
+    Taa,
+}
+
+impl AntiAliasing {
+    /// Gets the MSAA sample count to use for this anti-aliasing method. Post
+    /// processing methods require MSAA to be disabled.
+    pub fn msaa(self) -> Msaa {
+        match self {
+            AntiAliasing::Msaa2 => Msaa::Sample2,
+            AntiAliasing::Msaa4 => Msaa::Sample4,
+            AntiAliasing::Msaa8 => Msaa::Sample8,
+            AntiAliasing::Off | AntiAliasing::Fxaa | AntiAliasing::Taa => Msaa::Off,
+        }
+    }
+}
+
+/// The background color of the world, which is shown wherever no block is
+/// rendered. The fog of the main camera fades into this color.
+///
+/// Colors are stored as `[r, g, b]` arrays.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SkyColor {
+    /// The sky color used within the editor.
+    pub editor: [u8; 3],
+
+    /// The sky color used while the game is being played. If not set, the
+    /// editor sky color is used.
+    pub runtime: Option<[u8; 3]>,
+}
+
+impl SkyColor {
+    /// Gets the sky color to use within the given game state.
+    pub fn color(&self, state: &GameState) -> Color {
+        let [r, g, b] = match state {
+            GameState::Runtime => self.runtime.unwrap_or(self.editor),
+            _ => self.editor,
+        };
+
+        Color::srgb_u8(r, g, b)
+    }
+}
+
+/// This resource contains the render settings of the project. Changes to this
+/// resource are applied to the main camera and saved to the project settings.
+///
+/// Changing the MSAA sample count at runtime causes all render pipelines to be
+/// specialized again, which may cause a short stutter.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Resource)]
+pub struct RenderSettings {
+    /// The anti-aliasing method used to render the main camera.
+    pub anti_aliasing: AntiAliasing,
+
+    /// The background color of the world.
+    pub sky_color: SkyColor,
+}
+
+/// This system loads the render settings from the project settings.
+fn load_render_settings(
+    project_settings: Res<ProjectSettings>,
+    mut render_settings: ResMut<RenderSettings>,
+) {
+    let anti_aliasing = match project_settings.get_json(ANTI_ALIASING_KEY) {
+        Ok(mode) => mode.unwrap_or_default(),
+        Err(err) => {
+            error!("Failed to read anti-aliasing mode: {}", err);
+            AntiAliasing::default()
+        }
+    };
+
+    let sky_color = match project_settings.get_json(SKY_COLOR_KEY) {
+        Ok(color) => color.unwrap_or_default(),
+        Err(err) => {
+            error!("Failed to read sky color: {}", err);
+            SkyColor::default()
+        }
+    };
+
+    render_settings.anti_aliasing = anti_aliasing;
+    render_settings.sky_color = sky_color;
+}
+
+/// This system saves the render settings to the project settings whenever they
+/// are changed.
+fn save_render_settings(
+    project_settings: Res<ProjectSettings>,
+    render_settings: Res<RenderSettings>,
+) {
+    let mode = Some(&render_settings.anti_aliasing);
+    if let Err(err) = project_settings.set_json(ANTI_ALIASING_KEY, mode) {
+        error!("Failed to save anti-aliasing mode: {}", err);
+    }
+
+    let color = Some(&render_settings.sky_color);
+    if let Err(err) = project_settings.set_json(SKY_COLOR_KEY, color) {
+        error!("Failed to save sky color: {}", err);
+    }
+}
+
+/// This system applies the anti-aliasing method to the main camera.
+///
+/// When temporal anti-aliasing is disabled, only its settings and jitter are
+/// removed. The depth and motion vector prepasses are left on the camera, as
+/// other features may rely on them.
+fn apply_anti_aliasing(
+    render_settings: Res<RenderSettings>,
+    mut msaa: ResMut<Msaa>,
+    cameras: Query<Entity, With<MainCamera>>,
+    mut commands: Commands,
+) {
+    let anti_aliasing = render_settings.anti_aliasing;
+    *msaa = anti_aliasing.msaa();
+
+    for camera_id in cameras.iter() {
+        let mut camera = commands.entity(camera_id);
+
+        if anti_aliasing == AntiAliasing::Fxaa {
+            camera.insert(Fxaa::default());
+        } else {
+            camera.remove::<Fxaa>();
+        }
+
+        if anti_aliasing == AntiAliasing::Taa {
+            camera.insert(TemporalAntiAliasBundle::default());
+        } else {
+            camera.remove::<(TemporalAntiAliasSettings, TemporalJitter)>();
+        }
+    }
+
+    info!("Anti-aliasing mode: {:?}", anti_aliasing);
+}
+
+/// This system applies the sky color of the current game state to the clear
+/// color, and to the fog of the main camera.
+fn apply_sky_color(
+    render_settings: Res<RenderSettings>,
+    state: Res<State<GameState>>,
+    mut clear_color: ResMut<ClearColor>,
+    mut fogs: Query<&mut FogSettings, With<MainCamera>>,
+) {
+    let color = render_settings.sky_color.color(state.get());
+    clear_color.0 = color;
+
+    for mut fog in fogs.iter_mut() {
+        fog.color = color;
+    }
+}
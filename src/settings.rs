@@ -1,9 +1,13 @@
 //! This module implements a handler for reading and writing project settings in
 //! an SQLite database.
 
-use std::path::PathBuf;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 use bevy::prelude::*;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
 use sqlite::{Connection, ConnectionThreadSafe, OpenFlags};
 use uuid::Uuid;
 
@@ -14,6 +18,12 @@ use crate::blocks::tileset::TilesetDefinition;
 pub struct ProjectSettings {
     /// The SQLite connection to the project settings file.
     connection: ConnectionThreadSafe,
+
+    /// The root folder of the project.
+    project_folder: PathBuf,
+
+    /// The settings file, or `None` if the settings are stored in memory.
+    settings_file: Option<PathBuf>,
 }
 
 impl ProjectSettings {
@@ -34,9 +44,39 @@ impl ProjectSettings {
             flags = flags.with_create();
         }
 
-        let connection = Connection::open_thread_safe_with_flags(settings_file, flags)
+        let connection = Connection::open_thread_safe_with_flags(&settings_file, flags)
             .map_err(ProjectSettingsError::Io)?;
 
+        Self::from_connection(connection, project_folder, Some(settings_file))
+    }
+
+    /// Creates a new instance of the project settings resource that is stored
+    /// in memory rather than in a file. All settings are lost once the
+    /// resource is dropped. This is mainly useful for tests.
+    ///
+    /// The project folder is left empty, so asset paths are relative to the
+    /// working directory.
+    pub fn in_memory() -> Result<Self, ProjectSettingsError> {
+        let connection =
+            Connection::open_thread_safe(":memory:").map_err(ProjectSettingsError::Io)?;
+        Self::from_connection(connection, PathBuf::new(), None)
+    }
+
+    /// Creates the settings tables within the given connection, if they do not
+    /// exist yet, and wraps it in a new project settings resource.
+    ///
+    /// The connection uses write-ahead logging, so that reads on other
+    /// connections, such as a [`ProjectSettingsReader`], never block writes on
+    /// this connection. Writes that still find the database busy wait for a
+    /// short while before they fail.
+    fn from_connection(
+        connection: ConnectionThreadSafe,
+        project_folder: PathBuf,
+        settings_file: Option<PathBuf>,
+    ) -> Result<Self, ProjectSettingsError> {
+        connection.execute("PRAGMA journal_mode = WAL")?;
+        connection.execute(format!("PRAGMA busy_timeout = {BUSY_TIMEOUT_MS}"))?;
+
         connection.execute(
             "CREATE TABLE IF NOT EXISTS settings (
                 key TEXT PRIMARY KEY,
@@ -51,23 +91,75 @@ impl ProjectSettings {
             )",
         )?;
 
-        Ok(Self { connection })
+        Ok(Self {
+            connection,
+            project_folder,
+            settings_file,
+        })
     }
 
-    /// Gets a property from the project settings. Returns `None` if the
-    /// property does not exist. An error is returned if an SQL error occurs.
-    pub fn get(&self, key: &str) -> Result<Option<String>, ProjectSettingsError> {
-        let mut statement = self.connection.prepare(
-            "SELECT value FROM settings
-             WHERE key = :key",
-        )?;
-        statement.bind((":key", key))?;
+    /// Opens a read-only handle to these project settings that can be moved to
+    /// another thread, such as the script engine thread. The handle uses its
+    /// own connection, so it only ever observes committed writes and never the
+    /// partial writes of an open [`transaction`](Self::transaction).
+    ///
+    /// An error is returned if the settings are stored in memory, since they
+    /// cannot be shared with another connection.
+    pub fn reader(&self) -> Result<ProjectSettingsReader, ProjectSettingsError> {
+        let Some(settings_file) = &self.settings_file else {
+            return Err(ProjectSettingsError::InMemory);
+        };
 
-        if statement.next()? != sqlite::State::Row {
-            return Ok(None);
+        let flags = OpenFlags::new().with_read_only();
+        let connection = Connection::open_thread_safe_with_flags(settings_file, flags)
+            .map_err(ProjectSettingsError::Io)?;
+
+        connection.execute(format!("PRAGMA busy_timeout = {BUSY_TIMEOUT_MS}"))?;
+
+        Ok(ProjectSettingsReader { connection })
+    }
+
+    /// Gets the root folder of the project.
+    pub fn project_folder(&self) -> &Path {
+        &self.project_folder
+    }
+
+    /// Gets the folder containing the project assets, which is the root of the
+    /// `project://` asset source.
+    pub fn asset_folder(&self) -> PathBuf {
+        self.project_folder.join("assets")
+    }
+
+    /// Runs the given function within a single SQLite transaction, so that all
+    /// writes it makes are applied together. This is both atomic and much
+    /// faster than committing each write on its own.
+    ///
+    /// If the function returns an error, all of its writes are rolled back and
+    /// the error is returned. Transactions cannot be nested.
+    pub fn transaction<T>(
+        &self,
+        func: impl FnOnce(&Self) -> Result<T, ProjectSettingsError>,
+    ) -> Result<T, ProjectSettingsError> {
+        self.connection.execute("BEGIN IMMEDIATE")?;
+
+        let result = func(self).and_then(|value| {
+            self.connection.execute("COMMIT")?;
+            Ok(value)
+        });
+
+        if result.is_err() {
+            if let Err(err) = self.connection.execute("ROLLBACK") {
+                error!("Failed to roll back project settings transaction: {err}");
+            }
         }
 
-        Ok(Some(statement.read::<String, _>("value")?))
+        result
+    }
+
+    /// Gets a property from the project settings. Returns `None` if the
+    /// property does not exist. An error is returned if an SQL error occurs.
+    pub fn get(&self, key: &str) -> Result<Option<String>, ProjectSettingsError> {
+        read_setting(&self.connection, key)
     }
 
     /// Sets a property in the project settings. If the property already exists,
@@ -96,25 +188,76 @@ impl ProjectSettings {
         Ok(())
     }
 
-    /// Gets a list of all tilesets in the project. An error is returned if an
-    /// SQL error occurs.
-    pub fn list_tilesets(&self) -> Result<Vec<TilesetDefinition>, ProjectSettingsError> {
-        let mut statement = self.connection.prepare(
-            "SELECT uuid, name
-             FROM tilesets",
-        )?;
+    /// Gets a property from the project settings and parses it from its string
+    /// form. Returns `None` if the property does not exist. An error is
+    /// returned if an SQL error occurs, or if the value cannot be parsed.
+    pub fn get_parsed<T>(&self, key: &str) -> Result<Option<T>, ProjectSettingsError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let Some(value) = self.get(key)? else {
+            return Ok(None);
+        };
+
+        match value.parse() {
+            Ok(value) => Ok(Some(value)),
+            Err(err) => Err(ProjectSettingsError::invalid_value(key, err)),
+        }
+    }
 
-        let mut tilesets = Vec::new();
-        while statement.next()? == sqlite::State::Row {
-            let uuid = statement.read::<String, _>("uuid")?;
-            let name = statement.read::<String, _>("name")?;
-            tilesets.push(TilesetDefinition {
-                uuid: Uuid::parse_str(&uuid).unwrap(),
-                name,
-            });
+    /// Gets a property from the project settings and deserializes it from
+    /// JSON. Returns `None` if the property does not exist. An error is
+    /// returned if an SQL error occurs, or if the value cannot be deserialized.
+    pub fn get_json<T>(&self, key: &str) -> Result<Option<T>, ProjectSettingsError>
+    where
+        T: DeserializeOwned,
+    {
+        let Some(value) = self.get(key)? else {
+            return Ok(None);
+        };
+
+        match serde_json::from_str(&value) {
+            Ok(value) => Ok(Some(value)),
+            Err(err) => Err(ProjectSettingsError::invalid_value(key, err)),
         }
+    }
 
-        Ok(tilesets)
+    /// Serializes a property to JSON and stores it in the project settings. If
+    /// the property already exists, it will be updated. An error is returned if
+    /// an SQL error occurs, or if the value cannot be serialized.
+    ///
+    /// If the value is set to `None`, the property will be deleted.
+    pub fn set_json<T>(&self, key: &str, value: Option<&T>) -> Result<(), ProjectSettingsError>
+    where
+        T: Serialize,
+    {
+        let value = value
+            .map(serde_json::to_string)
+            .transpose()
+            .map_err(|err| ProjectSettingsError::invalid_value(key, err))?;
+
+        self.set(key, value.as_deref())
+    }
+
+    /// Moves a property to a new key, replacing any property already stored
+    /// under that key. If the property does not exist, the new key is removed
+    /// as well. This is used to keep properties that are keyed by name, such
+    /// as the tags of a block, attached to it when it is renamed.
+    pub fn rename(&self, from: &str, to: &str) -> Result<(), ProjectSettingsError> {
+        if from == to {
+            return Ok(());
+        }
+
+        let value = self.get(from)?;
+        self.set(to, value.as_deref())?;
+        self.set(from, None)
+    }
+
+    /// Gets a list of all tilesets in the project. An error is returned if an
+    /// SQL error occurs.
+    pub fn list_tilesets(&self) -> Result<Vec<TilesetDefinition>, ProjectSettingsError> {
+        read_tilesets(&self.connection)
     }
 
     /// Updates a tileset properties, creating a new tileset if needed. An error
@@ -143,6 +286,81 @@ impl ProjectSettings {
     }
 }
 
+/// A read-only handle to the project settings, created by
+/// [`ProjectSettings::reader`]. Unlike the resource, this handle can be owned
+/// by another thread, so reads do not need to round-trip through the main
+/// thread.
+pub struct ProjectSettingsReader {
+    /// The read-only SQLite connection to the project settings file.
+    connection: ConnectionThreadSafe,
+}
+
+impl ProjectSettingsReader {
+    /// Gets a property from the project settings. Returns `None` if the
+    /// property does not exist. An error is returned if an SQL error occurs.
+    pub fn get(&self, key: &str) -> Result<Option<String>, ProjectSettingsError> {
+        read_setting(&self.connection, key)
+    }
+
+    /// Gets a list of all tilesets in the project. An error is returned if an
+    /// SQL error occurs.
+    pub fn list_tilesets(&self) -> Result<Vec<TilesetDefinition>, ProjectSettingsError> {
+        read_tilesets(&self.connection)
+    }
+}
+
+/// Reads a property from the settings table of the given connection.
+fn read_setting(
+    connection: &ConnectionThreadSafe,
+    key: &str,
+) -> Result<Option<String>, ProjectSettingsError> {
+    let mut statement = connection.prepare(
+        "SELECT value FROM settings
+         WHERE key = :key",
+    )?;
+    statement.bind((":key", key))?;
+
+    if statement.next()? != sqlite::State::Row {
+        return Ok(None);
+    }
+
+    Ok(Some(statement.read::<String, _>("value")?))
+}
+
+/// Reads all tilesets from the tilesets table of the given connection.
+fn read_tilesets(
+    connection: &ConnectionThreadSafe,
+) -> Result<Vec<TilesetDefinition>, ProjectSettingsError> {
+    let mut statement = connection.prepare(
+        "SELECT uuid, name
+         FROM tilesets",
+    )?;
+
+    let mut tilesets = Vec::new();
+    while statement.next()? == sqlite::State::Row {
+        let uuid = statement.read::<String, _>("uuid")?;
+        let name = statement.read::<String, _>("name")?;
+        tilesets.push(TilesetDefinition {
+            uuid: Uuid::parse_str(&uuid).unwrap(),
+            name,
+        });
+    }
+
+    Ok(tilesets)
+}
+
+/// The time, in milliseconds, that a connection to the project settings waits
+/// for another connection to release its lock before an access fails as busy.
+const BUSY_TIMEOUT_MS: u32 = 1000;
+
+/// The SQLite result code that indicates the database file is locked by
+/// another connection.
+pub(crate) const SQLITE_BUSY: isize = 5;
+
+/// The SQLite result code that indicates a table is locked by another
+/// statement on the same connection.
+pub(crate) const SQLITE_LOCKED: isize = 6;
+
 /// An error that can occur when working with project settings.
 #[derive(Debug, thiserror::Error)]
 pub enum ProjectSettingsError {
@@ -153,4 +371,213 @@ pub enum ProjectSettingsError {
     /// An error occurred while executing a SQL query.
     #[error("An error occurred while executing a SQL query: {0}")]
     Sql(#[from] sqlite::Error),
+
+    /// A reader was requested for settings that are stored in memory.
+    #[error("In-memory project settings cannot be opened by another connection")]
+    InMemory,
+
+    /// A property could not be converted from or to its stored value.
+    #[error("Invalid value for setting {key}: {reason}")]
+    InvalidValue {
+        /// The key of the property.
+        key: String,
+
+        /// The reason the value is invalid.
+        reason: String,
+    },
+}
+
+impl ProjectSettingsError {
+    /// Creates an error for a property with the given key that could not be
+    /// converted from or to its stored value.
+    fn invalid_value(key: &str, reason: impl Display) -> Self {
+        Self::InvalidValue {
+            key: key.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+
+    /// Checks whether this error is caused by the database being temporarily
+    /// busy or locked, in which case the operation may succeed if retried.
+    pub fn is_busy(&self) -> bool {
+        match self {
+            ProjectSettingsError::Io(err) | ProjectSettingsError::Sql(err) => {
+                matches!(err.code, Some(SQLITE_BUSY | SQLITE_LOCKED))
+            }
+            ProjectSettingsError::InMemory | ProjectSettingsError::InvalidValue { .. } => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_and_get() {
+        let settings = ProjectSettings::in_memory().unwrap();
+        assert_eq!(settings.get("name").unwrap(), None);
+
+        settings.set("name", Some("first")).unwrap();
+        settings.set("name", Some("second")).unwrap();
+        assert_eq!(settings.get("name").unwrap().as_deref(), Some("second"));
+
+        settings.set("name", None).unwrap();
+        assert_eq!(settings.get("name").unwrap(), None);
+    }
+
+    #[test]
+    fn typed_values() {
+        let settings = ProjectSettings::in_memory().unwrap();
+
+        settings.set("distance", Some("12.5")).unwrap();
+        assert_eq!(settings.get_parsed::<f32>("distance").unwrap(), Some(12.5));
+        assert_eq!(settings.get_parsed::<f32>("missing").unwrap(), None);
+
+        settings.set("distance", Some("far")).unwrap();
+        assert!(matches!(
+            settings.get_parsed::<f32>("distance"),
+            Err(ProjectSettingsError::InvalidValue { .. })
+        ));
+
+        let tags = vec!["stone".to_string(), "natural".to_string()];
+        settings.set_json("tags", Some(&tags)).unwrap();
+        assert_eq!(
+            settings.get_json::<Vec<String>>("tags").unwrap(),
+            Some(tags)
+        );
+        assert!(settings.get_json::<u32>("tags").is_err());
+
+        settings.set_json::<Vec<String>>("tags", None).unwrap();
+        assert_eq!(settings.get("tags").unwrap(), None);
+    }
+
+    #[test]
+    fn rename_moves_values() {
+        let settings = ProjectSettings::in_memory().unwrap();
+        settings.set("old", Some("value")).unwrap();
+        settings.set("new", Some("stale")).unwrap();
+
+        settings.rename("old", "new").unwrap();
+        assert_eq!(settings.get("old").unwrap(), None);
+        assert_eq!(settings.get("new").unwrap().as_deref(), Some("value"));
+
+        settings.rename("old", "new").unwrap();
+        assert_eq!(settings.get("new").unwrap(), None);
+    }
+
+    #[test]
+    fn update_and_remove_tilesets() {
+        let settings = ProjectSettings::in_memory().unwrap();
+        let uuid = Uuid::new_v4();
+
+        let mut definition = TilesetDefinition {
+            uuid,
+            name: "stone".to_string(),
+        };
+        settings.update_tileset(&definition).unwrap();
+
+        definition.name = "granite".to_string();
+        settings.update_tileset(&definition).unwrap();
+
+        let tilesets = settings.list_tilesets().unwrap();
+        assert_eq!(tilesets.len(), 1);
+        assert_eq!(tilesets[0].uuid, uuid);
+        assert_eq!(tilesets[0].name, "granite");
+
+        settings.remove_tileset(&uuid).unwrap();
+        assert!(settings.list_tilesets().unwrap().is_empty());
+    }
+
+    #[test]
+    fn transaction_commits_all_writes() {
+        let settings = ProjectSettings::in_memory().unwrap();
+
+        settings
+            .transaction(|tx| {
+                for i in 0 .. 100 {
+                    tx.set(&format!("block_{i}"), Some(&i.to_string()))?;
+                }
+                Ok(())
+            })
+            .unwrap();
+
+        assert_eq!(settings.get("block_0").unwrap().as_deref(), Some("0"));
+        assert_eq!(settings.get("block_99").unwrap().as_deref(), Some("99"));
+    }
+
+    #[test]
+    fn transaction_rolls_back_on_error() {
+        let settings = ProjectSettings::in_memory().unwrap();
+        settings.set("name", Some("before")).unwrap();
+
+        let result: Result<(), _> = settings.transaction(|tx| {
+            tx.set("name", Some("after"))?;
+            tx.set("other", Some("value"))?;
+            Err(ProjectSettingsError::Sql(sqlite::Error {
+                code: Some(1),
+                message: Some("test failure".to_string()),
+            }))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(settings.get("name").unwrap().as_deref(), Some("before"));
+        assert_eq!(settings.get("other").unwrap(), None);
+
+        settings.set("name", Some("again")).unwrap();
+        assert_eq!(settings.get("name").unwrap().as_deref(), Some("again"));
+    }
+
+    #[test]
+    fn reader_ignores_uncommitted_writes() {
+        let folder = std::env::temp_dir().join(format!("awgen-settings-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&folder).unwrap();
+
+        let settings = ProjectSettings::new(&folder, true).unwrap();
+        settings.set("name", Some("before")).unwrap();
+
+        let reader = settings.reader().unwrap();
+        assert_eq!(reader.get("name").unwrap().as_deref(), Some("before"));
+
+        settings
+            .transaction(|tx| {
+                tx.set("name", Some("after"))?;
+                assert_eq!(reader.get("name").unwrap().as_deref(), Some("before"));
+                Ok(())
+            })
+            .unwrap();
+
+        assert_eq!(reader.get("name").unwrap().as_deref(), Some("after"));
+        assert!(ProjectSettings::in_memory().unwrap().reader().is_err());
+
+        drop(reader);
+        drop(settings);
+        std::fs::remove_dir_all(folder).unwrap();
+    }
+
+    #[test]
+    fn writes_do_not_wait_for_readers() {
+        let folder = std::env::temp_dir().join(format!("awgen-settings-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&folder).unwrap();
+
+        let settings = ProjectSettings::new(&folder, true).unwrap();
+        settings.set("name", Some("before")).unwrap();
+
+        let reader = settings.reader().unwrap();
+        reader.connection.execute("BEGIN").unwrap();
+        reader
+            .connection
+            .execute("SELECT value FROM settings")
+            .unwrap();
+
+        settings.set("name", Some("after")).unwrap();
+        assert_eq!(reader.get("name").unwrap().as_deref(), Some("before"));
+
+        reader.connection.execute("COMMIT").unwrap();
+        assert_eq!(reader.get("name").unwrap().as_deref(), Some("after"));
+
+        drop(reader);
+        drop(settings);
+        std::fs::remove_dir_all(folder).unwrap();
+    }
 }
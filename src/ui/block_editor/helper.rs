@@ -1,18 +1,32 @@
 //! This module handles storage and editing for the temporary block data that is
 //! actively being edited.
 
-use bevy::ecs::system::SystemParam;
+use bevy::ecs::system::{RunSystemOnce, SystemParam};
+use bevy::math::Vec3A;
 use bevy::prelude::*;
+use bevy::utils::{HashMap, HashSet};
 use bevy_egui::EguiContexts;
 use bevy_egui::egui::{self, FontFamily, FontId, RichText};
 
 use super::tileset::TileWidget;
-use crate::blocks::shape::{BlockFace, BlockShape};
-use crate::blocks::tileset::{TILESET_LENGTH, TilePos, Tileset};
-use crate::blocks::{AIR_BLOCK_UUID, Block};
+use crate::blocks::collision::{BlockCollision, FULL_CUBE_BOUNDS};
+use crate::blocks::shape::{BlockFace, BlockShape, BlockVariant, StairsFacing};
+use crate::blocks::sounds::BlockSounds;
+use crate::blocks::tags::BlockTags;
+use crate::blocks::tileset::{PROTOTYPE_TILESET_NAME, TILESET_LENGTH, TilePos, Tileset};
+use crate::blocks::{AIR_BLOCK_UUID, Block, DeleteBlock};
+use crate::map::editor::reveal::RevealBlock;
 use crate::math::FaceDirection;
+use crate::settings::ProjectSettings;
+use crate::ui::confirm::ConfirmDialog;
+use crate::ui::gui3d::export::{BLOCK_ICON_DEFAULT_SIZE, ExportBlockIcon, block_icon_file};
+use crate::ui::theme::EditorTheme;
 
 /// The data structure that holds the temporary block data that is being edited.
+///
+/// This is stored as a resource so that it is shared between all systems that
+/// use the [`BlockEditHelper`].
+#[derive(Resource)]
 pub struct BlockEditData {
     /// The block entity that is being edited.
     pub block_id: Entity,
@@ -25,6 +39,37 @@ pub struct BlockEditData {
 
     /// The name of the block.
     pub name: String,
+
+    /// The shape of the block. Changes to the shape are only applied to the
+    /// block when it is saved.
+    pub shape: BlockShape,
+
+    /// The collision shape of the block, or `None` if the block has no
+    /// collision shape. Changes to the collision shape are only applied to the
+    /// block when it is saved.
+    pub collision: Option<BlockCollision>,
+
+    /// The names of all custom block models found in the project `models`
+    /// folder, without the file extension.
+    pub models: Vec<String>,
+
+    /// The comma-separated list of tags of the block.
+    pub tags: String,
+
+    /// The asset path of the sound played when the block is placed, or an
+    /// empty string to use the default sound.
+    pub place_sound: String,
+
+    /// The asset path of the sound played when the block is removed, or an
+    /// empty string to use the default sound.
+    pub remove_sound: String,
+
+    /// The category that the block list is filtered to, or `None` to show all
+    /// blocks.
+    pub tag_filter: Option<String>,
+
+    /// The increment that custom model offsets are snapped to.
+    pub offset_snap: OffsetSnap,
 }
 
 impl Default for BlockEditData {
@@ -34,15 +79,93 @@ impl Default for BlockEditData {
             dirty: false,
             popup: Popup::None,
             name: String::new(),
+            shape: BlockShape::None,
+            collision: None,
+            models: Vec::new(),
+            tags: String::new(),
+            place_sound: String::new(),
+            remove_sound: String::new(),
+            tag_filter: None,
+            offset_snap: OffsetSnap::default(),
         }
     }
 }
 
+/// This system discards the block that is being edited when the project is
+/// unloaded. The block editor selects a block of the new project the next time
+/// it is opened. The offset snap is kept, since it is an editor preference.
+pub fn reset_block_edit_data(mut data: ResMut<BlockEditData>) {
+    *data = BlockEditData {
+        offset_snap: data.offset_snap,
+        ..default()
+    };
+}
+
+/// The increment that custom model offsets are snapped to, as a fraction of a
+/// block. This keeps decorations aligned to a consistent sub-grid.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OffsetSnap {
+    /// Offsets snap to whole blocks.
+    Whole,
+
+    /// Offsets snap to 1/2 of a block.
+    Half,
+
+    /// Offsets snap to 1/4 of a block.
+    Quarter,
+
+    /// Offsets snap to 1/8 of a block.
+    Eighth,
+
+    /// Offsets snap to 1/16 of a block, matching a typical 16 pixel texture.
+    #[default]
+    Sixteenth,
+}
+
+impl OffsetSnap {
+    /// All snap increments, from largest to smallest.
+    pub const ALL: [OffsetSnap; 5] = [
+        OffsetSnap::Whole,
+        OffsetSnap::Half,
+        OffsetSnap::Quarter,
+        OffsetSnap::Eighth,
+        OffsetSnap::Sixteenth,
+    ];
+
+    /// Gets the size of the increment, in blocks.
+    pub fn increment(self) -> f32 {
+        match self {
+            OffsetSnap::Whole => 1.0,
+            OffsetSnap::Half => 0.5,
+            OffsetSnap::Quarter => 0.25,
+            OffsetSnap::Eighth => 0.125,
+            OffsetSnap::Sixteenth => 0.0625,
+        }
+    }
+
+    /// Gets the display label of the increment.
+    pub fn label(self) -> &'static str {
+        match self {
+            OffsetSnap::Whole => "1",
+            OffsetSnap::Half => "1/2",
+            OffsetSnap::Quarter => "1/4",
+            OffsetSnap::Eighth => "1/8",
+            OffsetSnap::Sixteenth => "1/16",
+        }
+    }
+
+    /// Rounds the given value to the nearest multiple of the increment.
+    pub fn snap(self, value: f32) -> f32 {
+        let increment = self.increment();
+        (value / increment).round() * increment
+    }
+}
+
 /// A system parameter that helps with storing and editing block data.
 #[derive(SystemParam)]
 pub struct BlockEditHelper<'w, 's> {
     /// The block data cache that is being edited.
-    data: Local<'s, BlockEditData>,
+    data: ResMut<'w, BlockEditData>,
 
     /// The query that fetches all blocks.
     blocks: Query<
@@ -69,6 +192,33 @@ pub struct BlockEditHelper<'w, 's> {
         ),
         Without<Block>,
     >,
+
+    /// The block collision shapes query.
+    collisions: Query<'w, 's, &'static mut BlockCollision>,
+
+    /// The block tags query.
+    tags: Query<'w, 's, &'static mut BlockTags>,
+
+    /// The block sounds query.
+    sounds: Query<'w, 's, &'static mut BlockSounds>,
+
+    /// The editor theme.
+    theme: Res<'w, EditorTheme>,
+
+    /// The event writer used to delete blocks.
+    delete_events: EventWriter<'w, DeleteBlock>,
+
+    /// The event writer used to reveal blocks in the world.
+    reveal_events: EventWriter<'w, RevealBlock>,
+
+    /// The event writer used to export block icons.
+    icon_events: EventWriter<'w, ExportBlockIcon>,
+
+    /// The project settings, used to locate the project asset folder.
+    project_settings: Res<'w, ProjectSettings>,
+
+    /// The shared confirmation dialog.
+    confirm_dialog: ResMut<'w, ConfirmDialog>,
 }
 
 impl<'w, 's> BlockEditHelper<'w, 's> {
@@ -83,37 +233,202 @@ impl<'w, 's> BlockEditHelper<'w, 's> {
             contexts.add_image(handle.clone_weak());
         });
 
-        let air = self
-            .blocks
+        self.select_block(self.air_block());
+    }
+
+    /// Returns the air block entity.
+    fn air_block(&self) -> Entity {
+        self.blocks
             .iter()
             .find(|(_, _, block, _)| block.uuid == AIR_BLOCK_UUID)
             .map(|(entity, _, _, _)| entity)
-            .unwrap();
+            .unwrap()
+    }
 
-        self.select_block(air);
+    /// Returns the texture ids of all tilesets, keyed by tileset name. Tilesets
+    /// that have not been registered with the egui context yet, such as newly
+    /// imported tilesets, are registered first.
+    pub fn tileset_textures(
+        &self,
+        contexts: &mut EguiContexts,
+    ) -> HashMap<String, egui::TextureId> {
+        self.tilesets
+            .iter()
+            .map(|(_, name, _, handle)| {
+                let texture = contexts
+                    .image_id(handle)
+                    .unwrap_or_else(|| contexts.add_image(handle.clone_weak()));
+                (name.as_str().to_string(), texture)
+            })
+            .collect()
     }
 
-    /// Adds a selectable list of all blocks to the UI.
-    pub fn edit_block_list(&mut self, ui: &mut egui::Ui) {
-        let block_list = self.blocks.iter().sort_by::<&Name>(|a, b| a.cmp(b));
+    /// Adds a selectable list of all blocks to the UI. Each entry shows a
+    /// thumbnail of the top face of the block, or a generic icon if the block
+    /// is not a cube.
+    pub fn edit_block_list(
+        &mut self,
+        ui: &mut egui::Ui,
+        tileset_textures: &HashMap<String, egui::TextureId>,
+    ) {
+        let block_list = self
+            .blocks
+            .iter()
+            .sort_by::<&Name>(|a, b| a.cmp(b))
+            .filter(|(block_id, _, _, _)| self.matches_tag_filter(*block_id));
+        let thumbnail_size = self.theme.list_font_size;
 
         let mut sel_block = self.data.block_id;
-        for (block_id, name, _, _) in block_list {
-            ui.selectable_value(
-                &mut sel_block,
-                block_id,
-                RichText::new(name).monospace().size(20.0),
-            );
+        for (block_id, name, _, shape) in block_list {
+            let thumbnail = shape
+                .tileset()
+                .zip(shape.face(FaceDirection::Up))
+                .and_then(|(tileset, top)| {
+                    tileset_textures
+                        .get(tileset)
+                        .map(|texture| (*texture, top.tile))
+                });
+
+            let label = if block_id == self.data.block_id && self.data.dirty {
+                format!("{}*", name)
+            } else {
+                name.to_string()
+            };
+
+            ui.horizontal(|ui| {
+                block_thumbnail(ui, thumbnail, thumbnail_size);
+                ui.selectable_value(
+                    &mut sel_block,
+                    block_id,
+                    RichText::new(label)
+                        .monospace()
+                        .size(self.theme.list_font_size),
+                );
+            });
         }
 
-        if sel_block != self.data.block_id {
-            if self.data.dirty {
-                self.data.popup = Popup::UnsavedChanges {
-                    new_block: sel_block,
-                };
+        self.request_select_block(sel_block);
+    }
+
+    /// Adds a category selection field to the UI, which filters the block list
+    /// to only show blocks with the selected tag.
+    pub fn edit_tag_filter(&mut self, ui: &mut egui::Ui) {
+        let mut categories: Vec<&str> = self
+            .tags
+            .iter()
+            .flat_map(|tags| tags.categories())
+            .collect();
+        categories.sort();
+        categories.dedup();
+
+        let mut sel_filter = self.data.tag_filter.clone();
+        egui::ComboBox::from_label("Category")
+            .selected_text(sel_filter.as_deref().unwrap_or("All"))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut sel_filter, None, "All");
+                for category in categories {
+                    ui.selectable_value(&mut sel_filter, Some(category.to_string()), category);
+                }
+            });
+
+        if sel_filter != self.data.tag_filter {
+            self.data.tag_filter = sel_filter;
+        }
+    }
+
+    /// Checks whether the given block is shown in the block list with the
+    /// current category filter.
+    fn matches_tag_filter(&self, block: Entity) -> bool {
+        let Some(filter) = &self.data.tag_filter else {
+            return true;
+        };
+
+        match self.tags.get(block) {
+            Ok(tags) => tags.has(filter),
+            Err(_) => BlockTags::default().has(filter),
+        }
+    }
+
+    /// Handles keyboard navigation within the block list. The up and down
+    /// arrow keys move the selection to the previous or next block, stopping
+    /// at the ends of the list, and the delete key deletes the selected block
+    /// after asking the user for confirmation.
+    ///
+    /// Keys are ignored while a popup is open or a text field has focus.
+    pub fn handle_block_list_keys(&mut self, ctx: &egui::Context) {
+        if self.is_popup_open() || ctx.wants_keyboard_input() {
+            return;
+        }
+
+        let (up, down, delete) = ctx.input(|input| {
+            (
+                input.key_pressed(egui::Key::ArrowUp),
+                input.key_pressed(egui::Key::ArrowDown),
+                input.key_pressed(egui::Key::Delete),
+            )
+        });
+
+        if up || down {
+            let block_list: Vec<Entity> = self
+                .blocks
+                .iter()
+                .sort_by::<&Name>(|a, b| a.cmp(b))
+                .map(|(block_id, _, _, _)| block_id)
+                .filter(|block_id| self.matches_tag_filter(*block_id))
+                .collect();
+
+            let Some(index) = block_list.iter().position(|b| *b == self.data.block_id) else {
+                return;
+            };
+
+            let new_index = if up {
+                index.saturating_sub(1)
             } else {
-                self.select_block(sel_block);
+                (index + 1).min(block_list.len() - 1)
+            };
+
+            self.request_select_block(block_list[new_index]);
+        }
+
+        if delete {
+            let (_, _, block, _) = self.blocks.get(self.data.block_id).unwrap();
+            if block.uuid == AIR_BLOCK_UUID {
+                return;
             }
+
+            self.confirm(
+                "Delete this block? It will be replaced with air in the world.",
+                ConfirmAction::DeleteBlock {
+                    block: self.data.block_id,
+                },
+            );
+        }
+    }
+
+    /// Handles the `Ctrl+S` shortcut, which saves the current block. Saving
+    /// does nothing if there are no unsaved changes.
+    pub fn handle_save_shortcut(&mut self, ctx: &egui::Context) {
+        if self.is_popup_open() {
+            return;
+        }
+
+        let save = ctx.input_mut(|input| input.consume_key(egui::Modifiers::CTRL, egui::Key::S));
+        if save && self.data.dirty {
+            self.save_block();
+        }
+    }
+
+    /// Requests that the given block be selected. If the current block has
+    /// unsaved changes, the unsaved changes popup is opened instead.
+    fn request_select_block(&mut self, block: Entity) {
+        if block == self.data.block_id {
+            return;
+        }
+
+        if self.data.dirty {
+            self.data.popup = Popup::UnsavedChanges { new_block: block };
+        } else {
+            self.select_block(block);
         }
     }
 
@@ -128,8 +443,320 @@ impl<'w, 's> BlockEditHelper<'w, 's> {
         self.data.block_id = block;
         self.data.dirty = false;
 
-        let (_, name, _, _) = self.blocks.get(block).unwrap();
+        let (_, name, _, shape) = self.blocks.get(block).unwrap();
         self.data.name = name.as_str().to_string();
+        self.data.shape = shape.clone();
+        self.data.collision = self.collisions.get(block).ok().copied();
+        self.data.tags = self
+            .tags
+            .get(block)
+            .map(|tags| tags.0.join(", "))
+            .unwrap_or_default();
+
+        let sounds = self.sounds.get(block).cloned().unwrap_or_default();
+        self.data.place_sound = sounds.place.unwrap_or_default();
+        self.data.remove_sound = sounds.remove.unwrap_or_default();
+
+        self.refresh_models();
+    }
+
+    /// Adds a tag edit field to the UI. Tags are entered as a comma-separated
+    /// list.
+    pub fn edit_tags(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Tags");
+            ui.text_edit_singleline(&mut self.data.tags)
+                .on_hover_text("A comma-separated list of tags.");
+        });
+
+        let tags = BlockTags::parse(&self.data.tags);
+        let original_tags = self.tags.get(self.data.block_id).cloned().unwrap_or_default();
+        if tags != original_tags {
+            self.data.dirty = true;
+        }
+    }
+
+    /// Adds edit fields for the sounds of the block to the UI. Sounds are
+    /// entered as asset paths, and empty fields use the default sound.
+    pub fn edit_sounds(&mut self, ui: &mut egui::Ui) {
+        let hint = "An asset path, such as project://sounds/stone.ogg. Leave empty to use the \
+                    default sound.";
+
+        ui.horizontal(|ui| {
+            ui.label("Place Sound");
+            ui.text_edit_singleline(&mut self.data.place_sound)
+                .on_hover_text(hint);
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Remove Sound");
+            ui.text_edit_singleline(&mut self.data.remove_sound)
+                .on_hover_text(hint);
+        });
+
+        let sounds = BlockSounds::from_paths(&self.data.place_sound, &self.data.remove_sound);
+        let original_sounds = self.sounds.get(self.data.block_id).cloned().unwrap_or_default();
+        if sounds != original_sounds {
+            self.data.dirty = true;
+        }
+    }
+
+    /// Refreshes the list of custom block models found in the project `models`
+    /// folder. Files whose names are not valid model names are skipped. A
+    /// warning is logged if the selected block uses a custom model that cannot
+    /// be found.
+    pub fn refresh_models(&mut self) {
+        let folder = self.project_settings.asset_folder().join("models");
+
+        let mut models: Vec<String> = match std::fs::read_dir(&folder) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|ext| ext == "glb"))
+                .filter_map(|path| Some(path.file_stem()?.to_string_lossy().to_string()))
+                .filter(|name| BlockShape::is_valid_model_name(name))
+                .collect(),
+            Err(err) => {
+                debug!("Failed to list models in {}: {err}", folder.display());
+                Vec::new()
+            }
+        };
+        models.sort();
+        self.data.models = models;
+
+        if let BlockShape::Custom { asset, .. } = &self.data.shape {
+            if !self.data.models.contains(asset) {
+                warn!("Custom model not found for block {}: models/{asset}.glb", self.data.name);
+            }
+        }
+    }
+
+    /// Adds a shape type selection field to the UI. If the block uses a custom
+    /// shape, the model can also be selected from all `.glb` files within the
+    /// project `models` folder. The preview updates once the model has
+    /// finished loading.
+    pub fn edit_shape(&mut self, ui: &mut egui::Ui) {
+        let shape_type = match &self.data.shape {
+            BlockShape::None => "None",
+            BlockShape::Cube { .. } => "Cube",
+            BlockShape::Stairs { .. } => "Stairs",
+            BlockShape::Cross { .. } => "Cross",
+            BlockShape::Custom { .. } => "Custom",
+        };
+
+        let mut sel_shape_type = shape_type;
+        egui::ComboBox::from_label("Shape")
+            .selected_text(sel_shape_type)
+            .show_ui(ui, |ui| {
+                for option in ["None", "Cube", "Stairs", "Cross", "Custom"] {
+                    ui.selectable_value(&mut sel_shape_type, option, option);
+                }
+            });
+
+        if sel_shape_type != shape_type {
+            if sel_shape_type == "Custom" {
+                self.refresh_models();
+            }
+
+            self.data.shape = match sel_shape_type {
+                "Cube" => BlockShape::Cube {
+                    tileset: PROTOTYPE_TILESET_NAME.to_string(),
+                    top: default(),
+                    bottom: default(),
+                    north: default(),
+                    south: default(),
+                    east: default(),
+                    west: default(),
+                    transparent: false,
+                    non_occluding: false,
+                    variants: Vec::new(),
+                    randomize_rotation: false,
+                },
+                "Stairs" => BlockShape::Stairs {
+                    tileset: PROTOTYPE_TILESET_NAME.to_string(),
+                    facing: StairsFacing::North,
+                    top: default(),
+                    bottom: default(),
+                    north: default(),
+                    south: default(),
+                    east: default(),
+                    west: default(),
+                    transparent: false,
+                },
+                "Cross" => BlockShape::Cross {
+                    tileset: PROTOTYPE_TILESET_NAME.to_string(),
+                    tile: default(),
+                },
+                "Custom" => BlockShape::Custom {
+                    asset: self.data.models.first().cloned().unwrap_or_default(),
+                    offset: [0.0; 3],
+                    smooth_normals: false,
+                },
+                _ => BlockShape::None,
+            };
+            self.data.dirty = true;
+        }
+
+        if let BlockShape::Stairs { facing, .. } = &mut self.data.shape {
+            let mut sel_facing = *facing;
+            egui::ComboBox::from_label("Facing")
+                .selected_text(format!("{sel_facing:?}"))
+                .show_ui(ui, |ui| {
+                    for dir in StairsFacing::ALL {
+                        ui.selectable_value(&mut sel_facing, dir, format!("{dir:?}"));
+                    }
+                });
+
+            if sel_facing != *facing {
+                *facing = sel_facing;
+                self.data.dirty = true;
+            }
+        }
+
+        self.edit_variants(ui);
+
+        let BlockShape::Custom { asset, .. } = &self.data.shape else {
+            return;
+        };
+
+        let mut sel_asset = asset.clone();
+        let mut refresh = false;
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_label("Model")
+                .selected_text(&sel_asset)
+                .show_ui(ui, |ui| {
+                    for model in self.data.models.iter() {
+                        ui.selectable_value(&mut sel_asset, model.clone(), model);
+                    }
+                });
+
+            refresh = ui.button("Refresh").clicked();
+        });
+
+        if !self.data.models.contains(&sel_asset) {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                format!("Model not found: models/{sel_asset}.glb"),
+            );
+        }
+
+        if refresh {
+            self.refresh_models();
+        }
+
+        if let BlockShape::Custom { asset, .. } = &mut self.data.shape {
+            if *asset != sel_asset {
+                *asset = sel_asset;
+                self.data.dirty = true;
+            }
+        }
+
+        self.edit_model_offset(ui);
+
+        if let BlockShape::Custom { smooth_normals, .. } = &mut self.data.shape {
+            if ui
+                .checkbox(smooth_normals, "Smooth Normals")
+                .on_hover_text("Averages the normals of adjacent faces so the model is smooth shaded")
+                .changed()
+            {
+                self.data.dirty = true;
+            }
+        }
+    }
+
+    /// Adds texture variant fields for cube blocks to the UI. Each variant
+    /// shifts the tiles of all faces by a number of columns and rows. The top
+    /// face can also be rotated randomly per placed block.
+    fn edit_variants(&mut self, ui: &mut egui::Ui) {
+        let BlockShape::Cube {
+            variants,
+            randomize_rotation,
+            ..
+        } = &mut self.data.shape
+        else {
+            return;
+        };
+
+        let max = TILESET_LENGTH as u8 - 1;
+        let mut changed = false;
+        let mut remove = None;
+
+        changed |= ui
+            .checkbox(randomize_rotation, "Randomize Top Rotation")
+            .on_hover_text("Rotate the top texture of each placed block by its position")
+            .changed();
+
+        ui.label("Variants")
+            .on_hover_text("Placed blocks pick between the base textures and these variants by position");
+        for (index, variant) in variants.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                changed |= ui
+                    .add(egui::DragValue::new(&mut variant.columns).prefix("Columns: ").range(0 ..= max))
+                    .changed();
+                changed |= ui
+                    .add(egui::DragValue::new(&mut variant.rows).prefix("Rows: ").range(0 ..= max))
+                    .changed();
+                if ui.button("Remove").clicked() {
+                    remove = Some(index);
+                }
+            });
+        }
+
+        if let Some(index) = remove {
+            variants.remove(index);
+            changed = true;
+        }
+
+        if ui.button("Add Variant").clicked() {
+            variants.push(BlockVariant { columns: 1, rows: 0 });
+            changed = true;
+        }
+
+        if changed {
+            self.data.dirty = true;
+        }
+    }
+
+    /// Adds offset fields for custom block models to the UI. Offsets are
+    /// snapped to the selected increment of a block.
+    fn edit_model_offset(&mut self, ui: &mut egui::Ui) {
+        let mut snap = self.data.offset_snap;
+        egui::ComboBox::from_label("Offset Snap")
+            .selected_text(snap.label())
+            .show_ui(ui, |ui| {
+                for option in OffsetSnap::ALL {
+                    ui.selectable_value(&mut snap, option, option.label());
+                }
+            });
+        self.data.offset_snap = snap;
+
+        let BlockShape::Custom { offset, .. } = &mut self.data.shape else {
+            return;
+        };
+
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            ui.label("Offset");
+            for (axis, value) in ["X", "Y", "Z"].into_iter().zip(offset.iter_mut()) {
+                let mut sel_value = *value;
+                ui.add(
+                    egui::DragValue::new(&mut sel_value)
+                        .prefix(format!("{axis}: "))
+                        .speed(snap.increment())
+                        .range(-1.0 ..= 1.0),
+                );
+
+                let sel_value = snap.snap(sel_value);
+                if sel_value != *value {
+                    *value = sel_value;
+                    changed = true;
+                }
+            }
+        });
+
+        if changed {
+            self.data.dirty = true;
+        }
     }
 
     /// Adds a name edit field to the UI.
@@ -138,7 +765,7 @@ impl<'w, 's> BlockEditHelper<'w, 's> {
             egui::TextEdit::singleline(&mut self.data.name)
                 .char_limit(50)
                 .font(FontId {
-                    size: 32.0,
+                    size: self.theme.title_font_size,
                     family: FontFamily::Monospace,
                 }),
         );
@@ -149,23 +776,232 @@ impl<'w, 's> BlockEditHelper<'w, 's> {
         }
     }
 
+    /// Adds a transparency toggle to the UI. This field is only shown for cube
+    /// and stairs blocks.
+    pub fn edit_transparency(&mut self, ui: &mut egui::Ui) {
+        let (BlockShape::Cube { transparent, .. } | BlockShape::Stairs { transparent, .. }) =
+            &mut self.data.shape
+        else {
+            return;
+        };
+
+        let mut sel_transparent = *transparent;
+        ui.checkbox(&mut sel_transparent, "Transparent");
+
+        if sel_transparent != *transparent {
+            *transparent = sel_transparent;
+            self.data.dirty = true;
+        }
+    }
+
+    /// Adds a toggle to the UI that stops the block from culling the faces of
+    /// its neighbors. This field is only shown for cube blocks.
+    pub fn edit_occlusion(&mut self, ui: &mut egui::Ui) {
+        let BlockShape::Cube { non_occluding, .. } = &mut self.data.shape else {
+            return;
+        };
+
+        let mut sel_non_occluding = *non_occluding;
+        ui.checkbox(&mut sel_non_occluding, "Don't Cull Neighbors")
+            .on_hover_text("Keep the faces of neighboring blocks visible, such as for leaves.");
+
+        if sel_non_occluding != *non_occluding {
+            *non_occluding = sel_non_occluding;
+            self.data.dirty = true;
+        }
+    }
+
+    /// Adds a collision shape selection field to the UI. If the block uses a
+    /// custom collision shape, the bounds of the shape can also be edited. The
+    /// change is only applied to the block when it is saved.
+    pub fn edit_collision(&mut self, ui: &mut egui::Ui) {
+        let Some(collision) = &mut self.data.collision else {
+            return;
+        };
+
+        let mut sel_collision = *collision;
+        let custom = match sel_collision {
+            BlockCollision::Custom { .. } => sel_collision,
+            _ => BlockCollision::Custom {
+                bounds: FULL_CUBE_BOUNDS,
+            },
+        };
+
+        egui::ComboBox::from_label("Collision")
+            .selected_text(sel_collision.name())
+            .show_ui(ui, |ui| {
+                for option in [
+                    BlockCollision::Auto,
+                    BlockCollision::None,
+                    BlockCollision::FullCube,
+                    custom,
+                ] {
+                    ui.selectable_value(&mut sel_collision, option, option.name());
+                }
+            });
+
+        if let BlockCollision::Custom { bounds } = &mut sel_collision {
+            let mut min = bounds.min.to_array();
+            let mut max = bounds.max.to_array();
+
+            for (label, values) in [("Min", &mut min), ("Max", &mut max)] {
+                ui.horizontal(|ui| {
+                    ui.label(label);
+                    for value in values.iter_mut() {
+                        ui.add(egui::DragValue::new(value).speed(0.05).range(0.0 ..= 1.0));
+                    }
+                });
+            }
+
+            bounds.min = Vec3A::from_array(min);
+            bounds.max = Vec3A::from_array(max);
+            bounds.max = bounds.max.max(bounds.min);
+        }
+
+        if sel_collision != *collision {
+            *collision = sel_collision;
+            self.data.dirty = true;
+        }
+    }
+
     /// Saves the current block data.
     pub fn save_block(&mut self) {
-        let (_, mut name, _, _) = self.blocks.get_mut(self.data.block_id).unwrap();
+        let (_, mut name, _, mut shape) = self.blocks.get_mut(self.data.block_id).unwrap();
+        let old_name = name.as_str().to_string();
         name.set(self.data.name.clone());
+        shape.set_if_neq(self.data.shape.clone());
         self.data.dirty = false;
 
+        if let (Some(collision), Ok(mut block_collision)) = (
+            self.data.collision,
+            self.collisions.get_mut(self.data.block_id),
+        ) {
+            block_collision.set_if_neq(collision);
+        }
+
+        let tags = BlockTags::parse(&self.data.tags);
+        let sounds = BlockSounds::from_paths(&self.data.place_sound, &self.data.remove_sound);
+        let result = self.project_settings.transaction(|tx| {
+            tx.rename(
+                &BlockTags::settings_key(&old_name),
+                &BlockTags::settings_key(&self.data.name),
+            )?;
+            tx.rename(
+                &BlockSounds::settings_key(&old_name),
+                &BlockSounds::settings_key(&self.data.name),
+            )?;
+            tags.save(tx, &self.data.name)?;
+            sounds.save(tx, &self.data.name)
+        });
+        if let Err(err) = result {
+            error!("Failed to save tags and sounds for block {}: {err}", self.data.name);
+        }
+        if let Ok(mut block_tags) = self.tags.get_mut(self.data.block_id) {
+            block_tags.set_if_neq(tags);
+        }
+        if let Ok(mut block_sounds) = self.sounds.get_mut(self.data.block_id) {
+            block_sounds.set_if_neq(sounds);
+        }
+
         info!("Saving block data for: {}", *name);
     }
 
+    /// Adds a button to the UI that discards all unsaved changes to the current
+    /// block, after asking the user for confirmation. The button is disabled if
+    /// there are no unsaved changes.
+    pub fn discard_changes_button(&mut self, ui: &mut egui::Ui) {
+        if ui
+            .add_enabled(self.data.dirty, egui::Button::new("Discard Changes"))
+            .clicked()
+        {
+            self.confirm(
+                "Discard all unsaved changes to this block?",
+                ConfirmAction::DiscardChanges,
+            );
+        }
+    }
+
+    /// Adds a button to the UI that moves the camera to the next instance of
+    /// the current block within the world. Returns true if the button was
+    /// clicked.
+    pub fn reveal_in_world_button(&mut self, ui: &mut egui::Ui) -> bool {
+        if !ui.button("Reveal in World").clicked() {
+            return false;
+        }
+
+        let (_, _, block, _) = self.blocks.get(self.data.block_id).unwrap();
+        self.reveal_events.send(RevealBlock { uuid: block.uuid });
+        true
+    }
+
+    /// Adds a button to the UI that renders the current block to a PNG file
+    /// with a transparent background, within the project icons folder.
+    pub fn export_icon_button(&mut self, ui: &mut egui::Ui) {
+        if !ui
+            .button("Export Icon")
+            .on_hover_text("Render this block to a PNG file in the icons folder")
+            .clicked()
+        {
+            return;
+        }
+
+        let (_, _, block, _) = self.blocks.get(self.data.block_id).unwrap();
+        let path = block_icon_file(&self.project_settings.asset_folder(), block.uuid);
+        info!("Exporting block icon to: {}", path.display());
+
+        self.icon_events.send(ExportBlockIcon {
+            block: self.data.block_id,
+            size: BLOCK_ICON_DEFAULT_SIZE,
+            path,
+            background: None,
+        });
+    }
+
+    /// Opens the shared confirmation dialog with the given message. The given
+    /// action is only run if the user confirms it.
+    pub fn confirm(&mut self, message: &'static str, action: ConfirmAction) {
+        self.confirm_dialog.open(message, move |world: &mut World| {
+            world.run_system_once(move |mut helper: BlockEditHelper| {
+                helper.run_confirm_action(action);
+            });
+        });
+    }
+
+    /// Runs the given confirmed action.
+    pub fn run_confirm_action(&mut self, action: ConfirmAction) {
+        match action {
+            ConfirmAction::DiscardChanges => {
+                info!("Discarding unsaved block changes.");
+                self.select_block(self.data.block_id);
+            }
+            ConfirmAction::DeleteBlock { block } => {
+                self.delete_events.send(DeleteBlock { block });
+
+                self.select_block(self.air_block());
+            }
+        }
+    }
+
     /// Closes the current popup, if any.
     pub fn close_popup(&mut self) {
         self.data.popup = Popup::None;
     }
 
-    /// Returns whether a popup is currently open.
+    /// Returns whether the current block has unsaved changes.
+    pub fn is_dirty(&self) -> bool {
+        self.data.dirty
+    }
+
+    /// Opens the unsaved changes popup that is shown when the user tries to
+    /// close the Block Editor with unsaved changes.
+    pub fn prompt_unsaved_on_close(&mut self) {
+        self.data.popup = Popup::UnsavedChangesOnClose;
+    }
+
+    /// Returns whether a popup, or the shared confirmation dialog, is currently
+    /// open.
     pub fn is_popup_open(&self) -> bool {
-        self.data.popup != Popup::None
+        self.data.popup != Popup::None || self.confirm_dialog.is_pending()
     }
 
     /// Returns the currently selected block.
@@ -173,43 +1009,88 @@ impl<'w, 's> BlockEditHelper<'w, 's> {
         self.data.block_id
     }
 
-    /// This function updates the face of a block in the block editor.
+    /// Returns the shape of the current block, including any unsaved changes.
+    pub fn shape(&self) -> &BlockShape {
+        &self.data.shape
+    }
+
+    /// This function updates the face of a block in the block editor. The
+    /// change is only applied to the block when it is saved.
+    /// Cross blocks only have a single tile, which is replaced by the tile of
+    /// the given face regardless of the direction.
     pub fn update_block_face(&mut self, dir: FaceDirection, face: BlockFace) {
-        let (_, _, _, mut shape) = self.blocks.get_mut(self.data.block_id).unwrap();
+        if let BlockShape::Cross { tile, .. } = &mut self.data.shape {
+            *tile = face.tile;
+            self.data.dirty = true;
+            return;
+        }
 
-        let BlockShape::Cube {
-            top,
-            bottom,
-            north,
-            south,
-            east,
-            west,
-            ..
-        } = &mut *shape
-        else {
+        let Some(block_face) = self.data.shape.face_mut(dir) else {
+            return;
+        };
+
+        *block_face = face;
+        self.data.dirty = true;
+    }
+
+    /// Adds the options of the selected block faces to the UI. The options show
+    /// the state of the first selected face, and changing an option applies it
+    /// to all selected faces. Only cube faces support connected textures and
+    /// biome tinting.
+    pub fn edit_face_options(
+        &mut self,
+        ui: &mut egui::Ui,
+        selected_faces: &HashSet<FaceDirection>,
+    ) {
+        if !matches!(self.data.shape, BlockShape::Cube { .. }) {
+            return;
+        }
+
+        let faces = FaceDirection::DIRECTIONS
+            .into_iter()
+            .filter(|dir| selected_faces.contains(dir))
+            .collect::<Vec<_>>();
+
+        let Some(first) = faces.first().and_then(|dir| self.data.shape.face(*dir)) else {
             return;
         };
 
-        match dir {
-            FaceDirection::Up => *top = face,
-            FaceDirection::Down => *bottom = face,
-            FaceDirection::North => *north = face,
-            FaceDirection::South => *south = face,
-            FaceDirection::East => *east = face,
-            FaceDirection::West => *west = face,
+        let mut connected = first.connected;
+        let mut biome_tint = first.biome_tint;
+
+        if ui
+            .checkbox(&mut connected, "Connected Texture")
+            .on_hover_text("Join neighboring blocks of the same type, using a 4x4 grid of tiles")
+            .changed()
+        {
+            for dir in faces.iter() {
+                if let Some(face) = self.data.shape.face_mut(*dir) {
+                    face.connected = connected;
+                }
+            }
+            self.data.dirty = true;
         }
 
-        self.data.dirty = true;
+        if ui
+            .checkbox(&mut biome_tint, "Biome Tint")
+            .on_hover_text("Tint the face by the biome the block is placed in, such as for grass")
+            .changed()
+        {
+            for dir in faces.iter() {
+                if let Some(face) = self.data.shape.face_mut(*dir) {
+                    face.biome_tint = biome_tint;
+                }
+            }
+            self.data.dirty = true;
+        }
     }
 
     /// This function renders the combo box for selecting a tileset, or an empty
     /// combo box if the block does not use a tileset.
     pub fn tileset_list_combobox(&mut self, ui: &mut egui::Ui) {
-        let (_, _, _, shape) = self.blocks.get(self.data.block_id).unwrap();
-
-        match shape {
-            BlockShape::Cube { tileset, .. } => {
-                let mut sel_tileset = tileset.clone();
+        match self.data.shape.tileset() {
+            Some(tileset) => {
+                let mut sel_tileset = tileset.to_string();
                 egui::ComboBox::from_label("tileset_list_select")
                     .selected_text(tileset)
                     .show_ui(ui, |ui| {
@@ -219,7 +1100,7 @@ impl<'w, 's> BlockEditHelper<'w, 's> {
                         }
                     });
             }
-            _ => {
+            None => {
                 egui::ComboBox::from_label("tileset_list_select")
                     .selected_text("")
                     .show_ui(ui, |_| {});
@@ -229,25 +1110,22 @@ impl<'w, 's> BlockEditHelper<'w, 's> {
 
     /// Returns the currently selected tileset image, if any.
     pub fn get_selected_tileset_image(&self) -> Option<&Handle<Image>> {
-        let (_, _, _, shape) = self.blocks.get(self.data.block_id).unwrap();
-
-        match shape {
-            BlockShape::Cube { tileset, .. } => self
-                .tilesets
-                .iter()
-                .find(|(_, name, _, _)| name.as_str() == tileset)
-                .map(|(_, _, _, handle)| handle),
-            _ => None,
-        }
+        let tileset = self.data.shape.tileset()?;
+        self.tilesets
+            .iter()
+            .find(|(_, name, _, _)| name.as_str() == tileset)
+            .map(|(_, _, _, handle)| handle)
     }
 
     /// This function renders a list of tiles from the selected tileset, or an
-    /// empty list if no tileset is selected.
+    /// empty list if no tileset is selected. Clicking a tile assigns it to all
+    /// of the selected faces. Faces keep whether they use connected textures
+    /// and biome tinting.
     pub fn tile_list(
         &mut self,
         ui: &mut egui::Ui,
         tile_list_texture_id: Option<egui::TextureId>,
-        selected_face: Option<FaceDirection>,
+        selected_faces: &HashSet<FaceDirection>,
     ) {
         let Some(tile_list_texture_id) = tile_list_texture_id else {
             return;
@@ -267,18 +1145,25 @@ impl<'w, 's> BlockEditHelper<'w, 's> {
                 let mut i = 0;
                 for y in 0 .. TILESET_LENGTH as u8 {
                     for x in 0 .. TILESET_LENGTH as u8 {
-                        if ui
+                        let tile_pos = TilePos::new(x, y);
+                        let response = ui
                             .add(TileWidget {
                                 texture: tile_list_texture_id,
-                                tile_pos: TilePos::new(x, y),
+                                tile_pos,
                                 size: tile_size,
                             })
                             .interact(egui::Sense::click())
-                            .clicked()
-                        {
-                            if let Some(dir) = selected_face {
-                                self.update_block_face(dir, BlockFace {
+                            .on_hover_ui(|ui| {
+                                tile_tooltip(ui, tile_list_texture_id, tile_pos, selected_faces);
+                            });
+
+                        if response.clicked() {
+                            for dir in selected_faces.iter() {
+                                let face = self.data.shape.face(*dir).cloned().unwrap_or_default();
+                                self.update_block_face(*dir, BlockFace {
                                     tile: TilePos::new(x, y),
+                                    connected: face.connected,
+                                    biome_tint: face.biome_tint,
                                     ..default()
                                 });
                             }
@@ -294,6 +1179,69 @@ impl<'w, 's> BlockEditHelper<'w, 's> {
     }
 }
 
+/// Renders the hover tooltip for a tile in the tile list. This shows the
+/// coordinates of the tile within the tileset atlas and, if any faces are
+/// selected, a larger preview of the tile as it would be applied to them.
+fn tile_tooltip(
+    ui: &mut egui::Ui,
+    texture: egui::TextureId,
+    tile_pos: TilePos,
+    selected_faces: &HashSet<FaceDirection>,
+) {
+    ui.label(format!("Tile: ({}, {})", tile_pos.x(), tile_pos.y()));
+
+    if selected_faces.is_empty() {
+        return;
+    }
+
+    let faces = FaceDirection::DIRECTIONS
+        .into_iter()
+        .filter(|dir| selected_faces.contains(dir))
+        .map(|dir| dir.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    ui.separator();
+    ui.add(TileWidget {
+        texture,
+        tile_pos,
+        size: 128.0,
+    });
+    ui.label(format!("Applied to: {}", faces));
+}
+
+/// Adds a square block thumbnail to the UI. If no tile is given, a generic
+/// block icon is drawn instead.
+fn block_thumbnail(ui: &mut egui::Ui, tile: Option<(egui::TextureId, TilePos)>, size: f32) {
+    if let Some((texture, tile_pos)) = tile {
+        ui.add(TileWidget {
+            texture,
+            tile_pos,
+            size,
+        });
+        return;
+    }
+
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(size, size), egui::Sense::hover());
+    if !ui.is_rect_visible(rect) {
+        return;
+    }
+
+    let color = ui.visuals().weak_text_color();
+    ui.painter().rect_stroke(
+        rect.shrink(2.0),
+        egui::Rounding::same(2.0),
+        egui::Stroke::new(1.0, color),
+    );
+    ui.painter().text(
+        rect.center(),
+        egui::Align2::CENTER_CENTER,
+        "?",
+        FontId::monospace(size * 0.6),
+        color,
+    );
+}
+
 /// A small state machine that handles popups.
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Popup {
@@ -307,4 +1255,21 @@ pub enum Popup {
         /// The new block that the user is trying to open.
         new_block: Entity,
     },
+
+    /// A popup that appears when the user tries to close the Block Editor
+    /// without saving the current block.
+    UnsavedChangesOnClose,
+}
+
+/// An action that requires confirmation from the user before it is run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConfirmAction {
+    /// Discards all unsaved changes to the currently selected block.
+    DiscardChanges,
+
+    /// Deletes the given block.
+    DeleteBlock {
+        /// The block entity to delete.
+        block: Entity,
+    },
 }
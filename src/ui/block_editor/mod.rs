@@ -3,8 +3,11 @@
 use bevy::asset::embedded_asset;
 use bevy::prelude::*;
 
-use super::EditorWindowState;
+use super::command_palette::EditorActionsAppExt;
+use super::hud::EditorHud;
+use super::{EditorWindowState, UiKeyboardFocus};
 use crate::gamestate::GameState;
+use crate::project::UnloadProject;
 
 pub mod helper;
 pub mod preview;
@@ -15,44 +18,51 @@ pub mod ui;
 pub struct BlockEditorUiPlugin;
 impl Plugin for BlockEditorUiPlugin {
     fn build(&self, app_: &mut App) {
-        app_.add_systems(
-            Update,
-            (
-                ui::render
-                    .after_ignore_deferred(ui::open)
-                    .after_ignore_deferred(ui::close)
-                    .run_if(in_state(GameState::Editor))
-                    .run_if(in_state(EditorWindowState::BlockEditor))
-                    .run_if(resource_exists::<preview::BlockPreviewWidget>),
-                ui::open
-                    .run_if(in_state(GameState::Editor))
-                    .run_if(not(in_state(EditorWindowState::BlockEditor))),
-                ui::close
-                    .run_if(in_state(GameState::Editor))
-                    .run_if(in_state(EditorWindowState::BlockEditor)),
-                preview::update_preview
-                    .after_ignore_deferred(ui::render)
-                    .after_ignore_deferred(preview::update_face_hover)
-                    .run_if(in_state(GameState::Editor))
-                    .run_if(in_state(EditorWindowState::BlockEditor))
-                    .run_if(resource_exists::<preview::BlockPreviewWidget>),
-                preview::update_gizmo_render_layer,
-                preview::update_face_hover
-                    .run_if(in_state(GameState::Editor))
-                    .run_if(in_state(EditorWindowState::BlockEditor))
-                    .run_if(resource_exists::<preview::BlockPreviewWidget>),
-            ),
-        )
-        .add_systems(OnEnter(GameState::Editor), preview::prepare_camera)
-        .add_systems(OnExit(GameState::Editor), preview::cleanup_camera)
-        .add_systems(
-            OnEnter(EditorWindowState::BlockEditor),
-            preview::enable_camera,
-        )
-        .add_systems(
-            OnExit(EditorWindowState::BlockEditor),
-            preview::disable_camera,
-        );
+        app_.init_resource::<helper::BlockEditData>()
+            .add_systems(
+                Update,
+                (
+                    ui::render
+                        .after_ignore_deferred(ui::open)
+                        .after_ignore_deferred(ui::close)
+                        .run_if(in_state(GameState::Editor))
+                        .run_if(in_state(EditorWindowState::BlockEditor))
+                        .run_if(resource_exists::<preview::BlockPreviewWidget>)
+                        .run_if(EditorHud::is_visible),
+                    ui::open
+                        .run_if(in_state(GameState::Editor))
+                        .run_if(not(in_state(EditorWindowState::BlockEditor)))
+                        .run_if(UiKeyboardFocus::is_free),
+                    ui::close
+                        .run_if(in_state(GameState::Editor))
+                        .run_if(in_state(EditorWindowState::BlockEditor))
+                        .run_if(UiKeyboardFocus::is_free),
+                    ui::toggle_button
+                        .before(ui::render)
+                        .run_if(in_state(GameState::Editor))
+                        .run_if(EditorHud::is_visible),
+                    preview::update_preview
+                        .after_ignore_deferred(ui::render)
+                        .after_ignore_deferred(preview::update_face_hover)
+                        .run_if(in_state(GameState::Editor))
+                        .run_if(in_state(EditorWindowState::BlockEditor))
+                        .run_if(resource_exists::<preview::BlockPreviewWidget>),
+                    preview::refresh_loaded_gizmos,
+                    preview::update_face_hover
+                        .run_if(in_state(GameState::Editor))
+                        .run_if(in_state(EditorWindowState::BlockEditor))
+                        .run_if(resource_exists::<preview::BlockPreviewWidget>),
+                ),
+            )
+            .add_systems(UnloadProject, helper::reset_block_edit_data)
+            .add_systems(OnEnter(GameState::Editor), preview::prepare_camera)
+            .add_systems(OnExit(GameState::Editor), preview::cleanup_camera)
+            .register_editor_action(
+                "Open Block Editor",
+                |mut editor_window_state: ResMut<NextState<EditorWindowState>>| {
+                    editor_window_state.set(EditorWindowState::BlockEditor);
+                },
+            );
 
         embedded_asset!(app_, "block_face_rotation.glb");
     }
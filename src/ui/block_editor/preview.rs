@@ -1,22 +1,20 @@
 //! This module implements the block preview widget for the Block Editor UI
 //! screen.
+//!
+//! The preview renders a stand-in block through the [`BlockThumbnailer`]. The
+//! thumbnail is rendered again whenever the previewed shape, the camera
+//! rotation, or the face selection changes.
 
 use bevy::math::bounding::{Aabb3d, RayCast3d};
 use bevy::prelude::*;
-use bevy::render::camera::{RenderTarget, ScalingMode};
-use bevy::render::render_resource::{
-    Extent3d,
-    TextureDescriptor,
-    TextureDimension,
-    TextureFormat,
-    TextureUsages,
-};
-use bevy::render::view::RenderLayers;
-use bevy_egui::EguiUserTextures;
-
-use crate::blocks::RenderedBlock;
-use crate::blocks::params::BlockFinder;
+use bevy::render::camera::ScalingMode;
+use bevy::utils::HashSet;
+use bevy_egui::egui;
+
+use crate::blocks::model::BlockModel;
+use crate::blocks::shape::BlockShape;
 use crate::math::FaceDirection;
+use crate::ui::gui3d::thumbnail::{BlockThumbnail, BlockThumbnailer};
 
 /// The asset path to the block face rotation gizmo model.
 pub const GIZMO_FACESEL_MODEL: &str = "embedded://awgen/ui/block_editor/block_face_rotation.glb";
@@ -38,24 +36,34 @@ pub const DRAG_SENSITIVITY: f32 = 0.5;
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Component)]
 pub struct BlockPreviewElement;
 
-/// This is a marker component used to indicate that the entity is the block
-/// model that is previewed in the Block Editor UI.
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Component)]
-pub struct BlockPreviewModel;
-
-/// This is a marker component used to indicate that the entity is the face
-/// selection gizmo model that is used to select block faces in the Block Editor
-/// UI.
+/// This is a marker component used to indicate that the entity is a stand-in
+/// block that holds the unsaved shape of the block being edited. The block
+/// preview renders this entity instead of the edited block itself, so that
+/// edits are visible before they are saved.
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Component)]
-pub struct BlockSelectedFaceModel;
+pub struct BlockPreviewProxy;
+
+/// This component is used to indicate that the entity is a face selection gizmo
+/// model that is used to select block faces in the Block Editor UI. One gizmo
+/// exists for each face of the block, and is only visible while that face is
+/// selected. The gizmos are spawned within the icon of the preview thumbnail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Component)]
+pub struct BlockSelectedFaceModel {
+    /// The face of the block that this gizmo is rendered on.
+    pub face: FaceDirection,
+}
 
 /// This resource contains information about the block preview widget used in
 /// the Block Editor UI screen. This resource may not exist when the game is not
 /// in the Editor game state.
 #[derive(Debug, Resource)]
 pub struct BlockPreviewWidget {
-    /// The image handle of the target render texture.
-    handle: Handle<Image>,
+    /// The thumbnail that the preview is rendered into. Holding it keeps the
+    /// thumbnail from being evicted.
+    thumbnail: BlockThumbnail,
+
+    /// The stand-in block that is rendered by the preview.
+    proxy: Entity,
 
     /// The current image pixel size.
     size: u32,
@@ -63,11 +71,11 @@ pub struct BlockPreviewWidget {
     /// The local rotation euler angles of the camera. Measured in radians.
     rotation: Vec2,
 
-    /// The currently active block entity.
-    active_block: Entity,
+    /// The shape of the block that is currently being previewed.
+    shape: BlockShape,
 
-    /// The currently selected face of the block, if any.
-    selected_face: Option<FaceDirection>,
+    /// The currently selected faces of the block.
+    selected_faces: HashSet<FaceDirection>,
 
     /// The currently hovered face of the block, if any.
     hover_face: Option<FaceDirection>,
@@ -94,24 +102,37 @@ impl BlockPreviewWidget {
         self.size
     }
 
-    /// Returns the current image handle.
-    pub fn get_handle(&self) -> Handle<Image> {
-        self.handle.clone()
+    /// Returns the egui texture of the rendered preview.
+    pub fn get_texture(&self) -> egui::TextureId {
+        self.thumbnail.texture
     }
 
-    /// Sets the active block entity, replacing the current active block.
-    pub fn set_active_block(&mut self, block: Entity) {
-        self.active_block = block;
+    /// Sets the shape of the previewed block. This does nothing if the shape
+    /// is unchanged.
+    pub fn set_shape(&mut self, shape: &BlockShape) {
+        if self.shape != *shape {
+            self.shape = shape.clone();
+        }
     }
 
-    /// Sets the currently selected face of the block, or `None` to deselect.
+    /// Replaces the current face selection with the given face, or `None` to
+    /// deselect all faces.
     pub fn set_selected_face(&mut self, face: Option<FaceDirection>) {
-        self.selected_face = face;
+        self.selected_faces.clear();
+        self.selected_faces.extend(face);
     }
 
-    /// Returns the currently selected face of the block, if any.
-    pub fn get_selected_face(&self) -> Option<FaceDirection> {
-        self.selected_face
+    /// Adds the given face to the current face selection, or removes it if it
+    /// is already selected.
+    pub fn toggle_selected_face(&mut self, face: FaceDirection) {
+        if !self.selected_faces.remove(&face) {
+            self.selected_faces.insert(face);
+        }
+    }
+
+    /// Returns the currently selected faces of the block.
+    pub fn get_selected_faces(&self) -> &HashSet<FaceDirection> {
+        &self.selected_faces
     }
 
     /// Returns the currently selected face of the block, if any.
@@ -129,220 +150,141 @@ impl BlockPreviewWidget {
 /// This system prepares the camera for rendering block previews in the Block
 /// Editor UI.
 pub fn prepare_camera(
-    block_finger: BlockFinder,
     asset_server: Res<AssetServer>,
-    mut images: ResMut<Assets<Image>>,
-    mut egui_textures: ResMut<EguiUserTextures>,
+    mut thumbnailer: BlockThumbnailer,
     mut commands: Commands,
 ) {
-    let size = Extent3d {
-        width: BLOCK_PREVIEW_SIZE,
-        height: BLOCK_PREVIEW_SIZE,
-        ..default()
-    };
-
     let background_color = Color::srgb(0.5, 0.5, 0.5);
-    let air_id = block_finger.find_air();
-
-    let mut image = Image {
-        texture_descriptor: TextureDescriptor {
-            label: None,
-            size,
-            dimension: TextureDimension::D2,
-            format: TextureFormat::Bgra8UnormSrgb,
-            mip_level_count: 1,
-            sample_count: 1,
-            usage: TextureUsages::TEXTURE_BINDING
-                | TextureUsages::COPY_DST
-                | TextureUsages::RENDER_ATTACHMENT,
-            view_formats: &[],
-        },
-        ..default()
-    };
-    image.resize(size);
 
-    let image_handle = images.add(image);
-    egui_textures.add_image(image_handle.clone());
+    let proxy = commands
+        .spawn((
+            BlockPreviewElement,
+            BlockPreviewProxy,
+            Name::new("Block Preview"),
+            BlockShape::None,
+            BlockModel::None,
+        ))
+        .id();
 
-    let widget = BlockPreviewWidget {
-        handle: image_handle.clone(),
-        size: BLOCK_PREVIEW_SIZE,
-        rotation: Vec2::new(45f32.to_radians(), -45f32.to_radians()),
-        active_block: air_id,
-        selected_face: None,
-        hover_face: None,
-        local_mouse_pos: Vec2::ZERO,
-    };
+    let thumbnail =
+        thumbnailer.request_with_background(proxy, BLOCK_PREVIEW_SIZE, background_color);
 
-    // camera
+    // Leave room around the block for the face selection gizmos.
     commands
-        .spawn((
-            BlockPreviewElement,
-            RenderLayers::layer(2),
-            Camera3dBundle {
-                camera: Camera {
-                    order: 1,
-                    clear_color: background_color.into(),
-                    target: RenderTarget::Image(image_handle),
-                    ..default()
-                },
-                projection: OrthographicProjection {
-                    near: -10.0,
-                    far: 10.0,
-                    scaling_mode: ScalingMode::Fixed {
-                        width: 3f32.sqrt() * BLOCK_PREVIEW_SCALE,
-                        height: 3f32.sqrt() * BLOCK_PREVIEW_SCALE,
-                    },
-                    viewport_origin: Vec2::new(0.5, 0.5),
-                    ..default()
-                }
-                .into(),
-                transform: Transform::from_rotation(widget.get_rotation()),
-                ..default()
+        .entity(thumbnail.camera)
+        .insert(Projection::Orthographic(OrthographicProjection {
+            near: -10.0,
+            far: 10.0,
+            scaling_mode: ScalingMode::Fixed {
+                width: 3f32.sqrt() * BLOCK_PREVIEW_SCALE,
+                height: 3f32.sqrt() * BLOCK_PREVIEW_SCALE,
             },
-        ))
-        .with_children(|parent| {
-            // light source
+            viewport_origin: Vec2::new(0.5, 0.5),
+            ..default()
+        }));
+
+    let gizmo_scene = asset_server.load(GltfAssetLabel::Scene(0).from_asset(GIZMO_FACESEL_MODEL));
+    commands.entity(thumbnail.icon).with_children(|parent| {
+        for face in FaceDirection::DIRECTIONS {
             parent.spawn((
-                BlockPreviewElement,
-                RenderLayers::layer(2),
-                DirectionalLightBundle {
-                    directional_light: DirectionalLight {
-                        illuminance: light_consts::lux::FULL_DAYLIGHT,
-                        ..default()
-                    },
-                    transform: Transform::from_rotation(Quat::from_euler(
-                        EulerRot::XYZ,
-                        -30f32.to_radians(),
-                        30f32.to_radians(),
-                        0f32.to_radians(),
-                    )),
+                BlockSelectedFaceModel { face },
+                SceneBundle {
+                    scene: gizmo_scene.clone(),
+                    transform: Transform::from_rotation(face.rotation_quat()),
+                    visibility: Visibility::Hidden,
                     ..default()
                 },
             ));
-        });
-
-    // block
-    commands.spawn((
-        BlockPreviewElement,
-        BlockPreviewModel,
-        RenderLayers::layer(2),
-        RenderedBlock { block: air_id },
-        PbrBundle {
-            transform: Transform::from_translation(Vec3::splat(-0.5)),
-            ..default()
-        },
-    ));
-
-    // face selection gizmo
-    commands.spawn((
-        BlockPreviewElement,
-        BlockSelectedFaceModel,
-        RenderLayers::layer(2),
-        SceneBundle {
-            scene: asset_server.load(GltfAssetLabel::Scene(0).from_asset(GIZMO_FACESEL_MODEL)),
-            ..default()
-        },
-    ));
+        }
+    });
 
-    commands.insert_resource(widget);
+    commands.insert_resource(BlockPreviewWidget {
+        thumbnail,
+        proxy,
+        size: BLOCK_PREVIEW_SIZE,
+        rotation: Vec2::new(45f32.to_radians(), -45f32.to_radians()),
+        shape: BlockShape::None,
+        selected_faces: HashSet::default(),
+        hover_face: None,
+        local_mouse_pos: Vec2::ZERO,
+    });
 }
 
-/// This system cleans up the camera used to render block previews in the Block
-/// Editor UI.
-pub fn cleanup_camera(
-    mut egui_textures: ResMut<EguiUserTextures>,
-    preview_handle: Res<BlockPreviewWidget>,
-    elements: Query<Entity, With<BlockPreviewElement>>,
-    mut commands: Commands,
-) {
+/// This system cleans up the block previews in the Block Editor UI. The
+/// preview thumbnail is released once the stand-in block is despawned.
+pub fn cleanup_camera(elements: Query<Entity, With<BlockPreviewElement>>, mut commands: Commands) {
     for entity in elements.iter() {
         commands.entity(entity).despawn_recursive();
     }
 
-    egui_textures.remove_image(&preview_handle.handle);
     commands.remove_resource::<BlockPreviewWidget>();
 }
 
-/// This system is called when the Block Editor UI screen is opened to enable
-/// the camera used to render block previews.
-pub fn enable_camera(mut camera: Query<&mut Camera, With<BlockPreviewElement>>) {
-    let Ok(mut cam) = camera.get_single_mut() else {
-        return;
-    };
-    cam.is_active = true;
-}
-
-/// This system is called when the Block Editor UI screen is closed to disable
-/// the camera used to render block previews.
-pub fn disable_camera(mut camera: Query<&mut Camera, With<BlockPreviewElement>>) {
-    let Ok(mut cam) = camera.get_single_mut() else {
-        return;
-    };
-    cam.is_active = false;
-}
-
 /// This system is called when the active block widget is changed in the Block
 /// Editor UI screen. This will update the block preview widget with the new
-/// model and visuals.
-#[allow(clippy::type_complexity)]
+/// model and visuals, and render the preview again if anything changed.
 pub fn update_preview(
     preview_widget: Res<BlockPreviewWidget>,
-    mut model: Query<&mut RenderedBlock, With<BlockPreviewModel>>,
-    mut camera: Query<
-        &mut Transform,
-        (
-            With<Camera>,
-            With<BlockPreviewElement>,
-            Without<BlockSelectedFaceModel>,
-        ),
-    >,
-    mut face_gizmo: Query<
-        (&mut Transform, &mut Visibility),
-        (With<BlockSelectedFaceModel>, Without<Camera>),
-    >,
+    mut proxy: Query<&mut BlockShape, With<BlockPreviewProxy>>,
+    mut cameras: Query<&mut Transform, With<Camera>>,
+    mut face_gizmos: Query<(&BlockSelectedFaceModel, &mut Visibility)>,
+    mut thumbnailer: BlockThumbnailer,
 ) {
-    let mut rendered_block = model.single_mut();
-    if rendered_block.block != preview_widget.active_block {
-        rendered_block.block = preview_widget.active_block;
-        trace!(
-            "Updating block preview widget with new block model {:?}",
-            preview_widget.active_block
-        );
+    let mut proxy_shape = proxy.single_mut();
+    if *proxy_shape != preview_widget.shape {
+        *proxy_shape = preview_widget.shape.clone();
+        trace!("Updating block preview widget with new block shape");
     }
 
-    camera.single_mut().rotation = preview_widget.get_rotation();
+    let mut changed = false;
 
-    let (mut face_transform, mut face_visibility) = face_gizmo.single_mut();
-    if let Some(face) = preview_widget.selected_face {
-        *face_visibility = Visibility::Inherited;
-        face_transform.rotation = face.rotation_quat();
-    } else {
-        *face_visibility = Visibility::Hidden;
+    if let Ok(mut transform) = cameras.get_mut(preview_widget.thumbnail.camera) {
+        let rotation = preview_widget.get_rotation();
+        if transform.rotation != rotation {
+            transform.rotation = rotation;
+            changed = true;
+        }
+    }
+
+    for (gizmo, mut face_visibility) in face_gizmos.iter_mut() {
+        let new_visibility = if preview_widget.selected_faces.contains(&gizmo.face) {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+
+        if *face_visibility != new_visibility {
+            *face_visibility = new_visibility;
+            changed = true;
+        }
+    }
+
+    if changed {
+        thumbnailer.invalidate(preview_widget.proxy);
     }
 }
 
 /// This system listens for when the scene bundle for the face selection gizmo
-/// model is loaded and updates the render layer of the gizmo model.
-pub fn update_gizmo_render_layer(
+/// model is loaded and renders the block preview again, so that the gizmos
+/// are shown.
+pub fn refresh_loaded_gizmos(
     mut asset_load_evs: EventReader<AssetEvent<Scene>>,
-    face_gizmo: Query<(Entity, &Handle<Scene>), With<BlockSelectedFaceModel>>,
-    children: Query<&Children>,
-    mut commands: Commands,
+    preview_widget: Option<Res<BlockPreviewWidget>>,
+    face_gizmos: Query<&Handle<Scene>, With<BlockSelectedFaceModel>>,
+    mut thumbnailer: BlockThumbnailer,
 ) {
+    let Some(preview_widget) = preview_widget else {
+        asset_load_evs.clear();
+        return;
+    };
+
     for ev in asset_load_evs.read() {
-        if let AssetEvent::LoadedWithDependencies { id } = ev {
-            let Ok((root_entity, handle)) = face_gizmo.get_single() else {
-                return;
-            };
-
-            if *id != handle.id() {
-                return;
-            }
-
-            for entity in children.iter_descendants(root_entity) {
-                commands.entity(entity).insert(RenderLayers::layer(2));
-            }
+        let AssetEvent::LoadedWithDependencies { id } = ev else {
+            continue;
+        };
+
+        if face_gizmos.iter().any(|handle| *id == handle.id()) {
+            thumbnailer.invalidate(preview_widget.proxy);
         }
     }
 }
@@ -350,9 +292,11 @@ pub fn update_gizmo_render_layer(
 /// This system updates the currently hovered face of the block preview widget.
 pub fn update_face_hover(
     mut widget: ResMut<BlockPreviewWidget>,
-    camera: Query<(&Camera, &GlobalTransform), With<BlockPreviewElement>>,
+    camera: Query<(&Camera, &GlobalTransform)>,
 ) {
-    let (cam, cam_transform) = camera.single();
+    let Ok((cam, cam_transform)) = camera.get(widget.thumbnail.camera) else {
+        return;
+    };
     let Some(pos) = cam.viewport_to_world(cam_transform, widget.local_mouse_pos) else {
         widget.hover_face = None;
         return;
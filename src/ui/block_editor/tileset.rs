@@ -37,8 +37,8 @@ impl egui::Widget for TileWidget {
                 max: (center + desired_size / 2.0).min(rect.max),
             },
             egui::Rect {
-                min: pos(self.tile_pos.transform_uv(Vec2::ZERO)),
-                max: pos(self.tile_pos.transform_uv(Vec2::ONE)),
+                min: pos(self.tile_pos.transform_uv(Vec2::ZERO, None)),
+                max: pos(self.tile_pos.transform_uv(Vec2::ONE, None)),
             },
             egui::Color32::WHITE,
         );
@@ -3,60 +3,67 @@
 
 use bevy::prelude::*;
 use bevy_egui::EguiContexts;
-use bevy_egui::egui::{self, Color32, Frame, Margin, Rounding, Stroke};
+use bevy_egui::egui::{self, Margin};
 
 use super::helper::{BlockEditHelper, Popup};
 use super::preview::BlockPreviewWidget;
 use crate::ui::EditorWindowState;
+use crate::ui::theme::EditorTheme;
 
 /// Builds the Block Editor UI screen.
 pub fn render(
     mut block_edit_helper: BlockEditHelper,
     mut preview_widget: ResMut<BlockPreviewWidget>,
+    theme: Res<EditorTheme>,
+    mut editor_window_state: ResMut<NextState<EditorWindowState>>,
     mut contexts: EguiContexts,
 ) {
     block_edit_helper.initialize(&mut contexts);
 
-    let block_preview_texture_id = contexts.image_id(&preview_widget.get_handle()).unwrap();
+    let block_preview_texture_id = preview_widget.get_texture();
     let tile_list_texture_id = block_edit_helper
         .get_selected_tileset_image()
-        .map(|handle| contexts.image_id(handle).unwrap());
+        .map(|handle| {
+            contexts
+                .image_id(handle)
+                .unwrap_or_else(|| contexts.add_image(handle.clone_weak()))
+        });
+    let tileset_textures = block_edit_helper.tileset_textures(&mut contexts);
 
     let ctx = contexts.ctx_mut();
+    block_edit_helper.handle_save_shortcut(ctx);
+    block_edit_helper.handle_block_list_keys(ctx);
 
     egui::SidePanel::left("block_list_panel")
         .default_width(200.0)
         .min_width(100.0)
         .resizable(true)
-        .frame(Frame {
-            fill: Color32::from_gray(20),
-            ..default()
-        })
+        .frame(theme.panel_frame())
         .show(ctx, |ui| {
             if block_edit_helper.is_popup_open() {
                 ui.disable();
             }
 
+            block_edit_helper.edit_tag_filter(ui);
+
             egui::ScrollArea::vertical()
                 .id_salt("block_list_scroll")
                 .show(ui, |ui| {
                     ui.set_width(ui.available_width());
-                    block_edit_helper.edit_block_list(ui);
+                    block_edit_helper.edit_block_list(ui, &tileset_textures);
                 });
         });
 
     egui::SidePanel::right("tileset_panel")
         .resizable(false)
-        .frame(Frame {
-            fill: Color32::from_gray(20),
-            ..default()
-        })
+        .frame(theme.panel_frame())
         .show(ctx, |ui| {
             if block_edit_helper.is_popup_open() {
                 ui.disable();
             }
 
             block_edit_helper.tileset_list_combobox(ui);
+            block_edit_helper.edit_face_options(ui, preview_widget.get_selected_faces());
 
             egui::ScrollArea::vertical()
                 .id_salt("tileset_scroll")
@@ -71,8 +78,8 @@ pub fn render(
                         ..default()
                     }
                     .show(ui, |ui| {
-                        let selected_face = preview_widget.get_selected_face();
-                        block_edit_helper.tile_list(ui, tile_list_texture_id, selected_face);
+                        let selected_faces = preview_widget.get_selected_faces();
+                        block_edit_helper.tile_list(ui, tile_list_texture_id, selected_faces);
                     });
                 });
         });
@@ -84,6 +91,24 @@ pub fn render(
             }
 
             block_edit_helper.edit_name(ui);
+            block_edit_helper.edit_tags(ui);
+            block_edit_helper.edit_sounds(ui);
+            block_edit_helper.discard_changes_button(ui);
+            block_edit_helper.export_icon_button(ui);
+
+            if block_edit_helper.reveal_in_world_button(ui) {
+                if block_edit_helper.is_dirty() {
+                    block_edit_helper.prompt_unsaved_on_close();
+                } else {
+                    editor_window_state.set(EditorWindowState::MapEditor);
+                    info!("Closed Block Editor UI window.");
+                }
+            }
+
+            block_edit_helper.edit_shape(ui);
+            block_edit_helper.edit_transparency(ui);
+            block_edit_helper.edit_occlusion(ui);
+            block_edit_helper.edit_collision(ui);
 
             let preview_size = preview_widget.get_size() as f32;
             let block_preview_response = ui.image(egui::load::SizedTexture::new(
@@ -110,8 +135,14 @@ pub fn render(
                 .clicked()
             {
                 let face = preview_widget.get_hovered_face();
-                preview_widget.set_selected_face(face);
-                debug!("Selected face: {:?}", face);
+                let shift = ui.input(|input| input.modifiers.shift);
+
+                match (shift, face) {
+                    (true, Some(face)) => preview_widget.toggle_selected_face(face),
+                    _ => preview_widget.set_selected_face(face),
+                }
+
+                debug!("Selected faces: {:?}", preview_widget.get_selected_faces());
             }
         })
         .response
@@ -124,45 +155,75 @@ pub fn render(
         Popup::None => {}
 
         Popup::UnsavedChanges { new_block } => {
-            egui::Window::new("Unsaved Changes")
-                .resizable(false)
-                .collapsible(false)
-                .title_bar(false)
-                .fixed_size(popup_size)
-                .default_pos(popup_pos)
-                .frame(Frame {
-                    inner_margin: Margin::same(10.0),
-                    fill: Color32::from_gray(35),
-                    rounding: Rounding::same(6.0),
-                    stroke: Stroke {
-                        width: 3.0,
-                        color: Color32::from_gray(100),
-                    },
-                    ..default()
-                })
-                .show(ctx, |ui| {
-                    ui.heading("Warning");
-                    ui.label("You have unsaved changes. Do you want to save them?");
-
-                    ui.with_layout(egui::Layout::right_to_left(egui::Align::BOTTOM), |ui| {
-                        ui.set_row_height(ui.available_height());
-
-                        if ui.button("Discard").clicked() {
-                            block_edit_helper.select_block(new_block);
-                            block_edit_helper.close_popup();
-                        }
-
-                        if ui.button("Save").clicked() {
-                            block_edit_helper.save_block();
-                            block_edit_helper.select_block(new_block);
-                            block_edit_helper.close_popup();
-                        }
-                    });
+            popup_window("Unsaved Changes", &theme, popup_pos, popup_size).show(ctx, |ui| {
+                ui.heading("Warning");
+                ui.label("You have unsaved changes. Do you want to save them?");
+
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::BOTTOM), |ui| {
+                    ui.set_row_height(ui.available_height());
+
+                    if ui.button("Discard").clicked() {
+                        block_edit_helper.select_block(new_block);
+                        block_edit_helper.close_popup();
+                    }
+
+                    if ui.button("Save").clicked() {
+                        block_edit_helper.save_block();
+                        block_edit_helper.select_block(new_block);
+                        block_edit_helper.close_popup();
+                    }
+                });
+            });
+        }
+
+        Popup::UnsavedChangesOnClose => {
+            popup_window("Unsaved Changes", &theme, popup_pos, popup_size).show(ctx, |ui| {
+                ui.heading("Warning");
+                ui.label("You have unsaved changes. Do you want to save them before closing?");
+
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::BOTTOM), |ui| {
+                    ui.set_row_height(ui.available_height());
+
+                    if ui.button("Cancel").clicked() {
+                        block_edit_helper.close_popup();
+                    }
+
+                    if ui.button("Discard").clicked() {
+                        block_edit_helper.select_block(block_edit_helper.selected_block());
+                        block_edit_helper.close_popup();
+                        editor_window_state.set(EditorWindowState::MapEditor);
+                        info!("Closed Block Editor UI window.");
+                    }
+
+                    if ui.button("Save").clicked() {
+                        block_edit_helper.save_block();
+                        block_edit_helper.close_popup();
+                        editor_window_state.set(EditorWindowState::MapEditor);
+                        info!("Closed Block Editor UI window.");
+                    }
                 });
+            });
         }
     }
 
-    preview_widget.set_active_block(block_edit_helper.selected_block());
+    preview_widget.set_shape(block_edit_helper.shape());
+}
+
+/// Creates a fixed-size popup window with the given title, using the popup
+/// frame of the editor theme.
+fn popup_window<'a>(
+    title: &'a str,
+    theme: &EditorTheme,
+    pos: egui::Pos2,
+    size: (f32, f32),
+) -> egui::Window<'a> {
+    egui::Window::new(title)
+        .resizable(false)
+        .collapsible(false)
+        .title_bar(false)
+        .fixed_size(size)
+        .default_pos(pos)
+        .frame(theme.popup_frame())
 }
 
 /// This system transitions to the Block Editor UI screen.
@@ -177,18 +238,72 @@ pub fn open(
 }
 
 /// This system closes the Block Editor UI screen and returns to the Map Editor.
+/// If the current block has unsaved changes, the user is asked whether to save
+/// them first.
 pub fn close(
-    block_edit_helper: BlockEditHelper,
+    mut block_edit_helper: BlockEditHelper,
     keyboard_input: Res<ButtonInput<KeyCode>>,
     mut editor_window_state: ResMut<NextState<EditorWindowState>>,
 ) {
     if keyboard_input.just_pressed(KeyCode::F1) || keyboard_input.just_pressed(KeyCode::Escape) {
-        if block_edit_helper.is_popup_open() {
-            // Do not close the window if a popup is open.
-            return;
-        }
+        request_close(&mut block_edit_helper, &mut editor_window_state);
+    }
+}
 
-        editor_window_state.set(EditorWindowState::MapEditor);
-        info!("Closed Block Editor UI window.");
+/// This system renders a button in the corner of the editor HUD that toggles
+/// the Block Editor UI screen, mirroring the F1 shortcut. The button is
+/// highlighted while the Block Editor is open.
+pub fn toggle_button(
+    mut block_edit_helper: BlockEditHelper,
+    current_window_state: Res<State<EditorWindowState>>,
+    mut editor_window_state: ResMut<NextState<EditorWindowState>>,
+    theme: Res<EditorTheme>,
+    mut contexts: EguiContexts,
+) {
+    let active = *current_window_state.get() == EditorWindowState::BlockEditor;
+    let mut clicked = false;
+
+    egui::Area::new(egui::Id::new("block_editor_toggle"))
+        .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-8.0, 8.0))
+        .order(egui::Order::Foreground)
+        .show(contexts.ctx_mut(), |ui| {
+            theme.popup_frame().show(ui, |ui| {
+                clicked = ui
+                    .selectable_label(active, "Block Editor")
+                    .on_hover_text("Toggle the Block Editor (F1)")
+                    .clicked();
+            });
+        });
+
+    if !clicked {
+        return;
+    }
+
+    if active {
+        request_close(&mut block_edit_helper, &mut editor_window_state);
+    } else {
+        editor_window_state.set(EditorWindowState::BlockEditor);
+        info!("Opened Block Editor UI window.");
+    }
+}
+
+/// Closes the Block Editor UI screen and returns to the Map Editor. If the
+/// current block has unsaved changes, the user is asked whether to save them
+/// first. Nothing happens while a popup is open.
+fn request_close(
+    block_edit_helper: &mut BlockEditHelper,
+    editor_window_state: &mut NextState<EditorWindowState>,
+) {
+    if block_edit_helper.is_popup_open() {
+        // Do not close the window if a popup is open.
+        return;
     }
+
+    if block_edit_helper.is_dirty() {
+        block_edit_helper.prompt_unsaved_on_close();
+        return;
+    }
+
+    editor_window_state.set(EditorWindowState::MapEditor);
+    info!("Closed Block Editor UI window.");
 }
@@ -0,0 +1,305 @@
+//! This module implements the command palette, a searchable overlay that lists
+//! all named editor actions and runs the selected one.
+//!
+//! Actions are registered with [`EditorActionsAppExt::register_editor_action`],
+//! which allows any plugin to add its own entries to the palette.
+
+use bevy::ecs::system::SystemId;
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, egui};
+
+use super::hud::EditorHud;
+use super::theme::EditorTheme;
+use crate::gamestate::GameState;
+
+/// The plugin that adds the command palette to the app.
+pub struct CommandPalettePlugin;
+impl Plugin for CommandPalettePlugin {
+    fn build(&self, app_: &mut App) {
+        app_.init_resource::<EditorActions>()
+            .init_resource::<CommandPalette>()
+            .add_systems(
+                Update,
+                (
+                    toggle_palette.run_if(in_state(GameState::Editor)),
+                    render_palette
+                        .after_ignore_deferred(toggle_palette)
+                        .run_if(in_state(GameState::Editor))
+                        .run_if(|palette: Res<CommandPalette>| palette.visible)
+                        .run_if(EditorHud::is_visible),
+                ),
+            )
+            .add_systems(OnExit(GameState::Editor), close_palette);
+    }
+}
+
+/// A named editor action that can be run from the command palette.
+#[derive(Debug, Clone)]
+pub struct EditorAction {
+    /// The name of the action, as shown in the command palette.
+    pub name: String,
+
+    /// The one-shot system that is run when the action is selected.
+    pub system: SystemId,
+}
+
+/// This resource is the registry of all editor actions that are listed in the
+/// command palette, in order of registration.
+#[derive(Debug, Default, Resource)]
+pub struct EditorActions {
+    /// The registered actions.
+    actions: Vec<EditorAction>,
+}
+
+impl EditorActions {
+    /// Returns an iterator over all registered actions.
+    pub fn iter(&self) -> impl Iterator<Item = &EditorAction> {
+        self.actions.iter()
+    }
+
+    /// Returns all actions that match the given search query, ordered from the
+    /// best match to the worst match. Actions with an equal score are kept in
+    /// order of registration.
+    pub fn search(&self, query: &str) -> Vec<&EditorAction> {
+        let mut matches: Vec<(u32, &EditorAction)> = self
+            .actions
+            .iter()
+            .filter_map(|action| Some((fuzzy_score(query, &action.name)?, action)))
+            .collect();
+
+        matches.sort_by(|(a, _), (b, _)| b.cmp(a));
+        matches.into_iter().map(|(_, action)| action).collect()
+    }
+}
+
+/// An extension trait for registering editor actions on the app.
+pub trait EditorActionsAppExt {
+    /// Registers a new editor action with the given name. The given system is
+    /// run once each time the action is selected in the command palette.
+    fn register_editor_action<M>(
+        &mut self,
+        name: impl Into<String>,
+        system: impl IntoSystem<(), (), M> + 'static,
+    ) -> &mut Self;
+}
+
+impl EditorActionsAppExt for App {
+    fn register_editor_action<M>(
+        &mut self,
+        name: impl Into<String>,
+        system: impl IntoSystem<(), (), M> + 'static,
+    ) -> &mut Self {
+        let system = self.world_mut().register_system(system);
+        self.world_mut()
+            .get_resource_or_insert_with(EditorActions::default)
+            .actions
+            .push(EditorAction {
+                name: name.into(),
+                system,
+            });
+        self
+    }
+}
+
+/// This resource stores the state of the command palette.
+#[derive(Debug, Default, Resource)]
+pub struct CommandPalette {
+    /// Whether the command palette is currently visible.
+    pub visible: bool,
+
+    /// The current search query.
+    pub query: String,
+
+    /// The index of the selected action within the search results.
+    pub selected: usize,
+
+    /// Whether the search field should be focused on the next frame.
+    focus: bool,
+}
+
+impl CommandPalette {
+    /// Opens the command palette with an empty search query.
+    pub fn open(&mut self) {
+        self.visible = true;
+        self.query.clear();
+        self.selected = 0;
+        self.focus = true;
+    }
+
+    /// Closes the command palette.
+    pub fn close(&mut self) {
+        self.visible = false;
+    }
+}
+
+/// Scores how well the given search query matches the given text. Returns
+/// `None` if the characters of the query do not all appear in the text, in
+/// order. Matching is case-insensitive, and whitespace in the query is ignored.
+///
+/// Higher scores indicate better matches. Characters that start a word, or
+/// directly follow the previously matched character, score higher.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<u32> {
+    let text: Vec<char> = text.chars().collect();
+    let mut score = 0;
+    let mut index = 0;
+    let mut last_match: Option<usize> = None;
+
+    for query_char in query.chars().filter(|c| !c.is_whitespace()) {
+        let offset = text[index ..]
+            .iter()
+            .position(|c| c.to_lowercase().eq(query_char.to_lowercase()))?;
+        let pos = index + offset;
+
+        score += 1;
+
+        if pos == 0 || !text[pos - 1].is_alphanumeric() {
+            score += 10;
+        }
+
+        if last_match.is_some_and(|last| last + 1 == pos) {
+            score += 5;
+        }
+
+        last_match = Some(pos);
+        index = pos + 1;
+    }
+
+    Some(score)
+}
+
+/// This system toggles the command palette when Ctrl+Shift+P is pressed.
+fn toggle_palette(keyboard_input: Res<ButtonInput<KeyCode>>, mut palette: ResMut<CommandPalette>) {
+    let ctrl = keyboard_input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    let shift = keyboard_input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+
+    if !ctrl || !shift || !keyboard_input.just_pressed(KeyCode::KeyP) {
+        return;
+    }
+
+    if palette.visible {
+        palette.close();
+    } else {
+        palette.open();
+    }
+}
+
+/// This system renders the command palette. The search results can be
+/// navigated with the arrow keys, and the selected action is run when Enter is
+/// pressed or the action is clicked.
+fn render_palette(
+    actions: Res<EditorActions>,
+    theme: Res<EditorTheme>,
+    mut palette: ResMut<CommandPalette>,
+    mut contexts: EguiContexts,
+    mut commands: Commands,
+) {
+    let palette = &mut *palette;
+    let matches = actions.search(&palette.query);
+    let mut run = None;
+    let mut close = false;
+
+    egui::Window::new("Command Palette")
+        .title_bar(false)
+        .resizable(false)
+        .collapsible(false)
+        .anchor(egui::Align2::CENTER_TOP, egui::vec2(0.0, 48.0))
+        .default_width(400.0)
+        .frame(theme.popup_frame())
+        .show(contexts.ctx_mut(), |ui| {
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut palette.query)
+                    .hint_text("Type a command...")
+                    .desired_width(f32::INFINITY),
+            );
+
+            if palette.focus {
+                response.request_focus();
+                palette.focus = false;
+            }
+
+            if response.changed() {
+                palette.selected = 0;
+            }
+
+            let (up, down, enter, escape) = ui.input(|input| {
+                (
+                    input.key_pressed(egui::Key::ArrowUp),
+                    input.key_pressed(egui::Key::ArrowDown),
+                    input.key_pressed(egui::Key::Enter),
+                    input.key_pressed(egui::Key::Escape),
+                )
+            });
+
+            if up {
+                palette.selected = palette.selected.saturating_sub(1);
+            }
+
+            if down {
+                palette.selected = (palette.selected + 1).min(matches.len().saturating_sub(1));
+            }
+
+            ui.separator();
+
+            if matches.is_empty() {
+                ui.label("No matching commands.");
+            }
+
+            egui::ScrollArea::vertical()
+                .max_height(300.0)
+                .show(ui, |ui| {
+                    for (index, action) in matches.iter().enumerate() {
+                        let selected = index == palette.selected;
+                        let label = ui.selectable_label(selected, &action.name);
+
+                        if selected && (up || down) {
+                            label.scroll_to_me(None);
+                        }
+
+                        if label.clicked() {
+                            run = Some(action.system);
+                        }
+                    }
+                });
+
+            if enter {
+                run = matches.get(palette.selected).map(|action| action.system);
+            }
+
+            close = escape;
+        });
+
+    if let Some(system) = run {
+        commands.run_system(system);
+        close = true;
+    }
+
+    if close {
+        palette.close();
+    }
+}
+
+/// This system closes the command palette when leaving the editor.
+fn close_palette(mut palette: ResMut<CommandPalette>) {
+    palette.close();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fuzzy_score_matches_subsequences() {
+        assert!(fuzzy_score("obe", "Open Block Editor").is_some());
+        assert!(fuzzy_score("OPEN block", "Open Block Editor").is_some());
+        assert!(fuzzy_score("", "Open Block Editor").is_some());
+        assert!(fuzzy_score("eob", "Open Block Editor").is_none());
+        assert!(fuzzy_score("xyz", "Open Block Editor").is_none());
+    }
+
+    #[test]
+    fn fuzzy_score_prefers_word_starts() {
+        let word_starts = fuzzy_score("be", "Open Block Editor").unwrap();
+        let inner = fuzzy_score("be", "Reload Block Assets").unwrap();
+        assert!(word_starts > inner);
+    }
+}
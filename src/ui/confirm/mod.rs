@@ -0,0 +1,114 @@
+//! This module implements the confirmation dialog, which asks the user to
+//! confirm a destructive action before it is run. Any system can open the
+//! dialog through the [`ConfirmDialog`] resource.
+
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, egui};
+
+use super::theme::EditorTheme;
+use crate::gamestate::GameState;
+
+/// The size of the confirmation dialog.
+const DIALOG_SIZE: (f32, f32) = (250.0, 100.0);
+
+/// The plugin that adds the confirmation dialog to the app.
+pub struct ConfirmDialogPlugin;
+impl Plugin for ConfirmDialogPlugin {
+    fn build(&self, app_: &mut App) {
+        app_.init_resource::<ConfirmDialog>()
+            .add_systems(
+                Update,
+                render_dialog
+                    .run_if(in_state(GameState::Editor))
+                    .run_if(ConfirmDialog::is_open),
+            )
+            .add_systems(OnExit(GameState::Editor), close_dialog);
+    }
+}
+
+/// An action that is run once the user confirms it.
+type ConfirmCallback = Box<dyn FnOnce(&mut World) + Send + Sync>;
+
+/// This resource stores the confirmation dialog that is currently open, if
+/// any. Only one dialog can be open at a time.
+#[derive(Default, Resource)]
+pub struct ConfirmDialog {
+    /// The message and action of the open dialog.
+    pending: Option<(String, ConfirmCallback)>,
+}
+
+impl ConfirmDialog {
+    /// Opens the dialog with the given message. The given action is only run if
+    /// the user confirms it. Any dialog that is already open is replaced, and
+    /// its action is discarded.
+    pub fn open(
+        &mut self,
+        message: impl Into<String>,
+        action: impl FnOnce(&mut World) + Send + Sync + 'static,
+    ) {
+        self.pending = Some((message.into(), Box::new(action)));
+    }
+
+    /// Closes the dialog without running its action.
+    pub fn close(&mut self) {
+        self.pending = None;
+    }
+
+    /// Returns true if a dialog is currently open.
+    pub fn is_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// A run condition that returns true if a dialog is currently open.
+    pub fn is_open(dialog: Res<ConfirmDialog>) -> bool {
+        dialog.is_pending()
+    }
+}
+
+/// This system renders the confirmation dialog. Once confirmed, the action of
+/// the dialog is queued as a command.
+fn render_dialog(
+    theme: Res<EditorTheme>,
+    mut dialog: ResMut<ConfirmDialog>,
+    mut contexts: EguiContexts,
+    mut commands: Commands,
+) {
+    let Some((message, _)) = &dialog.pending else {
+        return;
+    };
+
+    let mut confirm = false;
+    let mut cancel = false;
+
+    egui::Window::new("Confirm")
+        .resizable(false)
+        .collapsible(false)
+        .title_bar(false)
+        .fixed_size(DIALOG_SIZE)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .order(egui::Order::Foreground)
+        .frame(theme.popup_frame())
+        .show(contexts.ctx_mut(), |ui| {
+            ui.heading("Are you sure?");
+            ui.label(message.as_str());
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::BOTTOM), |ui| {
+                ui.set_row_height(ui.available_height());
+                cancel = ui.button("Cancel").clicked();
+                confirm = ui.button("Confirm").clicked();
+            });
+        });
+
+    if cancel {
+        dialog.close();
+    } else if confirm {
+        if let Some((_, action)) = dialog.pending.take() {
+            commands.add(move |world: &mut World| action(world));
+        }
+    }
+}
+
+/// This system closes the confirmation dialog when leaving the editor.
+fn close_dialog(mut dialog: ResMut<ConfirmDialog>) {
+    dialog.close();
+}
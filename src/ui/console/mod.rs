@@ -0,0 +1,149 @@
+//! This module implements the script console, an editor panel that shows the
+//! output of `print`, the errors of the active AwgenScript engine, and warnings
+//! about script commands that could not be applied, so that script authors get
+//! immediate feedback without a terminal.
+
+use bevy::prelude::*;
+use bevy_egui::EguiContexts;
+use bevy_egui::egui::{self, Color32, RichText};
+
+use super::UiKeyboardFocus;
+use super::command_palette::EditorActionsAppExt;
+use super::hud::EditorHud;
+use super::theme::EditorTheme;
+use crate::gamestate::GameState;
+use crate::logic::commands::ScriptLogLevel;
+use crate::logic::resources::ScriptConsole;
+
+/// The color of info messages in the script console.
+const INFO_COLOR: Color32 = Color32::from_rgb(210, 210, 210);
+
+/// The color of warning messages in the script console.
+const WARN_COLOR: Color32 = Color32::from_rgb(240, 200, 60);
+
+/// The color of error messages in the script console.
+const ERROR_COLOR: Color32 = Color32::from_rgb(235, 90, 80);
+
+/// The plugin that adds the script console panel to the app.
+pub struct ScriptConsolePlugin;
+impl Plugin for ScriptConsolePlugin {
+    fn build(&self, app_: &mut App) {
+        app_.init_resource::<ScriptConsolePanel>()
+            .add_systems(
+                Update,
+                (
+                    toggle_console
+                        .run_if(in_state(GameState::Editor))
+                        .run_if(UiKeyboardFocus::is_free),
+                    render_console
+                        .after_ignore_deferred(toggle_console)
+                        .run_if(in_state(GameState::Editor))
+                        .run_if(|panel: Res<ScriptConsolePanel>| panel.visible)
+                        .run_if(EditorHud::is_visible),
+                ),
+            )
+            .register_editor_action(
+                "Toggle Script Console",
+                |mut panel: ResMut<ScriptConsolePanel>| {
+                    panel.visible = !panel.visible;
+                },
+            );
+    }
+}
+
+/// This resource stores the state of the script console panel.
+#[derive(Debug, Resource)]
+pub struct ScriptConsolePanel {
+    /// Whether the panel is currently visible.
+    pub visible: bool,
+
+    /// Whether info messages are shown.
+    pub show_info: bool,
+
+    /// Whether warning messages are shown.
+    pub show_warn: bool,
+
+    /// Whether error messages are shown.
+    pub show_error: bool,
+}
+
+impl Default for ScriptConsolePanel {
+    fn default() -> Self {
+        Self {
+            visible: false,
+            show_info: true,
+            show_warn: true,
+            show_error: true,
+        }
+    }
+}
+
+impl ScriptConsolePanel {
+    /// Checks whether messages of the given level are shown.
+    pub fn shows(&self, level: ScriptLogLevel) -> bool {
+        match level {
+            ScriptLogLevel::Info => self.show_info,
+            ScriptLogLevel::Warn => self.show_warn,
+            ScriptLogLevel::Error => self.show_error,
+        }
+    }
+}
+
+/// This system toggles the script console when the backquote key is pressed.
+fn toggle_console(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut panel: ResMut<ScriptConsolePanel>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Backquote) {
+        panel.visible = !panel.visible;
+        info!("Script console visible: {}", panel.visible);
+    }
+}
+
+/// This system renders the script console panel.
+fn render_console(
+    mut panel: ResMut<ScriptConsolePanel>,
+    mut console: ResMut<ScriptConsole>,
+    theme: Res<EditorTheme>,
+    mut contexts: EguiContexts,
+) {
+    let mut open = panel.visible;
+
+    egui::Window::new("Script Console")
+        .frame(theme.popup_frame())
+        .open(&mut open)
+        .default_size(egui::vec2(480.0, 240.0))
+        .resizable(true)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut panel.show_info, "Info");
+                ui.checkbox(&mut panel.show_warn, "Warnings");
+                ui.checkbox(&mut panel.show_error, "Errors");
+
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.button("Clear").clicked() {
+                        console.clear();
+                    }
+                });
+            });
+
+            ui.separator();
+
+            egui::ScrollArea::vertical()
+                .auto_shrink([false, false])
+                .stick_to_bottom(true)
+                .show(ui, |ui| {
+                    for line in console.lines().filter(|line| panel.shows(line.level)) {
+                        let color = match line.level {
+                            ScriptLogLevel::Info => INFO_COLOR,
+                            ScriptLogLevel::Warn => WARN_COLOR,
+                            ScriptLogLevel::Error => ERROR_COLOR,
+                        };
+
+                        ui.label(RichText::new(&line.message).monospace().color(color));
+                    }
+                });
+        });
+
+    panel.visible = open;
+}
@@ -0,0 +1,356 @@
+//! This module implements exporting 3D icons to PNG files, such as rendering a
+//! block from an isometric angle for item icons and documentation.
+//!
+//! Each export requests a block thumbnail from the [`BlockThumbnailer`], waits
+//! a few frames for the content and its textures to load, then copies the
+//! rendered image back from the GPU and writes it to disk. The thumbnail is
+//! released once the export is finished.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::mpsc::{Receiver, Sender, channel};
+
+use bevy::prelude::*;
+use bevy::render::render_asset::{RenderAssetUsages, RenderAssets};
+use bevy::render::render_resource::{
+    BufferDescriptor,
+    BufferUsages,
+    CommandEncoderDescriptor,
+    Extent3d,
+    ImageCopyBuffer,
+    ImageDataLayout,
+    Maintain,
+    MapMode,
+    TextureDimension,
+    TextureFormat,
+};
+use bevy::render::renderer::{RenderDevice, RenderQueue};
+use bevy::render::texture::GpuImage;
+use bevy::render::{Extract, ExtractSchedule, Render, RenderApp, RenderSet};
+use uuid::Uuid;
+
+use super::thumbnail::{BlockThumbnail, BlockThumbnailer};
+
+/// The folder within the project asset folder that block icons are exported
+/// to.
+pub const BLOCK_ICON_FOLDER: &str = "icons";
+
+/// The default image size of exported block icons, in pixels.
+pub const BLOCK_ICON_DEFAULT_SIZE: u32 = 256;
+
+/// The largest allowed image size of exported icons, in pixels.
+pub const ICON_EXPORT_MAX_SIZE: u32 = 2048;
+
+/// The number of frames an icon is rendered before it is exported. This gives
+/// the content of the icon, such as block models and tileset textures, time to
+/// finish loading.
+pub const ICON_EXPORT_DELAY_FRAMES: u32 = 10;
+
+/// The number of frames after which an export that has not received its image
+/// from the GPU is abandoned.
+pub const ICON_EXPORT_TIMEOUT_FRAMES: u32 = ICON_EXPORT_DELAY_FRAMES + 60;
+
+/// This event requests that the given block is rendered to a PNG file.
+#[derive(Debug, Clone, Event)]
+pub struct ExportBlockIcon {
+    /// The block to render.
+    pub block: Entity,
+
+    /// The image size of the icon, in pixels.
+    pub size: u32,
+
+    /// The file that the icon is written to.
+    pub path: PathBuf,
+
+    /// The background color of the icon, or `None` for a transparent
+    /// background.
+    pub background: Option<Color>,
+}
+
+/// Gets the file that the icon of the block with the given uuid is exported to.
+pub fn block_icon_file(asset_folder: &Path, uuid: Uuid) -> PathBuf {
+    asset_folder
+        .join(BLOCK_ICON_FOLDER)
+        .join(format!("{uuid}.png"))
+}
+
+/// Gets the asset path of the exported icon of the block with the given uuid.
+pub fn block_icon_asset_path(uuid: Uuid) -> String {
+    format!("project://{BLOCK_ICON_FOLDER}/{uuid}.png")
+}
+
+/// An icon that is currently being exported.
+#[derive(Debug)]
+struct IconExportJob {
+    /// The thumbnail that is being rendered.
+    thumbnail: BlockThumbnail,
+
+    /// The image size of the icon, in pixels.
+    size: u32,
+
+    /// The file that the icon is written to.
+    path: PathBuf,
+
+    /// The number of frames the icon has been rendered for.
+    frames: u32,
+}
+
+/// This resource contains all icons that are currently being exported.
+#[derive(Debug, Default, Resource)]
+pub struct IconExports {
+    /// The active export jobs.
+    jobs: Vec<IconExportJob>,
+}
+
+/// The pixels of an icon that were copied back from the GPU.
+#[derive(Debug)]
+struct IconReadback {
+    /// The image that was copied.
+    image: AssetId<Image>,
+
+    /// The pixel data of the image, in the format of the icon render target,
+    /// without any row padding.
+    data: Vec<u8>,
+}
+
+/// This resource receives the icon pixels that are copied back from the GPU
+/// by the render world. It only exists if rendering is enabled.
+#[derive(Resource)]
+pub struct IconExportReceiver(Mutex<Receiver<IconReadback>>);
+
+/// This render world resource sends the icon pixels that are copied back from
+/// the GPU to the main world.
+#[derive(Resource)]
+struct IconExportSender(Sender<IconReadback>);
+
+/// This render world resource contains the icons that should be copied back
+/// from the GPU during the current frame.
+#[derive(Debug, Default, Resource)]
+struct ExtractedIconExports {
+    /// The images to copy, along with their size in pixels.
+    images: Vec<(AssetId<Image>, u32)>,
+}
+
+/// The plugin that adds icon exporting to the app. Exporting is not available
+/// if the app has no render world, such as when running headless.
+pub struct IconExportPlugin;
+impl Plugin for IconExportPlugin {
+    fn build(&self, app_: &mut App) {
+        app_.add_event::<ExportBlockIcon>()
+            .init_resource::<IconExports>();
+
+        let Some(render_app) = app_.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        let (sender, receiver) = channel();
+        render_app
+            .insert_resource(IconExportSender(sender))
+            .init_resource::<ExtractedIconExports>()
+            .add_systems(ExtractSchedule, extract_icon_exports)
+            .add_systems(
+                Render,
+                read_icon_exports
+                    .after(RenderSet::Render)
+                    .before(RenderSet::Cleanup),
+            );
+
+        app_.insert_resource(IconExportReceiver(Mutex::new(receiver)))
+            .add_systems(
+                Update,
+                (
+                    start_icon_exports,
+                    finish_icon_exports.after_ignore_deferred(start_icon_exports),
+                ),
+            );
+    }
+}
+
+/// This system requests a block thumbnail for every requested block icon
+/// export.
+fn start_icon_exports(
+    mut export_events: EventReader<ExportBlockIcon>,
+    mut exports: ResMut<IconExports>,
+    mut thumbnailer: BlockThumbnailer,
+) {
+    for ev in export_events.read() {
+        let size = ev.size.clamp(1, ICON_EXPORT_MAX_SIZE);
+        let background = ev.background.unwrap_or(Color::NONE);
+        let thumbnail = thumbnailer.request_with_background(ev.block, size, background);
+
+        exports.jobs.push(IconExportJob {
+            thumbnail,
+            size,
+            path: ev.path.clone(),
+            frames: 0,
+        });
+    }
+}
+
+/// This system writes all icons that were copied back from the GPU to their
+/// files, and releases the thumbnails of all finished or abandoned exports.
+fn finish_icon_exports(
+    receiver: Res<IconExportReceiver>,
+    mut exports: ResMut<IconExports>,
+    mut thumbnailer: BlockThumbnailer,
+) {
+    let readbacks: Vec<IconReadback> = receiver.0.lock().unwrap().try_iter().collect();
+
+    for readback in readbacks {
+        let Some(index) = exports
+            .jobs
+            .iter()
+            .position(|job| job.thumbnail.image.id() == readback.image)
+        else {
+            continue;
+        };
+
+        let job = exports.jobs.swap_remove(index);
+        save_icon(&job, readback.data);
+        release_thumbnail(job, &mut thumbnailer);
+    }
+
+    let mut index = 0;
+    while index < exports.jobs.len() {
+        let job = &mut exports.jobs[index];
+        job.frames += 1;
+
+        if job.frames > ICON_EXPORT_TIMEOUT_FRAMES {
+            warn!("Timed out while exporting icon: {}", job.path.display());
+            let job = exports.jobs.swap_remove(index);
+            release_thumbnail(job, &mut thumbnailer);
+            continue;
+        }
+
+        index += 1;
+    }
+}
+
+/// Releases the thumbnail of the given finished export job. The thumbnail is
+/// kept if it is still used elsewhere, such as by the hotbar.
+fn release_thumbnail(job: IconExportJob, thumbnailer: &mut BlockThumbnailer) {
+    let image = job.thumbnail.image.id();
+    drop(job);
+    thumbnailer.release(image);
+}
+
+/// Writes the pixels of the given export job to its file as a PNG image.
+fn save_icon(job: &IconExportJob, data: Vec<u8>) {
+    let image = Image::new(
+        Extent3d {
+            width: job.size,
+            height: job.size,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Bgra8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+
+    let image = match image.try_into_dynamic() {
+        Ok(image) => image.to_rgba8(),
+        Err(err) => {
+            error!("Failed to convert icon {}: {}", job.path.display(), err);
+            return;
+        }
+    };
+
+    if let Some(folder) = job.path.parent() {
+        if let Err(err) = std::fs::create_dir_all(folder) {
+            error!("Failed to create icon folder {}: {}", folder.display(), err);
+            return;
+        }
+    }
+
+    match image.save(&job.path) {
+        Ok(()) => info!("Exported icon: {}", job.path.display()),
+        Err(err) => error!("Failed to write icon {}: {}", job.path.display(), err),
+    }
+}
+
+/// This system extracts the icons that have finished their render delay into
+/// the render world.
+fn extract_icon_exports(
+    exports: Extract<Res<IconExports>>,
+    mut extracted: ResMut<ExtractedIconExports>,
+) {
+    extracted.images.clear();
+    extracted.images.extend(
+        exports
+            .jobs
+            .iter()
+            .filter(|job| job.frames == ICON_EXPORT_DELAY_FRAMES)
+            .map(|job| (job.thumbnail.image.id(), job.size)),
+    );
+}
+
+/// This render world system copies the extracted icons back from the GPU once
+/// they have been rendered, and sends their pixels to the main world.
+fn read_icon_exports(
+    extracted: Res<ExtractedIconExports>,
+    gpu_images: Res<RenderAssets<GpuImage>>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    sender: Res<IconExportSender>,
+) {
+    for &(image, size) in extracted.images.iter() {
+        let Some(gpu_image) = gpu_images.get(image) else {
+            warn!("Icon render target is not ready; Skipping export.");
+            continue;
+        };
+
+        let row_bytes = size as usize * 4;
+        let padded_row_bytes = RenderDevice::align_copy_bytes_per_row(row_bytes);
+
+        let buffer = render_device.create_buffer(&BufferDescriptor {
+            label: Some("icon_export_buffer"),
+            size: (padded_row_bytes * size as usize) as u64,
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = render_device.create_command_encoder(&CommandEncoderDescriptor {
+            label: Some("icon_export_encoder"),
+        });
+        encoder.copy_texture_to_buffer(
+            gpu_image.texture.as_image_copy(),
+            ImageCopyBuffer {
+                buffer: &buffer,
+                layout: ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_row_bytes as u32),
+                    rows_per_image: None,
+                },
+            },
+            Extent3d {
+                width: size,
+                height: size,
+                depth_or_array_layers: 1,
+            },
+        );
+        render_queue.submit([encoder.finish()]);
+
+        let slice = buffer.slice(..);
+        let (map_sender, map_receiver) = channel();
+        slice.map_async(MapMode::Read, move |result| {
+            let _ = map_sender.send(result);
+        });
+        render_device.poll(Maintain::wait()).panic_on_timeout();
+
+        if !matches!(map_receiver.recv(), Ok(Ok(()))) {
+            error!("Failed to read icon from the GPU.");
+            continue;
+        }
+
+        let data = slice
+            .get_mapped_range()
+            .chunks(padded_row_bytes)
+            .flat_map(|row| &row[.. row_bytes])
+            .copied()
+            .collect();
+        buffer.unmap();
+
+        let _ = sender.0.send(IconReadback { image, data });
+    }
+}
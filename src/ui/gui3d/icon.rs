@@ -0,0 +1,325 @@
+//! This module implements an API for rendering arbitrary 3D content, such as
+//! blocks or scenes, into egui textures that can be displayed as icons in
+//! editor tools and menus.
+//!
+//! Each icon is rendered by its own camera into its own image, on a render
+//! layer that is reserved for that icon. Any content that is spawned as a
+//! descendant of the icon root entity is automatically moved onto the render
+//! layer of the icon.
+//!
+//! Blocks rendered by an icon are framed by the bounds of their model, so that
+//! custom models of any size fit within the icon.
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::render::camera::{RenderTarget, ScalingMode};
+use bevy::render::render_resource::{
+    Extent3d,
+    TextureDescriptor,
+    TextureDimension,
+    TextureFormat,
+    TextureUsages,
+};
+use bevy::render::view::{Layer, RenderLayers};
+use bevy_egui::{EguiUserTextures, egui};
+use bevy_mod_picking::prelude::Pickable;
+
+use crate::blocks::RenderedBlock;
+use crate::blocks::model::BlockModel;
+
+/// The first render layer that is reserved for 3D icons. Every icon uses its
+/// own render layer, counting upwards from this layer.
+pub const ICON3D_FIRST_LAYER: Layer = 8;
+
+/// The scale factor used to render 3D icons. A scale factor of 1.0 indicates
+/// that the icon will be exactly large enough to fit a block at an isometric
+/// angle. A value greater than 1.0 will add a percentage of padding around the
+/// icon.
+pub const ICON3D_SCALE: f32 = 1.1;
+
+/// This component marks the root entity of a 3D icon. All content of the icon
+/// is spawned as descendants of this entity.
+#[derive(Debug, Clone, PartialEq, Eq, Component)]
+pub struct Icon3D {
+    /// The render layer reserved for this icon.
+    layer: Layer,
+}
+
+/// This is a marker component used to indicate that the entity is the block
+/// rendered by a 3D icon.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Component)]
+pub struct Icon3DBlock;
+
+/// A handle to a 3D icon, returned when the icon is spawned.
+#[derive(Debug, Clone)]
+pub struct Icon3DHandle {
+    /// The root entity of the icon.
+    pub entity: Entity,
+
+    /// The camera that renders the icon.
+    pub camera: Entity,
+
+    /// The image that the icon is rendered into. This is the only strong
+    /// handle to the image that is held by the icon itself.
+    pub image: Handle<Image>,
+
+    /// The egui texture of the rendered icon.
+    pub texture: egui::TextureId,
+
+    /// The render layer reserved for this icon.
+    layer: Layer,
+}
+
+/// This resource keeps track of which render layers are reserved for 3D icons.
+#[derive(Debug, Default, Resource)]
+pub struct Icon3DLayers {
+    /// The next render layer that has never been reserved.
+    next: Layer,
+
+    /// The render layers that have been released and can be reused.
+    free: Vec<Layer>,
+}
+
+impl Icon3DLayers {
+    /// Reserves a render layer for a new icon.
+    fn reserve(&mut self) -> Layer {
+        self.free.pop().unwrap_or_else(|| {
+            let layer = ICON3D_FIRST_LAYER + self.next;
+            self.next += 1;
+            layer
+        })
+    }
+
+    /// Releases the given render layer so that it can be reused.
+    fn release(&mut self, layer: Layer) {
+        self.free.push(layer);
+    }
+}
+
+/// A system parameter that can be used to spawn and despawn 3D icons.
+#[derive(SystemParam)]
+pub struct Icon3DSpawner<'w, 's> {
+    /// The image assets, used to create the icon render targets.
+    images: ResMut<'w, Assets<Image>>,
+
+    /// The egui user textures, used to register the icon render targets.
+    egui_textures: ResMut<'w, EguiUserTextures>,
+
+    /// The render layers reserved for icons.
+    layers: ResMut<'w, Icon3DLayers>,
+
+    /// The commands used to spawn the icon entities.
+    commands: Commands<'w, 's>,
+}
+
+impl<'w, 's> Icon3DSpawner<'w, 's> {
+    /// Spawns a new, empty 3D icon that renders into a square image with the
+    /// given size in pixels. The content of the icon should be spawned as
+    /// children of the returned root entity, centered around the origin.
+    pub fn spawn(&mut self, size: u32) -> Icon3DHandle {
+        self.spawn_with_background(size, Color::NONE)
+    }
+
+    /// Spawns a new, empty 3D icon like [`Self::spawn`], but fills the
+    /// background of the icon with the given color instead of leaving it
+    /// transparent.
+    pub fn spawn_with_background(&mut self, size: u32, background: Color) -> Icon3DHandle {
+        let size = Extent3d {
+            width: size.max(1),
+            height: size.max(1),
+            ..default()
+        };
+
+        let mut image = Image {
+            texture_descriptor: TextureDescriptor {
+                label: None,
+                size,
+                dimension: TextureDimension::D2,
+                format: TextureFormat::Bgra8UnormSrgb,
+                mip_level_count: 1,
+                sample_count: 1,
+                usage: TextureUsages::TEXTURE_BINDING
+                    | TextureUsages::COPY_DST
+                    | TextureUsages::RENDER_ATTACHMENT,
+                view_formats: &[],
+            },
+            ..default()
+        };
+        image.resize(size);
+
+        let image = self.images.add(image);
+        let texture = self.egui_textures.add_image(image.clone_weak());
+        let layer = self.layers.reserve();
+        let render_layers = RenderLayers::layer(layer);
+
+        let mut camera = Entity::PLACEHOLDER;
+        let entity = self
+            .commands
+            .spawn((
+                Icon3D { layer },
+                Name::new("Icon3D"),
+                render_layers.clone(),
+                SpatialBundle::default(),
+            ))
+            .with_children(|parent| {
+                camera = parent
+                    .spawn((
+                        render_layers.clone(),
+                        Camera3dBundle {
+                            camera: Camera {
+                                order: -1,
+                                clear_color: background.into(),
+                                target: RenderTarget::Image(image.clone_weak()),
+                                ..default()
+                            },
+                            projection: OrthographicProjection {
+                                near: -10.0,
+                                far: 10.0,
+                                scaling_mode: ScalingMode::Fixed {
+                                    width: 3f32.sqrt() * ICON3D_SCALE,
+                                    height: 3f32.sqrt() * ICON3D_SCALE,
+                                },
+                                viewport_origin: Vec2::new(0.5, 0.5),
+                                ..default()
+                            }
+                            .into(),
+                            transform: Transform::from_rotation(Quat::from_euler(
+                                EulerRot::YXZ,
+                                45f32.to_radians(),
+                                -35.264f32.to_radians(),
+                                0.0,
+                            )),
+                            ..default()
+                        },
+                    ))
+                    .id();
+
+                parent.spawn((
+                    render_layers,
+                    DirectionalLightBundle {
+                        directional_light: DirectionalLight {
+                            illuminance: light_consts::lux::FULL_DAYLIGHT,
+                            ..default()
+                        },
+                        transform: Transform::from_rotation(Quat::from_euler(
+                            EulerRot::XYZ,
+                            -30f32.to_radians(),
+                            30f32.to_radians(),
+                            0f32.to_radians(),
+                        )),
+                        ..default()
+                    },
+                ));
+            })
+            .id();
+
+        Icon3DHandle {
+            entity,
+            camera,
+            image,
+            texture,
+            layer,
+        }
+    }
+
+    /// Spawns a new 3D icon that renders the given block into a square image
+    /// with the given size in pixels. The block is framed by
+    /// [`frame_icon3d_blocks`] once the bounds of its model are known.
+    pub fn spawn_block(&mut self, block: Entity, size: u32) -> Icon3DHandle {
+        self.spawn_block_with_background(block, size, Color::NONE)
+    }
+
+    /// Spawns a new 3D icon like [`Self::spawn_block`], but fills the
+    /// background of the icon with the given color instead of leaving it
+    /// transparent.
+    pub fn spawn_block_with_background(
+        &mut self,
+        block: Entity,
+        size: u32,
+        background: Color,
+    ) -> Icon3DHandle {
+        let handle = self.spawn_with_background(size, background);
+
+        self.commands.entity(handle.entity).with_children(|parent| {
+            parent.spawn((
+                Icon3DBlock,
+                RenderedBlock { block },
+                PbrBundle {
+                    transform: Transform::from_translation(Vec3::splat(-0.5)),
+                    ..default()
+                },
+                Pickable::IGNORE,
+            ));
+        });
+
+        handle
+    }
+
+    /// Despawns the given 3D icon and all of its content, and releases its
+    /// image and render layer.
+    pub fn despawn(&mut self, handle: Icon3DHandle) {
+        self.commands.entity(handle.entity).despawn_recursive();
+        self.egui_textures.remove_image(&handle.image);
+        self.images.remove(&handle.image);
+        self.layers.release(handle.layer);
+    }
+}
+
+/// This system moves all content spawned within a 3D icon onto the render
+/// layer of that icon. This also applies to scenes that finish spawning after
+/// the icon was created.
+pub fn update_icon3d_layers(
+    icons: Query<(Entity, &Icon3D)>,
+    children: Query<&Children>,
+    unlayered: Query<(), Without<RenderLayers>>,
+    mut commands: Commands,
+) {
+    for (root, icon) in icons.iter() {
+        for entity in children.iter_descendants(root) {
+            if unlayered.contains(entity) {
+                commands
+                    .entity(entity)
+                    .insert(RenderLayers::layer(icon.layer));
+            }
+        }
+    }
+}
+
+/// This system frames the blocks rendered by 3D icons. The bounds of each block
+/// model are centered within the icon and scaled to fit, so that custom models
+/// of any size are displayed correctly. Blocks without a model are hidden.
+/// Blocks are framed again when their model changes, such as when a custom
+/// model finishes loading.
+#[allow(clippy::type_complexity)]
+pub fn frame_icon3d_blocks(
+    models: Query<Ref<BlockModel>>,
+    mut rendered: Query<(Ref<RenderedBlock>, &mut Transform, &mut Visibility), With<Icon3DBlock>>,
+) {
+    for (block, mut transform, mut visibility) in rendered.iter_mut() {
+        let model = models.get(block.block).ok();
+        if !block.is_changed() && !model.as_ref().is_some_and(|model| model.is_changed()) {
+            continue;
+        }
+
+        let bounds = model
+            .and_then(|model| model.get_bounds())
+            .filter(|bounds| bounds.max.cmpgt(bounds.min).any());
+
+        let Some(bounds) = bounds else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+
+        // The icon camera fits the diagonal of a full block.
+        let center = Vec3::from((bounds.min + bounds.max) * 0.5);
+        let diagonal = Vec3::from(bounds.max - bounds.min).length();
+        let scale = 3f32.sqrt() / diagonal;
+
+        *transform = Transform {
+            translation: -center * scale,
+            rotation: Quat::IDENTITY,
+            scale: Vec3::splat(scale),
+        };
+        *visibility = Visibility::Inherited;
+    }
+}
@@ -2,12 +2,26 @@
 
 use bevy::prelude::*;
 
-pub mod renderer;
+pub mod export;
+pub mod icon;
+pub mod thumbnail;
 
 /// This plugin adds the 3D icon rendering systems and components to the app.
 pub struct Icon3DPlugin;
 impl Plugin for Icon3DPlugin {
     fn build(&self, app_: &mut App) {
-        app_.add_systems(Startup, renderer::setup_icon3d_camera);
+        app_.init_resource::<icon::Icon3DLayers>()
+            .init_resource::<thumbnail::BlockThumbnailCache>()
+            .add_systems(
+                Update,
+                (
+                    icon::update_icon3d_layers,
+                    icon::frame_icon3d_blocks,
+                    thumbnail::invalidate_block_thumbnails,
+                    thumbnail::update_thumbnail_cameras
+                        .after_ignore_deferred(thumbnail::invalidate_block_thumbnails),
+                ),
+            )
+            .add_plugins(export::IconExportPlugin);
     }
 }
@@ -0,0 +1,269 @@
+//! This module implements [`BlockThumbnailer`], a shared service for rendering
+//! block thumbnails into images that can be displayed by any editor tool.
+//!
+//! Each thumbnail is rendered offscreen by a 3D icon, and is cached by block,
+//! image size and background color so that repeated requests share the same
+//! image. The camera of a thumbnail only renders for a few frames after it is
+//! created or after it is invalidated, such as when the model of its block
+//! changes.
+//!
+//! Once the cache is full, the least recently requested thumbnails are evicted.
+//! Thumbnails are never evicted while a strong handle to their image is still
+//! held outside of the cache, so callers that keep the returned image handle,
+//! such as UI images, keep their thumbnail alive.
+
+use std::sync::Arc;
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy_egui::egui;
+
+use super::icon::{Icon3DHandle, Icon3DSpawner};
+use crate::blocks::model::BlockModel;
+
+/// The number of frames a thumbnail is rendered after it is created or
+/// invalidated. This gives the block model and its textures time to finish
+/// loading.
+pub const THUMBNAIL_RENDER_FRAMES: u32 = 10;
+
+/// The largest number of thumbnails that are kept in the cache. The cache may
+/// grow beyond this size while more thumbnails than this are in use.
+pub const THUMBNAIL_CACHE_CAPACITY: usize = 64;
+
+/// A rendered block thumbnail, as returned by [`BlockThumbnailer::request`].
+#[derive(Debug, Clone)]
+pub struct BlockThumbnail {
+    /// The image that the thumbnail is rendered into. The thumbnail is kept in
+    /// the cache for as long as this handle, or any clone of it, is held.
+    pub image: Handle<Image>,
+
+    /// The egui texture of the thumbnail, for use in editor tools.
+    pub texture: egui::TextureId,
+
+    /// The camera that renders the thumbnail.
+    pub camera: Entity,
+
+    /// The root entity of the icon that renders the thumbnail. Content that is
+    /// spawned as a descendant of this entity is rendered with the block.
+    pub icon: Entity,
+}
+
+/// The key of a cached block thumbnail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct ThumbnailKey {
+    /// The block that is rendered.
+    block: Entity,
+
+    /// The image size, in pixels.
+    size: u32,
+
+    /// The background color, as sRGBA bytes.
+    background: [u8; 4],
+}
+
+/// A cached block thumbnail.
+#[derive(Debug)]
+struct Thumbnail {
+    /// The icon that renders the thumbnail.
+    handle: Icon3DHandle,
+
+    /// The number of frames the thumbnail has been rendered since it was last
+    /// invalidated.
+    frames: u32,
+
+    /// The value of [`BlockThumbnailCache::tick`] when the thumbnail was last
+    /// requested.
+    last_used: u64,
+}
+
+impl Thumbnail {
+    /// Returns true if a strong handle to the image of the thumbnail is still
+    /// held outside of the cache.
+    fn is_in_use(&self) -> bool {
+        match &self.handle.image {
+            Handle::Strong(handle) => Arc::strong_count(handle) > 1,
+            Handle::Weak(_) => false,
+        }
+    }
+
+    /// Gets the thumbnail that is handed out to callers.
+    fn get(&self) -> BlockThumbnail {
+        BlockThumbnail {
+            image: self.handle.image.clone(),
+            texture: self.handle.texture,
+            camera: self.handle.camera,
+            icon: self.handle.entity,
+        }
+    }
+}
+
+/// This resource stores all cached block thumbnails.
+#[derive(Debug, Default, Resource)]
+pub struct BlockThumbnailCache {
+    /// The cached thumbnails.
+    thumbnails: HashMap<ThumbnailKey, Thumbnail>,
+
+    /// A counter that is incremented for every request, used to find the least
+    /// recently requested thumbnail.
+    tick: u64,
+}
+
+/// A system parameter that can be used to request block thumbnails.
+#[derive(SystemParam)]
+pub struct BlockThumbnailer<'w, 's> {
+    /// The spawner used to create the thumbnail icons.
+    spawner: Icon3DSpawner<'w, 's>,
+
+    /// The cache of all thumbnails.
+    cache: ResMut<'w, BlockThumbnailCache>,
+}
+
+impl<'w, 's> BlockThumbnailer<'w, 's> {
+    /// Gets the thumbnail of the given block, rendered into a square image
+    /// with the given size in pixels and a transparent background. The
+    /// thumbnail is created if it is not cached yet, in which case the image is
+    /// filled over the next few frames.
+    pub fn request(&mut self, block: Entity, size: u32) -> BlockThumbnail {
+        self.request_with_background(block, size, Color::NONE)
+    }
+
+    /// Gets the thumbnail of the given block like [`Self::request`], but fills
+    /// the background of the image with the given color.
+    pub fn request_with_background(
+        &mut self,
+        block: Entity,
+        size: u32,
+        background: Color,
+    ) -> BlockThumbnail {
+        let key = ThumbnailKey {
+            block,
+            size: size.max(1),
+            background: background.to_srgba().to_u8_array(),
+        };
+
+        self.cache.tick += 1;
+        let tick = self.cache.tick;
+
+        if let Some(thumbnail) = self.cache.thumbnails.get_mut(&key) {
+            thumbnail.last_used = tick;
+            return thumbnail.get();
+        }
+
+        let handle = self
+            .spawner
+            .spawn_block_with_background(block, key.size, background);
+        let thumbnail = Thumbnail {
+            handle,
+            frames: 0,
+            last_used: tick,
+        };
+        let requested = thumbnail.get();
+        self.cache.thumbnails.insert(key, thumbnail);
+
+        while self.cache.thumbnails.len() > THUMBNAIL_CACHE_CAPACITY {
+            let Some(key) = self
+                .cache
+                .thumbnails
+                .iter()
+                .filter(|(_, thumbnail)| !thumbnail.is_in_use())
+                .min_by_key(|(_, thumbnail)| thumbnail.last_used)
+                .map(|(key, _)| *key)
+            else {
+                break;
+            };
+
+            self.evict(key);
+        }
+
+        requested
+    }
+
+    /// Renders all cached thumbnails of the given block again, such as after
+    /// its model has changed.
+    pub fn invalidate(&mut self, block: Entity) {
+        for (key, thumbnail) in self.cache.thumbnails.iter_mut() {
+            if key.block == block {
+                thumbnail.frames = 0;
+            }
+        }
+    }
+
+    /// Removes all cached thumbnails of the given block that are no longer in
+    /// use, and releases their images. Thumbnails that are still in use are
+    /// evicted once the cache is full and they are no longer used.
+    pub fn remove(&mut self, block: Entity) {
+        self.evict_unused(|key, _| key.block == block);
+    }
+
+    /// Removes the thumbnail that renders into the given image from the cache,
+    /// unless it is still in use. This should be called once a thumbnail that
+    /// is not requested again is no longer needed, such as a large exported
+    /// icon.
+    pub fn release(&mut self, image: AssetId<Image>) {
+        self.evict_unused(|_, thumbnail| thumbnail.handle.image.id() == image);
+    }
+
+    /// Removes all thumbnails that match the given filter and are no longer in
+    /// use.
+    fn evict_unused(&mut self, filter: impl Fn(&ThumbnailKey, &Thumbnail) -> bool) {
+        let keys: Vec<_> = self
+            .cache
+            .thumbnails
+            .iter()
+            .filter(|(key, thumbnail)| filter(key, thumbnail) && !thumbnail.is_in_use())
+            .map(|(key, _)| *key)
+            .collect();
+
+        for key in keys {
+            self.evict(key);
+        }
+    }
+
+    /// Removes the thumbnail with the given key from the cache and despawns
+    /// its icon.
+    fn evict(&mut self, key: ThumbnailKey) {
+        if let Some(thumbnail) = self.cache.thumbnails.remove(&key) {
+            self.spawner.despawn(thumbnail.handle);
+        }
+    }
+}
+
+/// This system invalidates the thumbnails of all blocks whose model changed,
+/// and removes the thumbnails of all blocks that were despawned.
+pub(super) fn invalidate_block_thumbnails(
+    models: Query<Entity, Changed<BlockModel>>,
+    mut removed: RemovedComponents<BlockModel>,
+    mut thumbnailer: BlockThumbnailer,
+) {
+    for block in removed.read() {
+        thumbnailer.remove(block);
+    }
+
+    for block in models.iter() {
+        thumbnailer.invalidate(block);
+    }
+}
+
+/// This system enables the camera of every thumbnail for the first few frames
+/// after it was created or invalidated, and disables it afterwards so that
+/// finished thumbnails are not rendered every frame.
+pub(super) fn update_thumbnail_cameras(
+    mut cache: ResMut<BlockThumbnailCache>,
+    mut cameras: Query<&mut Camera>,
+) {
+    for thumbnail in cache.thumbnails.values_mut() {
+        let active = thumbnail.frames < THUMBNAIL_RENDER_FRAMES;
+        if active {
+            thumbnail.frames += 1;
+        }
+
+        let Ok(mut camera) = cameras.get_mut(thumbnail.handle.camera) else {
+            continue;
+        };
+
+        if camera.is_active != active {
+            camera.is_active = active;
+        }
+    }
+}
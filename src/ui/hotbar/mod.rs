@@ -5,7 +5,9 @@ use bevy::prelude::*;
 use resource::Hotbar;
 
 use crate::gamestate::GameState;
-use crate::ui::EditorWindowState;
+#[cfg(feature = "editor")]
+use crate::ui::command_palette::EditorActionsAppExt;
+use crate::ui::{EditorWindowState, UiKeyboardFocus};
 
 pub mod resource;
 pub mod systems;
@@ -19,9 +21,21 @@ const HOTBAR_SEL_IMG: &str = "embedded://awgen/ui/hotbar/selection.png";
 /// The pixel size of a single hotbar element.
 const HOTBAR_SIZE: f32 = 48.0;
 
+/// The pixel size of the block thumbnails shown in the hotbar slots. This is
+/// larger than the slots themselves, so that thumbnails stay sharp on high DPI
+/// displays.
+const HOTBAR_THUMBNAIL_SIZE: u32 = 96;
+
 /// The number of pixels between each hotbar element.
 const HOTBAR_GAP: f32 = 2.0;
 
+/// The number of pages of slots in the hotbar.
+const HOTBAR_PAGE_COUNT: usize = 4;
+
+/// The key used to store the hotbar scroll mode in the settings file, as either
+/// `wrap` or `clamp`.
+pub const HOTBAR_SCROLL_MODE_KEY: &str = "HOTBAR_SCROLL_MODE";
+
 /// This plugin adds the editor hotbar systems and components to the app.
 pub struct UiHotbarPlugin;
 impl Plugin for UiHotbarPlugin {
@@ -36,11 +50,20 @@ impl Plugin for UiHotbarPlugin {
             .add_systems(
                 Update,
                 (
-                    systems::select_slot_with_numkeys.in_set(HotbarSystems::SelectSlot),
+                    systems::select_slot_with_numkeys
+                        .in_set(HotbarSystems::SelectSlot)
+                        .run_if(UiKeyboardFocus::is_free),
                     systems::click_slot.in_set(HotbarSystems::SelectSlot),
                     systems::scroll_slots.in_set(HotbarSystems::SelectSlot),
+                    systems::switch_page_with_keys
+                        .in_set(HotbarSystems::SelectSlot)
+                        .run_if(UiKeyboardFocus::is_free),
+                    systems::clear_deleted_blocks
+                        .run_if(in_state(GameState::Editor))
+                        .before_ignore_deferred(HotbarSystems::UpdateSlotLogic),
                     systems::update_selected_index.in_set(HotbarSystems::UpdateSlotLogic),
                     systems::update_slot_visuals.in_set(HotbarSystems::UpdateSlotVisuals),
+                    systems::update_page_label.in_set(HotbarSystems::UpdateSlotVisuals),
                 ),
             )
             .configure_sets(
@@ -60,6 +83,9 @@ impl Plugin for UiHotbarPlugin {
                 ),
             );
 
+        #[cfg(feature = "editor")]
+        app_.register_editor_action("Toggle Hotbar Scroll Mode", systems::toggle_scroll_mode);
+
         embedded_asset!(app_, "bg.png");
         embedded_asset!(app_, "selection.png");
     }
@@ -96,6 +122,11 @@ pub struct HotbarSlot {
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Component)]
 pub struct HotbarSlotIcon;
 
+/// This is a marker component used to indicate that the entity is the label
+/// displaying the current hotbar page.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Component)]
+pub struct HotbarPageLabel;
+
 /// This is a marker component used to indicate that the entity is the hotbar
 /// selection element.
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Component)]
@@ -1,9 +1,17 @@
 //! This module contains the [`Hotbar`] resource and related data structures.
 
+use std::fmt;
+use std::str::FromStr;
+
 use bevy::prelude::*;
 
+use super::HOTBAR_PAGE_COUNT;
+
 /// This resource contains information about the current state of the hotbar.
-#[derive(Debug, Default, Clone, Resource)]
+///
+/// The hotbar contains multiple pages of slots, of which only the current page
+/// is displayed. All slot indices refer to the slots of the current page.
+#[derive(Debug, Clone, Resource)]
 pub struct Hotbar {
     /// Whether or not the hotbar is currently active.
     active: bool,
@@ -11,8 +19,30 @@ pub struct Hotbar {
     /// The currently selected index in the hotbar.
     selection: usize,
 
+    /// The index of the currently displayed page.
+    page: usize,
+
+    /// How the selection behaves when scrolling past either end of the hotbar.
+    scroll_mode: HotbarScrollMode,
+
     /// Entity pointers for the hotbar slots.
     slots: Vec<HotbarSlotMeta>,
+
+    /// The data stored in the slots of each page.
+    pages: Vec<Vec<HotbarSlotData>>,
+}
+
+impl Default for Hotbar {
+    fn default() -> Self {
+        Self {
+            active: false,
+            selection: 0,
+            page: 0,
+            scroll_mode: HotbarScrollMode::default(),
+            slots: Vec::new(),
+            pages: vec![Vec::new(); HOTBAR_PAGE_COUNT],
+        }
+    }
 }
 
 impl Hotbar {
@@ -21,11 +51,12 @@ impl Hotbar {
         self.active = true;
     }
 
-    /// Deactivates the hotbar and resets all data.
+    /// Deactivates the hotbar and resets all data. The scroll mode is kept.
     pub fn deactivate(&mut self) {
-        self.active = false;
-        self.selection = 0;
-        self.slots.clear();
+        *self = Self {
+            scroll_mode: self.scroll_mode,
+            ..default()
+        };
     }
 
     /// Whether or not the hotbar is currently active.
@@ -37,9 +68,12 @@ impl Hotbar {
     pub fn insert_slot(&mut self, slot: Entity) {
         self.slots.push(HotbarSlotMeta {
             slot_id: slot,
-            data: HotbarSlotData::Empty,
             is_dirty: true,
         });
+
+        for page in self.pages.iter_mut() {
+            page.push(HotbarSlotData::Empty);
+        }
     }
 
     /// Selects a slot in the hotbar by index.
@@ -47,20 +81,57 @@ impl Hotbar {
         self.selection = index.min(self.slots.len() - 1);
     }
 
-    /// Replaces the data in the slot at the given index.
+    /// Replaces the data in the slot at the given index on the current page.
     ///
     /// Panics if the index is out of bounds.
     pub fn set_slot(&mut self, index: usize, data: HotbarSlotData) {
-        let meta = self.slots.get_mut(index).unwrap();
-        meta.data = data;
-        meta.is_dirty = true;
+        self.pages[self.page][index] = data;
+        self.slots.get_mut(index).unwrap().is_dirty = true;
     }
 
-    /// Returns the data in the slot at the given index.
+    /// Returns the data in the slot at the given index on the current page.
     ///
     /// Panics if the index is out of bounds.
     pub fn get_slot(&self, index: usize) -> HotbarSlotData {
-        self.slots.get(index).map(|slot| slot.data).unwrap()
+        self.pages[self.page].get(index).copied().unwrap()
+    }
+
+    /// Clears all slots on all pages that contain the given data.
+    pub fn clear_all(&mut self, data: HotbarSlotData) {
+        for (page_index, page) in self.pages.iter_mut().enumerate() {
+            for (index, slot) in page.iter_mut().enumerate() {
+                if *slot != data {
+                    continue;
+                }
+
+                *slot = HotbarSlotData::Empty;
+                if page_index == self.page {
+                    self.slots[index].is_dirty = true;
+                }
+            }
+        }
+    }
+
+    /// Returns the index of the currently displayed page.
+    pub fn get_page(&self) -> usize {
+        self.page
+    }
+
+    /// Returns the number of pages in the hotbar.
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// Switches the displayed page by the given delta, wrapping around at
+    /// either end. The selected slot index is kept, and all slots are marked
+    /// as dirty.
+    pub fn scroll_page(&mut self, delta: i32) {
+        let new_page = self.page as i32 + delta;
+        self.page = new_page.rem_euclid(self.pages.len() as i32) as usize;
+
+        for slot in self.slots.iter_mut() {
+            slot.is_dirty = true;
+        }
     }
 
     /// Returns the data in the currently selected slot.
@@ -100,10 +171,75 @@ impl Hotbar {
         }
     }
 
-    /// Scrolls the selection by the given delta.
+    /// Returns how the selection behaves when scrolling past either end of the
+    /// hotbar.
+    pub fn scroll_mode(&self) -> HotbarScrollMode {
+        self.scroll_mode
+    }
+
+    /// Sets how the selection behaves when scrolling past either end of the
+    /// hotbar.
+    pub fn set_scroll_mode(&mut self, mode: HotbarScrollMode) {
+        self.scroll_mode = mode;
+    }
+
+    /// Scrolls the selection by the given delta. Scrolling past either end of
+    /// the hotbar wraps around or stops at the end, depending on the scroll
+    /// mode. Does nothing if the hotbar has no slots.
     pub fn scroll(&mut self, delta: i32) {
+        let len = self.slots.len() as i32;
+        if len == 0 {
+            return;
+        }
+
         let new_selection = self.selection as i32 + delta;
-        self.selection = new_selection.rem_euclid(self.slots.len() as i32) as usize;
+        self.selection = match self.scroll_mode {
+            HotbarScrollMode::Wrap => new_selection.rem_euclid(len),
+            HotbarScrollMode::Clamp => new_selection.clamp(0, len - 1),
+        } as usize;
+    }
+}
+
+/// How the hotbar selection behaves when scrolling past either end of the
+/// hotbar.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HotbarScrollMode {
+    /// Scrolling past the last slot selects the first slot, and vice versa.
+    #[default]
+    Wrap,
+
+    /// Scrolling stops at the first and last slot.
+    Clamp,
+}
+
+impl HotbarScrollMode {
+    /// Returns the other scroll mode.
+    pub fn toggled(self) -> Self {
+        match self {
+            HotbarScrollMode::Wrap => HotbarScrollMode::Clamp,
+            HotbarScrollMode::Clamp => HotbarScrollMode::Wrap,
+        }
+    }
+}
+
+impl fmt::Display for HotbarScrollMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HotbarScrollMode::Wrap => write!(f, "wrap"),
+            HotbarScrollMode::Clamp => write!(f, "clamp"),
+        }
+    }
+}
+
+impl FromStr for HotbarScrollMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "wrap" => Ok(HotbarScrollMode::Wrap),
+            "clamp" => Ok(HotbarScrollMode::Clamp),
+            _ => Err(format!("Unknown hotbar scroll mode: {s}")),
+        }
     }
 }
 
@@ -126,9 +262,71 @@ struct HotbarSlotMeta {
     /// The entity associated with the slot.
     slot_id: Entity,
 
-    /// The data stored in the slot.
-    data: HotbarSlotData,
-
     /// Whether or not the slot is dirty and needs to be updated.
     is_dirty: bool,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates an active hotbar with the given number of slots.
+    fn hotbar(slots: u32) -> Hotbar {
+        let mut hotbar = Hotbar::default();
+        hotbar.activate();
+        for i in 0 .. slots {
+            hotbar.insert_slot(Entity::from_raw(i));
+        }
+        hotbar
+    }
+
+    #[test]
+    fn scroll_wraps_at_ends() {
+        let mut hotbar = hotbar(10);
+
+        hotbar.scroll(-1);
+        assert_eq!(hotbar.get_selected_index(), 9);
+
+        hotbar.scroll(1);
+        assert_eq!(hotbar.get_selected_index(), 0);
+
+        hotbar.scroll(23);
+        assert_eq!(hotbar.get_selected_index(), 3);
+    }
+
+    #[test]
+    fn scroll_clamps_at_ends() {
+        let mut hotbar = hotbar(10);
+        hotbar.set_scroll_mode(HotbarScrollMode::Clamp);
+
+        hotbar.scroll(-1);
+        assert_eq!(hotbar.get_selected_index(), 0);
+
+        hotbar.scroll(23);
+        assert_eq!(hotbar.get_selected_index(), 9);
+
+        hotbar.scroll(-2);
+        assert_eq!(hotbar.get_selected_index(), 7);
+    }
+
+    #[test]
+    fn scroll_mode_round_trips() {
+        for mode in [HotbarScrollMode::Wrap, HotbarScrollMode::Clamp] {
+            assert_eq!(mode.to_string().parse(), Ok(mode));
+            assert_eq!(mode.toggled().toggled(), mode);
+        }
+        assert!("bounce".parse::<HotbarScrollMode>().is_err());
+    }
+
+    #[test]
+    fn empty_hotbar_ignores_scroll() {
+        for mode in [HotbarScrollMode::Wrap, HotbarScrollMode::Clamp] {
+            let mut hotbar = hotbar(0);
+            hotbar.set_scroll_mode(mode);
+
+            hotbar.scroll(1);
+            hotbar.scroll(-1);
+            assert_eq!(hotbar.get_selected_index(), 0);
+        }
+    }
+}
@@ -3,34 +3,50 @@
 
 use bevy::input::mouse::MouseWheel;
 use bevy::prelude::*;
-use bevy::render::view::RenderLayers;
+use bevy_egui::EguiContexts;
 use bevy_mod_picking::PickableBundle;
 use bevy_mod_picking::events::{Click, Pointer};
 use bevy_mod_picking::prelude::{Pickable, PointerButton};
 
-use super::resource::{Hotbar, HotbarSlotData};
+use super::resource::{Hotbar, HotbarScrollMode, HotbarSlotData};
 use super::{
     HOTBAR_BG_IMG,
     HOTBAR_GAP,
+    HOTBAR_SCROLL_MODE_KEY,
     HOTBAR_SEL_IMG,
     HOTBAR_SIZE,
+    HOTBAR_THUMBNAIL_SIZE,
+    HotbarPageLabel,
     HotbarRoot,
     HotbarSelector,
     HotbarSlot,
     HotbarSlotIcon,
 };
-use crate::blocks::RenderedBlock;
+use crate::blocks::DeleteBlock;
+use crate::settings::ProjectSettings;
 use crate::tools::Tool;
+use crate::ui::gui3d::thumbnail::BlockThumbnailer;
 
-/// This system is used to create the editor hotbar HUD element.
+/// This system is used to create the editor hotbar HUD element. The scroll
+/// mode of the hotbar is loaded from the project settings.
 pub fn setup_hotbar(
     asset_server: Res<AssetServer>,
+    project_settings: Res<ProjectSettings>,
     mut hotbar: ResMut<Hotbar>,
     mut commands: Commands,
 ) {
     let hotbar_bg = asset_server.load(HOTBAR_BG_IMG);
     let hotbar_sel = asset_server.load(HOTBAR_SEL_IMG);
 
+    let scroll_mode = match project_settings.get_parsed(HOTBAR_SCROLL_MODE_KEY) {
+        Ok(mode) => mode.unwrap_or_default(),
+        Err(err) => {
+            error!("Failed to read hotbar scroll mode: {}", err);
+            HotbarScrollMode::default()
+        }
+    };
+
+    hotbar.set_scroll_mode(scroll_mode);
     hotbar.activate();
 
     commands
@@ -99,6 +115,22 @@ pub fn setup_hotbar(
                             });
                     }
 
+                    parent.spawn((
+                        HotbarPageLabel,
+                        TextBundle {
+                            text: Text::from_section(page_label(&hotbar), TextStyle::default()),
+                            style: Style {
+                                position_type: PositionType::Absolute,
+                                right: Val::Percent(100.0),
+                                bottom: Val::Px(0.0),
+                                margin: UiRect::right(Val::Px(HOTBAR_GAP * 2.0)),
+                                ..default()
+                            },
+                            ..default()
+                        },
+                        Pickable::IGNORE,
+                    ));
+
                     parent.spawn((
                         HotbarSelector,
                         ImageBundle {
@@ -119,6 +151,19 @@ pub fn setup_hotbar(
         });
 }
 
+/// This system switches the hotbar between wrapping and clamping the selection
+/// when scrolling past either end, and saves the new scroll mode to the project
+/// settings.
+pub fn toggle_scroll_mode(project_settings: Res<ProjectSettings>, mut hotbar: ResMut<Hotbar>) {
+    let mode = hotbar.scroll_mode().toggled();
+    hotbar.set_scroll_mode(mode);
+    info!("Hotbar scroll mode: {}", mode);
+
+    if let Err(err) = project_settings.set(HOTBAR_SCROLL_MODE_KEY, Some(&mode.to_string())) {
+        error!("Failed to save hotbar scroll mode: {}", err);
+    }
+}
+
 /// This system is used to cleanup the editor hotbar HUD element.
 pub fn cleanup_hotbar(
     mut hotbar: ResMut<Hotbar>,
@@ -147,8 +192,21 @@ pub fn update_selected_index(
     }
 }
 
+/// This system updates the page label of the hotbar to display the current
+/// page.
+pub fn update_page_label(hotbar: Res<Hotbar>, mut labels: Query<&mut Text, With<HotbarPageLabel>>) {
+    for mut text in labels.iter_mut() {
+        *text = Text::from_section(page_label(&hotbar), TextStyle::default());
+    }
+}
+
+/// Returns the text displayed by the hotbar page label.
+fn page_label(hotbar: &Hotbar) -> String {
+    format!("{}/{}", hotbar.get_page() + 1, hotbar.page_count())
+}
+
 /// This system listens for number key presses and selects the corresponding
-/// slot if it exists.
+/// slot on the current page if it exists.
 pub fn select_slot_with_numkeys(mut hotbar: ResMut<Hotbar>, input: Res<ButtonInput<KeyCode>>) {
     /// The key codes for the first 10 keyboard number keys.
     const KEYS: [KeyCode; 10] = [
@@ -175,12 +233,12 @@ pub fn select_slot_with_numkeys(mut hotbar: ResMut<Hotbar>, input: Res<ButtonInp
 }
 
 /// This system updates the visuals of the hotbar slots based on the data in the
-/// hotbar resource.
+/// hotbar resource. Blocks are shown by their thumbnail.
 pub fn update_slot_visuals(
     mut hotbar: ResMut<Hotbar>,
     tools: Query<&UiImage, (With<Tool>, Without<HotbarSlotIcon>)>,
     mut slots: Query<&mut UiImage, With<HotbarSlotIcon>>,
-    mut commands: Commands,
+    mut thumbnailer: BlockThumbnailer,
 ) {
     for i in 0 .. hotbar.slot_count() {
         if !hotbar.is_dirty(i) {
@@ -188,8 +246,6 @@ pub fn update_slot_visuals(
         }
 
         let slot_id = hotbar.get_slot_entity(i);
-        commands.entity(slot_id).despawn_descendants();
-
         let mut slot_icon = slots.get_mut(slot_id).unwrap();
 
         match hotbar.get_slot(i) {
@@ -201,37 +257,8 @@ pub fn update_slot_visuals(
                 *slot_icon = tool_icon.clone();
             }
             HotbarSlotData::Block(block_id) => {
-                *slot_icon = UiImage::default();
-
-                let mut block_transform = Transform::from_rotation(Quat::from_euler(
-                    EulerRot::XYZ,
-                    45f32.to_radians(),
-                    45f32.to_radians(),
-                    180f32.to_radians(),
-                ));
-                block_transform.scale = Vec3::splat(HOTBAR_SIZE / 3f32.sqrt());
-
-                commands
-                    .spawn(SpatialBundle {
-                        transform: Transform::from_translation(Vec3::new(
-                            0.0,
-                            HOTBAR_SIZE / 2.0,
-                            0.0,
-                        )),
-                        ..default()
-                    })
-                    .with_children(|parent| {
-                        parent.spawn((
-                            RenderLayers::layer(1),
-                            RenderedBlock { block: block_id },
-                            PbrBundle {
-                                transform: block_transform,
-                                ..default()
-                            },
-                            Pickable::IGNORE,
-                        ));
-                    })
-                    .set_parent(slot_id);
+                let thumbnail = thumbnailer.request(block_id, HOTBAR_THUMBNAIL_SIZE);
+                *slot_icon = UiImage::new(thumbnail.image);
             }
         }
     }
@@ -260,20 +287,62 @@ pub fn click_slot(
 }
 
 /// This system listens for mouse wheel events and scrolls the hotbar slots.
+/// While control is held, the hotbar pages are scrolled instead. Scrolling
+/// while the cursor is over an editor panel is ignored, so that it does not
+/// interfere with scrolling the panel.
 pub fn scroll_slots(
     mut wheel_events: EventReader<MouseWheel>,
     keyboard_input: Res<ButtonInput<KeyCode>>,
     mut hotbar: ResMut<Hotbar>,
+    mut contexts: EguiContexts,
 ) {
     if keyboard_input.pressed(KeyCode::AltLeft) {
         return;
     }
 
+    let over_panel = contexts
+        .try_ctx_mut()
+        .is_some_and(|ctx| ctx.is_pointer_over_area());
+
+    if over_panel {
+        wheel_events.clear();
+        return;
+    }
+
+    let page_modifier = keyboard_input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+
     for ev in wheel_events.read() {
         if ev.y.abs() < 1.0 {
             continue;
         }
 
-        hotbar.scroll(-ev.y as i32);
+        if page_modifier {
+            hotbar.scroll_page(-ev.y as i32);
+        } else {
+            hotbar.scroll(-ev.y as i32);
+        }
+    }
+}
+
+/// This system listens for page up and page down key presses and switches the
+/// displayed hotbar page.
+pub fn switch_page_with_keys(mut hotbar: ResMut<Hotbar>, input: Res<ButtonInput<KeyCode>>) {
+    if input.just_pressed(KeyCode::PageUp) {
+        hotbar.scroll_page(-1);
+    }
+
+    if input.just_pressed(KeyCode::PageDown) {
+        hotbar.scroll_page(1);
+    }
+}
+
+/// This system listens for deleted blocks and clears any hotbar slots that
+/// contain them, on all pages.
+pub fn clear_deleted_blocks(
+    mut delete_events: EventReader<DeleteBlock>,
+    mut hotbar: ResMut<Hotbar>,
+) {
+    for ev in delete_events.read() {
+        hotbar.clear_all(HotbarSlotData::Block(ev.block));
     }
 }
@@ -0,0 +1,83 @@
+//! This module implements toggling the visibility of the editor HUD, for
+//! taking screenshots or distraction-free building.
+
+use bevy::prelude::*;
+
+use super::UiKeyboardFocus;
+use super::command_palette::EditorActionsAppExt;
+use super::hotbar::HotbarRoot;
+use crate::gamestate::GameState;
+
+/// The plugin that adds the editor HUD visibility systems to the app.
+pub struct EditorHudPlugin;
+impl Plugin for EditorHudPlugin {
+    fn build(&self, app_: &mut App) {
+        app_.init_resource::<EditorHud>()
+            .add_systems(
+                Update,
+                (
+                    toggle_editor_hud
+                        .run_if(in_state(GameState::Editor))
+                        .run_if(UiKeyboardFocus::is_free),
+                    update_hotbar_visibility.after_ignore_deferred(toggle_editor_hud),
+                ),
+            )
+            .add_systems(OnExit(GameState::Editor), reset_editor_hud)
+            .register_editor_action("Toggle Editor HUD", |mut hud: ResMut<EditorHud>| {
+                hud.visible = !hud.visible;
+            });
+    }
+}
+
+/// This resource stores whether the editor HUD is currently visible. Editor UI
+/// systems should check this flag before rendering any panels.
+#[derive(Debug, Resource)]
+pub struct EditorHud {
+    /// Whether the editor HUD is visible.
+    pub visible: bool,
+}
+
+impl Default for EditorHud {
+    fn default() -> Self {
+        Self { visible: true }
+    }
+}
+
+impl EditorHud {
+    /// A run condition that returns true if the editor HUD is visible.
+    pub fn is_visible(hud: Res<EditorHud>) -> bool {
+        hud.visible
+    }
+}
+
+/// This system toggles the visibility of the editor HUD when F10 is pressed.
+fn toggle_editor_hud(keyboard_input: Res<ButtonInput<KeyCode>>, mut hud: ResMut<EditorHud>) {
+    if keyboard_input.just_pressed(KeyCode::F10) {
+        hud.visible = !hud.visible;
+        info!("Editor HUD visible: {}", hud.visible);
+    }
+}
+
+/// This system updates the visibility of the hotbar to match the editor HUD
+/// state. This also applies to newly spawned hotbars.
+fn update_hotbar_visibility(
+    hud: Res<EditorHud>,
+    mut hotbar: Query<(&mut Visibility, Ref<HotbarRoot>)>,
+) {
+    let new_visibility = if hud.visible {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+
+    for (mut visibility, root) in hotbar.iter_mut() {
+        if (hud.is_changed() || root.is_added()) && *visibility != new_visibility {
+            *visibility = new_visibility;
+        }
+    }
+}
+
+/// This system restores the editor HUD when leaving the editor.
+fn reset_editor_hud(mut hud: ResMut<EditorHud>) {
+    hud.visible = true;
+}
@@ -0,0 +1,149 @@
+//! This module implements a top-down minimap of the loaded chunks, which helps
+//! with navigating large worlds within the map editor.
+//!
+//! The minimap shows a window of chunk columns around the camera, with north
+//! pointing up. Clicking the minimap moves the camera to that location.
+
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use bevy_egui::EguiContexts;
+use bevy_egui::egui::{self, Color32, Frame, Margin, Rounding, Sense, Stroke};
+
+use super::command_palette::EditorActionsAppExt;
+use super::hud::EditorHud;
+use super::{EditorWindowState, UiKeyboardFocus};
+use crate::camera::{CameraTarget, focus_camera_on};
+use crate::gamestate::GameState;
+use crate::map::world::VoxelWorld;
+use crate::math::{BlockPos, CHUNK_SIZE, ChunkPos};
+
+/// The size of the minimap, in logical pixels.
+pub const MINIMAP_SIZE: f32 = 160.0;
+
+/// The number of chunks shown on each side of the camera. Chunks further away
+/// from the camera are not drawn, so the minimap stays readable in very large
+/// worlds.
+pub const MINIMAP_RADIUS: i32 = 16;
+
+/// The color of loaded chunk columns on the minimap.
+const CHUNK_COLOR: Color32 = Color32::from_rgb(90, 140, 90);
+
+/// The color of the camera marker on the minimap.
+const CAMERA_COLOR: Color32 = Color32::from_rgb(240, 200, 60);
+
+/// The plugin that adds the minimap to the app.
+pub struct MinimapPlugin;
+impl Plugin for MinimapPlugin {
+    fn build(&self, app_: &mut App) {
+        app_.init_resource::<Minimap>()
+            .add_systems(
+                Update,
+                (
+                    toggle_minimap
+                        .run_if(in_state(GameState::Editor))
+                        .run_if(UiKeyboardFocus::is_free),
+                    render_minimap
+                        .after_ignore_deferred(toggle_minimap)
+                        .run_if(in_state(GameState::Editor))
+                        .run_if(in_state(EditorWindowState::MapEditor))
+                        .run_if(EditorHud::is_visible)
+                        .run_if(|minimap: Res<Minimap>| minimap.visible),
+                ),
+            )
+            .register_editor_action("Toggle Minimap", |mut minimap: ResMut<Minimap>| {
+                minimap.visible = !minimap.visible;
+            });
+    }
+}
+
+/// This resource stores the state of the minimap.
+#[derive(Debug, Default, Resource)]
+pub struct Minimap {
+    /// Whether the minimap is currently visible.
+    pub visible: bool,
+}
+
+/// This system toggles the minimap when F4 is pressed.
+fn toggle_minimap(keyboard_input: Res<ButtonInput<KeyCode>>, mut minimap: ResMut<Minimap>) {
+    if keyboard_input.just_pressed(KeyCode::F4) {
+        minimap.visible = !minimap.visible;
+        info!("Minimap visible: {}", minimap.visible);
+    }
+}
+
+/// This system renders the minimap, and moves the camera to the clicked
+/// location.
+fn render_minimap(
+    world: Res<VoxelWorld>,
+    mut camera_target: Query<(&mut Transform, &CameraTarget)>,
+    mut contexts: EguiContexts,
+) {
+    let Ok((mut target, target_props)) = camera_target.get_single_mut() else {
+        return;
+    };
+
+    let center: ChunkPos = BlockPos::from_vec3(target.translation).into();
+    let chunk_size = CHUNK_SIZE as f32;
+    let cells = (MINIMAP_RADIUS * 2 + 1) as f32;
+    let cell = MINIMAP_SIZE / cells;
+    let min_x = (center.x - MINIMAP_RADIUS) as f32;
+    let min_z = (center.z - MINIMAP_RADIUS) as f32;
+
+    let columns: HashSet<(i32, i32)> = world
+        .iter_chunks()
+        .map(|(pos, _)| (pos.x, pos.z))
+        .filter(|(x, z)| {
+            (x - center.x).abs() <= MINIMAP_RADIUS && (z - center.z).abs() <= MINIMAP_RADIUS
+        })
+        .collect();
+
+    let mut clicked = None;
+
+    egui::Area::new(egui::Id::new("minimap"))
+        .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-8.0, 8.0))
+        .show(contexts.ctx_mut(), |ui| {
+            Frame {
+                inner_margin: Margin::same(6.0),
+                fill: Color32::from_black_alpha(180),
+                rounding: Rounding::same(4.0),
+                ..default()
+            }
+            .show(ui, |ui| {
+                let (response, painter) =
+                    ui.allocate_painter(egui::Vec2::splat(MINIMAP_SIZE), Sense::click());
+                let rect = response.rect;
+
+                let to_map = |x: f32, z: f32| {
+                    rect.min + egui::vec2(x / chunk_size - min_x, z / chunk_size - min_z) * cell
+                };
+
+                for &(x, z) in columns.iter() {
+                    let min = to_map(x as f32 * chunk_size, z as f32 * chunk_size);
+                    let chunk = egui::Rect::from_min_size(min, egui::Vec2::splat(cell));
+                    painter.rect_filled(chunk.shrink(0.5), 0.0, CHUNK_COLOR);
+                }
+
+                let marker = to_map(target.translation.x, target.translation.z);
+                let forward = target_props.rotation() * Vec3::NEG_Z;
+                let heading = Vec2::new(forward.x, forward.z).normalize_or_zero() * cell * 2.0;
+
+                painter.line_segment(
+                    [marker, marker + egui::vec2(heading.x, heading.y)],
+                    Stroke::new(2.0, CAMERA_COLOR),
+                );
+                painter.circle_filled(marker, 3.0, CAMERA_COLOR);
+
+                if response.clicked() {
+                    clicked = response.interact_pointer_pos().map(|pos| {
+                        let local = (pos - rect.min) / cell;
+                        Vec2::new(local.x + min_x, local.y + min_z) * chunk_size
+                    });
+                }
+            });
+        });
+
+    if let Some(pos) = clicked {
+        let y = target.translation.y;
+        focus_camera_on(&mut target, Vec3::new(pos.x, y, pos.y));
+    }
+}
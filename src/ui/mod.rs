@@ -2,23 +2,63 @@
 
 #[cfg(feature = "editor")]
 pub mod block_editor;
+#[cfg(feature = "editor")]
+pub mod command_palette;
+#[cfg(feature = "editor")]
+pub mod confirm;
+#[cfg(feature = "editor")]
+pub mod console;
 pub mod gui3d;
 pub mod hotbar;
+#[cfg(feature = "editor")]
+pub mod hud;
+#[cfg(feature = "editor")]
+pub mod minimap;
+#[cfg(feature = "editor")]
+pub mod open_project;
+#[cfg(feature = "editor")]
+pub mod perf;
 pub mod splash;
+#[cfg(feature = "editor")]
+pub mod theme;
+#[cfg(feature = "editor")]
+pub mod tileset_editor;
 
 use bevy::prelude::*;
+use bevy_egui::EguiContexts;
 
 /// The plugin that adds the UI systems and components to the app.
 pub struct AwgenUIPlugin;
 impl Plugin for AwgenUIPlugin {
     fn build(&self, app_: &mut App) {
-        app_.init_state::<EditorWindowState>().add_plugins((
-            #[cfg(feature = "editor")]
-            block_editor::BlockEditorUiPlugin,
-            gui3d::Icon3DPlugin,
-            hotbar::UiHotbarPlugin,
-            splash::SplashPlugin,
-        ));
+        app_.init_state::<EditorWindowState>()
+            .init_resource::<UiKeyboardFocus>()
+            .add_systems(Last, update_keyboard_focus)
+            .add_plugins((
+                #[cfg(feature = "editor")]
+                block_editor::BlockEditorUiPlugin,
+                #[cfg(feature = "editor")]
+                command_palette::CommandPalettePlugin,
+                #[cfg(feature = "editor")]
+                confirm::ConfirmDialogPlugin,
+                #[cfg(feature = "editor")]
+                console::ScriptConsolePlugin,
+                gui3d::Icon3DPlugin,
+                hotbar::UiHotbarPlugin,
+                #[cfg(feature = "editor")]
+                hud::EditorHudPlugin,
+                #[cfg(feature = "editor")]
+                minimap::MinimapPlugin,
+                #[cfg(feature = "editor")]
+                open_project::OpenProjectUiPlugin,
+                #[cfg(feature = "editor")]
+                perf::PerfOverlayPlugin,
+                splash::SplashPlugin,
+                #[cfg(feature = "editor")]
+                theme::EditorThemePlugin,
+                #[cfg(feature = "editor")]
+                tileset_editor::TilesetEditorUiPlugin,
+            ));
     }
 }
 
@@ -31,4 +71,41 @@ pub enum EditorWindowState {
 
     /// The block editor window.
     BlockEditor,
+
+    /// The tileset editor window.
+    TilesetEditor,
+}
+
+/// This resource tracks whether a UI text field currently has keyboard focus.
+/// While it does, keyboard shortcuts are disabled, so that typing into the
+/// field does not trigger them.
+#[derive(Debug, Default, Resource)]
+pub struct UiKeyboardFocus {
+    /// Whether the UI currently wants keyboard input.
+    focused: bool,
+}
+
+impl UiKeyboardFocus {
+    /// Returns true if a UI text field currently has keyboard focus.
+    pub fn has_focus(&self) -> bool {
+        self.focused
+    }
+
+    /// A run condition that returns true if keyboard shortcuts may be handled,
+    /// meaning that no UI text field has keyboard focus.
+    pub fn is_free(focus: Res<Self>) -> bool {
+        !focus.has_focus()
+    }
+}
+
+/// This system updates the [`UiKeyboardFocus`] resource at the end of every
+/// frame, once all UI has been built.
+fn update_keyboard_focus(mut contexts: EguiContexts, mut focus: ResMut<UiKeyboardFocus>) {
+    let focused = contexts
+        .try_ctx_mut()
+        .is_some_and(|ctx| ctx.wants_keyboard_input());
+
+    if focus.focused != focused {
+        focus.focused = focused;
+    }
 }
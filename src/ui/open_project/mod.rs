@@ -0,0 +1,159 @@
+//! This module implements the "Open Project..." dialog, which allows another
+//! project to be opened from within the editor.
+
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, egui};
+
+use super::block_editor::helper::BlockEditData;
+use super::command_palette::EditorActionsAppExt;
+use super::confirm::ConfirmDialog;
+use super::theme::EditorTheme;
+use crate::gamestate::GameState;
+use crate::project::OpenProject;
+use crate::settings::ProjectSettings;
+use crate::utilities::file_dialog::FileDialog;
+
+/// The plugin that adds the "Open Project..." dialog to the app.
+pub struct OpenProjectUiPlugin;
+impl Plugin for OpenProjectUiPlugin {
+    fn build(&self, app_: &mut App) {
+        app_.init_resource::<OpenProjectDialog>()
+            .add_systems(
+                Update,
+                (
+                    toggle_dialog.run_if(in_state(GameState::Editor)),
+                    render_dialog
+                        .after_ignore_deferred(toggle_dialog)
+                        .run_if(in_state(GameState::Editor))
+                        .run_if(|dialog: Res<OpenProjectDialog>| dialog.visible),
+                ),
+            )
+            .add_systems(OnExit(GameState::Editor), close_dialog)
+            .register_editor_action("Open Project...", open_dialog);
+    }
+}
+
+/// This resource stores the state of the "Open Project..." dialog.
+#[derive(Debug, Default, Resource)]
+pub struct OpenProjectDialog {
+    /// Whether the dialog is currently visible.
+    pub visible: bool,
+
+    /// The path of the project folder to open.
+    pub path: String,
+
+    /// The error message of the last failed attempt to open a project, if any.
+    pub error: Option<String>,
+
+    /// The native dialog used to pick the project folder.
+    pub folder_dialog: FileDialog,
+}
+
+impl OpenProjectDialog {
+    /// Closes the dialog and clears the last error.
+    pub fn close(&mut self) {
+        self.visible = false;
+        self.error = None;
+    }
+}
+
+/// This system toggles the "Open Project..." dialog when Ctrl+O is pressed.
+fn toggle_dialog(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    project_settings: Res<ProjectSettings>,
+    mut dialog: ResMut<OpenProjectDialog>,
+) {
+    let ctrl = keyboard_input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    if !ctrl || !keyboard_input.just_pressed(KeyCode::KeyO) {
+        return;
+    }
+
+    if dialog.visible {
+        dialog.close();
+    } else {
+        open_dialog(project_settings, dialog);
+    }
+}
+
+/// This system opens the "Open Project..." dialog. The path defaults to the
+/// folder of the current project.
+fn open_dialog(project_settings: Res<ProjectSettings>, mut dialog: ResMut<OpenProjectDialog>) {
+    dialog.visible = true;
+    dialog.error = None;
+
+    if dialog.path.is_empty() {
+        dialog.path = project_settings.project_folder().display().to_string();
+    }
+}
+
+/// This system renders the "Open Project..." dialog. If the Block Editor has
+/// unsaved changes, the user is asked to confirm before they are discarded.
+fn render_dialog(
+    theme: Res<EditorTheme>,
+    block_edit: Res<BlockEditData>,
+    mut dialog: ResMut<OpenProjectDialog>,
+    mut confirm_dialog: ResMut<ConfirmDialog>,
+    mut open_events: EventWriter<OpenProject>,
+    mut contexts: EguiContexts,
+) {
+    let dialog = &mut *dialog;
+    let mut open = false;
+    let mut cancel = false;
+
+    egui::Window::new("Open Project")
+        .resizable(false)
+        .collapsible(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .frame(theme.popup_frame())
+        .show(contexts.ctx_mut(), |ui| {
+            ui.label("Project Folder:");
+            dialog
+                .folder_dialog
+                .folder_input(ui, &mut dialog.path, "Open Project");
+
+            if let Some(error) = &dialog.error {
+                ui.colored_label(egui::Color32::RED, error);
+            }
+
+            ui.horizontal(|ui| {
+                open = ui.button("Open").clicked();
+                cancel = ui.button("Cancel").clicked();
+            });
+        });
+
+    if cancel {
+        dialog.close();
+        return;
+    }
+
+    if !open {
+        return;
+    }
+
+    let path = PathBuf::from(dialog.path.trim());
+    if !path.is_dir() {
+        dialog.error = Some(format!("Folder not found: {}", path.display()));
+        return;
+    }
+
+    dialog.close();
+
+    if block_edit.dirty {
+        confirm_dialog.open(
+            "The current block has unsaved changes. Discard them and open the project?",
+            move |world: &mut World| {
+                world.send_event(OpenProject { path });
+            },
+        );
+        return;
+    }
+
+    open_events.send(OpenProject { path });
+}
+
+/// This system closes the "Open Project..." dialog when leaving the editor.
+fn close_dialog(mut dialog: ResMut<OpenProjectDialog>) {
+    dialog.close();
+}
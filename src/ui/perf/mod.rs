@@ -0,0 +1,128 @@
+//! This module implements a performance overlay that displays live engine
+//! statistics for debugging.
+
+use bevy::diagnostic::{DiagnosticPath, DiagnosticsStore, FrameTimeDiagnosticsPlugin};
+use bevy::prelude::*;
+use bevy_egui::EguiContexts;
+use bevy_egui::egui::{self, Color32, Frame, Margin, RichText, Rounding};
+
+use super::UiKeyboardFocus;
+use super::command_palette::EditorActionsAppExt;
+use super::hud::EditorHud;
+use crate::logic::resources::ScriptCommandMetrics;
+use crate::map::remesh::{
+    CHUNK_MESH_BYTES,
+    CHUNK_MESH_COUNT,
+    CHUNK_TRIANGLE_COUNT,
+    MESH_ASSET_COUNT,
+    NeedsRemesh,
+    NeedsRemeshLater,
+};
+use crate::map::world::VoxelWorld;
+
+/// The plugin that adds the performance overlay to the app.
+pub struct PerfOverlayPlugin;
+impl Plugin for PerfOverlayPlugin {
+    fn build(&self, app_: &mut App) {
+        if !app_.is_plugin_added::<FrameTimeDiagnosticsPlugin>() {
+            app_.add_plugins(FrameTimeDiagnosticsPlugin);
+        }
+
+        app_.init_resource::<PerfOverlay>()
+            .add_systems(
+                Update,
+                (
+                    toggle_overlay.run_if(UiKeyboardFocus::is_free),
+                    render_overlay
+                        .after_ignore_deferred(toggle_overlay)
+                        .run_if(|overlay: Res<PerfOverlay>| overlay.visible)
+                        .run_if(EditorHud::is_visible),
+                ),
+            )
+            .register_editor_action(
+                "Toggle Performance Overlay",
+                |mut overlay: ResMut<PerfOverlay>| {
+                    overlay.visible = !overlay.visible;
+                },
+            );
+    }
+}
+
+/// This resource stores the state of the performance overlay.
+#[derive(Debug, Default, Resource)]
+pub struct PerfOverlay {
+    /// Whether the overlay is currently visible.
+    pub visible: bool,
+}
+
+/// This system toggles the performance overlay when F3 is pressed.
+fn toggle_overlay(keyboard_input: Res<ButtonInput<KeyCode>>, mut overlay: ResMut<PerfOverlay>) {
+    if keyboard_input.just_pressed(KeyCode::F3) {
+        overlay.visible = !overlay.visible;
+        info!("Performance overlay visible: {}", overlay.visible);
+    }
+}
+
+/// This system renders the performance overlay. The overlay is not
+/// interactable, so it never captures mouse input meant for the world.
+fn render_overlay(
+    diagnostics: Res<DiagnosticsStore>,
+    world: Res<VoxelWorld>,
+    script_metrics: Res<ScriptCommandMetrics>,
+    remesh_now: Query<(), With<NeedsRemesh>>,
+    remesh_later: Query<(), With<NeedsRemeshLater>>,
+    mut contexts: EguiContexts,
+) {
+    let fps = diagnostics
+        .get(&FrameTimeDiagnosticsPlugin::FPS)
+        .and_then(|fps| fps.smoothed())
+        .unwrap_or_default();
+
+    let frame_time = diagnostics
+        .get(&FrameTimeDiagnosticsPlugin::FRAME_TIME)
+        .and_then(|time| time.smoothed())
+        .unwrap_or_default();
+
+    let measurement = |path: &DiagnosticPath| {
+        diagnostics
+            .get(path)
+            .and_then(|diagnostic| diagnostic.value())
+            .unwrap_or_default()
+    };
+    let mesh_assets = measurement(&MESH_ASSET_COUNT);
+    let chunk_meshes = measurement(&CHUNK_MESH_COUNT);
+    let chunk_mesh_bytes = measurement(&CHUNK_MESH_BYTES);
+    let chunk_triangles = measurement(&CHUNK_TRIANGLE_COUNT);
+
+    let pending_remesh = remesh_now.iter().count() + remesh_later.iter().count();
+
+    egui::Area::new(egui::Id::new("perf_overlay"))
+        .anchor(egui::Align2::LEFT_TOP, egui::vec2(8.0, 8.0))
+        .interactable(false)
+        .show(contexts.ctx_mut(), |ui| {
+            Frame {
+                inner_margin: Margin::same(6.0),
+                fill: Color32::from_black_alpha(180),
+                rounding: Rounding::same(4.0),
+                ..default()
+            }
+            .show(ui, |ui| {
+                let line = |text: String| RichText::new(text).monospace().color(Color32::WHITE);
+                ui.label(line(format!("FPS:        {fps:.1}")));
+                ui.label(line(format!("Frame time: {frame_time:.2} ms")));
+                ui.label(line(format!("Chunks:     {}", world.chunk_count())));
+                ui.label(line(format!("Remeshing:  {pending_remesh}")));
+                ui.label(line(format!(
+                    "Script:     {} cmds, {:.2} ms",
+                    script_metrics.commands,
+                    script_metrics.duration.as_secs_f64() * 1000.0
+                )));
+                ui.label(line(format!("Backlog:    {}", script_metrics.backlog)));
+                ui.label(line(format!(
+                    "Meshes:     {mesh_assets:.0} ({chunk_meshes:.0} chunk, {:.1} MiB)",
+                    chunk_mesh_bytes / (1024.0 * 1024.0)
+                )));
+                ui.label(line(format!("Triangles:  {chunk_triangles:.0}")));
+            });
+        });
+}
@@ -3,7 +3,8 @@
 use bevy::asset::embedded_asset;
 use bevy::prelude::*;
 
-use crate::gamestate::GameState;
+use crate::gamestate::{GameState, to_playing_state};
+use crate::settings::ProjectSettings;
 
 /// The asset path to the Wraithaven Games splash screen icon.
 const WHG_SPLASH_ICON: &str = "embedded://awgen/ui/splash/whg.png";
@@ -11,11 +12,31 @@ const WHG_SPLASH_ICON: &str = "embedded://awgen/ui/splash/whg.png";
 /// The maximum size of the splash screen icon.
 const SPLASH_MAX_SIZE: f32 = 1024.0;
 
+/// The key used to store the splash screen initial delay in the settings file.
+pub const SPLASH_INIT_TIME_KEY: &str = "SPLASH_INIT_TIME";
+
+/// The key used to store the splash screen fade duration in the settings file.
+pub const SPLASH_FADE_TIME_KEY: &str = "SPLASH_FADE_TIME";
+
+/// The key used to store the splash screen hold duration in the settings file.
+pub const SPLASH_HOLD_TIME_KEY: &str = "SPLASH_HOLD_TIME";
+
+/// The key used to store the splash screen end delay in the settings file.
+pub const SPLASH_END_TIME_KEY: &str = "SPLASH_END_TIME";
+
+/// The key used to store whether the splash screen is shown in the settings
+/// file. The splash screen is shown unless this is set to `false`.
+pub const SPLASH_ENABLED_KEY: &str = "SPLASH_ENABLED";
+
 /// The plugin responsible for managing the splash screen UI.
 pub struct SplashPlugin;
 impl Plugin for SplashPlugin {
     fn build(&self, app_: &mut App) {
-        app_.add_systems(OnEnter(GameState::Splash), build_splash)
+        app_.init_resource::<SplashTimings>()
+            .add_systems(
+                OnEnter(GameState::Splash),
+                (load_splash_timings, build_splash),
+            )
             .add_systems(OnExit(GameState::Splash), dispose_splash)
             .add_systems(Update, update_splash.run_if(in_state(GameState::Splash)));
 
@@ -23,6 +44,37 @@ impl Plugin for SplashPlugin {
     }
 }
 
+/// This resource contains the timings of the splash screen animation, in
+/// seconds. These values are loaded from the project settings when the splash
+/// screen is shown, and fall back to the defaults if missing or invalid.
+///
+/// Setting all timings to zero skips the splash screen entirely.
+#[derive(Debug, Clone, Copy, PartialEq, Resource)]
+pub struct SplashTimings {
+    /// The time in seconds to wait before fading in the splash icon.
+    pub init_time: f32,
+
+    /// The time in seconds to fade in/out the splash icon.
+    pub fade_time: f32,
+
+    /// The time in seconds to hold the splash icon at full opacity.
+    pub hold_time: f32,
+
+    /// The time in seconds to wait before transitioning to the main menu.
+    pub end_time: f32,
+}
+
+impl Default for SplashTimings {
+    fn default() -> Self {
+        Self {
+            init_time: 1.0,
+            fade_time: 1.0,
+            hold_time: 1.5,
+            end_time: 1.0,
+        }
+    }
+}
+
 /// This is a marker component that indicates the root of the splash screen.
 #[derive(Debug, Component)]
 struct SplashScreenRoot;
@@ -38,8 +90,58 @@ struct SplashIcon {
     init_time: f32,
 }
 
-/// Builds the splash screen.
-fn build_splash(time: Res<Time>, asset_server: Res<AssetServer>, mut commands: Commands) {
+/// Loads the splash screen timings from the project settings.
+fn load_splash_timings(project_settings: Res<ProjectSettings>, mut timings: ResMut<SplashTimings>) {
+    let defaults = SplashTimings::default();
+    let read = |key: &str, default: f32| -> f32 {
+        match project_settings.get_parsed::<f32>(key) {
+            Ok(Some(seconds)) if seconds.is_finite() && seconds >= 0.0 => seconds,
+            Ok(Some(seconds)) => {
+                warn!("Invalid value for {}: {}; Using default.", key, seconds);
+                default
+            }
+            Ok(None) => default,
+            Err(err) => {
+                error!("Failed to read {}: {}", key, err);
+                default
+            }
+        }
+    };
+
+    *timings = SplashTimings {
+        init_time: read(SPLASH_INIT_TIME_KEY, defaults.init_time),
+        fade_time: read(SPLASH_FADE_TIME_KEY, defaults.fade_time),
+        hold_time: read(SPLASH_HOLD_TIME_KEY, defaults.hold_time),
+        end_time: read(SPLASH_END_TIME_KEY, defaults.end_time),
+    };
+}
+
+/// Returns whether the splash screen is enabled in the project settings.
+fn is_splash_enabled(project_settings: &ProjectSettings) -> bool {
+    match project_settings.get_parsed::<bool>(SPLASH_ENABLED_KEY) {
+        Ok(enabled) => enabled.unwrap_or(true),
+        Err(err) => {
+            error!("Failed to read {}: {}", SPLASH_ENABLED_KEY, err);
+            true
+        }
+    }
+}
+
+/// Builds the splash screen, or skips directly to the playable state if the
+/// splash screen is disabled in the project settings.
+fn build_splash(
+    time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    project_settings: Res<ProjectSettings>,
+    next_state: ResMut<NextState<GameState>>,
+    mut commands: Commands,
+) {
+    if !is_splash_enabled(&project_settings) {
+        info!("Splash screen disabled; Skipping.");
+        to_playing_state(next_state);
+        return;
+    }
+
     commands
         .spawn((SplashScreenRoot, NodeBundle {
             style: Style {
@@ -75,39 +177,35 @@ fn build_splash(time: Res<Time>, asset_server: Res<AssetServer>, mut commands: C
 /// Updates the splash screen animation.
 fn update_splash(
     time: Res<Time>,
+    timings: Res<SplashTimings>,
     mut icon: Query<(&mut UiImage, &SplashIcon)>,
     mut next_state: ResMut<NextState<GameState>>,
 ) {
-    /// The time in seconds to wait before fading in the splash icon.
-    const INIT_TIME: f32 = 1.0;
-
-    /// The time in seconds to fade in/out the splash icon.
-    const FADE_TIME: f32 = 1.0;
-
-    /// The time in seconds to hold the splash icon at full opacity.
-    const HOLD_TIME: f32 = 1.5;
-
-    /// The time in seconds to wait before transitioning to the main menu.
-    const END_TIME: f32 = 1.0;
+    let SplashTimings {
+        init_time,
+        fade_time,
+        hold_time,
+        end_time,
+    } = *timings;
 
     for (mut image, icon) in icon.iter_mut() {
         let seconds = time.elapsed_seconds() - icon.init_time;
 
-        let alpha = if seconds < INIT_TIME {
+        let alpha = if seconds < init_time {
             0.0
-        } else if seconds < INIT_TIME + FADE_TIME {
-            (seconds - INIT_TIME) / FADE_TIME
-        } else if seconds < INIT_TIME + FADE_TIME + HOLD_TIME {
+        } else if seconds < init_time + fade_time {
+            (seconds - init_time) / fade_time
+        } else if seconds < init_time + fade_time + hold_time {
             1.0
-        } else if seconds < INIT_TIME + FADE_TIME + HOLD_TIME + FADE_TIME {
-            1.0 - (seconds - INIT_TIME - FADE_TIME - HOLD_TIME) / FADE_TIME
+        } else if seconds < init_time + fade_time + hold_time + fade_time {
+            1.0 - (seconds - init_time - fade_time - hold_time) / fade_time
         } else {
             0.0
         };
 
         image.color = Color::srgba(1.0, 1.0, 1.0, alpha);
 
-        if seconds >= INIT_TIME + FADE_TIME + HOLD_TIME + FADE_TIME + END_TIME {
+        if seconds >= init_time + fade_time + hold_time + fade_time + end_time {
             #[cfg(feature = "editor")]
             next_state.set(GameState::Editor);
             #[cfg(not(feature = "editor"))]
@@ -0,0 +1,137 @@
+//! This module implements the editor theme, which controls the appearance of
+//! all egui editor panels.
+
+use bevy::prelude::*;
+use bevy_egui::EguiContexts;
+use bevy_egui::egui::{Color32, Frame, Margin, Rounding, Stroke};
+use serde::{Deserialize, Serialize};
+
+use crate::project::LoadProject;
+use crate::settings::ProjectSettings;
+
+/// The key used to store the editor theme in the settings file.
+pub const EDITOR_THEME_KEY: &str = "EDITOR_THEME";
+
+/// The plugin that loads and applies the editor theme.
+pub struct EditorThemePlugin;
+impl Plugin for EditorThemePlugin {
+    fn build(&self, app_: &mut App) {
+        app_.init_resource::<EditorTheme>()
+            .add_systems(LoadProject, load_editor_theme)
+            .add_systems(Update, apply_editor_theme);
+    }
+}
+
+/// This resource contains the editor theme. The theme is stored in the project
+/// settings as a JSON object, and any missing fields use the default values.
+///
+/// Colors are stored as `[r, g, b, a]` arrays.
+#[derive(Debug, Clone, PartialEq, Resource, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct EditorTheme {
+    /// The fill color of editor side panels.
+    pub panel_fill: [u8; 4],
+
+    /// The fill color of popup windows.
+    pub popup_fill: [u8; 4],
+
+    /// The stroke color of popup windows.
+    pub popup_stroke: [u8; 4],
+
+    /// The stroke width of popup windows.
+    pub popup_stroke_width: f32,
+
+    /// The corner rounding of popup windows and widgets.
+    pub rounding: f32,
+
+    /// The font size of list entries, such as the block list.
+    pub list_font_size: f32,
+
+    /// The font size of title fields, such as the block name.
+    pub title_font_size: f32,
+}
+
+impl Default for EditorTheme {
+    fn default() -> Self {
+        Self {
+            panel_fill: [20, 20, 20, 255],
+            popup_fill: [35, 35, 35, 255],
+            popup_stroke: [100, 100, 100, 255],
+            popup_stroke_width: 3.0,
+            rounding: 6.0,
+            list_font_size: 20.0,
+            title_font_size: 32.0,
+        }
+    }
+}
+
+impl EditorTheme {
+    /// Converts an `[r, g, b, a]` array into an egui color.
+    fn color(rgba: [u8; 4]) -> Color32 {
+        Color32::from_rgba_unmultiplied(rgba[0], rgba[1], rgba[2], rgba[3])
+    }
+
+    /// Gets the frame used for editor side panels.
+    pub fn panel_frame(&self) -> Frame {
+        Frame {
+            fill: Self::color(self.panel_fill),
+            ..default()
+        }
+    }
+
+    /// Gets the frame used for popup windows.
+    pub fn popup_frame(&self) -> Frame {
+        Frame {
+            inner_margin: Margin::same(10.0),
+            fill: Self::color(self.popup_fill),
+            rounding: Rounding::same(self.rounding),
+            stroke: Stroke {
+                width: self.popup_stroke_width,
+                color: Self::color(self.popup_stroke),
+            },
+            ..default()
+        }
+    }
+}
+
+/// This system loads the editor theme from the project settings.
+fn load_editor_theme(project_settings: Res<ProjectSettings>, mut theme: ResMut<EditorTheme>) {
+    match project_settings.get(EDITOR_THEME_KEY) {
+        Ok(Some(value)) => match serde_json::from_str(&value) {
+            Ok(loaded) => *theme = loaded,
+            Err(err) => warn!("Invalid editor theme in project settings: {}", err),
+        },
+        Ok(None) => {}
+        Err(err) => error!("Failed to read editor theme: {}", err),
+    }
+}
+
+/// This system applies the editor theme to the egui context style whenever the
+/// theme changes. The theme is applied as soon as the egui context is ready.
+fn apply_editor_theme(
+    theme: Res<EditorTheme>,
+    mut applied: Local<bool>,
+    mut contexts: EguiContexts,
+) {
+    if *applied && !theme.is_changed() {
+        return;
+    }
+
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+
+    let mut style = (*ctx.style()).clone();
+    style.visuals.panel_fill = EditorTheme::color(theme.panel_fill);
+    style.visuals.window_fill = EditorTheme::color(theme.popup_fill);
+    style.visuals.window_stroke = Stroke::new(
+        theme.popup_stroke_width,
+        EditorTheme::color(theme.popup_stroke),
+    );
+    style.visuals.window_rounding = Rounding::same(theme.rounding);
+    style.visuals.menu_rounding = Rounding::same(theme.rounding);
+    ctx.set_style(style);
+
+    *applied = true;
+    debug!("Applied editor theme.");
+}
@@ -0,0 +1,81 @@
+//! This module implements the Tileset Editor UI screen within the editor mode.
+
+use bevy::prelude::*;
+use uuid::Uuid;
+
+use super::command_palette::EditorActionsAppExt;
+use super::hud::EditorHud;
+use super::{EditorWindowState, UiKeyboardFocus};
+use crate::gamestate::GameState;
+use crate::project::UnloadProject;
+use crate::utilities::file_dialog::FileDialog;
+
+pub mod ui;
+
+/// The plugin that adds the Tileset Editor UI systems to the app.
+pub struct TilesetEditorUiPlugin;
+impl Plugin for TilesetEditorUiPlugin {
+    fn build(&self, app_: &mut App) {
+        app_.init_resource::<TilesetEditData>()
+            .add_systems(
+                Update,
+                (
+                    ui::render
+                        .after_ignore_deferred(ui::open)
+                        .after_ignore_deferred(ui::close)
+                        .run_if(in_state(GameState::Editor))
+                        .run_if(in_state(EditorWindowState::TilesetEditor))
+                        .run_if(EditorHud::is_visible),
+                    ui::open
+                        .run_if(in_state(GameState::Editor))
+                        .run_if(not(in_state(EditorWindowState::TilesetEditor)))
+                        .run_if(UiKeyboardFocus::is_free),
+                    ui::close
+                        .run_if(in_state(GameState::Editor))
+                        .run_if(in_state(EditorWindowState::TilesetEditor))
+                        .run_if(UiKeyboardFocus::is_free),
+                ),
+            )
+            .add_systems(UnloadProject, reset_tileset_edit_data)
+            .register_editor_action(
+                "Open Tileset Editor",
+                |mut editor_window_state: ResMut<NextState<EditorWindowState>>| {
+                    editor_window_state.set(EditorWindowState::TilesetEditor);
+                },
+            );
+    }
+}
+
+/// The temporary data of the Tileset Editor UI screen.
+#[derive(Debug, Default, Resource)]
+pub struct TilesetEditData {
+    /// The uuid of the selected tileset, if any.
+    pub selected: Option<Uuid>,
+
+    /// The name that is being edited for the selected tileset.
+    pub name: String,
+
+    /// The name of the tileset to import.
+    pub import_name: String,
+
+    /// The path of the PNG image to copy into the project when importing a
+    /// tileset. If empty, the image is expected to already exist in the
+    /// project tileset folder under the import name.
+    pub import_path: String,
+
+    /// The error message of the last failed import, if any.
+    pub import_error: Option<String>,
+
+    /// The error message of the last failed project settings operation, if
+    /// any.
+    pub settings_error: Option<String>,
+
+    /// The file dialog used to pick the image to import.
+    pub import_dialog: FileDialog,
+}
+
+/// This system discards the tileset that is being edited, as well as any
+/// pending import, when the project is unloaded.
+fn reset_tileset_edit_data(mut data: ResMut<TilesetEditData>) {
+    *data = TilesetEditData::default();
+}
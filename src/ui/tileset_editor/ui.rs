@@ -0,0 +1,459 @@
+//! This module handles the construction of the Tileset Editor UI screen within
+//! the editor mode.
+
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use bevy_egui::EguiContexts;
+use bevy_egui::egui::{self, RichText};
+use uuid::Uuid;
+
+use super::TilesetEditData;
+use crate::blocks::shape::BlockShape;
+use crate::blocks::tileset::{
+    TILESET_LENGTH,
+    Tileset,
+    TilesetDefinition,
+    TilesetMaterial,
+    import_tileset_image,
+    load_tileset,
+};
+use crate::logic::error::{LogicError, retry_settings};
+use crate::settings::ProjectSettings;
+use crate::ui::EditorWindowState;
+use crate::ui::block_editor::helper::BlockEditData;
+use crate::ui::theme::EditorTheme;
+
+/// Builds the Tileset Editor UI screen.
+///
+/// The tileset list contains all tilesets stored in the project settings, as
+/// well as any loaded tilesets that have not been stored yet.
+#[allow(clippy::too_many_arguments)]
+pub fn render(
+    mut data: ResMut<TilesetEditData>,
+    project_settings: Res<ProjectSettings>,
+    asset_server: Res<AssetServer>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut tilesets: Query<(&Tileset, &mut Name, &Handle<Image>)>,
+    mut tileset_materials: Query<(&Tileset, &mut TilesetMaterial)>,
+    mut shapes: Query<&mut BlockShape, Without<Tileset>>,
+    mut block_edit: ResMut<BlockEditData>,
+    theme: Res<EditorTheme>,
+    mut contexts: EguiContexts,
+    mut commands: Commands,
+) {
+    let mut definitions = match retry_settings(|| project_settings.list_tilesets()) {
+        Ok(definitions) => definitions,
+        Err(err) => {
+            report_settings_error(&mut data, "Failed to list tilesets", err);
+            Vec::new()
+        }
+    };
+    for (tileset, name, _) in tilesets.iter() {
+        if !definitions.iter().any(|def| def.uuid == tileset.uuid) {
+            definitions.push(TilesetDefinition {
+                uuid: tileset.uuid,
+                name: name.as_str().to_string(),
+            });
+        }
+    }
+    definitions.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let selected = data
+        .selected
+        .and_then(|uuid| definitions.iter().find(|def| def.uuid == uuid))
+        .cloned();
+
+    let texture = selected.as_ref().and_then(|def| {
+        let (_, _, handle) = tilesets
+            .iter()
+            .find(|(tileset, _, _)| tileset.uuid == def.uuid)?;
+
+        Some(
+            contexts
+                .image_id(handle)
+                .unwrap_or_else(|| contexts.add_image(handle.clone_weak())),
+        )
+    });
+
+    let ctx = contexts.ctx_mut();
+
+    egui::SidePanel::left("tileset_list_panel")
+        .default_width(200.0)
+        .min_width(100.0)
+        .resizable(true)
+        .frame(theme.panel_frame())
+        .show(ctx, |ui| {
+            egui::ScrollArea::vertical()
+                .id_salt("tileset_list_scroll")
+                .show(ui, |ui| {
+                    ui.set_width(ui.available_width());
+
+                    for def in definitions.iter() {
+                        let label = RichText::new(&def.name)
+                            .monospace()
+                            .size(theme.list_font_size);
+
+                        if ui
+                            .selectable_label(data.selected == Some(def.uuid), label)
+                            .clicked()
+                        {
+                            data.selected = Some(def.uuid);
+                            data.name = def.name.clone();
+                        }
+                    }
+                });
+
+            ui.separator();
+            ui.label("Import tileset");
+            egui::Grid::new("tileset_import_grid")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    let TilesetEditData {
+                        import_path,
+                        import_dialog,
+                        ..
+                    } = &mut *data;
+
+                    ui.label("Image");
+                    import_dialog.path_input(
+                        ui,
+                        import_path,
+                        "Import Tileset",
+                        &[("PNG Image", &["png"])],
+                    );
+                    ui.end_row();
+
+                    ui.label("Name");
+                    ui.text_edit_singleline(&mut data.import_name);
+                    ui.end_row();
+                });
+
+            let path = data.import_path.trim().to_string();
+            let mut name = data.import_name.trim().to_string();
+            if name.is_empty() {
+                name = Path::new(&path)
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+                    .unwrap_or_default();
+            }
+
+            let valid_name = TilesetDefinition::is_valid_name(&name);
+            let can_import = valid_name && !definitions.iter().any(|def| def.name == name);
+
+            if !name.is_empty() && !valid_name {
+                ui.colored_label(
+                    ui.visuals().error_fg_color,
+                    "Tileset names may only contain letters, digits, spaces, '_', '-' and '.'",
+                );
+            }
+
+            if ui
+                .add_enabled(can_import, egui::Button::new("Import"))
+                .clicked()
+            {
+                data.import_error = None;
+                data.settings_error = None;
+
+                if !path.is_empty() {
+                    if let Err(err) = import_tileset_image(
+                        Path::new(&path),
+                        &project_settings.asset_folder(),
+                        &name,
+                    ) {
+                        error!("Failed to import tileset {name}: {err}");
+                        data.import_error = Some(err.to_string());
+                    }
+                }
+
+                if data.import_error.is_none() {
+                    let definition = TilesetDefinition {
+                        uuid: Uuid::new_v4(),
+                        name,
+                    };
+
+                    info!("Importing tileset {}", definition.name);
+                    match retry_settings(|| project_settings.update_tileset(&definition)) {
+                        Ok(()) => {
+                            load_tileset(
+                                &asset_server,
+                                &project_settings,
+                                &mut materials,
+                                &mut commands,
+                                definition.uuid,
+                                &definition.name,
+                            );
+
+                            data.selected = Some(definition.uuid);
+                            data.name = definition.name;
+                            data.import_name.clear();
+                            data.import_path.clear();
+                        }
+                        Err(err) => {
+                            report_settings_error(&mut data, "Failed to save tileset", err);
+                        }
+                    }
+                }
+            }
+
+            if let Some(err) = &data.import_error {
+                ui.colored_label(ui.visuals().error_fg_color, err.as_str());
+            }
+
+            if let Some(err) = &data.settings_error {
+                ui.colored_label(ui.visuals().error_fg_color, err.as_str());
+            }
+        });
+
+    egui::CentralPanel::default().show(ctx, |ui| {
+        let Some(selected) = selected else {
+            ui.label("Select a tileset to edit.");
+            return;
+        };
+
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut data.name)
+                    .char_limit(50)
+                    .font(egui::FontId::monospace(theme.title_font_size)),
+            );
+
+            let new_name = data.name.trim().to_string();
+            let can_rename = TilesetDefinition::is_valid_name(&new_name)
+                && new_name != selected.name
+                && !definitions.iter().any(|def| def.name == new_name);
+
+            if ui
+                .add_enabled(can_rename, egui::Button::new("Rename"))
+                .clicked()
+            {
+                data.settings_error = None;
+                match rename_tileset(
+                    &project_settings,
+                    &mut tilesets,
+                    &mut shapes,
+                    &mut block_edit.shape,
+                    &selected,
+                    &new_name,
+                ) {
+                    Ok(()) => data.name = new_name,
+                    Err(err) => {
+                        let context = format!("Failed to rename tileset {}", selected.name);
+                        report_settings_error(&mut data, &context, err);
+                    }
+                }
+            }
+        });
+
+        let Some(texture) = texture else {
+            ui.label("This tileset is not loaded.");
+            return;
+        };
+
+        if let Some((_, mut material)) = tileset_materials
+            .iter_mut()
+            .find(|(tileset, _)| tileset.uuid == selected.uuid)
+        {
+            material_sliders(ui, &project_settings, &selected.name, &mut material);
+        }
+
+        let size = ui.available_width().min(ui.available_height()).max(64.0);
+        let response = ui.add(
+            egui::Image::new(egui::load::SizedTexture::new(
+                texture,
+                egui::vec2(size, size),
+            ))
+            .sense(egui::Sense::hover()),
+        );
+
+        if let Some(hover) = response.hover_pos() {
+            let local = (hover - response.rect.min) / response.rect.size();
+            let x = ((local.x * TILESET_LENGTH as f32) as usize).min(TILESET_LENGTH - 1);
+            let y = ((local.y * TILESET_LENGTH as f32) as usize).min(TILESET_LENGTH - 1);
+            response.on_hover_text_at_pointer(format!("Tile: ({x}, {y})"));
+        }
+    });
+}
+
+/// Renders the sliders used to edit the material properties of a tileset, and
+/// the toggle for insetting tile edges. Changes are previewed in the world
+/// immediately, and saved to the project settings once a slider is released.
+fn material_sliders(
+    ui: &mut egui::Ui,
+    project_settings: &ProjectSettings,
+    tileset: &str,
+    material: &mut Mut<TilesetMaterial>,
+) {
+    let mut properties = **material;
+    let mut save = false;
+
+    egui::Grid::new("tileset_material_grid")
+        .num_columns(2)
+        .show(ui, |ui| {
+            let sliders = [
+                ("Roughness", &mut properties.roughness),
+                ("Metallic", &mut properties.metallic),
+                ("Reflectance", &mut properties.reflectance),
+                ("Alpha Cutoff", &mut properties.alpha_cutoff),
+            ];
+
+            for (label, value) in sliders {
+                ui.label(label);
+                let response = ui.add(egui::Slider::new(value, 0.0 ..= 1.0));
+                save |= response.drag_stopped() || (response.changed() && !response.dragged());
+                ui.end_row();
+            }
+        });
+
+    let response = ui
+        .checkbox(&mut properties.inset_uvs, "Inset Tile Edges")
+        .on_hover_text("Prevents neighboring tiles from bleeding in at fractional zoom levels.");
+    save |= response.changed();
+
+    material.set_if_neq(properties);
+
+    if save {
+        properties.save(project_settings, tileset);
+    }
+}
+
+/// Logs the given project settings error and shows it in the Tileset Editor.
+/// Errors that repeat every frame are only logged once.
+fn report_settings_error(data: &mut TilesetEditData, context: &str, err: impl Display) {
+    let message = format!("{context}: {err}");
+    if data.settings_error.as_ref() != Some(&message) {
+        error!("{message}");
+    }
+    data.settings_error = Some(message);
+}
+
+/// An error that can occur while renaming a tileset.
+#[derive(Debug, thiserror::Error)]
+enum RenameTilesetError {
+    /// The tileset image could not be renamed.
+    #[error("Failed to rename the tileset image: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The project settings could not be updated.
+    #[error("{0}")]
+    Logic(#[from] LogicError),
+}
+
+/// Gets the path of the image of the tileset with the given name.
+fn tileset_image_path(project_settings: &ProjectSettings, name: &str) -> PathBuf {
+    project_settings
+        .asset_folder()
+        .join("tilesets")
+        .join(format!("{name}.png"))
+}
+
+/// Renames the given tileset in the project settings, on disk, and in the
+/// world. The tileset image is renamed to match the new name, all blocks that
+/// use the tileset are updated to refer to the new name, and the material
+/// properties of the tileset are moved to the new name. The staged shape of the
+/// block that is open in the Block Editor is updated as well, so that saving it
+/// does not restore the old name.
+///
+/// The tileset image is reloaded from its new path by
+/// [`reload_renamed_tileset_images`](crate::blocks::tileset::reload_renamed_tileset_images).
+///
+/// If the project settings cannot be updated, the tileset image is moved back
+/// to its old name.
+fn rename_tileset(
+    project_settings: &ProjectSettings,
+    tilesets: &mut Query<(&Tileset, &mut Name, &Handle<Image>)>,
+    shapes: &mut Query<&mut BlockShape, Without<Tileset>>,
+    staged_shape: &mut BlockShape,
+    definition: &TilesetDefinition,
+    new_name: &str,
+) -> Result<(), RenameTilesetError> {
+    info!("Renaming tileset {} to {}", definition.name, new_name);
+
+    let old_image = tileset_image_path(project_settings, &definition.name);
+    let new_image = tileset_image_path(project_settings, new_name);
+    let move_image = old_image.exists();
+    if move_image {
+        if new_image.exists() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::AlreadyExists,
+                format!("{} already exists", new_image.display()),
+            )
+            .into());
+        }
+
+        std::fs::rename(&old_image, &new_image)?;
+    }
+
+    let result = retry_settings(|| {
+        project_settings.transaction(|tx| {
+            tx.update_tileset(&TilesetDefinition {
+                uuid: definition.uuid,
+                name: new_name.to_string(),
+            })?;
+            tx.rename(
+                &TilesetMaterial::settings_key(&definition.name),
+                &TilesetMaterial::settings_key(new_name),
+            )
+        })
+    });
+
+    if let Err(err) = result {
+        if move_image {
+            if let Err(rollback_err) = std::fs::rename(&new_image, &old_image) {
+                error!(
+                    "Failed to move tileset image {} back: {rollback_err}",
+                    new_image.display()
+                );
+            }
+        }
+        return Err(err.into());
+    }
+
+    for (tileset, mut name, _) in tilesets.iter_mut() {
+        if tileset.uuid == definition.uuid {
+            name.set(new_name.to_string());
+        }
+    }
+
+    for mut shape in shapes.iter_mut() {
+        if !shape.uses_tileset(&definition.name) {
+            continue;
+        }
+
+        if let Some(tileset) = shape.tileset_mut() {
+            *tileset = new_name.to_string();
+        }
+    }
+
+    if staged_shape.uses_tileset(&definition.name) {
+        if let Some(tileset) = staged_shape.tileset_mut() {
+            *tileset = new_name.to_string();
+        }
+    }
+
+    Ok(())
+}
+
+/// This system transitions to the Tileset Editor UI screen.
+pub fn open(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut editor_window_state: ResMut<NextState<EditorWindowState>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::F2) {
+        editor_window_state.set(EditorWindowState::TilesetEditor);
+        info!("Opened Tileset Editor UI window.");
+    }
+}
+
+/// This system closes the Tileset Editor UI screen and returns to the Map
+/// Editor.
+pub fn close(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut editor_window_state: ResMut<NextState<EditorWindowState>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::F2) || keyboard_input.just_pressed(KeyCode::Escape) {
+        editor_window_state.set(EditorWindowState::MapEditor);
+        info!("Closed Tileset Editor UI window.");
+    }
+}
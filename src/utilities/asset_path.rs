@@ -0,0 +1,57 @@
+//! This module implements validating asset paths that are provided by scripts,
+//! so that they cannot refer to files outside of the project asset source.
+
+/// Splits the given project asset path into its segments, using `/` as the path
+/// separator. An optional `project://` prefix is ignored, and empty and `.`
+/// segments are skipped.
+///
+/// Returns `None` if the path is empty, absolute, or contains a `..` segment,
+/// since such paths could refer to files outside of the asset source.
+pub fn project_path_segments(path: &str) -> Option<Vec<&str>> {
+    let path = path.strip_prefix("project://").unwrap_or(path);
+    if path.starts_with('/') || path.contains(['\\', ':']) {
+        return None;
+    }
+
+    let mut segments = Vec::new();
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => continue,
+            ".." => return None,
+            segment => segments.push(segment),
+        }
+    }
+
+    if segments.is_empty() {
+        return None;
+    }
+
+    Some(segments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_paths() {
+        assert_eq!(
+            project_path_segments("project://maps//./island.png"),
+            Some(vec!["maps", "island.png"])
+        );
+        assert_eq!(
+            project_path_segments("island.png"),
+            Some(vec!["island.png"])
+        );
+    }
+
+    #[test]
+    fn rejects_paths_outside_of_project() {
+        assert_eq!(project_path_segments("../secrets.png"), None);
+        assert_eq!(project_path_segments("maps/../../secrets.png"), None);
+        assert_eq!(project_path_segments("/etc/passwd"), None);
+        assert_eq!(project_path_segments("C:/Windows/win.ini"), None);
+        assert_eq!(project_path_segments("maps\\island.png"), None);
+        assert_eq!(project_path_segments("project://"), None);
+    }
+}
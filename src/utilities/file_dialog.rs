@@ -0,0 +1,151 @@
+//! This module implements a non-blocking native file dialog that editor panels
+//! can use to pick files or folders from disk.
+//!
+//! Native dialogs are only available when the engine is built with the
+//! `file-dialog` feature. Without it, [`FileDialog::path_input`] falls back to
+//! a plain text field where the path can be typed in manually.
+
+use std::path::PathBuf;
+
+#[cfg(feature = "file-dialog")]
+use bevy::tasks::{IoTaskPool, Task, block_on, futures_lite::future};
+use bevy_egui::egui;
+
+/// A file filter for a file dialog, given as a display name and a list of
+/// accepted file extensions, without the leading dot.
+pub type FileFilter<'a> = (&'a str, &'a [&'a str]);
+
+/// A handle to a native file dialog. The dialog runs in the background while
+/// it is open, and the selected file can be retrieved by polling the handle
+/// once per frame.
+#[derive(Debug, Default)]
+pub struct FileDialog {
+    /// The task running the currently open dialog, if any.
+    #[cfg(feature = "file-dialog")]
+    task: Option<Task<Option<PathBuf>>>,
+}
+
+impl FileDialog {
+    /// Checks whether native file dialogs are supported by this build.
+    pub fn is_supported() -> bool {
+        cfg!(feature = "file-dialog")
+    }
+
+    /// Checks whether the dialog is currently open.
+    pub fn is_open(&self) -> bool {
+        #[cfg(feature = "file-dialog")]
+        {
+            self.task.is_some()
+        }
+
+        #[cfg(not(feature = "file-dialog"))]
+        {
+            false
+        }
+    }
+
+    /// Opens a dialog for picking a single file with the given title. Only
+    /// files matching one of the given filters can be selected. If no filters
+    /// are given, any file can be selected.
+    ///
+    /// Does nothing if the dialog is already open, or if native file dialogs
+    /// are not supported.
+    #[cfg_attr(not(feature = "file-dialog"), allow(unused_variables))]
+    pub fn open(&mut self, title: &str, filters: &[FileFilter]) {
+        #[cfg(feature = "file-dialog")]
+        {
+            if self.task.is_some() {
+                return;
+            }
+
+            let mut dialog = rfd::AsyncFileDialog::new().set_title(title);
+            for (name, extensions) in filters {
+                dialog = dialog.add_filter(*name, *extensions);
+            }
+
+            self.task = Some(IoTaskPool::get().spawn(async move {
+                let file = dialog.pick_file().await?;
+                Some(file.path().to_path_buf())
+            }));
+        }
+    }
+
+    /// Opens a dialog for picking a single folder with the given title.
+    ///
+    /// Does nothing if the dialog is already open, or if native file dialogs
+    /// are not supported.
+    #[cfg_attr(not(feature = "file-dialog"), allow(unused_variables))]
+    pub fn open_folder(&mut self, title: &str) {
+        #[cfg(feature = "file-dialog")]
+        {
+            if self.task.is_some() {
+                return;
+            }
+
+            let dialog = rfd::AsyncFileDialog::new().set_title(title);
+
+            self.task = Some(IoTaskPool::get().spawn(async move {
+                let folder = dialog.pick_folder().await?;
+                Some(folder.path().to_path_buf())
+            }));
+        }
+    }
+
+    /// Polls the dialog for the selected file. Returns the path of the file
+    /// once the user has selected one. If the dialog is still open, or has been
+    /// cancelled, `None` is returned.
+    pub fn poll(&mut self) -> Option<PathBuf> {
+        #[cfg(feature = "file-dialog")]
+        {
+            let task = self.task.as_mut()?;
+            let result = block_on(future::poll_once(task))?;
+            self.task = None;
+            result
+        }
+
+        #[cfg(not(feature = "file-dialog"))]
+        {
+            None
+        }
+    }
+
+    /// Adds a file path input to the UI, consisting of a text field and, if
+    /// native file dialogs are supported, a button that opens a file dialog.
+    /// The selected file is written to the given path once the dialog closes.
+    pub fn path_input(
+        &mut self,
+        ui: &mut egui::Ui,
+        path: &mut String,
+        title: &str,
+        filters: &[FileFilter],
+    ) {
+        self.input(ui, path, |dialog| dialog.open(title, filters));
+    }
+
+    /// Adds a folder path input to the UI, consisting of a text field and, if
+    /// native file dialogs are supported, a button that opens a folder dialog.
+    /// The selected folder is written to the given path once the dialog closes.
+    pub fn folder_input(&mut self, ui: &mut egui::Ui, path: &mut String, title: &str) {
+        self.input(ui, path, |dialog| dialog.open_folder(title));
+    }
+
+    /// Adds a path input to the UI, calling `open` to open the dialog when the
+    /// browse button is clicked.
+    fn input(&mut self, ui: &mut egui::Ui, path: &mut String, open: impl FnOnce(&mut Self)) {
+        if let Some(selected) = self.poll() {
+            *path = selected.to_string_lossy().to_string();
+        }
+
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(path);
+
+            if Self::is_supported()
+                && ui
+                    .add_enabled(!self.is_open(), egui::Button::new("Browse..."))
+                    .clicked()
+            {
+                open(self);
+            }
+        });
+    }
+}
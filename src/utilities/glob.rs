@@ -0,0 +1,150 @@
+//! This module implements simple glob patterns for matching asset paths, such
+//! as `models/**/*.glb`.
+//!
+//! Patterns are always relative to the root of an asset source, and use `/` as
+//! the path separator. Within a path segment, `*` matches any number of
+//! characters and `?` matches a single character. A segment that only consists
+//! of `**` matches any number of nested folders.
+
+use std::path::PathBuf;
+
+use super::asset_path::project_path_segments;
+
+/// A parsed glob pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Glob {
+    /// The segments of the pattern, split on `/`.
+    segments: Vec<Vec<char>>,
+}
+
+impl Glob {
+    /// Parses the given glob pattern. An optional `project://` prefix is
+    /// ignored.
+    ///
+    /// Returns `None` if the pattern is empty, absolute, or contains a `..`
+    /// segment, since such patterns could match files outside of the asset
+    /// source. See [`project_path_segments`].
+    pub fn new(pattern: &str) -> Option<Self> {
+        let segments = project_path_segments(pattern)?
+            .into_iter()
+            .map(|segment| segment.chars().collect())
+            .collect();
+
+        Some(Self { segments })
+    }
+
+    /// Gets the folder that contains all paths this pattern may match, made up
+    /// of all leading segments without any wildcards.
+    pub fn base(&self) -> PathBuf {
+        self.segments
+            .iter()
+            .take(self.segments.len() - 1)
+            .take_while(|segment| !segment.iter().any(|c| matches!(c, '*' | '?')))
+            .map(|segment| segment.iter().collect::<String>())
+            .collect()
+    }
+
+    /// Returns true if the given `/` separated path matches this pattern.
+    pub fn matches(&self, path: &str) -> bool {
+        let path: Vec<Vec<char>> = path
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .map(|segment| segment.chars().collect())
+            .collect();
+
+        match_segments(&self.segments, &path)
+    }
+}
+
+/// Returns true if the given path segments match the given pattern segments.
+fn match_segments(pattern: &[Vec<char>], path: &[Vec<char>]) -> bool {
+    match_wildcards(
+        pattern,
+        path,
+        |segment| segment[..] == ['*', '*'],
+        |p, s| match_segment(p, s),
+    )
+}
+
+/// Returns true if the given path segment matches the given pattern segment.
+fn match_segment(pattern: &[char], text: &[char]) -> bool {
+    match_wildcards(pattern, text, |c| *c == '*', |p, c| *p == '?' || p == c)
+}
+
+/// Returns true if the given items match the given pattern, where pattern
+/// elements that are a `star` match any number of items, and all other
+/// pattern elements must `accept` exactly one item.
+///
+/// Only the most recent star is backtracked to on a mismatch, which runs in
+/// `O(pattern * items)` time, as matching any earlier star differently can
+/// never succeed where the most recent one failed.
+fn match_wildcards<P, T>(
+    pattern: &[P],
+    items: &[T],
+    star: impl Fn(&P) -> bool,
+    accept: impl Fn(&P, &T) -> bool,
+) -> bool {
+    let mut p = 0;
+    let mut i = 0;
+    let mut backtrack = None;
+
+    while i < items.len() {
+        match pattern.get(p) {
+            Some(element) if star(element) => {
+                p += 1;
+                backtrack = Some((p, i));
+            }
+            Some(element) if accept(element, &items[i]) => {
+                p += 1;
+                i += 1;
+            }
+            _ => match backtrack {
+                Some((star_p, star_i)) => {
+                    p = star_p;
+                    i = star_i + 1;
+                    backtrack = Some((star_p, i));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p ..].iter().all(star)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_wildcards() {
+        let glob = Glob::new("models/*.glb").unwrap();
+        assert!(glob.matches("models/tree.glb"));
+        assert!(!glob.matches("models/tree.png"));
+        assert!(!glob.matches("models/plants/tree.glb"));
+
+        let glob = Glob::new("project://models/**/t?ee.glb").unwrap();
+        assert!(glob.matches("models/tree.glb"));
+        assert!(glob.matches("models/plants/big/tree.glb"));
+        assert!(!glob.matches("models/plants/bush.glb"));
+        assert_eq!(glob.base(), PathBuf::from("models"));
+    }
+
+    #[test]
+    fn matches_many_stars() {
+        let glob = Glob::new("**/**/**/a*a*a*a*a*a*a*a*a*b").unwrap();
+        let name = "a".repeat(64);
+        assert!(!glob.matches(&format!("x/y/z/{name}")));
+        assert!(glob.matches(&format!("x/y/z/{name}b")));
+        assert!(glob.matches(&format!("{name}b")));
+    }
+
+    #[test]
+    fn rejects_escaping_patterns() {
+        assert_eq!(Glob::new("../secrets/*"), None);
+        assert_eq!(Glob::new("models/../../*"), None);
+        assert_eq!(Glob::new("/etc/*"), None);
+        assert_eq!(Glob::new("C:/Windows/*"), None);
+        assert_eq!(Glob::new(""), None);
+    }
+}
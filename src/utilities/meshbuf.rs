@@ -17,6 +17,10 @@ pub struct MeshBuf {
     /// The normals of the mesh.
     pub normals: Vec<[f32; 3]>,
 
+    /// The vertex colors of the mesh. This may be left empty, in which case
+    /// the mesh is compiled without vertex colors.
+    pub colors: Vec<[f32; 4]>,
+
     /// The indices of the mesh.
     pub indices: Vec<u32>,
 }
@@ -34,6 +38,7 @@ impl MeshBuf {
             positions: Vec::with_capacity(Self::INIT_CAPACITY_VERTS),
             uvs: Vec::with_capacity(Self::INIT_CAPACITY_VERTS),
             normals: Vec::with_capacity(Self::INIT_CAPACITY_VERTS),
+            colors: Vec::new(),
             indices: Vec::with_capacity(Self::INIT_CAPACITY_INDICES),
         }
     }
@@ -79,13 +84,21 @@ impl From<MeshBuf> for Mesh {
             Indices::U16(value.indices.iter().map(|&i| i as u16).collect())
         };
 
-        Mesh::new(
+        let has_colors = !value.colors.is_empty() && value.colors.len() == value.positions.len();
+
+        let mesh = Mesh::new(
             PrimitiveTopology::TriangleList,
             RenderAssetUsages::default(),
         )
         .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, value.positions)
         .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, value.normals)
         .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, value.uvs)
-        .with_inserted_indices(indices)
+        .with_inserted_indices(indices);
+
+        if has_colors {
+            mesh.with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, value.colors)
+        } else {
+            mesh
+        }
     }
 }
@@ -1,7 +1,11 @@
 //! This module contains a collection of miscellaneous utilities that are used
 //! throughout the project.
 
+pub mod asset_path;
 pub mod chunk_iter;
+pub mod file_dialog;
+pub mod glob;
 pub mod meshbuf;
 pub mod raycast;
+pub mod rng;
 pub mod vec_cmp;
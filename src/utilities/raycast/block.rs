@@ -71,6 +71,7 @@ impl<'w, 's> VoxelRaycast<'w, 's> {
 
             return Some(VoxelRaycastHit {
                 block: block_pos,
+                block_id,
                 face,
                 distance: block_dist,
                 hit_pos,
@@ -87,6 +88,9 @@ pub struct VoxelRaycastHit {
     /// The position of the block that was hit.
     pub block: BlockPos,
 
+    /// The block type entity that was hit.
+    pub block_id: Entity,
+
     /// The face of the block that was hit.
     pub face: FaceDirection,
 